@@ -0,0 +1,89 @@
+//! How long searching the apps takes, with `cargo bench --bench search`
+//!
+//! The apps are the ones in `src/search/corpus.txt`, with numbered copies of each so that there
+//! are about as many as a Mac with its files indexed has
+
+use std::hint::black_box;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use rustcast::{
+    app::apps::{App, AppCommand},
+    commands::Function,
+    config::Scoring,
+    search::Searcher,
+};
+
+/// How many copies of each app in the corpus are indexed
+const COPIES: usize = 100;
+
+/// The queries that are searched for, from single letters to several words
+const QUERIES: [&str; 6] = ["s", "saf", "code", "vsc", "visual code", "microsoft w"];
+
+fn app(name: String) -> App {
+    App {
+        ranking: 0,
+        open_command: AppCommand::Function(Function::OpenApp(format!("/Applications/{name}.app"))),
+        desc: "Application".to_string(),
+        icons: None,
+        search_name: name.to_lowercase(),
+        display_name: name,
+        version: None,
+        last_used: None,
+        children: None,
+    }
+}
+
+fn searcher() -> Searcher {
+    let names = include_str!("../src/search/corpus.txt")
+        .lines()
+        .filter(|x| !x.is_empty() && !x.starts_with('#'));
+    Searcher::from_apps(
+        names
+            .flat_map(|name| (0..COPIES).map(move |i| app(format!("{name} {i}"))))
+            .collect(),
+    )
+}
+
+fn query(c: &mut Criterion) {
+    let searcher = searcher();
+    let scoring = Scoring::default();
+
+    let mut group = c.benchmark_group("query");
+    for query in QUERIES {
+        group.bench_function(query, |b| {
+            b.iter(|| searcher.query(black_box(query), &scoring))
+        });
+    }
+    group.finish();
+}
+
+/// Taking the results out of the index, as the shared [`Arc`]s that searching gives, and as copies
+/// of the apps, which is what searching used to give
+///
+/// [`Arc`]: std::sync::Arc
+fn results(c: &mut Criterion) {
+    let searcher = searcher();
+    let scoring = Scoring::default();
+
+    let mut group = c.benchmark_group("results");
+    group.bench_function("arc", |b| {
+        b.iter(|| searcher.query(black_box("s"), &scoring))
+    });
+    group.bench_function("cloned", |b| {
+        b.iter(|| {
+            searcher
+                .query(black_box("s"), &scoring)
+                .into_iter()
+                .map(|x| (*x.app).clone())
+                .collect::<Vec<App>>()
+        })
+    });
+    group.finish();
+}
+
+fn index(c: &mut Criterion) {
+    c.bench_function("index", |b| b.iter(searcher));
+}
+
+criterion_group!(benches, query, results, index);
+criterion_main!(benches);
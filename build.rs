@@ -1,4 +1,9 @@
 fn main() {
+    // The frameworks are only linked on macOS, since they don't exist (and can't be linked)
+    // anywhere else
+    if std::env::var("CARGO_CFG_TARGET_OS").as_deref() != Ok("macos") {
+        return;
+    }
     println!("cargo:rustc-link-search=framework=/System/Library/PrivateFrameworks");
     println!("cargo:rustc-link-lib=framework=IOKit");
     println!("cargo:rustc-link-lib=framework=MultitouchSupport");
@@ -0,0 +1,91 @@
+# German
+
+## The footer
+
+results-none = Keine Ergebnisse gefunden
+results-found = { $count ->
+    [0] Keine Ergebnisse gefunden
+    [one] 1 Ergebnis gefunden
+   *[other] { $count } Ergebnisse gefunden
+}
+indexing-apps = Indexiere… { $count } Apps
+
+## What the screen reader reads out for the focused result
+
+result-focused = { $name }, { $desc }, { $position } von { $count }
+
+## The pages
+
+page-app-search = App-Suche
+page-file-search = Dateisuche
+page-emoji-search = Emoji-Suche
+page-clipboard-history = Zwischenablage-Verlauf
+page-settings = Einstellungen
+page-keybindings = Tastenkürzel
+page-calculation-history = Rechenverlauf
+
+## Searching the web
+
+web-search = Suchen nach: { $query }
+web-search-with = Mit { $engine } suchen nach: { $query }
+web-search-desc = Websuche
+
+## Built in commands
+
+quit-rustcast = RustCast beenden
+quit-all-apps = Alle Apps beenden
+test-notification = Testbenachrichtigung senden
+open-preferences = RustCast-Einstellungen öffnen
+emoji-search = Nach einem Emoji suchen
+clipboard-history = Zwischenablage-Verlauf
+file-search = Nach einer Datei suchen
+calculation-history = Rechenverlauf
+clear-search-history = Suchverlauf löschen
+open-log-file = RustCast-Logdatei öffnen
+toggle-wifi = WLAN ein- oder ausschalten
+toggle-bluetooth = Bluetooth ein- oder ausschalten
+toggle-dark-mode = Dunkelmodus ein- oder ausschalten
+toggle-night-shift = Night Shift ein- oder ausschalten
+toggle-true-tone = True Tone ein- oder ausschalten
+reload-rustcast = RustCast neu laden
+current-version = Aktuelle RustCast-Version: { $version }
+update-available = Update verfügbar: RustCast { $version }
+start-at-login-on = RustCast bei der Anmeldung starten: An
+start-at-login-off = RustCast bei der Anmeldung starten: Aus
+prevent-sleep-on = Ruhezustand verhindern: An
+prevent-sleep-off = Ruhezustand verhindern: Aus
+confirm-uninstall = { $name } deinstallieren? Enter zum Bestätigen, Esc zum Abbrechen
+
+## The actions for a result, which right clicking it shows
+
+actions = Aktionen
+action-open = Öffnen
+action-browse-into = Durchsuchen
+action-reveal = Im Dateimanager zeigen
+action-open-with = Mit einer anderen App öffnen
+action-copy-path = Pfad kopieren
+action-copy-link = Link kopieren
+action-quit = Beenden
+action-force-quit = Sofort beenden
+action-add-favourite = Zu den Favoriten hinzufügen
+action-remove-favourite = Aus den Favoriten entfernen
+action-hide = In den Ergebnissen ausblenden
+action-uninstall = Deinstallieren
+action-copy-name = Namen kopieren
+
+## Copying logins from the password manager
+
+copy-username = Benutzernamen für { $title } kopieren
+copy-password = Passwort für { $title } kopieren
+copy-one-time-password = Einmalpasswort für { $title } kopieren
+
+## Results that were looked up, when they couldn't be looked up again
+
+lookup-stale = { $desc } · Zuletzt aktualisiert { $age }
+
+## Finding what is listening on a port
+
+port-desc = Port
+port-nothing-listening = Auf Port { $port } lauscht nichts
+port-listening = Lauscht auf Port { $port }
+port-kill = { $name } beenden (PID { $pid })
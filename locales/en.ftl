@@ -0,0 +1,96 @@
+# English, which is used for any message that a language doesn't have
+#
+# This is a Fluent file (https://projectfluent.org), where each message is `message-id = text` and
+# `{ $name }` is replaced with a value. Numbers can pick a plural form with a selector, like
+# `results-found` does. To add a language, copy this file to `<language code>.ftl`, translate the
+# text and add it to `LOCALES` in src/i18n.rs
+
+## The footer
+
+results-none = No results found
+results-found = { $count ->
+    [0] No results found
+    [one] 1 result found
+   *[other] { $count } results found
+}
+indexing-apps = Indexing… { $count } apps
+
+## What the screen reader reads out for the focused result
+
+result-focused = { $name }, { $desc }, { $position } of { $count }
+
+## The pages
+
+page-app-search = App search
+page-file-search = File search
+page-emoji-search = Emoji search
+page-clipboard-history = Clipboard history
+page-settings = Settings
+page-keybindings = Keyboard shortcuts
+page-calculation-history = Calculation history
+
+## Searching the web
+
+web-search = Search for: { $query }
+web-search-with = Search { $engine } for: { $query }
+web-search-desc = Web Search
+
+## Built in commands
+
+quit-rustcast = Quit RustCast
+quit-all-apps = Quit All Apps
+test-notification = Send a Test Notification
+open-preferences = Open RustCast Preferences
+emoji-search = Search for an Emoji
+clipboard-history = Clipboard History
+file-search = Search for a file
+calculation-history = Calculation History
+clear-search-history = Clear Search History
+open-log-file = Open RustCast Log File
+toggle-wifi = Turn Wi-Fi On or Off
+toggle-bluetooth = Turn Bluetooth On or Off
+toggle-dark-mode = Turn Dark Mode On or Off
+toggle-night-shift = Turn Night Shift On or Off
+toggle-true-tone = Turn True Tone On or Off
+reload-rustcast = Reload RustCast
+current-version = Current RustCast Version: { $version }
+update-available = Update Available: RustCast { $version }
+start-at-login-on = Start RustCast at Login: On
+start-at-login-off = Start RustCast at Login: Off
+prevent-sleep-on = Prevent Sleep: On
+prevent-sleep-off = Prevent Sleep: Off
+confirm-uninstall = Uninstall { $name }? Enter to confirm, Esc to cancel
+
+## The actions for a result, which right clicking it shows
+
+actions = Actions
+action-open = Open
+action-browse-into = Browse into it
+action-reveal = Show in the file manager
+action-open-with = Open with another app
+action-copy-path = Copy the path
+action-copy-link = Copy the link
+action-quit = Quit
+action-force-quit = Force quit
+action-add-favourite = Add to favourites
+action-remove-favourite = Remove from favourites
+action-hide = Hide from the results
+action-uninstall = Uninstall
+action-copy-name = Copy the name
+
+## Copying logins from the password manager
+
+copy-username = Copy the username for { $title }
+copy-password = Copy the password for { $title }
+copy-one-time-password = Copy the one-time password for { $title }
+
+## Results that were looked up, when they couldn't be looked up again
+
+lookup-stale = { $desc } · Last updated { $age }
+
+## Finding what is listening on a port
+
+port-desc = Port
+port-nothing-listening = Nothing is listening on port { $port }
+port-listening = Listening on port { $port }
+port-kill = Kill { $name } (pid { $pid })
@@ -2,35 +2,118 @@
 use std::collections::HashMap;
 
 use crate::app::apps::{App, AppCommand, ICNS_ICON};
+use crate::calendar;
 use crate::commands::Function;
-use crate::config::{Config, MainPage, Shelly};
+use crate::config::{
+    Config, DisplayMode, MainPage, PasswordManager, Shelly, SkinTone, WindowConfig,
+};
+use crate::contacts;
 use crate::debounce::DebouncePolicy;
-use crate::platform::macos::launching::Shortcut;
+use crate::i18n::tr;
+use crate::passwords;
+use crate::platform;
+use crate::platform::Shortcut;
+use crate::providers::Lookup;
+use crate::updater::Release;
 use crate::utils::icns_data_to_handle;
-use crate::{app::tile::ExtSender, clipboard::ClipBoardContentType};
-use iced::time::Duration;
+use crate::{
+    app::tile::ExtSender,
+    clipboard::{ClipBoardContentType, ClipboardSource},
+};
+use iced::time::{Duration, Instant};
 
 pub mod apps;
+pub mod keybindings;
 pub mod menubar;
 pub mod pages;
 pub mod tile;
 
 use iced::window::{self, Id, Settings};
-/// The default window width
-pub const WINDOW_WIDTH: f32 = 500.;
-
-/// The default window height
+/// The height of the window when there are no results
 pub const DEFAULT_WINDOW_HEIGHT: f32 = 100.;
 
+/// The height of the list on the pages that fill the window, like clipboard history and settings
+pub const FULL_PAGE_LIST_HEIGHT: f32 = 385.;
+
 /// Maximum file search results returned by a single mdfind invocation.
 pub const FILE_SEARCH_MAX_RESULTS: u32 = 400;
 
 /// Number of results to accumulate before flushing a batch to the UI.
 pub const FILE_SEARCH_BATCH_SIZE: u32 = 10;
 
+/// Number of recently used emojis shown in the first row of the emoji page.
+pub const MAX_RECENT_EMOJIS: usize = 6;
+
+/// Number of calculations kept on the calculation history page.
+pub const MAX_CALCULATIONS: usize = 100;
+
 /// The rustcast descriptor name to be put for all rustcast commands
 pub const RUSTCAST_DESC_NAME: &str = "Utility";
 
+/// How long the window takes to fade in or out, and to grow or shrink to fit the results
+pub const ANIMATION_TIME: Duration = Duration::from_millis(120);
+
+/// How long an error stays in the footer before it is dismissed
+pub const ERROR_DISPLAY_TIME: Duration = Duration::from_secs(8);
+
+/// The kinds of errors that are reported in the footer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorKind {
+    Launch,
+    Config,
+    FileSearch,
+    Notification,
+    Autostart,
+    Extension,
+    Containers,
+    Network,
+    UrlExpansion,
+    Weather,
+    Markets,
+    Packages,
+    GitHub,
+    IssueTracker,
+    Contacts,
+    Calendar,
+    Media,
+    Ports,
+    Passwords,
+    TextRecognition,
+    Drag,
+    Clipboard,
+    Paste,
+}
+
+impl std::fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ErrorKind::Launch => "Couldn't open",
+            ErrorKind::Config => "Config error",
+            ErrorKind::FileSearch => "File search failed",
+            ErrorKind::Notification => "Couldn't show a notification",
+            ErrorKind::Autostart => "Couldn't change starting at login",
+            ErrorKind::Extension => "Extension failed",
+            ErrorKind::Containers => "Couldn't list the containers",
+            ErrorKind::Network => "Couldn't look up the public IP address",
+            ErrorKind::UrlExpansion => "Couldn't expand the link",
+            ErrorKind::Weather => "Couldn't get the weather",
+            ErrorKind::Markets => "Couldn't get the price",
+            ErrorKind::Packages => "Couldn't search the package registry",
+            ErrorKind::GitHub => "Couldn't get the list from GitHub",
+            ErrorKind::IssueTracker => "Couldn't get your assigned issues",
+            ErrorKind::Contacts => "Couldn't read your contacts",
+            ErrorKind::Calendar => "Couldn't read your calendar",
+            ErrorKind::Media => "Couldn't get what is playing",
+            ErrorKind::Ports => "Couldn't find what is listening on the port",
+            ErrorKind::Passwords => "Couldn't get it from the password manager",
+            ErrorKind::TextRecognition => "Couldn't extract the text",
+            ErrorKind::Drag => "Couldn't drag it out",
+            ErrorKind::Clipboard => "Clipboard history is unavailable",
+            ErrorKind::Paste => "Couldn't paste",
+        })
+    }
+}
+
 /// The different pages that rustcast can have / has
 #[derive(Debug, Clone, PartialEq)]
 pub enum Page {
@@ -39,20 +122,36 @@ pub enum Page {
     ClipboardHistory,
     EmojiSearch,
     Settings,
+    Keybindings,
+    CalculationHistory,
 }
 
 impl std::fmt::Display for Page {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(match self.to_owned() {
-            Page::Main => "App search",
-            Page::FileSearch => "File search",
-            Page::EmojiSearch => "Emoji search",
-            Page::ClipboardHistory => "Clipboard history",
-            Page::Settings => "Settings",
+        f.write_str(&match self.to_owned() {
+            Page::Main => tr!("page-app-search"),
+            Page::FileSearch => tr!("page-file-search"),
+            Page::EmojiSearch => tr!("page-emoji-search"),
+            Page::ClipboardHistory => tr!("page-clipboard-history"),
+            Page::Settings => tr!("page-settings"),
+            Page::Keybindings => tr!("page-keybindings"),
+            Page::CalculationHistory => tr!("page-calculation-history"),
         })
     }
 }
 
+/// The keywords that scope the search to a page, when they are typed followed by a space
+///
+/// Extensions scope the search to their results with their own keywords the same way
+pub const PAGE_KEYWORDS: &[(&str, Page)] = &[
+    ("emoji", Page::EmojiSearch),
+    ("file", Page::FileSearch),
+    ("grep", Page::FileSearch),
+    ("cb", Page::ClipboardHistory),
+    ("cbhist", Page::ClipboardHistory),
+    ("calc", Page::CalculationHistory),
+];
+
 /// The types of arrow keys
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
@@ -63,13 +162,6 @@ pub enum ArrowKey {
     Right,
 }
 
-/// The ways the cursor can move when a key is pressed
-#[derive(Debug, Clone)]
-pub enum Move {
-    Back,
-    Forwards(String),
-}
-
 #[derive(Debug, Clone)]
 pub enum Editable<T> {
     Create(T),
@@ -85,17 +177,37 @@ pub enum Message {
     ToggleAutoStartup(bool),
     LoadRanking,
     ToggleFavouriteApp(String),
-    UpdateAvailable,
+    UpdateAvailable(Release),
     ResizeWindow(Id, f32),
     OpenWindow,
+    RunningApps(Vec<String>),
+    ShowWindow,
     OpenResult(u32),
+    FocusResult(u32),
+    HoverResult(u32),
+    PressResult(u32),
+    LeaveResult(u32),
+    PointerMoved,
+    PointerReleased,
+    ShowActions(Id, Option<u32>),
+    RunAction(Id, Box<Message>),
     OpenToSettings,
     SearchQueryChanged(String, Id),
     KeyPressed(Shortcut),
-    FocusTextInput(Move),
+    FocusTextInput,
+    ImeComposing(bool),
     HideWindow(Id),
+    HideTimeout(Id),
+    AnimationFrame(Instant),
+    WindowClosed(Id),
     RunFunction(Function),
     OpenFocused,
+    RevealFocused,
+    OpenWithFocused(Id),
+    OpenWithApps(Id, String, Vec<(String, String)>),
+    QuitFocused(bool),
+    UninstallFocused,
+    HideFocusedResult,
     SetConfig(SetConfigFields),
     OpenFileDialogue(String),
     ReturnFocus,
@@ -107,13 +219,49 @@ pub enum Message {
     SwitchMode(String),
     ReloadConfig,
     UpdateApps,
+    AppsIndexed(u64, Vec<App>),
     SetSender(ExtSender),
     SwitchToPage(Page),
+    PageBack,
+    EnterScope(String, Id),
+    SetEmojiCategory(emojis::Group),
     EditClipboardHistory(Editable<ClipBoardContentType>),
     ClearClipboardHistory,
+    TogglePinClipboardItem(ClipBoardContentType),
+    ClipboardCaptured(ClipBoardContentType, Option<ClipboardSource>),
+    ClipboardUnavailable(String),
+    ExpireClipboardItem(ClipBoardContentType),
+    PasteFocused(bool),
+    PasteClipboardItem(ClipBoardContentType, bool),
+    ExtractClipboardText(ClipBoardContentType),
+    TransformClipboardItem(ClipBoardContentType),
+    ClipboardTextExtracted(Result<String, String>),
+    SynthesizePaste,
     ChangeFocus(ArrowKey, u32),
+    ModifiersChanged(iced::keyboard::Modifiers),
+    CompleteQuery(Id),
+    BrowseInto(Id),
+    BrowseBack(Id),
+    ClearQueryHistory,
+    Error(ErrorKind, String),
+    DismissError(String),
+    StartTimer(Duration, String),
+    CancelTimer(u32),
+    TimerFinished(u32),
+    StartStopwatch,
+    StopStopwatch,
     FileSearchResult(Vec<App>),
     FileSearchClear,
+    ContentSearchResult(String, Vec<App>),
+    ExtensionResults(Id, String, Result<Vec<App>, String>),
+    LookupResults(Id, String, Lookup, Result<Vec<App>, String>),
+    ContactsLoaded(Result<Vec<contacts::Contact>, String>),
+    CalendarEvents(Result<Vec<calendar::Event>, String>),
+    RevealSecret(PasswordManager, String, passwords::SecretField),
+    SecretRevealed(Result<String, String>),
+    ClearSecret(String),
+    ToggleKeepAwake,
+    KeepAwakeTimedOut(u64),
     SetFileSearchSender(tokio::sync::watch::Sender<(String, Vec<String>)>),
     DebouncedSearch(Id),
 }
@@ -133,8 +281,9 @@ pub enum SetConfigFields {
     Modes(Editable<(String, String)>),
     Aliases(Editable<(String, String)>),
     SearchDirs(Editable<String>),
-    ShellCommands(Editable<Shelly>),
+    ShellCommands(Editable<Box<Shelly>>),
     DebounceDelay(u64),
+    EmojiSkinTone(SkinTone),
     SetThemeFields(SetConfigThemeFields),
     SetBufferFields(SetConfigBufferFields),
 }
@@ -146,6 +295,7 @@ pub enum SetConfigThemeFields {
     BackgroundColor(f32, f32, f32),
     ShowIcons(bool),
     Font(String),
+    DisplayMode(DisplayMode),
 }
 
 #[derive(Debug, Clone)]
@@ -155,17 +305,26 @@ pub enum SetConfigBufferFields {
 }
 
 /// The window settings for rustcast
-pub fn default_settings() -> Settings {
+pub fn default_settings(window_config: &WindowConfig) -> Settings {
+    let size = iced::Size {
+        width: window_config.width,
+        height: DEFAULT_WINDOW_HEIGHT,
+    };
+
     Settings {
         resizable: false,
         decorations: false,
         minimizable: false,
         level: window::Level::AlwaysOnTop,
         transparent: true,
-        blur: true,
-        size: iced::Size {
-            width: WINDOW_WIDTH,
-            height: DEFAULT_WINDOW_HEIGHT,
+        size,
+        position: window_config
+            .position
+            .on_monitor(platform::monitor_frame(window_config), size),
+        #[cfg(target_os = "linux")]
+        platform_specific: window::settings::PlatformSpecific {
+            application_id: platform::compositor::APP_ID.to_string(),
+            ..Default::default()
         },
         ..Default::default()
     }
@@ -208,6 +367,9 @@ impl ToApps for HashMap<String, String> {
                     desc: "Switch Modes".to_string(),
                     icons: icons.clone(),
                     display_name,
+                    version: None,
+                    last_used: None,
+                    children: None,
                 }
             })
             .collect();
@@ -220,6 +382,9 @@ impl ToApps for HashMap<String, String> {
                 icons: icons.clone(),
                 display_name: "Default mode".to_string(),
                 search_name: "default".to_string(),
+                version: None,
+                last_used: None,
+                children: None,
             });
         };
 
@@ -230,7 +395,13 @@ impl ToApps for HashMap<String, String> {
 impl DebouncePolicy for Page {
     fn debounce_delay(&self, config: &Config) -> Option<Duration> {
         match self {
-            Page::Main | Page::ClipboardHistory | Page::Settings => None,
+            // App search is instant unless there are so many apps that it needs a delay
+            Page::Main => (config.search_debounce_delay > 0)
+                .then(|| Duration::from_millis(config.search_debounce_delay)),
+            Page::ClipboardHistory
+            | Page::Settings
+            | Page::Keybindings
+            | Page::CalculationHistory => None,
             Page::FileSearch | Page::EmojiSearch => {
                 Some(Duration::from_millis(config.debounce_delay))
             }
@@ -10,16 +10,21 @@ use iced::{
     widget::{
         Button, Row, Text, container,
         image::{Handle, Viewer},
+        mouse_area,
         text::Wrapping,
     },
+    window::Id,
 };
 
 use crate::{
     app::{Message, Page, RUSTCAST_DESC_NAME},
     clipboard::ClipBoardContentType,
     commands::Function,
+    config::SkinTone,
+    i18n::tr,
+    path_completion,
     styles::{favourite_button_style, result_button_style, result_row_container_style},
-    utils::icns_data_to_handle,
+    utils::{icns_data_to_handle, time_ago},
 };
 
 /// The rustcast icns icons bytes
@@ -47,6 +52,33 @@ pub struct App {
     pub icons: Option<iced::widget::image::Handle>,
     pub display_name: String,
     pub search_name: String,
+    /// The version of an installed app
+    pub version: Option<String>,
+    /// When the app was last opened from rustcast, in seconds since the unix epoch
+    pub last_used: Option<u64>,
+    /// The results in this one, which → or Tab browses into
+    pub children: Option<Children>,
+}
+
+/// The results that a result has in it, like an extension's nested items or a folder's entries
+#[derive(Debug, Clone)]
+pub enum Children {
+    /// Results that are already known, like the ones an extension gave
+    Apps(Vec<App>),
+    /// The entries of a folder, which are read when it is browsed into
+    Folder(String),
+}
+
+impl Children {
+    /// The results, reading the folder's entries for folders
+    pub fn apps(&self) -> Vec<App> {
+        match self {
+            Children::Apps(apps) => apps.clone(),
+            Children::Folder(path) => {
+                path_completion::completions(&format!("{}/", path.trim_end_matches('/')))
+            }
+        }
+    }
 }
 
 impl PartialEq for App {
@@ -60,23 +92,52 @@ impl PartialEq for App {
 
 impl App {
     /// A vec of all the emojis as App structs
-    pub fn emoji_apps() -> Vec<App> {
+    ///
+    /// The search name of each emoji contains its unicode name, followed by its shortcodes (e.g.
+    /// "thumbsup" and "+1") so that they can be searched for as keywords
+    pub fn emoji_apps(skin_tone: SkinTone) -> Vec<App> {
         emojis::iter()
             .filter(|x| x.unicode_version() < emojis::UnicodeVersion::new(17, 13))
-            .map(|x| App {
-                ranking: 0,
-                icons: None,
-                display_name: x.to_string(),
-                search_name: x.name().to_string(),
-                open_command: AppCommand::Function(Function::CopyToClipboard(
-                    ClipBoardContentType::Text(x.to_string()),
-                )),
-                desc: x.name().to_string(),
-            })
+            .map(|x| App::from_emoji(x, skin_tone))
             .collect()
     }
+
+    /// All the emojis in a category, in the order that they are defined in by unicode
+    pub fn emoji_category_apps(category: emojis::Group, skin_tone: SkinTone) -> Vec<App> {
+        emojis::iter()
+            .filter(|x| x.group() == category)
+            .filter(|x| x.unicode_version() < emojis::UnicodeVersion::new(17, 13))
+            .map(|x| App::from_emoji(x, skin_tone))
+            .collect()
+    }
+
+    pub fn from_emoji(emoji: &'static emojis::Emoji, skin_tone: SkinTone) -> App {
+        let toned = skin_tone.apply(emoji);
+        let search_name = std::iter::once(emoji.name())
+            .chain(emoji.shortcodes())
+            .collect::<Vec<&str>>()
+            .join(" ");
+
+        App {
+            ranking: 0,
+            icons: None,
+            display_name: toned.to_string(),
+            search_name,
+            open_command: AppCommand::Function(Function::CopyToClipboard(
+                ClipBoardContentType::Text(toned.to_string()),
+            )),
+            desc: emoji.name().to_string(),
+            version: None,
+            last_used: None,
+            children: None,
+        }
+    }
+
     /// This returns the basic apps that rustcast has, such as quiting rustcast and opening preferences
-    pub fn basic_apps() -> Vec<App> {
+    ///
+    /// `start_at_login` is whether rustcast currently starts at login, and `keeping_awake` is
+    /// whether sleep is being prevented, which are shown in the results that toggle them
+    pub fn basic_apps(start_at_login: bool, keeping_awake: bool) -> Vec<App> {
         let app_version = option_env!("APP_VERSION").unwrap_or("Unknown Version");
 
         let icons = icns_data_to_handle(ICNS_ICON.to_vec());
@@ -89,7 +150,7 @@ impl App {
                 .ok()
                 .map(|img| Handle::from_rgba(img.width(), img.height(), img.into_bytes()));
 
-        vec![
+        let mut apps = vec![
             App {
                 ranking: 0,
                 open_command: AppCommand::Function(Function::OpenWebsite(
@@ -99,98 +160,339 @@ impl App {
                 desc: "Easter Egg".to_string(),
                 display_name: "Ferris Plushies".to_string(),
                 search_name: "ferris.rs".to_string(),
+                version: None,
+                last_used: None,
+                children: None,
             },
             App {
                 ranking: 0,
                 open_command: AppCommand::Function(Function::Quit),
                 desc: RUSTCAST_DESC_NAME.to_string(),
                 icons: icons.clone(),
-                display_name: "Quit RustCast".to_string(),
+                display_name: tr!("quit-rustcast"),
                 search_name: "quit".to_string(),
+                version: None,
+                last_used: None,
+                children: None,
             },
             App {
                 ranking: 0,
                 open_command: AppCommand::Function(Function::QuitAllApps),
                 desc: RUSTCAST_DESC_NAME.to_string(),
                 icons: icons.clone(),
-                display_name: "Quit All Apps".to_string(),
+                display_name: tr!("quit-all-apps"),
                 search_name: "quit all apps".to_string(),
+                version: None,
+                last_used: None,
+                children: None,
+            },
+            App {
+                ranking: 0,
+                open_command: AppCommand::Function(Function::Notify(
+                    "RustCast".to_string(),
+                    "Notifications are working".to_string(),
+                )),
+                desc: RUSTCAST_DESC_NAME.to_string(),
+                icons: icons.clone(),
+                display_name: tr!("test-notification"),
+                search_name: "test notification".to_string(),
+                version: None,
+                last_used: None,
+                children: None,
             },
             App {
                 ranking: 0,
                 open_command: AppCommand::Message(Message::SwitchToPage(Page::Settings)),
                 desc: RUSTCAST_DESC_NAME.to_string(),
                 icons: icons.clone(),
-                display_name: "Open RustCast Preferences".to_string(),
+                display_name: tr!("open-preferences"),
                 search_name: "settings".to_string(),
+                version: None,
+                last_used: None,
+                children: None,
             },
             App {
                 ranking: 0,
                 open_command: AppCommand::Message(Message::SwitchToPage(Page::EmojiSearch)),
                 desc: RUSTCAST_DESC_NAME.to_string(),
                 icons: icons.clone(),
-                display_name: "Search for an Emoji".to_string(),
+                display_name: tr!("emoji-search"),
                 search_name: "emoji".to_string(),
+                version: None,
+                last_used: None,
+                children: None,
             },
             App {
                 ranking: 0,
                 open_command: AppCommand::Message(Message::SwitchToPage(Page::ClipboardHistory)),
                 desc: RUSTCAST_DESC_NAME.to_string(),
                 icons: icons.clone(),
-                display_name: "Clipboard History".to_string(),
+                display_name: tr!("clipboard-history"),
                 search_name: "clipboard".to_string(),
+                version: None,
+                last_used: None,
+                children: None,
             },
             App {
                 ranking: 0,
                 open_command: AppCommand::Message(Message::SwitchToPage(Page::FileSearch)),
                 desc: RUSTCAST_DESC_NAME.to_string(),
                 icons: icons.clone(),
-                display_name: "Search for a file".to_string(),
+                display_name: tr!("file-search"),
                 search_name: "file search".to_string(),
+                version: None,
+                last_used: None,
+                children: None,
+            },
+            App {
+                ranking: 0,
+                open_command: AppCommand::Message(Message::SwitchToPage(Page::CalculationHistory)),
+                desc: RUSTCAST_DESC_NAME.to_string(),
+                icons: icons.clone(),
+                display_name: tr!("calculation-history"),
+                search_name: "calc".to_string(),
+                version: None,
+                last_used: None,
+                children: None,
+            },
+            App {
+                ranking: 0,
+                open_command: AppCommand::Message(Message::ClearQueryHistory),
+                desc: RUSTCAST_DESC_NAME.to_string(),
+                icons: icons.clone(),
+                display_name: tr!("clear-search-history"),
+                search_name: "clear search history".to_string(),
+                version: None,
+                last_used: None,
+                children: None,
+            },
+            App {
+                ranking: 0,
+                open_command: AppCommand::Function(Function::ToggleWifi),
+                desc: RUSTCAST_DESC_NAME.to_string(),
+                icons: icons.clone(),
+                display_name: tr!("toggle-wifi"),
+                search_name: "wifi".to_string(),
+                version: None,
+                last_used: None,
+                children: None,
+            },
+            App {
+                ranking: 0,
+                open_command: AppCommand::Function(Function::ToggleBluetooth),
+                desc: RUSTCAST_DESC_NAME.to_string(),
+                icons: icons.clone(),
+                display_name: tr!("toggle-bluetooth"),
+                search_name: "bluetooth".to_string(),
+                version: None,
+                last_used: None,
+                children: None,
+            },
+            App {
+                ranking: 0,
+                open_command: AppCommand::Function(Function::ToggleDarkMode),
+                desc: RUSTCAST_DESC_NAME.to_string(),
+                icons: icons.clone(),
+                display_name: tr!("toggle-dark-mode"),
+                search_name: "dark mode".to_string(),
+                version: None,
+                last_used: None,
+                children: None,
+            },
+            App {
+                ranking: 0,
+                open_command: AppCommand::Function(Function::OpenLogFile),
+                desc: RUSTCAST_DESC_NAME.to_string(),
+                icons: icons.clone(),
+                display_name: tr!("open-log-file"),
+                search_name: "log file".to_string(),
+                version: None,
+                last_used: None,
+                children: None,
             },
             App {
                 ranking: 0,
                 open_command: AppCommand::Message(Message::ReloadConfig),
                 desc: RUSTCAST_DESC_NAME.to_string(),
                 icons: icons.clone(),
-                display_name: "Reload RustCast".to_string(),
+                display_name: tr!("reload-rustcast"),
                 search_name: "refresh".to_string(),
+                version: None,
+                last_used: None,
+                children: None,
             },
             App {
                 ranking: 0,
                 open_command: AppCommand::Display,
                 desc: RUSTCAST_DESC_NAME.to_string(),
                 icons: icons.clone(),
-                display_name: format!("Current RustCast Version: {app_version}"),
+                display_name: tr!("current-version", version = app_version),
                 search_name: "version".to_string(),
+                version: None,
+                last_used: None,
+                children: None,
             },
-        ]
+            App::start_at_login_app(start_at_login),
+            App::keep_awake_app(keeping_awake),
+        ];
+
+        // Night Shift and True Tone are only on macOS
+        if cfg!(target_os = "macos") {
+            apps.extend([
+                App {
+                    ranking: 0,
+                    open_command: AppCommand::Function(Function::ToggleNightShift),
+                    desc: RUSTCAST_DESC_NAME.to_string(),
+                    icons: icons.clone(),
+                    display_name: tr!("toggle-night-shift"),
+                    search_name: "night shift".to_string(),
+                    version: None,
+                    last_used: None,
+                    children: None,
+                },
+                App {
+                    ranking: 0,
+                    open_command: AppCommand::Function(Function::ToggleTrueTone),
+                    desc: RUSTCAST_DESC_NAME.to_string(),
+                    icons,
+                    display_name: tr!("toggle-true-tone"),
+                    search_name: "true tone".to_string(),
+                    version: None,
+                    last_used: None,
+                    children: None,
+                },
+            ]);
+        }
+        apps
+    }
+
+    /// The command that turns preventing sleep on or off, with whether it is on in its name
+    pub fn keep_awake_app(enabled: bool) -> App {
+        App {
+            ranking: 0,
+            open_command: AppCommand::Message(Message::ToggleKeepAwake),
+            desc: RUSTCAST_DESC_NAME.to_string(),
+            icons: icns_data_to_handle(ICNS_ICON.to_vec()),
+            display_name: if enabled {
+                tr!("prevent-sleep-on")
+            } else {
+                tr!("prevent-sleep-off")
+            },
+            search_name: "prevent sleep".to_string(),
+            version: None,
+            last_used: None,
+            children: None,
+        }
+    }
+
+    /// The command that turns starting rustcast at login on or off, with whether it is on in its
+    /// name
+    pub fn start_at_login_app(enabled: bool) -> App {
+        App {
+            ranking: 0,
+            open_command: AppCommand::Message(Message::ToggleAutoStartup(!enabled)),
+            desc: RUSTCAST_DESC_NAME.to_string(),
+            icons: icns_data_to_handle(ICNS_ICON.to_vec()),
+            display_name: if enabled {
+                tr!("start-at-login-on")
+            } else {
+                tr!("start-at-login-off")
+            },
+            search_name: "start rustcast at login".to_string(),
+            version: None,
+            last_used: None,
+            children: None,
+        }
+    }
+
+    /// An app like the ones found in `/Applications`, for the tests
+    #[cfg(test)]
+    pub fn test_app(name: &str) -> App {
+        App {
+            ranking: 0,
+            open_command: AppCommand::Function(Function::OpenApp(format!(
+                "/Applications/{name}.app"
+            ))),
+            desc: "Application".to_string(),
+            icons: None,
+            display_name: name.to_string(),
+            search_name: name.to_lowercase(),
+            version: None,
+            last_used: None,
+            children: None,
+        }
+    }
+
+    /// Whether the result is a calculation or a shell command, whose names are shown in the
+    /// monospace font
+    fn uses_monospace(&self) -> bool {
+        matches!(
+            &self.open_command,
+            AppCommand::Function(
+                Function::Calculate(_) | Function::RunShellCommand(_) | Function::RunInTerminal(_)
+            )
+        ) || self.desc == "Calculation"
+    }
+
+    /// Whether the name or the description contain every word of a filter, ignoring case
+    pub fn matches_words(&self, filter: &str) -> bool {
+        let text = format!("{} {}", self.display_name, self.desc).to_lowercase();
+        filter
+            .split_whitespace()
+            .all(|x| text.contains(&x.to_lowercase()))
+    }
+
+    /// The description shown under the name, followed by the app's version and when it was last
+    /// used, if they are known
+    pub fn subtitle(&self) -> String {
+        let mut subtitle = self.desc.clone();
+        if let Some(version) = &self.version {
+            subtitle.push_str(&format!(" · {version}"));
+        }
+        if let Some(last_used) = self.last_used {
+            subtitle.push_str(&format!(" · Used {}", time_ago(last_used)));
+        }
+        subtitle
     }
 
     /// This renders the app into an iced element, allowing it to be displayed in the search results
+    ///
+    /// Like the arrow keys, hovering over or clicking the result focuses it, and double clicking it
+    /// opens it like Enter does, while right clicking it shows its actions in `window` (if it is
+    /// given). Pressing it and moving the pointer out of it drags it out of the window. When `show_index` is set, the first nine results show the number that quick opens
+    /// them, and `running` marks apps that are running with a dot
     pub fn render(
         self,
         theme: crate::config::Theme,
         id_num: u32,
         focussed_id: u32,
-        on_press: Option<Message>,
+        window: Option<Id>,
+        show_index: bool,
+        running: bool,
     ) -> iced::Element<'static, Message> {
         let focused = focussed_id == id_num;
+        let display_mode = theme.display_mode;
+        let font = if self.uses_monospace() {
+            theme.monospace_font()
+        } else {
+            theme.font()
+        };
 
         // Title + subtitle (Raycast style)
+        let subtitle = self.subtitle();
         let text_block = iced::widget::Column::new()
             .spacing(2)
             .push(
                 Text::new(self.display_name)
-                    .font(theme.font())
-                    .size(16)
+                    .font(font)
+                    .size(theme.title_size())
                     .wrapping(Wrapping::None)
                     .color(theme.text_color(1.0)),
             )
             .push(
-                Text::new(self.desc)
+                Text::new(subtitle)
                     .font(theme.font())
-                    .size(13)
+                    .size(theme.desc_size())
                     .color(theme.text_color(0.55)),
             );
 
@@ -198,19 +500,49 @@ impl App {
             .align_y(Alignment::Center)
             .width(Fill)
             .spacing(10)
-            .height(50);
+            .height(display_mode.row_height());
 
         if theme.show_icons
             && let Some(icon) = &self.icons
         {
             row = row.push(
-                container(Viewer::new(icon).height(40).width(40))
-                    .width(40)
-                    .height(40),
+                container(
+                    Viewer::new(icon)
+                        .height(display_mode.icon_size())
+                        .width(display_mode.icon_size()),
+                )
+                .width(display_mode.icon_size())
+                .height(display_mode.icon_size()),
             );
         }
         row = row.push(container(text_block).width(Fill));
 
+        if running {
+            row = row.push(
+                Text::new("●")
+                    .size(theme.desc_size() * 0.6)
+                    .color(theme.text_color(0.55)),
+            );
+        }
+
+        if self.children.is_some() {
+            row = row.push(
+                Text::new("›")
+                    .font(theme.font())
+                    .size(theme.title_size())
+                    .color(theme.text_color(0.55)),
+            );
+        }
+
+        if show_index && id_num < 9 {
+            row = row.push(
+                Text::new((id_num + 1).to_string())
+                    .font(theme.font())
+                    .size(theme.desc_size())
+                    .color(theme.text_color(0.55)),
+            );
+        }
+
         let name = self.search_name.clone();
         let theme_clone = theme.clone();
         let is_favourite = self.ranking == -1;
@@ -221,26 +553,32 @@ impl App {
                 .style(move |_, status| favourite_button_style(&theme_clone, status, is_favourite)),
         );
 
-        let msg = on_press.or(match self.open_command.clone() {
-            AppCommand::Function(func) => Some(Message::RunFunction(func)),
-            AppCommand::Message(msg) => Some(msg),
-            AppCommand::Display => None,
-        });
-
         let theme_clone = theme.clone();
 
+        // The button has no message, so that the clicks go to the mouse area around it
         let content = Button::new(row)
-            .on_press_maybe(msg)
             .style(move |_, _| result_button_style(&theme_clone))
             .width(Fill)
             .padding(0)
-            .height(50);
+            .height(display_mode.row_height());
 
-        container(content)
-            .id(format!("result-{}", id_num))
-            .style(move |_| result_row_container_style(&theme, focused))
-            .padding(8)
-            .width(Fill)
-            .into()
+        let row = mouse_area(
+            container(content)
+                .id(format!("result-{}", id_num))
+                .style(move |_| result_row_container_style(&theme, focused))
+                .padding(8)
+                .width(Fill),
+        )
+        .on_press(Message::PressResult(id_num))
+        .on_double_click(Message::OpenResult(id_num))
+        .on_enter(Message::HoverResult(id_num))
+        .on_exit(Message::LeaveResult(id_num));
+
+        match window {
+            Some(window) => row
+                .on_right_press(Message::ShowActions(window, Some(id_num)))
+                .into(),
+            None => row.into(),
+        }
     }
 }
@@ -0,0 +1,369 @@
+//! The keybindings that rustcast responds to while its window is open
+//!
+//! These are what the keyboard subscription matches key presses against, and what the shortcuts
+//! page and the footer hints are generated from
+use iced::{
+    event,
+    keyboard::{Key, Modifiers, key::Named},
+    window::Id,
+};
+
+use crate::app::{ArrowKey, Message, Page};
+
+/// A key press and the message it sends
+pub struct Keybinding {
+    pub key: Key<&'static str>,
+    /// The modifiers that have to be held, or [`None`] if the modifiers don't matter
+    pub modifiers: Option<Modifiers>,
+    pub description: &'static str,
+    /// The short text shown in the footer, for the bindings that are hinted there
+    pub hint: Option<&'static str>,
+    /// Whether the binding still works when the search bar has already handled the key press
+    when_captured: bool,
+    message: fn(Id) -> Message,
+}
+
+/// Every keybinding, in the order they are shown in the shortcuts page
+pub const KEYBINDINGS: &[Keybinding] = &[
+    Keybinding {
+        key: Key::Named(Named::Enter),
+        modifiers: None,
+        description: "Open the focused result",
+        hint: Some("Open"),
+        when_captured: false,
+        message: |_| Message::OpenFocused,
+    },
+    Keybinding {
+        key: Key::Named(Named::Enter),
+        modifiers: Some(Modifiers::COMMAND),
+        description: "Show the focused app or file in the file manager",
+        hint: None,
+        when_captured: false,
+        message: |_| Message::RevealFocused,
+    },
+    Keybinding {
+        key: Key::Character("o"),
+        modifiers: Some(Modifiers::COMMAND),
+        description: "Open the focused file or website with another app",
+        hint: None,
+        when_captured: false,
+        message: Message::OpenWithFocused,
+    },
+    Keybinding {
+        key: Key::Character("q"),
+        modifiers: Some(Modifiers::COMMAND),
+        description: "Quit the focused app, if it is running",
+        hint: None,
+        when_captured: false,
+        message: |_| Message::QuitFocused(false),
+    },
+    Keybinding {
+        key: Key::Character("k"),
+        modifiers: Some(Modifiers::COMMAND),
+        description: "Show the actions for the focused result, like right clicking it",
+        hint: None,
+        when_captured: false,
+        message: |id| Message::ShowActions(id, None),
+    },
+    Keybinding {
+        key: Key::Character("q"),
+        modifiers: Some(Modifiers::COMMAND.union(Modifiers::ALT)),
+        description: "Force quit the focused app, if it is running",
+        hint: None,
+        when_captured: false,
+        message: |_| Message::QuitFocused(true),
+    },
+    Keybinding {
+        key: Key::Character("u"),
+        modifiers: Some(Modifiers::COMMAND.union(Modifiers::SHIFT)),
+        description: "Uninstall the focused app, after confirming it with Enter",
+        hint: None,
+        when_captured: false,
+        message: |_| Message::UninstallFocused,
+    },
+    Keybinding {
+        key: Key::Character("h"),
+        modifiers: Some(Modifiers::COMMAND.union(Modifiers::SHIFT)),
+        description: "Hide the focused result, by adding it to `ignore_apps`",
+        hint: None,
+        when_captured: false,
+        message: |_| Message::HideFocusedResult,
+    },
+    Keybinding {
+        key: Key::Named(Named::Tab),
+        modifiers: Some(Modifiers::empty()),
+        description: "Complete the search to the focused result's name (or calculation), or \
+                      browse into it",
+        hint: None,
+        when_captured: false,
+        message: Message::CompleteQuery,
+    },
+    Keybinding {
+        key: Key::Named(Named::Escape),
+        modifiers: None,
+        description: "Clear the search, go back, or close rustcast",
+        hint: None,
+        when_captured: true,
+        message: Message::EscKeyPressed,
+    },
+    Keybinding {
+        key: Key::Named(Named::ArrowUp),
+        modifiers: None,
+        description: "Focus the previous result",
+        hint: None,
+        when_captured: false,
+        message: |_| Message::ChangeFocus(ArrowKey::Up, 1),
+    },
+    Keybinding {
+        key: Key::Named(Named::ArrowDown),
+        modifiers: None,
+        description: "Focus the next result",
+        hint: None,
+        when_captured: false,
+        message: |_| Message::ChangeFocus(ArrowKey::Down, 1),
+    },
+    Keybinding {
+        key: Key::Character("p"),
+        modifiers: Some(Modifiers::CTRL),
+        description: "Focus the previous result",
+        hint: None,
+        when_captured: false,
+        message: |_| Message::ChangeFocus(ArrowKey::Up, 1),
+    },
+    Keybinding {
+        key: Key::Character("n"),
+        modifiers: Some(Modifiers::CTRL),
+        description: "Focus the next result",
+        hint: None,
+        when_captured: false,
+        message: |_| Message::ChangeFocus(ArrowKey::Down, 1),
+    },
+    Keybinding {
+        key: Key::Named(Named::ArrowLeft),
+        modifiers: None,
+        description: "Focus the previous emoji",
+        hint: None,
+        when_captured: false,
+        message: |_| Message::ChangeFocus(ArrowKey::Left, 1),
+    },
+    Keybinding {
+        key: Key::Named(Named::ArrowRight),
+        modifiers: None,
+        description: "Focus the next emoji",
+        hint: None,
+        when_captured: false,
+        message: |_| Message::ChangeFocus(ArrowKey::Right, 1),
+    },
+    Keybinding {
+        key: Key::Named(Named::ArrowLeft),
+        modifiers: None,
+        description: "Go back out of the result that was browsed into (when the search is empty)",
+        hint: None,
+        when_captured: true,
+        message: Message::BrowseBack,
+    },
+    Keybinding {
+        key: Key::Named(Named::ArrowRight),
+        modifiers: None,
+        description: "Browse into the focused result, if it has results in it (›)",
+        hint: None,
+        when_captured: true,
+        message: Message::BrowseInto,
+    },
+    Keybinding {
+        key: Key::Character("1"),
+        modifiers: Some(Modifiers::COMMAND),
+        description: "Open the result with that number",
+        hint: Some("Quick open"),
+        when_captured: false,
+        message: |_| Message::OpenResult(0),
+    },
+    Keybinding {
+        key: Key::Character("2"),
+        modifiers: Some(Modifiers::COMMAND),
+        description: "Open the result with that number",
+        hint: Some("Quick open"),
+        when_captured: false,
+        message: |_| Message::OpenResult(1),
+    },
+    Keybinding {
+        key: Key::Character("3"),
+        modifiers: Some(Modifiers::COMMAND),
+        description: "Open the result with that number",
+        hint: Some("Quick open"),
+        when_captured: false,
+        message: |_| Message::OpenResult(2),
+    },
+    Keybinding {
+        key: Key::Character("4"),
+        modifiers: Some(Modifiers::COMMAND),
+        description: "Open the result with that number",
+        hint: Some("Quick open"),
+        when_captured: false,
+        message: |_| Message::OpenResult(3),
+    },
+    Keybinding {
+        key: Key::Character("5"),
+        modifiers: Some(Modifiers::COMMAND),
+        description: "Open the result with that number",
+        hint: Some("Quick open"),
+        when_captured: false,
+        message: |_| Message::OpenResult(4),
+    },
+    Keybinding {
+        key: Key::Character("6"),
+        modifiers: Some(Modifiers::COMMAND),
+        description: "Open the result with that number",
+        hint: Some("Quick open"),
+        when_captured: false,
+        message: |_| Message::OpenResult(5),
+    },
+    Keybinding {
+        key: Key::Character("7"),
+        modifiers: Some(Modifiers::COMMAND),
+        description: "Open the result with that number",
+        hint: Some("Quick open"),
+        when_captured: false,
+        message: |_| Message::OpenResult(6),
+    },
+    Keybinding {
+        key: Key::Character("8"),
+        modifiers: Some(Modifiers::COMMAND),
+        description: "Open the result with that number",
+        hint: Some("Quick open"),
+        when_captured: false,
+        message: |_| Message::OpenResult(7),
+    },
+    Keybinding {
+        key: Key::Character("9"),
+        modifiers: Some(Modifiers::COMMAND),
+        description: "Open the result with that number",
+        hint: Some("Quick open"),
+        when_captured: false,
+        message: |_| Message::OpenResult(8),
+    },
+    Keybinding {
+        key: Key::Character("p"),
+        modifiers: Some(Modifiers::COMMAND),
+        description: "Paste the focused clipboard item",
+        hint: None,
+        when_captured: false,
+        message: |_| Message::PasteFocused(false),
+    },
+    Keybinding {
+        key: Key::Character("p"),
+        modifiers: Some(Modifiers::COMMAND.union(Modifiers::SHIFT)),
+        description: "Paste the focused clipboard item as plain text",
+        hint: None,
+        when_captured: false,
+        message: |_| Message::PasteFocused(true),
+    },
+    Keybinding {
+        key: Key::Character(","),
+        modifiers: Some(Modifiers::COMMAND),
+        description: "Open the settings",
+        hint: None,
+        when_captured: true,
+        message: |_| Message::SwitchToPage(Page::Settings),
+    },
+    Keybinding {
+        key: Key::Character("r"),
+        modifiers: Some(Modifiers::COMMAND),
+        description: "Reload the config",
+        hint: None,
+        when_captured: false,
+        message: |_| Message::ReloadConfig,
+    },
+    Keybinding {
+        key: Key::Character("?"),
+        modifiers: None,
+        description: "Show these shortcuts (when the search bar is empty)",
+        hint: Some("Shortcuts"),
+        when_captured: false,
+        message: |_| Message::SwitchToPage(Page::Keybindings),
+    },
+];
+
+impl Keybinding {
+    fn matches(&self, key: &Key, modifiers: Modifiers) -> bool {
+        let key_matches = match (&self.key, key.as_ref()) {
+            (Key::Character(a), Key::Character(b)) => a.eq_ignore_ascii_case(b),
+            (a, b) => *a == b,
+        };
+
+        key_matches && self.modifiers.is_none_or(|x| x == modifiers)
+    }
+
+    /// The keys to press, such as `⌘⇧P`
+    pub fn label(&self) -> String {
+        self.modifiers_label() + &self.key_label()
+    }
+
+    fn modifiers_label(&self) -> String {
+        let mut label = String::new();
+        if let Some(modifiers) = self.modifiers {
+            for (held, symbol) in [
+                (modifiers.control(), "⌃"),
+                (modifiers.alt(), "⌥"),
+                (modifiers.shift(), "⇧"),
+                (modifiers.logo(), "⌘"),
+            ] {
+                if held {
+                    label.push_str(symbol);
+                }
+            }
+        }
+        label
+    }
+
+    fn key_label(&self) -> String {
+        match &self.key {
+            Key::Named(Named::Enter) => "↵".to_string(),
+            Key::Named(Named::Escape) => "Esc".to_string(),
+            Key::Named(Named::Tab) => "⇥".to_string(),
+            Key::Named(Named::ArrowUp) => "↑".to_string(),
+            Key::Named(Named::ArrowDown) => "↓".to_string(),
+            Key::Named(Named::ArrowLeft) => "←".to_string(),
+            Key::Named(Named::ArrowRight) => "→".to_string(),
+            Key::Named(named) => format!("{named:?}"),
+            Key::Character(chr) => chr.to_uppercase(),
+            Key::Unidentified => String::new(),
+        }
+    }
+}
+
+/// The keybindings, with runs of bindings that do the same thing merged into one with a label
+/// like `⌘1–9`
+pub fn grouped_keybindings() -> Vec<(String, &'static Keybinding)> {
+    KEYBINDINGS
+        .chunk_by(|a, b| a.description == b.description && a.modifiers == b.modifiers)
+        .map(|group| {
+            let first = &group[0];
+            let label = match group.last() {
+                Some(last) if group.len() > 1 => {
+                    format!("{}–{}", first.label(), last.key_label())
+                }
+                _ => first.label(),
+            };
+            (label, first)
+        })
+        .collect()
+}
+
+/// The message for a key press, if it matches one of the keybindings
+///
+/// Bindings that need certain modifiers are picked over ones that don't care about them, like
+/// `⌘↵` over `↵`
+pub fn message_for(
+    key: &Key,
+    modifiers: Modifiers,
+    status: event::Status,
+    id: Id,
+) -> Option<Message> {
+    KEYBINDINGS
+        .iter()
+        .filter(|x| x.when_captured || status == event::Status::Ignored)
+        .filter(|x| x.matches(key, modifiers))
+        .min_by_key(|x| x.modifiers.is_none())
+        .map(|x| (x.message)(id))
+}
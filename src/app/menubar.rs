@@ -7,7 +7,7 @@ use log::info;
 use tray_icon::{
     Icon, TrayIcon, TrayIconBuilder,
     menu::{
-        AboutMetadataBuilder, Icon as Ico, IsMenuItem, Menu, MenuEvent, MenuItem,
+        AboutMetadataBuilder, CheckMenuItem, Icon as Ico, IsMenuItem, Menu, MenuEvent, MenuItem,
         PredefinedMenuItem, Submenu,
     },
 };
@@ -15,7 +15,7 @@ use tray_icon::{
 use crate::{
     app::{Message, tile::ExtSender},
     config::Config,
-    platform::macos::launching::Shortcut,
+    platform::Shortcut,
     utils::open_url,
 };
 
@@ -26,7 +26,7 @@ use tokio::runtime::Runtime;
 /// This create a new menubar icon for the app
 pub fn menu_icon(config: Config, sender: ExtSender) -> TrayIcon {
     let builder = TrayIconBuilder::new();
-    let menu = menu_builder(config, sender, false);
+    let menu = menu_builder(config, sender, false, false);
 
     let image = get_image();
     let icon = Icon::from_rgba(image.as_bytes().to_vec(), image.width(), image.height()).unwrap();
@@ -38,7 +38,14 @@ pub fn menu_icon(config: Config, sender: ExtSender) -> TrayIcon {
         .unwrap()
 }
 
-pub fn menu_builder(config: Config, sender: ExtSender, update_item: bool) -> Menu {
+/// `keeping_awake` is whether sleep is being prevented, which the "Prevent Sleep" item is checked
+/// for
+pub fn menu_builder(
+    config: Config,
+    sender: ExtSender,
+    update_item: bool,
+    keeping_awake: bool,
+) -> Menu {
     let shortcut =
         Shortcut::parse(&config.toggle_hotkey).unwrap_or(Shortcut::parse("opt+space").unwrap());
 
@@ -67,6 +74,7 @@ pub fn menu_builder(config: Config, sender: ExtSender, update_item: bool) -> Men
         &refresh_item(),
         &open_item(),
         &mode_item(modes),
+        &keep_awake_item(keeping_awake),
         &PredefinedMenuItem::separator(),
         &open_issue_item(),
         &get_help_item(),
@@ -102,6 +110,9 @@ fn init_event_handler(sender: ExtSender, shortcut: Shortcut) {
                     sender.clone().try_send(Message::ReloadConfig).unwrap();
                 });
             }
+            "keep_awake" => {
+                runtime.spawn(async move { sender.clone().try_send(Message::ToggleKeepAwake) });
+            }
             "hide_tray_icon" => {
                 runtime
                     .spawn(async move { sender.clone().try_send(Message::HideTrayIcon).unwrap() });
@@ -164,6 +175,10 @@ fn hide_tray_icon() -> MenuItem {
     MenuItem::with_id("hide_tray_icon", "Hide Tray Icon", true, None)
 }
 
+fn keep_awake_item(keeping_awake: bool) -> CheckMenuItem {
+    CheckMenuItem::with_id("keep_awake", "Prevent Sleep", true, keeping_awake, None)
+}
+
 fn mode_item(modes: HashMap<String, String>) -> Submenu {
     let owned_items: Vec<MenuItem> = modes
         .keys()
@@ -3,5 +3,6 @@
 pub mod clipboard;
 pub mod common;
 pub mod emoji;
+pub mod keybindings;
 pub mod prelude;
 pub mod settings;
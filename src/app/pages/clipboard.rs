@@ -13,8 +13,8 @@ use iced::{
 
 use crate::{
     app::{Editable, ToApp, pages::prelude::*},
-    clipboard::ClipBoardContentType,
-    styles::{delete_button_style, settings_text_input_item_style},
+    clipboard::{ClipBoardContentType, ClipboardEntry},
+    styles::{delete_button_style, settings_add_button_style, settings_text_input_item_style},
 };
 
 /// The clipboard view
@@ -22,14 +22,18 @@ use crate::{
 /// Takes:
 /// - the clipboard content to render,
 /// - the id of which element is focussed,
-/// - and the [`Theme`]
+/// - the [`Theme`]
+/// - the width of the window
+/// - and whether to show the quick open numbers next to the entries
 ///
 /// Returns:
 /// - the iced Element to render
 pub fn clipboard_view(
-    clipboard_content: Vec<ClipBoardContentType>,
+    clipboard_content: Vec<ClipboardEntry>,
     focussed_id: u32,
     theme: Theme,
+    window_width: f32,
+    show_indices: bool,
 ) -> Element<'static, Message> {
     let theme_clone = theme.clone();
     let theme_clone_2 = theme.clone();
@@ -51,18 +55,23 @@ pub fn clipboard_view(
 
     let viewport_content: Element<'static, Message> =
         match clipboard_content.get(focussed_id as usize) {
-            Some(content) => viewport_content(content, &theme),
+            Some(entry) => viewport_content(entry, &theme),
             None => Text::new("").into(),
         };
     container(Row::from_iter([
         container(
             Scrollable::with_direction(
-                Column::from_iter(clipboard_content.iter().enumerate().map(|(i, content)| {
-                    content
-                        .to_app()
-                        .render(theme.clone(), i as u32, focussed_id, None)
+                Column::from_iter(clipboard_content.iter().enumerate().map(|(i, entry)| {
+                    entry.to_app().render(
+                        theme.clone(),
+                        i as u32,
+                        focussed_id,
+                        None,
+                        show_indices,
+                        false,
+                    )
                 }))
-                .width(WINDOW_WIDTH / 3.),
+                .width(window_width / 3.),
                 Direction::Vertical(Scrollbar::hidden()),
             )
             .id("results"),
@@ -74,14 +83,15 @@ pub fn clipboard_view(
             .height(10000)
             .padding(10)
             .style(move |_| result_row_container_style(&theme_clone, false))
-            .width((WINDOW_WIDTH / 3.) * 2.)
+            .width((window_width / 3.) * 2.)
             .into(),
     ]))
     .height(280)
     .into()
 }
 
-fn viewport_content(content: &ClipBoardContentType, theme: &Theme) -> Element<'static, Message> {
+fn viewport_content(entry: &ClipboardEntry, theme: &Theme) -> Element<'static, Message> {
+    let content = &entry.content;
     let viewer: Element<'static, Message> = match content {
         ClipBoardContentType::Text(txt) => Scrollable::with_direction(
             container(
@@ -131,21 +141,70 @@ fn viewport_content(content: &ClipBoardContentType, theme: &Theme) -> Element<'s
 
     let theme_clone = theme.clone();
     let theme_clone_2 = theme.clone();
+    let theme_clone_3 = theme.clone();
+    let theme_clone_4 = theme.clone();
+    let theme_clone_5 = theme.clone();
+    let theme_clone_6 = theme.clone();
+
+    // Images have no formatting to strip, so they can only be pasted as they are, but the text in
+    // them can be extracted
+    let paste_plain_button = match content {
+        ClipBoardContentType::Text(_) => Button::new("Paste as text")
+            .on_press(Message::PasteClipboardItem(content.to_owned(), true))
+            .style(move |_, _| settings_add_button_style(&theme_clone_5))
+            .into(),
+        ClipBoardContentType::Image(_) => Button::new("Extract text")
+            .on_press(Message::ExtractClipboardText(content.to_owned()))
+            .style(move |_, _| settings_add_button_style(&theme_clone_5))
+            .into(),
+    };
+    let transform_button = match content {
+        ClipBoardContentType::Text(_) => Some(
+            Button::new("Transform")
+                .on_press(Message::TransformClipboardItem(content.to_owned()))
+                .style(move |_, _| settings_add_button_style(&theme_clone_6))
+                .into(),
+        ),
+        ClipBoardContentType::Image(_) => None,
+    };
+
     Column::from_iter([
         viewer,
         container(
-            Row::from_iter([
-                Button::new("Delete")
-                    .on_press(Message::EditClipboardHistory(Editable::Delete(
-                        content.to_owned(),
-                    )))
-                    .style(move |_, _| delete_button_style(&theme_clone))
-                    .into(),
-                Button::new("Clear")
-                    .on_press(Message::ClearClipboardHistory)
-                    .style(move |_, _| delete_button_style(&theme_clone_2))
-                    .into(),
-            ])
+            Row::from_iter(
+                [
+                    Some(
+                        Button::new("Paste")
+                            .on_press(Message::PasteClipboardItem(content.to_owned(), false))
+                            .style(move |_, _| settings_add_button_style(&theme_clone_4))
+                            .into(),
+                    ),
+                    Some(paste_plain_button),
+                    transform_button,
+                    Some(
+                        Button::new(if entry.pinned { "Unpin" } else { "Pin" })
+                            .on_press(Message::TogglePinClipboardItem(content.to_owned()))
+                            .style(move |_, _| settings_add_button_style(&theme_clone_3))
+                            .into(),
+                    ),
+                    Some(
+                        Button::new("Delete")
+                            .on_press(Message::EditClipboardHistory(Editable::Delete(
+                                content.to_owned(),
+                            )))
+                            .style(move |_, _| delete_button_style(&theme_clone))
+                            .into(),
+                    ),
+                    Some(
+                        Button::new("Clear")
+                            .on_press(Message::ClearClipboardHistory)
+                            .style(move |_, _| delete_button_style(&theme_clone_2))
+                            .into(),
+                    ),
+                ]
+                .into_iter()
+                .flatten(),
+            )
             .spacing(10),
         )
         .width(Length::Fill)
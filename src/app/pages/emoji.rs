@@ -1,4 +1,6 @@
 //! This contains the functions to use for rendering the emoji page
+use std::sync::Arc;
+
 use iced::{Border, Length::Fill, border::Radius, widget::tooltip};
 
 use crate::{
@@ -8,27 +10,66 @@ use crate::{
     styles::{glass_border, glass_surface, with_alpha},
 };
 
+/// The emoji categories that can be browsed when the query is empty, and the emoji used as the
+/// icon for their tab
+pub const EMOJI_CATEGORIES: [(emojis::Group, &str); 9] = [
+    (emojis::Group::SmileysAndEmotion, "😀"),
+    (emojis::Group::PeopleAndBody, "👋"),
+    (emojis::Group::AnimalsAndNature, "🐻"),
+    (emojis::Group::FoodAndDrink, "🍔"),
+    (emojis::Group::TravelAndPlaces, "✈️"),
+    (emojis::Group::Activities, "⚽"),
+    (emojis::Group::Objects, "💡"),
+    (emojis::Group::Symbols, "🔣"),
+    (emojis::Group::Flags, "🏁"),
+];
+
 /// The emoji pages element to render
 ///
 /// Takes:
 /// - the [`Theme`]
 /// - the emojis to render
 /// - the focussed id
+/// - the category being browsed, which is only [`Some`] when the query is empty
+/// - how many of the emojis are recently used ones, which are rendered in their own row
+/// - the width of the window
 pub fn emoji_page(
     tile_theme: Theme,
-    emojis: Vec<App>,
+    emojis: Vec<Arc<App>>,
     focussed_id: u32,
+    category: Option<emojis::Group>,
+    recent_count: usize,
+    window_width: f32,
 ) -> Element<'static, Message> {
-    let emoji_vec = emojis
+    let (recent, emojis) = emojis.split_at(recent_count.min(emojis.len()));
+    let mut emoji_vec = emojis
         .chunks(6)
         .map(|x| x.to_vec())
-        .collect::<Vec<Vec<App>>>();
+        .collect::<Vec<Vec<Arc<App>>>>();
+
+    if !recent.is_empty() {
+        emoji_vec.insert(0, recent.to_vec());
+    }
 
     let mut column = Vec::new();
 
+    if let Some(category) = category {
+        column.push(category_tabs(&tile_theme, category));
+    }
+
     let mut id_num = 0;
 
-    for emoji_row in emoji_vec {
+    for (row_num, emoji_row) in emoji_vec.into_iter().enumerate() {
+        if row_num == 0 && !recent.is_empty() {
+            column.push(
+                Text::new("Recently used")
+                    .font(tile_theme.font())
+                    .size(12)
+                    .color(tile_theme.text_color(0.7))
+                    .into(),
+            );
+        }
+
         let mut emoji_row_element = Row::new().spacing(10);
         for emoji in emoji_row {
             let theme_clone = tile_theme.clone();
@@ -53,7 +94,7 @@ pub fn emoji_page(
                         .width(70)
                         .height(70)
                         .on_press(Message::RunFunction(Function::CopyToClipboard(
-                            ClipBoardContentType::Text(emoji.display_name),
+                            ClipBoardContentType::Text(emoji.display_name.clone()),
                         )))
                         .style(move |_, _| emoji_button_style(&value)),
                 )
@@ -62,7 +103,7 @@ pub fn emoji_page(
                 .id(format!("result-{}", id_num))
                 .style(move |_| emoji_button_container_style(&theme_clone, focussed_id == id_num)),
                 container(
-                    Text::new(emoji.desc)
+                    Text::new(emoji.desc.clone())
                         .font(tile_theme.font())
                         .size(20)
                         .color(tile_theme.text_color(0.7)),
@@ -84,15 +125,12 @@ pub fn emoji_page(
     container(Column::from_vec(column).spacing(10))
         .padding(10)
         .style(move |_| container::Style {
-            background: Some(Background::Color(glass_surface(
-                tile_theme_clone.bg_color(),
-                false,
-            ))),
+            background: Some(Background::Color(glass_surface(&tile_theme_clone, false))),
             text_color: None,
             border: Border {
-                color: glass_border(tile_theme_clone.text_color(1.0), false),
+                color: glass_border(&tile_theme_clone, false),
                 width: 0.5,
-                radius: Radius::new(14.0).top(0),
+                radius: Radius::new(tile_theme_clone.advanced.window_radius).top(0),
             },
             shadow: iced::Shadow {
                 color: with_alpha(iced::Color::TRANSPARENT, 0.),
@@ -101,6 +139,32 @@ pub fn emoji_page(
             },
             snap: false,
         })
-        .center_x(WINDOW_WIDTH)
+        .center_x(window_width)
+        .into()
+}
+
+/// The row of tabs for browsing the emoji categories
+fn category_tabs(tile_theme: &Theme, selected: emojis::Group) -> Element<'static, Message> {
+    Row::from_iter(EMOJI_CATEGORIES.iter().map(|(group, icon)| {
+        let theme_clone = tile_theme.clone();
+        let is_selected = *group == selected;
+        Button::new(
+            Text::new(*icon)
+                .size(18)
+                .width(Length::Fill)
+                .align_x(Alignment::Center),
+        )
+        .width(40)
+        .on_press(Message::SetEmojiCategory(*group))
+        .style(move |_, _| {
+            let mut style = emoji_button_style(&theme_clone);
+            if !is_selected {
+                style.border.width = 0.;
+            }
+            style
+        })
         .into()
+    }))
+    .spacing(8)
+    .into()
 }
@@ -0,0 +1,84 @@
+//! The shortcuts page, listing every keybinding and global hotkey
+use iced::widget::{Scrollable, scrollable::Direction, scrollable::Scrollbar};
+
+use crate::{
+    app::{keybindings::grouped_keybindings, pages::prelude::*},
+    config::Config,
+};
+
+/// The shortcuts page
+///
+/// Takes the [`Config`] to list the global hotkeys from
+pub fn keybindings_page(config: &Config) -> Element<'static, Message> {
+    let theme = config.theme.clone();
+
+    let mut hotkeys = vec![
+        (config.toggle_hotkey.clone(), "Toggle rustcast".to_string()),
+        (
+            config.clipboard_hotkey.clone(),
+            "Open the clipboard history".to_string(),
+        ),
+    ];
+    hotkeys.extend(
+        config
+            .shells
+            .iter()
+            .filter_map(|shell| Some((shell.hotkey.clone()?, format!("Run \"{}\"", shell.alias)))),
+    );
+
+    let mut rows = vec![section_title(&theme, "In rustcast")];
+    rows.extend(
+        grouped_keybindings()
+            .into_iter()
+            .map(|(keys, binding)| keybinding_row(&theme, keys, binding.description)),
+    );
+    rows.push(section_title(&theme, "Global hotkeys"));
+    rows.extend(
+        hotkeys
+            .into_iter()
+            .map(|(keys, description)| keybinding_row(&theme, keys, description)),
+    );
+
+    Scrollable::with_direction(
+        Column::from_iter(rows).spacing(5).padding(10),
+        Direction::Vertical(Scrollbar::hidden()),
+    )
+    .into()
+}
+
+fn section_title(theme: &Theme, title: &str) -> Element<'static, Message> {
+    Text::new(title.to_string())
+        .font(theme.font())
+        .size(14)
+        .color(theme.text_color(0.7))
+        .into()
+}
+
+fn keybinding_row(
+    theme: &Theme,
+    keys: impl ToString,
+    description: impl ToString,
+) -> Element<'static, Message> {
+    let theme_clone = theme.clone();
+    container(
+        Row::from_iter([
+            Text::new(keys.to_string())
+                .font(theme.font())
+                .size(15)
+                .color(theme.text_color(1.0))
+                .width(Length::FillPortion(1))
+                .into(),
+            Text::new(description.to_string())
+                .font(theme.font())
+                .size(14)
+                .color(theme.text_color(0.7))
+                .width(Length::FillPortion(3))
+                .into(),
+        ])
+        .align_y(Alignment::Center),
+    )
+    .padding(8)
+    .width(Length::Fill)
+    .style(move |_| result_row_container_style(&theme_clone, false))
+    .into()
+}
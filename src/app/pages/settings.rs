@@ -13,8 +13,10 @@ use crate::app::Editable;
 use crate::app::SetConfigBufferFields;
 use crate::app::SetConfigThemeFields;
 use crate::commands::Function;
+use crate::config::DisplayMode;
 use crate::config::MainPage;
 use crate::config::Shelly;
+use crate::config::SkinTone;
 use crate::styles::delete_button_style;
 use crate::styles::settings_add_button_style;
 use crate::styles::settings_checkbox_style;
@@ -22,6 +24,7 @@ use crate::styles::settings_radio_button_style;
 use crate::styles::settings_save_button_style;
 use crate::styles::settings_slider_style;
 use crate::styles::settings_text_input_item_style;
+use crate::utils::config_path;
 use crate::{
     app::{SetConfigFields, pages::prelude::*},
     config::Config,
@@ -34,6 +37,7 @@ const SETTINGS_ITEM_COL_SPACING: u32 = 5;
 pub fn settings_page(config: Config) -> Element<'static, Message> {
     let config = Box::new(config.clone());
     let theme = config.theme.clone();
+    let window_width = config.window.width;
 
     let hotkey_theme = theme.clone();
     let hotkey = settings_item_column([
@@ -196,6 +200,51 @@ pub fn settings_page(config: Config) -> Element<'static, Message> {
         notice_item(theme.clone(), "What an empty query should show"),
     ]);
 
+    let theme_clone = theme.clone();
+    let display_mode = settings_item_column([
+        settings_hint_text(theme.clone(), "Display mode"),
+        Row::from_iter(DisplayMode::ALL.into_iter().map(|mode| {
+            let theme_clone = theme_clone.clone();
+            radio(
+                mode.to_string(),
+                mode,
+                Some(config.theme.display_mode),
+                |mode| {
+                    Message::SetConfig(SetConfigFields::SetThemeFields(
+                        SetConfigThemeFields::DisplayMode(mode),
+                    ))
+                },
+            )
+            .style(move |_, _| settings_radio_button_style(&theme_clone))
+            .into()
+        }))
+        .spacing(20)
+        .into(),
+        notice_item(theme.clone(), "The size of the rows, icons and text"),
+    ]);
+
+    let theme_clone = theme.clone();
+    let skin_tone = settings_item_column([
+        settings_hint_text(theme.clone(), "Emoji skin tone"),
+        Row::from_iter(SkinTone::ALL.into_iter().map(|tone| {
+            let theme_clone = theme_clone.clone();
+            radio(
+                skin_tone_label(tone),
+                tone,
+                Some(config.emoji_skin_tone),
+                |tone| Message::SetConfig(SetConfigFields::EmojiSkinTone(tone)),
+            )
+            .style(move |_, _| settings_radio_button_style(&theme_clone))
+            .into()
+        }))
+        .spacing(20)
+        .into(),
+        notice_item(
+            theme.clone(),
+            "Applied to emojis that support skin tones when copied",
+        ),
+    ]);
+
     let theme_clone = theme.clone();
     let show_scrollbar = settings_item_row([
         settings_hint_text(theme.clone(), "Show scrollbar"),
@@ -300,7 +349,7 @@ pub fn settings_page(config: Config) -> Element<'static, Message> {
                 },
             )
             .style(move |_, _| settings_slider_style(&theme_clone_1))
-            .width((WINDOW_WIDTH / 5.) * 4.)
+            .width((window_width / 5.) * 4.)
             .into(),
             settings_hint_text(
                 theme.clone(),
@@ -318,7 +367,7 @@ pub fn settings_page(config: Config) -> Element<'static, Message> {
                 },
             )
             .style(move |_, _| settings_slider_style(&theme_clone_2))
-            .width((WINDOW_WIDTH / 5.) * 4.)
+            .width((window_width / 5.) * 4.)
             .into(),
             settings_hint_text(
                 theme.clone(),
@@ -336,7 +385,7 @@ pub fn settings_page(config: Config) -> Element<'static, Message> {
                 },
             )
             .style(move |_, _| settings_slider_style(&theme_clone_3))
-            .width((WINDOW_WIDTH / 5.) * 4.)
+            .width((window_width / 5.) * 4.)
             .into(),
             notice_item(theme.clone(), "Text colour in RGB format"),
         ])
@@ -369,7 +418,7 @@ pub fn settings_page(config: Config) -> Element<'static, Message> {
                 },
             )
             .style(move |_, _| settings_slider_style(&theme_clone_1))
-            .width((WINDOW_WIDTH / 5.) * 4.)
+            .width((window_width / 5.) * 4.)
             .into(),
             settings_hint_text(
                 theme.clone(),
@@ -387,7 +436,7 @@ pub fn settings_page(config: Config) -> Element<'static, Message> {
                 },
             )
             .style(move |_, _| settings_slider_style(&theme_clone_2))
-            .width((WINDOW_WIDTH / 5.) * 4.)
+            .width((window_width / 5.) * 4.)
             .into(),
             settings_hint_text(
                 theme.clone(),
@@ -405,7 +454,7 @@ pub fn settings_page(config: Config) -> Element<'static, Message> {
                 },
             )
             .style(move |_, _| settings_slider_style(&theme_clone_3))
-            .width((WINDOW_WIDTH / 5.) * 4.)
+            .width((window_width / 5.) * 4.)
             .into(),
             notice_item(theme.clone(), "Background colour in RGB format"),
         ])
@@ -426,10 +475,12 @@ pub fn settings_page(config: Config) -> Element<'static, Message> {
         tray_icon.into(),
         clipboard_history.into(),
         auto_suggest.into(),
+        skin_tone.into(),
         show_scrollbar.into(),
         clear_on_hide.into(),
         clear_on_enter.into(),
         show_icons.into(),
+        display_mode.into(),
         font_family.into(),
         text_clr.into(),
         bg_clr.into(),
@@ -441,7 +492,7 @@ pub fn settings_page(config: Config) -> Element<'static, Message> {
         search_dirs_item(&theme, config.search_dirs.clone()),
         Space::new().height(30).into(),
         settings_hint_text(theme.clone(), "Shell commands"),
-        shell_commands_item(config.shells.clone(), theme.clone()),
+        shell_commands_item(config.shells.clone(), theme.clone(), window_width),
         Row::from_iter([
             savebutton(theme.clone()),
             default_button(theme.clone()),
@@ -499,7 +550,7 @@ fn wiki_button(theme: Theme) -> Element<'static, Message> {
     .style(move |_, _| settings_save_button_style(&theme))
     .width(Length::Fill)
     .on_press(Message::RunFunction(crate::commands::Function::OpenApp(
-        std::env::var("HOME").unwrap_or("".to_string()) + "/.config/rustcast/config.toml",
+        config_path("config.toml").to_string_lossy().to_string(),
     )))
     .into()
 }
@@ -522,6 +573,17 @@ fn copy_config_button(config: Box<Config>) -> Element<'static, Message> {
     .into()
 }
 
+fn skin_tone_label(tone: SkinTone) -> &'static str {
+    match tone {
+        SkinTone::Default => "👋",
+        SkinTone::Light => "👋🏻",
+        SkinTone::MediumLight => "👋🏼",
+        SkinTone::Medium => "👋🏽",
+        SkinTone::MediumDark => "👋🏾",
+        SkinTone::Dark => "👋🏿",
+    }
+}
+
 fn settings_hint_text(theme: Theme, text: impl ToString) -> Element<'static, Message> {
     let text = text.to_string();
 
@@ -779,9 +841,17 @@ fn dir_adder_button(dir: &str, theme: Theme) -> Button<'static, Message> {
         .style(move |_, _| settings_add_button_style(&theme.clone()))
 }
 
-fn shell_commands_item(shells: Vec<Shelly>, theme: Theme) -> Element<'static, Message> {
-    let mut col =
-        Column::from_iter(shells.iter().map(|x| x.editable_render(theme.clone()))).spacing(30);
+fn shell_commands_item(
+    shells: Vec<Shelly>,
+    theme: Theme,
+    window_width: f32,
+) -> Element<'static, Message> {
+    let mut col = Column::from_iter(
+        shells
+            .iter()
+            .map(|x| x.editable_render(theme.clone(), window_width)),
+    )
+    .spacing(30);
 
     let theme_clone = theme.clone();
 
@@ -794,7 +864,7 @@ fn shell_commands_item(shells: Vec<Shelly>, theme: Theme) -> Element<'static, Me
             )
             .style(move |_, _| settings_add_button_style(&theme_clone.clone()))
             .on_press(Message::SetConfig(SetConfigFields::ShellCommands(
-                Editable::Create(Shelly::default()),
+                Editable::Create(Box::default()),
             ))),
         )
         .width(Length::Fill)
@@ -804,11 +874,11 @@ fn shell_commands_item(shells: Vec<Shelly>, theme: Theme) -> Element<'static, Me
 }
 
 impl Shelly {
-    pub fn editable_render(&self, theme: Theme) -> Element<'static, Message> {
-        let shell = self.to_owned();
+    pub fn editable_render(&self, theme: Theme, window_width: f32) -> Element<'static, Message> {
+        let shell = Box::new(self.to_owned());
         Column::from_iter([
             tuple_row(
-                shellcommand_hint_text(theme.clone(), "Display name"),
+                shellcommand_hint_text(theme.clone(), "Display name", window_width),
                 text_input_cell(self.alias.clone(), &theme, "Display Name")
                     .on_input({
                         let shell = shell.clone();
@@ -826,7 +896,7 @@ impl Shelly {
             )
             .into(),
             tuple_row(
-                shellcommand_hint_text(theme.clone(), "Search name"),
+                shellcommand_hint_text(theme.clone(), "Search name", window_width),
                 text_input_cell(self.alias_lc.clone(), &theme, "Search Name")
                     .on_input({
                         let shell = shell.clone();
@@ -844,7 +914,7 @@ impl Shelly {
             )
             .into(),
             tuple_row(
-                shellcommand_hint_text(theme.clone(), "Command"),
+                shellcommand_hint_text(theme.clone(), "Command", window_width),
                 text_input_cell(self.command.clone(), &theme, "Command")
                     .on_input({
                         let shell = shell.clone();
@@ -862,7 +932,29 @@ impl Shelly {
             )
             .into(),
             tuple_row(
-                shellcommand_hint_text(theme.clone(), "Icon File"),
+                shellcommand_hint_text(theme.clone(), "Working directory", window_width),
+                text_input_cell(
+                    self.cwd.clone().unwrap_or("".to_string()),
+                    &theme,
+                    "Working directory",
+                )
+                .on_input({
+                    let shell = shell.clone();
+                    move |input| {
+                        let old = shell.clone();
+                        let mut new = old.clone();
+                        new.cwd = if input.is_empty() { None } else { Some(input) };
+                        Message::SetConfig(SetConfigFields::ShellCommands(Editable::Update {
+                            old,
+                            new,
+                        }))
+                    }
+                })
+                .into(),
+            )
+            .into(),
+            tuple_row(
+                shellcommand_hint_text(theme.clone(), "Icon File", window_width),
                 text_input_cell(
                     self.icon_path.clone().unwrap_or("".to_string()),
                     &theme,
@@ -884,7 +976,7 @@ impl Shelly {
             )
             .into(),
             tuple_row(
-                shellcommand_hint_text(theme.clone(), "Hotkey"),
+                shellcommand_hint_text(theme.clone(), "Hotkey", window_width),
                 text_input_cell(
                     self.hotkey.clone().unwrap_or("".to_string()),
                     &theme,
@@ -905,17 +997,42 @@ impl Shelly {
                 .into(),
             )
             .into(),
+            tuple_row(
+                shellcommand_hint_text(theme.clone(), "Run in terminal", window_width),
+                checkbox(self.run_in_terminal)
+                    .style({
+                        let theme = theme.clone();
+                        move |_, _| settings_checkbox_style(&theme)
+                    })
+                    .on_toggle({
+                        let shell = shell.clone();
+                        move |input| {
+                            let old = shell.clone();
+                            let mut new = old.clone();
+                            new.run_in_terminal = input;
+                            Message::SetConfig(SetConfigFields::ShellCommands(Editable::Update {
+                                old,
+                                new,
+                            }))
+                        }
+                    })
+                    .into(),
+            )
+            .into(),
             tuple_row(
                 Button::new("Delete")
                     .on_press(Message::SetConfig(SetConfigFields::ShellCommands(
-                        Editable::Delete(self.clone()),
+                        Editable::Delete(shell.clone()),
                     )))
                     .style({
                         let theme = theme.clone();
                         move |_, _| delete_button_style(&theme)
                     })
                     .into(),
-                notice_item(theme.clone(), "Icon path and hotkey are optional"),
+                notice_item(
+                    theme.clone(),
+                    "Working directory, icon path and hotkey are optional",
+                ),
             )
             .into(),
         ])
@@ -935,12 +1052,16 @@ fn tuple_row(
         .width(Length::Fill)
 }
 
-fn shellcommand_hint_text(theme: Theme, text: impl ToString) -> Element<'static, Message> {
+fn shellcommand_hint_text(
+    theme: Theme,
+    text: impl ToString,
+    window_width: f32,
+) -> Element<'static, Message> {
     let text = text.to_string();
 
     Text::new(text)
         .font(theme.font())
         .color(theme.text_color(0.7))
-        .width(WINDOW_WIDTH * 0.3)
+        .width(window_width * 0.3)
         .into()
 }
@@ -1,39 +1,54 @@
 //! This module handles the logic for the tile, AKA rustcast's main window
+pub mod app_cache;
 pub mod elm;
+#[cfg(feature = "headless")]
+pub mod headless;
 pub mod update;
 
-use crate::app::apps::App;
-use crate::app::{ArrowKey, Message, Move, Page};
-use crate::clipboard::ClipBoardContentType;
-use crate::config::{Config, Shelly};
+use crate::app::apps::{App, AppCommand};
+use crate::app::keybindings;
+use crate::app::{
+    ErrorKind, MAX_CALCULATIONS, MAX_RECENT_EMOJIS, Message, PAGE_KEYWORDS, Page, ToApp,
+};
+use crate::calculator::Calculation;
+use crate::calendar::Event;
+use crate::clipboard::{ClipBoardContentType, ClipboardHistory};
+use crate::commands::Function;
+use crate::config::{Config, ConfigDiagnostic, Extension, Scoring, Shelly};
+use crate::contacts::Contact;
 use crate::debounce::Debouncer;
-use crate::platform::default_app_paths;
-use crate::platform::macos::launching::Shortcut;
+use crate::platform::Shortcut;
+use crate::platform::{self, SleepAssertion, default_app_paths};
+use crate::providers::{ResultProvider, Search};
+use crate::search::Searcher;
+use crate::single_instance;
+use crate::timers::Timers;
+use crate::updater::{self, Release};
+use crate::utils::config_path;
 
 use arboard::Clipboard;
 
-use iced::futures::SinkExt;
 use iced::futures::channel::mpsc::{Sender, channel};
-use iced::keyboard::Modifiers;
+use iced::futures::{SinkExt, StreamExt};
 use iced::{
-    Subscription, Theme, futures,
+    Animation, Subscription, Theme,
+    advanced::input_method,
+    futures,
     keyboard::{self, key::Named},
-    stream,
+    mouse, stream,
 };
 use iced::{event, window};
 
 use log::{info, warn};
-use objc2::rc::Retained;
-use objc2_app_kit::NSRunningApplication;
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
-use rayon::slice::ParallelSliceMut;
 use tokio::io::AsyncBufReadExt;
 use tray_icon::TrayIcon;
 
 use std::collections::HashMap;
 use std::fmt::Debug;
-use std::str::FromStr;
-use std::time::Duration;
+use std::fs;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// This is a wrapper around the sender to disable dropping
 #[derive(Clone, Debug)]
@@ -44,98 +59,6 @@ impl Drop for ExtSender {
     fn drop(&mut self) {}
 }
 
-/// All the indexed apps that rustcast can search for
-#[derive(Clone, Debug)]
-struct AppIndex {
-    by_name: HashMap<String, App>,
-}
-
-impl AppIndex {
-    /// Search for an element in the index that starts with the provided prefix
-    fn search_prefix<'a>(&'a self, prefix: &'a str) -> impl ParallelIterator<Item = &'a App> + 'a {
-        self.by_name.par_iter().filter_map(move |(name, app)| {
-            if name.starts_with(prefix) || name.contains(format!(" {prefix}").as_str()) {
-                Some(app)
-            } else {
-                None
-            }
-        })
-    }
-
-    fn update_ranking(&mut self, name: &str) {
-        let app = match self.by_name.get_mut(name) {
-            Some(a) => a,
-            None => return,
-        };
-
-        app.ranking += 1;
-    }
-
-    fn set_ranking(&mut self, name: &str, rank: i32) {
-        let app = match self.by_name.get_mut(name) {
-            Some(a) => a,
-            None => return,
-        };
-
-        app.ranking = rank;
-    }
-
-    fn get_rankings(&self) -> HashMap<String, i32> {
-        HashMap::from_iter(self.by_name.iter().filter_map(|(name, app)| {
-            if app.ranking > 0 {
-                Some((name.to_owned(), app.ranking.to_owned()))
-            } else {
-                None
-            }
-        }))
-    }
-
-    fn top_ranked(&self, limit: usize) -> Vec<App> {
-        let mut ranked: Vec<App> = self
-            .by_name
-            .values()
-            .filter(|app| app.ranking > 0)
-            .cloned()
-            .collect();
-
-        ranked.par_sort_by(|left, right| {
-            right
-                .ranking
-                .cmp(&left.ranking)
-                .then_with(|| left.display_name.cmp(&right.display_name))
-        });
-        ranked.truncate(limit);
-        ranked
-    }
-
-    fn get_favourites(&self) -> Vec<App> {
-        let mut favs: Vec<App> = self
-            .by_name
-            .values()
-            .filter(|x| x.ranking == -1)
-            .cloned()
-            .collect();
-        favs.sort_by(|a, b| a.display_name.cmp(&b.display_name));
-        favs
-    }
-
-    fn empty() -> AppIndex {
-        AppIndex {
-            by_name: HashMap::new(),
-        }
-    }
-
-    /// Factory function for creating
-    pub fn from_apps(options: Vec<App>) -> Self {
-        let mut hmap = HashMap::new();
-        for app in options {
-            hmap.insert(app.search_name.clone(), app);
-        }
-
-        AppIndex { by_name: hmap }
-    }
-}
-
 /// This is the base window, and its a "Tile"
 /// Its fields are:
 /// - Theme ([`iced::Theme`])
@@ -143,43 +66,134 @@ impl AppIndex {
 /// - Query (String)
 /// - Query Lowercase (String, but lowercase)
 /// - Previous Query Lowercase (String)
-/// - Results (Vec<[`App`]>) the results of the search
-/// - Options ([`AppIndex`]) the options to search through (is a HashMap wrapper)
-/// - Emoji Apps ([`AppIndex`]) emojis that are considered as "apps"
+/// - Results (Vec<[`Arc<App>`]>) the results of the search, sharing the apps with the index
+/// - Options ([`Searcher`]) the options to search through
+/// - Emoji Apps ([`Searcher`]) emojis that are considered as "apps"
+/// - Emoji Category ([`emojis::Group`]) the category shown on the emoji page when the query is empty
+/// - Recent Emojis (`Vec<String>`) the most recently copied emojis and symbols, newest first
 /// - Visible (bool) whether the window is visible or not
 /// - Focused (bool) whether the window is focused or not
-/// - Frontmost ([`Option<Retained<NSRunningApplication>>`]) the frontmost application before the window was opened
+/// - Composing (bool) whether text is being composed with an input method, which the keys belong
+///   to until it is done
+/// - Frontmost ([`Option<platform::PreviousFocus>`]) the frontmost application (or window) before the window was opened
 /// - Config ([`Config`]) the app's config
 /// - Hotkeys, storing the hotkey used for directly opening to the clipboard history page, and
 ///   opening the app
 /// - Sender (The [`ExtSender`] that sends messages, used by the tray icon currently)
-/// - Clipboard Content (`Vec<`[`ClipBoardContentType`]`>`) all of the cliboard contents
+/// - Clipboard Content ([`ClipboardHistory`]) all of the cliboard contents, with pinned ones first
+/// - Clipboard unavailable: why the clipboard can't be read (like on Linux without a display
+///   server), which turns off clipboard history and is shown on the main page
 /// - Page ([`Page`]) the current page of the window (main or clipboard history)
+/// - Page stack: the pages that the current one was opened from, which Escape goes back through to
+///   the main page, shown as breadcrumbs before the search bar
+/// - Scope: the keyword that the search was scoped to (see [`PAGE_KEYWORDS`]), shown before the
+///   search bar
+/// - Browsing: the results that were browsed into (see [`Browsed`]), innermost last, whose
+///   children are the results until ← or Escape goes back out of them
+/// - Pointer moved: whether the pointer moved since the focus was last moved with the keyboard (or
+///   the results changed), so that results only take the focus when hovered by moving the pointer
+/// - Pressed: the result that the mouse button was pressed on, until it is released, which is
+///   dragged out of the window (see [`crate::drag`]) if the pointer leaves it before then
+/// - Window: the one window, which is hidden instead of closed and shown again (so that its
+///   platform config only runs once), until something else closes it
 /// - RustCast's height: to figure out which height to resize to
+/// - Fade and animated height: the window fading in (or out) and growing or shrinking to its
+///   height, which every frame applies until they are done, unless motion is reduced
+/// - Hide timer: the task that hides the window once it has been unfocused for
+///   `focus_loss_grace_ms`, or left alone for `auto_hide_seconds`, which replacing cancels
+/// - Last activity: when the search or the focused result last changed, for `auto_hide_seconds`
+/// - Whether the command key is held, to show the quick open numbers next to the results
+/// - Query history: the submitted queries (newest first), and which one is being recalled
+/// - Calculations: the calculations that were copied (newest first), for the calculation history
+/// - Timers: the running timers and the stopwatch
+/// - Confirming: an action that can't be undone, and the question about it that is shown in the
+///   footer until Enter confirms it (or anything else cancels it)
+/// - Lookup cache: the results of looking things up online (like the weather), by what was looked
+///   up, and when, so that they are only looked up again once they are old
+/// - Lookups: the lookups that are running, by the provider that started them, which are stopped
+///   when they are replaced
+/// - Keep awake: the assertion that keeps the computer from sleeping while "Prevent Sleep" is on,
+///   and which time it was turned on, so that the timeout from an earlier time is ignored
+/// - Learned: how many times each result (by its search name) was opened after searching for each
+///   query, so that those results come first for the same query, which is saved with the rankings
+/// - Last used: when each app was last opened (by its search name), which is saved with the
+///   rankings
+/// - Error: the most recent error, shown in the footer until it is dismissed
+/// - Config diagnostics: the problems with the config file, shown above the main page's results
+/// - Available update: the newer release of rustcast, if `check_for_updates` found one
+/// - Content search: the `grep` search that is running, which is stopped when it is replaced
+/// - Running: the paths of the running apps, updated when the window opens, which the results
+///   that are running apps are marked with
+/// - Providers: the providers of the results besides the apps (see [`crate::providers`]), sorted
+///   by their priority
+/// - Events: the upcoming events from the calendars, and when they were read, which are read again
+///   when the window opens once they are old
+/// - Contacts: the people in Contacts, read when the apps are indexed if `search_contacts` is on
+/// - Secret: the last secret copied from the password manager, which is left out of the clipboard
+///   history, until it is cleared from the clipboard
+/// - Indexing: whether the installed apps are being found, and which run of finding them is the
+///   latest one, so that the apps from an older run that was replaced are ignored
 #[derive(Clone)]
 pub struct Tile {
     pub theme: iced::Theme,
     pub focus_id: u32,
     pub query: String,
     pub current_mode: String,
-    pub update_available: bool,
+    pub available_update: Option<Release>,
     pub ranking: HashMap<String, i32>,
+    pub last_used: HashMap<String, u64>,
+    pub learned: HashMap<String, HashMap<String, u32>>,
     query_lc: String,
-    results: Vec<App>,
-    options: AppIndex,
-    emoji_apps: AppIndex,
+    results: Vec<Arc<App>>,
+    options: Searcher,
+    emoji_apps: Searcher,
+    emoji_category: emojis::Group,
+    recent_emojis: Vec<String>,
     visible: bool,
     focused: bool,
-    frontmost: Option<Retained<NSRunningApplication>>,
+    composing: bool,
+    frontmost: Option<platform::PreviousFocus>,
     pub config: Config,
     hotkeys: Hotkeys,
-    clipboard_content: Vec<ClipBoardContentType>,
+    clipboard_content: ClipboardHistory,
+    clipboard_unavailable: Option<String>,
     tray_icon: Option<TrayIcon>,
     sender: Option<ExtSender>,
     page: Page,
+    page_stack: Vec<Page>,
+    scope: Option<String>,
+    browsing: Vec<Browsed>,
+    pointer_moved: bool,
+    pressed: Option<u32>,
+    window: Option<window::Id>,
     pub height: f32,
+    fade: Animation<bool>,
+    animated_height: Animation<f32>,
     pub file_search_sender: Option<tokio::sync::watch::Sender<(String, Vec<String>)>>,
+    content_search: Option<iced::task::Handle>,
+    hide_timer: Option<iced::task::Handle>,
+    last_activity: Instant,
     debouncer: Debouncer,
+    command_held: bool,
+    query_history: Vec<String>,
+    history_index: Option<usize>,
+    calculations: Vec<Calculation>,
+    timers: Timers,
+    confirming: Option<(String, Function)>,
+    lookup_cache: HashMap<String, (u64, Vec<App>)>,
+    lookups: HashMap<&'static str, iced::task::Handle>,
+    keep_awake: Option<Arc<SleepAssertion>>,
+    keep_awake_run: u64,
+    error: Option<(ErrorKind, String)>,
+    config_diagnostics: Vec<ConfigDiagnostic>,
+    providers: Vec<Arc<dyn ResultProvider>>,
+    running: Vec<String>,
+    events: Vec<Event>,
+    events_updated: u64,
+    contacts: Vec<Contact>,
+    secret: Option<String>,
+    indexing: bool,
+    index_run: u64,
 }
 
 /// A struct to store all the hotkeys
@@ -192,6 +206,46 @@ pub struct Hotkeys {
     pub shells: HashMap<Shortcut, Shelly>,
 }
 
+impl Hotkeys {
+    /// The hotkeys set in the config, with the default ones for any that aren't valid
+    pub fn from_config(config: &Config) -> Self {
+        let toggle = Shortcut::parse(&config.toggle_hotkey)
+            .unwrap_or(Shortcut::parse("option+space").unwrap());
+
+        let clipboard_hotkey = Shortcut::parse(&config.clipboard_hotkey.to_lowercase())
+            .unwrap_or_else(|_| Shortcut::parse("cmd+shift+c").unwrap());
+
+        let mut shells = HashMap::new();
+
+        for shell in &config.shells {
+            if let Some(hk_str) = &shell.hotkey
+                && let Ok(hk) = Shortcut::parse(hk_str)
+            {
+                shells.insert(hk, shell.clone());
+            }
+        }
+
+        Hotkeys {
+            toggle,
+            clipboard_hotkey,
+            shells,
+        }
+    }
+}
+
+/// A result that was browsed into, and the search that it was browsed into from
+#[derive(Clone, Debug)]
+pub struct Browsed {
+    /// The name of the result, shown before the search bar
+    pub title: String,
+    /// All of the result's children, which the search filters
+    pub children: Vec<Arc<App>>,
+    /// The search, its results and the focused one, which going back out puts back
+    query: String,
+    results: Vec<Arc<App>>,
+    focus_id: u32,
+}
+
 impl Tile {
     /// This returns the theme of the window
     pub fn theme(&self, _: window::Id) -> Option<Theme> {
@@ -205,70 +259,96 @@ impl Tile {
     /// - Hot reloading
     /// - Clipboard history
     /// - Window close events
-    /// - Keypresses (escape to close the window)
+    /// - Keypresses (see [`keybindings`]), except while text is being composed
+    /// - Input method composition
+    /// - Frames, while the window is fading or changing its height
+    /// - Pointer movement, until the pointer moves after the focus was moved with the keyboard
+    /// - The mouse button being released, while it is held on a result
     /// - Window focus changes
     pub fn subscription(&self) -> Subscription<Message> {
-        let keyboard = event::listen_with(|event, _, id| match event {
-            iced::Event::Keyboard(keyboard::Event::KeyPressed {
-                key: keyboard::Key::Named(keyboard::key::Named::Escape),
-                ..
-            }) => Some(Message::EscKeyPressed(id)),
-            iced::Event::Keyboard(keyboard::Event::KeyPressed {
-                key: keyboard::Key::Character(cha),
-                modifiers: Modifiers::LOGO,
-                ..
-            }) => {
-                if cha.to_string() == "," {
-                    return Some(Message::SwitchToPage(Page::Settings));
+        let keyboard = event::listen_with(|event, status, id| {
+            let (key, modifiers) = match event {
+                iced::Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) => {
+                    (key, modifiers)
                 }
-                None
+                iced::Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+                    return Some(Message::ModifiersChanged(modifiers));
+                }
+                _ => return None,
+            };
+
+            if let Some(message) = keybindings::message_for(&key, modifiers, status, id) {
+                return Some(message);
+            }
+
+            // Typing while the search bar isn't focused focuses it, and the search bar takes the
+            // text from there, so that input methods work
+            if status == event::Status::Captured {
+                return None;
             }
+            match key {
+                keyboard::Key::Character(_) | keyboard::Key::Named(Named::Backspace) => {
+                    Some(Message::FocusTextInput)
+                }
+                _ => None,
+            }
+        });
+        let input_method = event::listen_with(|event, _, _| match event {
+            iced::Event::InputMethod(input_method::Event::Preedit(content, _)) => {
+                Some(Message::ImeComposing(!content.is_empty()))
+            }
+            iced::Event::InputMethod(
+                input_method::Event::Commit(_) | input_method::Event::Closed,
+            ) => Some(Message::ImeComposing(false)),
             _ => None,
         });
+        let pointer = event::listen_with(|event, _, _| match event {
+            iced::Event::Mouse(mouse::Event::CursorMoved { .. }) => Some(Message::PointerMoved),
+            _ => None,
+        });
+        let release = event::listen_with(|event, _, _| match event {
+            iced::Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                Some(Message::PointerReleased)
+            }
+            _ => None,
+        });
+        let now = Instant::now();
         Subscription::batch([
             Subscription::run(handle_hot_reloading),
-            keyboard,
+            // While text is being composed, the keys (like enter and the arrows) are for the input
+            // method
+            if self.composing {
+                Subscription::none()
+            } else {
+                keyboard
+            },
+            input_method,
+            if self.pointer_moved {
+                Subscription::none()
+            } else {
+                pointer
+            },
+            if self.pressed.is_some() {
+                release
+            } else {
+                Subscription::none()
+            },
+            if self.fade.is_animating(now) || self.animated_height.is_animating(now) {
+                window::frames().map(Message::AnimationFrame)
+            } else {
+                Subscription::none()
+            },
             Subscription::run(handle_recipient),
-            Subscription::run(handle_version_and_rankings),
+            Subscription::run(single_instance::listen),
+            Subscription::run(handle_rankings),
+            if self.config.check_for_updates {
+                Subscription::run(updater::check_for_updates)
+            } else {
+                Subscription::none()
+            },
             Subscription::run(handle_clipboard_history),
             Subscription::run(handle_file_search),
-            window::close_events().map(Message::HideWindow),
-            keyboard::listen().filter_map(|event| {
-                if let keyboard::Event::KeyPressed { key, modifiers, .. } = event {
-                    match key {
-                        keyboard::Key::Named(Named::ArrowUp) => {
-                            Some(Message::ChangeFocus(ArrowKey::Up, 1))
-                        }
-                        keyboard::Key::Named(Named::ArrowLeft) => {
-                            Some(Message::ChangeFocus(ArrowKey::Left, 1))
-                        }
-                        keyboard::Key::Named(Named::ArrowRight) => {
-                            Some(Message::ChangeFocus(ArrowKey::Right, 1))
-                        }
-                        keyboard::Key::Named(Named::ArrowDown) => {
-                            Some(Message::ChangeFocus(ArrowKey::Down, 1))
-                        }
-                        keyboard::Key::Character(chr) => {
-                            if modifiers.command() && chr.to_string() == "r" {
-                                Some(Message::ReloadConfig)
-                            } else if chr.to_string() == "p" && modifiers.control() {
-                                Some(Message::ChangeFocus(ArrowKey::Up, 1))
-                            } else if chr.to_string() == "n" && modifiers.control() {
-                                Some(Message::ChangeFocus(ArrowKey::Down, 1))
-                            } else {
-                                Some(Message::FocusTextInput(Move::Forwards(chr.to_string())))
-                            }
-                        }
-                        keyboard::Key::Named(Named::Enter) => Some(Message::OpenFocused),
-                        keyboard::Key::Named(Named::Backspace) => {
-                            Some(Message::FocusTextInput(Move::Back))
-                        }
-                        _ => None,
-                    }
-                } else {
-                    None
-                }
-            }),
+            window::close_events().map(Message::WindowClosed),
             window::events()
                 .with(self.focused)
                 .filter_map(|(focused, (wid, event))| match event {
@@ -291,41 +371,264 @@ impl Tile {
     /// should be separated out to make it easier to test. This function is called by the `update`
     /// function to handle the search query changed event.
     pub fn handle_search_query_changed(&mut self) {
-        let query = self.query_lc.clone();
-        let options = if self.page == Page::Main {
-            &self.options
-        } else if self.page == Page::EmojiSearch {
-            &self.emoji_apps
-        } else {
-            &AppIndex::empty()
+        let results = match self.page {
+            Page::Main => self.options.query(&self.query_lc, &self.config.scoring),
+            Page::EmojiSearch => self.emoji_apps.query(&self.query_lc, &Scoring::default()),
+            _ => vec![],
         };
-        let results: Vec<App> = options
-            .search_prefix(&query)
-            .map(|x| x.to_owned())
+        self.results = results.into_iter().map(|x| x.app).collect();
+
+        // Results that were opened for this exact query come first, the most opened one first
+        if self.page == Page::Main
+            && let Some(learned) = self.learned.get(self.query_lc.trim())
+        {
+            self.results.sort_by_key(|app| {
+                std::cmp::Reverse(learned.get(&app.search_name).copied().unwrap_or(0))
+            });
+        }
+    }
+
+    /// Remember that a result was opened after searching for the current query
+    pub fn learn_selection(&mut self, search_name: &str) {
+        let query = self.query_lc.trim();
+        if query.is_empty() || search_name.is_empty() {
+            return;
+        }
+
+        *self
+            .learned
+            .entry(query.to_string())
+            .or_default()
+            .entry(search_name.to_string())
+            .or_insert(0) += 1;
+    }
+
+    /// The keyword that some text is, if it scopes the search to a page or an extension
+    pub fn scope_keyword(&self, text: &str) -> Option<String> {
+        let text = text.trim().to_lowercase();
+        let is_keyword = PAGE_KEYWORDS.iter().any(|(keyword, _)| *keyword == text)
+            || self
+                .config
+                .extensions
+                .iter()
+                .any(|x| x.keyword.trim().eq_ignore_ascii_case(&text));
+        (is_keyword && !text.is_empty()).then_some(text)
+    }
+
+    /// The extension that the search is scoped to, if it is scoped to one
+    pub fn scoped_extension(&self) -> Option<&Extension> {
+        let scope = self.scope.as_ref()?;
+        self.config
+            .extensions
+            .iter()
+            .find(|x| x.keyword.trim().eq_ignore_ascii_case(scope))
+    }
+
+    /// Where the search is, shown before the search bar: the pages it was opened through (besides
+    /// the main page), with the keyword instead of the page for a scoped search, and then the
+    /// results that were browsed into
+    pub fn breadcrumbs(&self) -> Vec<String> {
+        let mut crumbs: Vec<String> = self
+            .page_stack
+            .iter()
+            .chain([&self.page])
+            .filter(|x| **x != Page::Main)
+            .map(Page::to_string)
             .collect();
+        if let Some(keyword) = &self.scope {
+            if self.page != Page::Main {
+                crumbs.pop();
+            }
+            crumbs.push(keyword.clone());
+        }
+        crumbs.extend(self.browsing.iter().map(|x| x.title.clone()));
+        crumbs
+    }
 
-        self.results = results;
+    /// Whether the window fades and changes its height smoothly, which `reduce_motion` (or the
+    /// system's setting) stops
+    pub fn animates(&self) -> bool {
+        !self.config.reduce_motion && !platform::prefers_reduced_motion()
     }
 
-    pub fn frequent_results(&self) -> Vec<App> {
+    /// Whether an app (or a program in it) is running
+    pub fn is_running(&self, app: &App) -> bool {
+        let AppCommand::Function(Function::OpenApp(path)) = &app.open_command else {
+            return false;
+        };
+        self.running.iter().any(|x| platform::runs_from(x, path))
+    }
+
+    pub fn frequent_results(&self) -> Vec<Arc<App>> {
         self.options.top_ranked(5)
     }
 
+    /// The emojis and symbols shown in the "Recently used" row of the emoji page
+    pub fn recent_emoji_apps(&self) -> Vec<App> {
+        self.recent_emojis
+            .iter()
+            .map(|emoji| App {
+                ranking: 0,
+                icons: None,
+                display_name: emoji.to_owned(),
+                search_name: String::new(),
+                open_command: AppCommand::Function(Function::CopyToClipboard(
+                    ClipBoardContentType::Text(emoji.to_owned()),
+                )),
+                desc: "Recently used".to_string(),
+                version: None,
+                last_used: None,
+                children: None,
+            })
+            .collect()
+    }
+
+    /// Moves an emoji to the front of the recently used emojis, and saves them to disk
+    pub fn record_recent_emoji(&mut self, emoji: String) {
+        self.recent_emojis.retain(|x| *x != emoji);
+        self.recent_emojis.insert(0, emoji);
+        self.recent_emojis.truncate(MAX_RECENT_EMOJIS);
+
+        fs::write(
+            config_path("recent_emojis.txt"),
+            self.recent_emojis.join("\n"),
+        )
+        .ok();
+    }
+
+    /// Moves the current query to the front of the query history, and saves it to disk
+    pub fn record_query(&mut self) {
+        let query = self.query.trim().to_string();
+        if query.is_empty() || self.config.query_history_limit == 0 {
+            return;
+        }
+
+        self.query_history.retain(|x| *x != query);
+        self.query_history.insert(0, query);
+        self.query_history.truncate(self.config.query_history_limit);
+        self.save_query_history();
+    }
+
+    pub fn clear_query_history(&mut self) {
+        self.query_history.clear();
+        self.history_index = None;
+        self.save_query_history();
+    }
+
+    fn save_query_history(&self) {
+        fs::write(
+            config_path("query_history.txt"),
+            self.query_history.join("\n"),
+        )
+        .ok();
+    }
+
+    /// Moves a calculation to the front of the calculation history, and saves it to disk if the
+    /// history is kept between launches
+    pub fn record_calculation(&mut self, calculation: Calculation) {
+        self.calculations
+            .retain(|x| x.expression != calculation.expression);
+        self.calculations.insert(0, calculation);
+        self.calculations.truncate(MAX_CALCULATIONS);
+
+        if self.config.save_calculation_history {
+            fs::write(
+                config_path("calculations.txt"),
+                self.calculations
+                    .iter()
+                    .map(|x| x.to_line())
+                    .collect::<Vec<String>>()
+                    .join("\n"),
+            )
+            .ok();
+        }
+    }
+
+    /// The calculations whose expression contains the query, newest first
+    pub fn calculation_apps(&self) -> Vec<App> {
+        self.calculations
+            .iter()
+            .filter(|x| x.expression.to_lowercase().contains(&self.query_lc))
+            .map(|x| x.to_app())
+            .collect()
+    }
+
+    /// What the providers are given to find results for the search
+    pub fn search(&self) -> Search<'_> {
+        Search {
+            query: &self.query,
+            query_lc: &self.query_lc,
+            config: &self.config,
+            timers: &self.timers,
+            contacts: &self.contacts,
+            events: &self.events,
+            events_updated: self.events_updated,
+            available_update: self.available_update.as_ref(),
+        }
+    }
+
+    /// The result for a newer release of rustcast, if one was found
+    pub fn update_apps(&self) -> Vec<App> {
+        self.available_update.iter().map(|x| x.to_app()).collect()
+    }
+
+    /// Whether clipboard history is turned on, and the clipboard can be read
+    pub fn clipboard_history_on(&self) -> bool {
+        self.config.cbhist && self.clipboard_unavailable.is_none()
+    }
+
+    /// A result explaining why clipboard history is off, when it is turned on but the clipboard
+    /// can't be read
+    pub fn clipboard_status_apps(&self) -> Vec<App> {
+        let Some(reason) = self
+            .clipboard_unavailable
+            .as_ref()
+            .filter(|_| self.config.cbhist)
+        else {
+            return vec![];
+        };
+        vec![App {
+            ranking: 0,
+            open_command: AppCommand::Display,
+            desc: reason.clone(),
+            icons: None,
+            display_name: "⚠ Clipboard history is off, since the clipboard can't be read"
+                .to_string(),
+            search_name: String::new(),
+            version: None,
+            last_used: None,
+            children: None,
+        }]
+    }
+
+    /// A result for each problem with the config file, that opens it to be fixed
+    pub fn config_diagnostic_apps(&self) -> Vec<App> {
+        let config_file = config_path("config.toml").to_string_lossy().to_string();
+        self.config_diagnostics
+            .iter()
+            .map(|diagnostic| App {
+                ranking: 0,
+                open_command: AppCommand::Function(Function::OpenApp(config_file.clone())),
+                desc: format!("At {}", diagnostic.location()),
+                icons: None,
+                display_name: format!("⚠ Config error: {}", diagnostic.message),
+                search_name: String::new(),
+                version: None,
+                last_used: None,
+                children: None,
+            })
+            .collect()
+    }
+
     /// Gets the frontmost application to focus later.
     pub fn capture_frontmost(&mut self) {
-        use objc2_app_kit::NSWorkspace;
-
-        let ws = NSWorkspace::sharedWorkspace();
-        self.frontmost = ws.frontmostApplication();
+        self.frontmost = platform::previous_focus(&self.config.window);
     }
 
     /// Restores the frontmost application.
-    #[allow(deprecated)]
     pub fn restore_frontmost(&mut self) {
-        use objc2_app_kit::NSApplicationActivationOptions;
-
-        if let Some(app) = self.frontmost.take() {
-            app.activateWithOptions(NSApplicationActivationOptions::ActivateIgnoringOtherApps);
+        if let Some(focus) = self.frontmost.take() {
+            platform::restore_focus(focus);
         }
     }
 }
@@ -333,10 +636,29 @@ impl Tile {
 /// This is the subscription function that handles the change in clipboard history
 fn handle_clipboard_history() -> impl futures::Stream<Item = Message> {
     stream::channel(100, async |mut output| {
-        let mut clipboard = Clipboard::new().unwrap();
+        let mut clipboard = match Clipboard::new() {
+            Ok(clipboard) => clipboard,
+            Err(e) => {
+                warn!("The clipboard can't be read, so clipboard history is off: {e}");
+                output
+                    .send(Message::ClipboardUnavailable(e.to_string()))
+                    .await
+                    .ok();
+                return;
+            }
+        };
         let mut prev_byte_rep: Option<ClipBoardContentType> = None;
+        let mut prev_change_count = None;
 
         loop {
+            // Reading the clipboard (especially images) is slow, so it is only read once it changed
+            let change_count = platform::clipboard_change_count();
+            if change_count.is_some() && change_count == prev_change_count {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                continue;
+            }
+            prev_change_count = change_count;
+
             let byte_rep = if let Ok(a) = clipboard.get_image() {
                 Some(ClipBoardContentType::Image(a))
             } else if let Ok(a) = clipboard.get_text()
@@ -352,9 +674,10 @@ fn handle_clipboard_history() -> impl futures::Stream<Item = Message> {
             {
                 info!("Adding item to cbhist");
                 output
-                    .send(Message::EditClipboardHistory(crate::app::Editable::Create(
+                    .send(Message::ClipboardCaptured(
                         content.to_owned(),
-                    )))
+                        platform::frontmost_app(),
+                    ))
                     .await
                     .ok();
                 prev_byte_rep = byte_rep;
@@ -427,9 +750,37 @@ async fn read_mdfind_results(
     }
 }
 
+/// Finds the apps again when apps are installed or removed, which is found out by watching the app
+/// directories
+///
+/// If the directories can't be watched, they are checked every second instead
 fn handle_hot_reloading() -> impl futures::Stream<Item = Message> {
     stream::channel(100, async |mut output| {
         let paths = default_app_paths();
+
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let watched: Vec<String> = paths.par_iter().cloned().collect();
+        let watcher = tokio::task::spawn_blocking(move || {
+            platform::watch_dirs(&watched, || {
+                sender.send(()).ok();
+            })
+        });
+
+        while receiver.recv().await.is_some() {
+            // Installing an app changes its directory many times, so wait for the changes to stop
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            while receiver.try_recv().is_ok() {}
+
+            info!("The app directories were changed");
+            output.send(Message::UpdateApps).await.ok();
+        }
+
+        match watcher.await {
+            Ok(Err(e)) => warn!("Could not watch the app directories, checking them instead: {e}"),
+            Err(e) => warn!("Could not watch the app directories, checking them instead: {e}"),
+            Ok(Ok(())) => {}
+        }
+
         let mut total_files: usize = paths
             .par_iter()
             .map(|dir| count_dirs_in_dir(std::path::Path::new(dir)))
@@ -527,6 +878,13 @@ fn handle_file_search() -> impl futures::Stream<Item = Message> {
                 Ok(child) => child,
                 Err(error) => {
                     warn!("Failed to spawn mdfind: {error}");
+                    output
+                        .send(Message::Error(
+                            ErrorKind::FileSearch,
+                            format!("Could not run mdfind: {error}"),
+                        ))
+                        .await
+                        .ok();
                     continue;
                 }
             };
@@ -575,69 +933,20 @@ fn handle_recipient() -> impl futures::Stream<Item = Message> {
             .send(Message::SetSender(ExtSender(sender)))
             .await
             .expect("Sender not sent");
-        loop {
-            let abcd = recipient
-                .try_recv()
-                .map(async |msg| {
-                    output.send(msg).await.unwrap();
-                })
-                .ok();
-
-            if let Some(abcd) = abcd {
-                abcd.await;
-            }
-            tokio::time::sleep(Duration::from_millis(100)).await;
+        // Waiting for the next message (instead of checking for one every so often) keeps this
+        // from waking up while nothing is sent
+        while let Some(msg) = recipient.next().await {
+            output.send(msg).await.ok();
         }
     })
 }
 
-fn handle_version_and_rankings() -> impl futures::Stream<Item = Message> {
+fn handle_rankings() -> impl futures::Stream<Item = Message> {
     stream::channel(100, async |mut output| {
-        let current_version = format!("\"{}\"", option_env!("APP_VERSION").unwrap_or(""));
-
-        if current_version.is_empty() {
-            println!("empty version");
-            return;
-        }
-
-        let req = minreq::Request::new(
-            minreq::Method::Get,
-            "https://api.github.com/repos/RustCastLabs/rustcast/releases/latest",
-        )
-        .with_header("User-Agent", "rustcast-update-checker")
-        .with_header("Accept", "application/vnd.github+json")
-        .with_header("X-GitHub-Api-Version", "2022-11-28");
-
         loop {
-            let resp = req
-                .clone()
-                .send()
-                .and_then(|x| x.as_str().map(serde_json::Value::from_str));
-
-            info!("Made a req for latest version");
-
-            if let Ok(Ok(val)) = resp {
-                let new_ver = val
-                    .get("name")
-                    .map(|x| x.to_string())
-                    .unwrap_or("".to_string());
-
-                // new_ver is in the format "\"v0.0.0\""
-                // note that it is encapsulated in double quotes
-                if new_ver.trim() != current_version
-                    && !new_ver.is_empty()
-                    && new_ver.starts_with("\"v")
-                {
-                    info!("new version available: {new_ver}");
-                    output.send(Message::UpdateAvailable).await.ok();
-                }
-            } else {
-                warn!("Error getting resp");
-            }
-            tokio::time::sleep(Duration::from_secs(30)).await;
+            tokio::time::sleep(Duration::from_secs(60)).await;
             output.send(Message::SaveRanking).await.ok();
             info!("Sent save ranking");
-            tokio::time::sleep(Duration::from_secs(30)).await;
         }
     })
 }
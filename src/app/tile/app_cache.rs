@@ -0,0 +1,87 @@
+//! The installed apps saved to disk, so that they can be searched as soon as rustcast starts
+//!
+//! Finding the installed apps (and loading their icons) is slow, so the apps found last time are
+//! loaded when rustcast starts, and are replaced once the apps have been found again. The apps
+//! from the cache don't have icons until then
+use std::fs;
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app::apps::{App, AppCommand},
+    commands::Function,
+    utils::config_path,
+};
+
+/// The file the apps are saved to, in the config directory
+const CACHE_FILE: &str = "app_index.toml";
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct AppCache {
+    apps: Vec<CachedApp>,
+}
+
+/// An installed app, without its icon
+#[derive(Debug, Deserialize, Serialize)]
+struct CachedApp {
+    name: String,
+    search_name: String,
+    desc: String,
+    path: String,
+    #[serde(default)]
+    version: Option<String>,
+}
+
+/// The apps that were found last time, if they were saved
+pub fn load() -> Option<Vec<App>> {
+    let cache: AppCache = toml::from_str(&fs::read_to_string(config_path(CACHE_FILE)).ok()?)
+        .inspect_err(|e| warn!("Could not read the app cache: {e}"))
+        .ok()?;
+    if cache.apps.is_empty() {
+        return None;
+    }
+
+    info!("Loaded {} apps from the cache", cache.apps.len());
+    Some(
+        cache
+            .apps
+            .into_iter()
+            .map(|app| App {
+                ranking: 0,
+                open_command: AppCommand::Function(Function::OpenApp(app.path)),
+                desc: app.desc,
+                icons: None,
+                display_name: app.name,
+                search_name: app.search_name,
+                version: app.version,
+                last_used: None,
+                children: None,
+            })
+            .collect(),
+    )
+}
+
+/// Save the installed apps, so they can be loaded the next time rustcast starts
+pub fn save(apps: &[App]) {
+    let apps = apps
+        .iter()
+        .filter_map(|app| match &app.open_command {
+            AppCommand::Function(Function::OpenApp(path)) => Some(CachedApp {
+                name: app.display_name.clone(),
+                search_name: app.search_name.clone(),
+                desc: app.desc.clone(),
+                path: path.clone(),
+                version: app.version.clone(),
+            }),
+            _ => None,
+        })
+        .collect();
+
+    match toml::to_string(&AppCache { apps }) {
+        Ok(cache) => {
+            fs::write(config_path(CACHE_FILE), cache).ok();
+        }
+        Err(e) => warn!("Could not save the app cache: {e}"),
+    }
+}
@@ -3,50 +3,108 @@
 
 use std::collections::HashMap;
 use std::fs;
+use std::time::Instant;
 
 use iced::border::Radius;
+use iced::widget::image::Handle;
 use iced::widget::scrollable::{Anchor, Direction, Scrollbar};
 use iced::widget::text::LineHeight;
-use iced::widget::{Column, Row, Scrollable, Text, container, space};
-use iced::{Alignment, Color, Length, Vector, window};
+use iced::widget::{Column, Row, Scrollable, Text, container, image, space, stack};
+use iced::{Alignment, Animation, Color, ContentFit, Length, Vector, window};
 use iced::{Element, Task};
 use iced::{Length::Fill, widget::text_input};
 
 use log::info;
-use rayon::iter::ParallelIterator;
 use rayon::slice::ParallelSliceMut;
 
+use crate::app::keybindings::grouped_keybindings;
+use crate::app::pages::clipboard::clipboard_view;
 use crate::app::pages::emoji::emoji_page;
+use crate::app::pages::keybindings::keybindings_page;
 use crate::app::pages::settings::settings_page;
-use crate::app::tile::{AppIndex, Hotkeys};
-use crate::app::{DEFAULT_WINDOW_HEIGHT, ToApp, ToApps};
-use crate::config::Theme;
+use crate::app::tile::{Hotkeys, app_cache};
+use crate::app::{
+    ANIMATION_TIME, DEFAULT_WINDOW_HEIGHT, ErrorKind, FULL_PAGE_LIST_HEIGHT, MAX_CALCULATIONS,
+    MAX_RECENT_EMOJIS, ToApp, ToApps,
+};
+use crate::calculator::Calculation;
+use crate::calendar;
+use crate::clipboard::ClipboardHistory;
+use crate::config::{ConfigDiagnostic, Theme};
 use crate::debounce::Debouncer;
+use crate::i18n::tr;
+use crate::platform;
+use crate::projects;
+use crate::providers;
+use crate::search::Searcher;
 use crate::styles::{
-    contents_style, glass_border, glass_surface, results_scrollbar_style, rustcast_text_input_style,
+    background_dim_style, contents_style, glass_border, glass_surface, results_scrollbar_style,
+    rustcast_text_input_style, scope_chip_style,
 };
-use crate::{app::WINDOW_WIDTH, platform};
-use crate::{app::pages::clipboard::clipboard_view, platform::get_installed_apps};
+use crate::symbols::symbol_apps;
+use crate::timers::Timers;
+use crate::utils::config_path;
 use crate::{
-    app::{Message, Page, apps::App, default_settings, tile::Tile},
+    app::{
+        Message, Page,
+        apps::{App, AppCommand},
+        default_settings,
+        tile::Tile,
+    },
+    commands::Function,
     config::Config,
     platform::transform_process_to_ui_element,
 };
 
 /// Initialise the base window
-pub fn new(hotkeys: Hotkeys, config: &Config) -> (Tile, Task<Message>) {
-    let (id, open) = window::open(default_settings());
+///
+/// `config_diagnostics` are the problems found when loading the config file, which are logged and
+/// reported in the window
+pub fn new(
+    hotkeys: Hotkeys,
+    config: &Config,
+    config_diagnostics: Vec<ConfigDiagnostic>,
+) -> (Tile, Task<Message>) {
+    platform::add_window_rules(&config.window);
+    let (id, open) = window::open(default_settings(&config.window));
     info!("Opening window");
 
-    let open = open.discard().chain(window::run(id, |handle| {
-        platform::window_config(&handle.window_handle().expect("Unable to get window handle"));
+    let hide_from_screen_sharing = config.window.hide_from_screen_sharing;
+    let theme = config.theme.clone();
+    let open = open.discard().chain(window::run(id, move |handle| {
+        let handle = handle.window_handle().expect("Unable to get window handle");
+        platform::window_config(&handle);
+        platform::set_hidden_from_capture(&handle, hide_from_screen_sharing);
+        platform::set_blur(&handle, &theme);
         transform_process_to_ui_element();
     }));
     info!("MacOS platform config applied");
 
-    let store_icons = config.theme.show_icons;
+    let mut tile = initial_tile(hotkeys, config, config_diagnostics.clone());
+    tile.window = Some(id);
+    (
+        tile,
+        Task::batch([
+            open.map(|_| Message::OpenWindow),
+            Task::done(Message::UpdateApps),
+            Task::batch(
+                config_diagnostics
+                    .iter()
+                    .map(|x| Task::done(Message::Error(ErrorKind::Config, x.to_string()))),
+            ),
+        ]),
+    )
+}
 
-    let mut options = get_installed_apps(store_icons);
+/// The tile as rustcast starts, before the window has been opened
+pub(super) fn initial_tile(
+    hotkeys: Hotkeys,
+    config: &Config,
+    config_diagnostics: Vec<ConfigDiagnostic>,
+) -> Tile {
+    // The apps found last time can be searched straight away, while the apps are found again in
+    // the background
+    let mut options = app_cache::load().unwrap_or_default();
 
     options.extend(config.shells.iter().map(|x| x.to_app()));
     info!("Loaded shell commands");
@@ -54,45 +112,131 @@ pub fn new(hotkeys: Hotkeys, config: &Config) -> (Tile, Task<Message>) {
     options.extend(config.modes.to_apps());
     info!("Loaded modes");
 
-    options.extend(App::basic_apps());
+    options.extend(App::basic_apps(config.start_at_login, false));
     info!("Loaded basic apps / default apps");
-    options.par_sort_by_key(|x| x.display_name.len());
-    let options = AppIndex::from_apps(options);
 
-    let home = std::env::var("HOME").unwrap_or("/".to_string());
+    options.extend(projects::project_apps());
+    info!("Loaded recent projects");
+    options.retain(|x| !config.ignores_app(x));
+    options.par_sort_by_key(|x| x.display_name.len());
+    let options = Searcher::from_apps(options);
 
-    let ranking = toml::from_str(
-        &fs::read_to_string(home + "/.config/rustcast/ranking.toml").unwrap_or("".to_string()),
+    let ranking =
+        toml::from_str(&fs::read_to_string(config_path("ranking.toml")).unwrap_or("".to_string()))
+            .unwrap_or(HashMap::new());
+    let learned =
+        toml::from_str(&fs::read_to_string(config_path("learned.toml")).unwrap_or("".to_string()))
+            .unwrap_or(HashMap::new());
+    let last_used = toml::from_str(
+        &fs::read_to_string(config_path("last_used.toml")).unwrap_or("".to_string()),
     )
     .unwrap_or(HashMap::new());
 
-    (
-        Tile {
-            update_available: false,
-            current_mode: "Default".to_string(),
-            query: String::new(),
-            query_lc: String::new(),
-            focus_id: 0,
-            results: vec![],
-            options,
-            hotkeys,
-            emoji_apps: AppIndex::from_apps(App::emoji_apps()),
-            visible: true,
-            frontmost: None,
-            focused: false,
-            config: config.clone(),
-            ranking,
-            theme: config.theme.to_owned().clone().into(),
-            clipboard_content: vec![],
-            tray_icon: None,
-            sender: None,
-            page: Page::Main,
-            height: DEFAULT_WINDOW_HEIGHT,
-            file_search_sender: None,
-            debouncer: Debouncer::new(config.debounce_delay),
-        },
-        Task::batch([open.map(|_| Message::OpenWindow)]),
-    )
+    let recent_emojis = fs::read_to_string(config_path("recent_emojis.txt"))
+        .unwrap_or("".to_string())
+        .lines()
+        .filter(|x| !x.is_empty())
+        .take(MAX_RECENT_EMOJIS)
+        .map(|x| x.to_string())
+        .collect();
+
+    let query_history = fs::read_to_string(config_path("query_history.txt"))
+        .unwrap_or("".to_string())
+        .lines()
+        .filter(|x| !x.is_empty())
+        .take(config.query_history_limit)
+        .map(|x| x.to_string())
+        .collect();
+
+    let calculations = if config.save_calculation_history {
+        fs::read_to_string(config_path("calculations.txt"))
+            .unwrap_or("".to_string())
+            .lines()
+            .filter_map(Calculation::from_line)
+            .take(MAX_CALCULATIONS)
+            .collect()
+    } else {
+        vec![]
+    };
+
+    Tile {
+        available_update: None,
+        current_mode: "Default".to_string(),
+        query: String::new(),
+        query_lc: String::new(),
+        focus_id: 0,
+        results: vec![],
+        options,
+        hotkeys,
+        emoji_apps: emoji_index(config),
+        emoji_category: emojis::Group::SmileysAndEmotion,
+        recent_emojis,
+        visible: true,
+        frontmost: None,
+        focused: false,
+        composing: false,
+        pointer_moved: false,
+        pressed: None,
+        window: None,
+        config: config.clone(),
+        ranking,
+        last_used,
+        learned,
+        theme: config.theme.to_owned().clone().into(),
+        clipboard_content: ClipboardHistory::load(),
+        clipboard_unavailable: None,
+        tray_icon: None,
+        sender: None,
+        page: Page::Main,
+        page_stack: vec![],
+        scope: None,
+        browsing: vec![],
+        height: DEFAULT_WINDOW_HEIGHT,
+        file_search_sender: None,
+        content_search: None,
+        hide_timer: None,
+        fade: Animation::new(true).duration(ANIMATION_TIME),
+        animated_height: Animation::new(DEFAULT_WINDOW_HEIGHT).duration(ANIMATION_TIME),
+        last_activity: Instant::now(),
+        debouncer: Debouncer::new(),
+        command_held: false,
+        query_history,
+        history_index: None,
+        calculations,
+        timers: Timers::default(),
+        confirming: None,
+        lookup_cache: HashMap::new(),
+        lookups: HashMap::new(),
+        keep_awake: None,
+        keep_awake_run: 0,
+        error: None,
+        config_diagnostics,
+        providers: providers::configured(config),
+        running: vec![],
+        events: vec![],
+        events_updated: 0,
+        contacts: vec![],
+        secret: None,
+        indexing: false,
+        index_run: 0,
+    }
+}
+
+/// The steps of working out the focused result, if it is a calculation with more than a number
+fn focused_calculation_steps(tile: &Tile) -> Option<String> {
+    let app = tile.results.get(tile.focus_id as usize)?;
+    let AppCommand::Function(Function::Calculate(expr)) = &app.open_command else {
+        return None;
+    };
+    let steps = expr.steps();
+    (!steps.is_empty()).then(|| steps.join("  →  "))
+}
+
+/// The index searched on the emoji page, containing both emojis and symbols
+pub(super) fn emoji_index(config: &Config) -> Searcher {
+    let mut apps = App::emoji_apps(config.emoji_skin_tone);
+    apps.extend(symbol_apps());
+    Searcher::from_apps(apps)
 }
 
 /// The elm View function that renders the entire rustcast window
@@ -102,6 +246,7 @@ pub fn view(tile: &Tile, wid: window::Id) -> Element<'_, Message> {
             .on_input(move |a| Message::SearchQueryChanged(a, wid))
             .on_paste(move |a| Message::SearchQueryChanged(a, wid))
             .font(tile.config.theme.font())
+            .size(tile.config.theme.font_size)
             .on_submit(Message::OpenFocused)
             .id("query")
             .width(Fill)
@@ -109,6 +254,36 @@ pub fn view(tile: &Tile, wid: window::Id) -> Element<'_, Message> {
             .style(move |_, _| rustcast_text_input_style(&tile.config.theme))
             .padding(20);
 
+        let crumbs = tile.breadcrumbs();
+        let search_bar: Element<'_, Message> = if crumbs.is_empty() {
+            title_input.into()
+        } else {
+            let mut row = Row::new()
+                .padding(iced::Padding::ZERO.left(16))
+                .spacing(6)
+                .align_y(Alignment::Center);
+            for (i, crumb) in crumbs.into_iter().enumerate() {
+                if i > 0 {
+                    row = row.push(
+                        Text::new("›")
+                            .font(tile.config.theme.font())
+                            .size(tile.config.theme.font_size)
+                            .color(tile.config.theme.text_color(0.55)),
+                    );
+                }
+                row = row.push(
+                    container(
+                        Text::new(crumb)
+                            .font(tile.config.theme.font())
+                            .size(tile.config.theme.font_size),
+                    )
+                    .padding([4, 10])
+                    .style(|_| scope_chip_style(&tile.config.theme)),
+                );
+            }
+            row.push(title_input).into()
+        };
+
         let scrollbar_direction =
             if !tile.config.theme.show_scroll_bar || tile.page == Page::Settings {
                 Direction::Vertical(Scrollbar::hidden())
@@ -123,44 +298,74 @@ pub fn view(tile: &Tile, wid: window::Id) -> Element<'_, Message> {
 
         let results = match tile.page {
             Page::ClipboardHistory => clipboard_view(
-                tile.clipboard_content.clone(),
+                tile.clipboard_content.iter().cloned().collect(),
                 tile.focus_id,
                 tile.config.theme.clone(),
+                tile.config.window.width,
+                tile.command_held,
             ),
-            Page::EmojiSearch => emoji_page(
-                tile.config.theme.clone(),
-                tile.emoji_apps
-                    .search_prefix(&tile.query_lc)
-                    .map(|x| x.to_owned())
-                    .collect(),
-                tile.focus_id,
-            ),
+            Page::EmojiSearch => {
+                let browsing = tile.query_lc.is_empty();
+                emoji_page(
+                    tile.config.theme.clone(),
+                    tile.results.clone(),
+                    tile.focus_id,
+                    browsing.then_some(tile.emoji_category),
+                    if browsing {
+                        tile.recent_emojis.len()
+                    } else {
+                        0
+                    },
+                    tile.config.window.width,
+                )
+            }
             Page::Settings => settings_page(tile.config.clone()),
-            Page::FileSearch | Page::Main => container(Column::from_iter(
-                tile.results.iter().enumerate().map(|(i, app)| {
-                    app.clone().render(
+            Page::Keybindings => keybindings_page(&tile.config),
+            Page::FileSearch | Page::Main | Page::CalculationHistory => container(
+                Column::from_iter(tile.results.iter().enumerate().map(|(i, app)| {
+                    App::clone(app).render(
                         tile.config.theme.clone(),
                         i as u32,
                         tile.focus_id,
-                        Some(Message::OpenResult(i as u32)),
+                        Some(wid),
+                        tile.command_held,
+                        tile.is_running(app),
                     )
-                }),
-            ))
+                })),
+            )
             .into(),
         };
 
         let results_count = match &tile.page {
-            Page::Main | Page::EmojiSearch | Page::FileSearch => tile.results.len(),
+            Page::Main | Page::EmojiSearch | Page::FileSearch | Page::CalculationHistory => {
+                tile.results.len()
+            }
             Page::ClipboardHistory => tile.clipboard_content.len(),
-            Page::Settings => 0,
+            Page::Settings | Page::Keybindings => 0,
         };
 
         // This determines the height of the scrollable window
         let height = match tile.page {
-            Page::ClipboardHistory | Page::Settings => 385,
+            Page::ClipboardHistory | Page::Settings | Page::Keybindings => {
+                FULL_PAGE_LIST_HEIGHT as usize
+            }
             // Height of each emoji is EMOJI_HEIGHT + 20 for padding
-            Page::EmojiSearch => std::cmp::min(tile.results.len().div_ceil(6) * 90, 290),
-            _ => std::cmp::min(tile.results.len() * 60, 290),
+            Page::EmojiSearch => {
+                let (tabs_height, recent_rows) = if tile.query_lc.is_empty() {
+                    (50, tile.recent_emojis.len().min(1))
+                } else {
+                    (0, 0)
+                };
+                std::cmp::min(
+                    (tile.results.len().div_ceil(6) + recent_rows) * 90 + tabs_height,
+                    290,
+                )
+            }
+            _ => {
+                let row_height = tile.config.theme.display_mode.list_row_height();
+                let max_height = tile.config.window.max_rows as f32 * row_height - 10.;
+                (tile.results.len() as f32 * row_height).min(max_height) as usize
+            }
         };
 
         let theme = tile.config.theme.clone();
@@ -169,29 +374,39 @@ pub fn view(tile: &Tile, wid: window::Id) -> Element<'_, Message> {
             .id("results")
             .height(height as u32);
 
-        let text = if tile.query_lc.is_empty() {
+        let text = if tile.indexing && tile.page == Page::Main && tile.query_lc.is_empty() {
+            tr!("indexing-apps", count = tile.options.len())
+        } else if tile.query_lc.is_empty() && tile.browsing.is_empty() {
             match &tile.page {
+                Page::Main if tile.config.calendar.show_next_meeting => {
+                    calendar::next_meeting(&tile.events)
+                        .unwrap_or_else(|| tile.config.main_page.to_string())
+                }
                 Page::Main => tile.config.main_page.to_string(),
                 page => page.to_string(),
             }
+        } else if let Some(steps) = focused_calculation_steps(tile) {
+            steps
         } else {
-            match results_count {
-                1 => "1 result found".to_string(),
-                0 => "No results found".to_string(),
-                count => {
-                    format!("{count} results found")
-                }
-            }
+            tr!("results-found", count = results_count)
         };
 
         let contents = container(
             Column::new()
-                .push(title_input)
+                .push(search_bar)
                 .push(scrollable)
                 .push(footer(
                     tile.config.theme.clone(),
                     tile.current_mode.clone(),
                     text,
+                    tile.confirming
+                        .as_ref()
+                        .map(|(question, _)| question.clone())
+                        .or(tile
+                            .error
+                            .as_ref()
+                            .map(|(kind, message)| format!("{kind}: {message}"))),
+                    tile.config.window.width,
                 ))
                 .spacing(0),
         )
@@ -201,28 +416,65 @@ pub fn view(tile: &Tile, wid: window::Id) -> Element<'_, Message> {
             border: iced::Border {
                 color: Color::TRANSPARENT,
                 width: 0.,
-                radius: Radius::new(15),
+                radius: Radius::new(tile.config.theme.advanced.window_radius),
             },
             ..Default::default()
         });
 
-        container(contents)
-            .style(|_| contents_style(&tile.config.theme))
-            .into()
+        let contents = container(contents).style(|_| contents_style(&tile.config.theme));
+
+        match tile.config.theme.background_image_path() {
+            Some(path) => stack![
+                image(Handle::from_path(path))
+                    .content_fit(ContentFit::Cover)
+                    .border_radius(tile.config.theme.advanced.window_radius)
+                    .width(Fill)
+                    .height(Fill),
+                container(space())
+                    .width(Fill)
+                    .height(Fill)
+                    .style(|_| background_dim_style(&tile.config.theme)),
+                contents,
+            ]
+            .into(),
+            None => contents.into(),
+        }
     } else {
         space().into()
     }
 }
 
 /// The footer at the bottom displaying the mode and results found, and its styling
-fn footer(theme: Theme, current_mode: String, text: String) -> Element<'static, Message> {
-    let radius = 15.0;
+///
+/// The keybinding hints in the middle are replaced by the most recent error while there is one,
+/// or the question about an action that is waiting to be confirmed
+fn footer(
+    theme: Theme,
+    current_mode: String,
+    text: String,
+    error: Option<String>,
+    window_width: f32,
+) -> Element<'static, Message> {
+    let radius = theme.advanced.window_radius;
 
     let current_mode = format!(
         "{}{} Mode",
         current_mode.split_at(1).0.to_uppercase(),
         current_mode.split_at(1).1
     );
+
+    let (hints, hints_color) = match error {
+        Some(error) => (error, Color::from_rgb(1.0, 0.2, 0.2)),
+        None => (
+            grouped_keybindings()
+                .into_iter()
+                .filter_map(|(keys, binding)| Some(format!("{keys} {}", binding.hint?)))
+                .collect::<Vec<String>>()
+                .join("   "),
+            theme.text_color(0.5),
+        ),
+    };
+
     container(
         Row::new()
             .push(
@@ -234,13 +486,22 @@ fn footer(theme: Theme, current_mode: String, text: String) -> Element<'static,
                     .align_y(Alignment::Center)
                     .align_x(Alignment::Center),
             )
+            .push(
+                Text::new(hints)
+                    .size(12)
+                    .height(30)
+                    .color(hints_color)
+                    .font(theme.font())
+                    .width(Fill)
+                    .align_y(Alignment::Center)
+                    .align_x(Alignment::Center),
+            )
             .push(
                 Text::new(current_mode)
                     .size(12)
                     .height(30)
                     .color(theme.text_color(0.7))
                     .font(theme.font())
-                    .width(Fill)
                     .align_y(Alignment::Center)
                     .align_x(Alignment::End),
             )
@@ -251,17 +512,14 @@ fn footer(theme: Theme, current_mode: String, text: String) -> Element<'static,
     )
     .align_y(Alignment::Center)
     .center(Length::Fill)
-    .width(WINDOW_WIDTH)
+    .width(window_width)
     .padding(5)
     .height(30)
     .style(move |_| container::Style {
         text_color: None,
-        background: Some(iced::Background::Color(glass_surface(
-            theme.bg_color(),
-            false,
-        ))),
+        background: Some(iced::Background::Color(glass_surface(&theme, false))),
         border: iced::Border {
-            color: glass_border(theme.text_color(1.0), false),
+            color: glass_border(&theme, false),
             width: 0.,
             radius: Radius::new(radius).top(0.0),
         },
@@ -0,0 +1,88 @@
+//! Running the tile's update loop without a window, for tests
+//!
+//! This is only built with the `headless` feature. Messages are sent to [`Headless::send`], which
+//! runs them through [`handle_update`] and then runs the tasks they return, sending the messages
+//! that those tasks produce too. Tasks that need a window (or anything else from iced's runtime)
+//! are dropped, and subscriptions aren't run, so only messages that are sent get handled
+
+use std::{collections::VecDeque, sync::Arc};
+
+use iced::futures::StreamExt;
+use iced_runtime::{Action, task};
+
+use crate::{
+    app::{
+        Message, Page,
+        apps::App,
+        tile::{Hotkeys, Tile, elm::initial_tile, update::handle_update},
+    },
+    config::Config,
+};
+
+/// A tile that is driven by sending it messages, instead of by a window
+pub struct Headless {
+    tile: Tile,
+    runtime: tokio::runtime::Runtime,
+}
+
+impl Headless {
+    /// A tile with the same state that rustcast starts with, without the apps being indexed
+    pub fn new(config: Config) -> Self {
+        Headless {
+            tile: initial_tile(Hotkeys::from_config(&config), &config, vec![]),
+            runtime: tokio::runtime::Builder::new_multi_thread()
+                .enable_all()
+                .build()
+                .expect("the tokio runtime should start"),
+        }
+    }
+
+    /// Handle a message, and every message from the tasks it returns, until there are none left
+    pub fn send(&mut self, message: Message) {
+        let mut queue = VecDeque::from([message]);
+
+        while let Some(message) = queue.pop_front() {
+            let Some(mut stream) = task::into_stream(handle_update(&mut self.tile, message)) else {
+                continue;
+            };
+
+            self.runtime.block_on(async {
+                // Other actions are dropped as they come, since some of them wait for a reply
+                // which would never come while they are kept
+                while let Some(action) = stream.next().await {
+                    if let Action::Output(message) = action {
+                        queue.push_back(message);
+                    }
+                }
+            });
+        }
+    }
+
+    /// Give the tile the apps, as if they had just been indexed
+    pub fn index(&mut self, apps: Vec<App>) {
+        self.send(Message::AppsIndexed(self.tile.index_run, apps));
+    }
+
+    /// Type a query into the search bar
+    pub fn search(&mut self, query: &str) {
+        self.send(Message::SearchQueryChanged(
+            query.to_string(),
+            iced::window::Id::unique(),
+        ));
+    }
+
+    pub fn tile(&self) -> &Tile {
+        &self.tile
+    }
+
+    pub fn results(&self) -> &[Arc<App>] {
+        &self.tile.results
+    }
+
+    pub fn page(&self) -> &Page {
+        &self.tile.page
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,205 @@
+//! Searching, switching pages and copying things through [`handle_update`], with [`Headless`]
+//!
+//! The tests keep their files in a directory of their own (through `$RUSTCAST_CONFIG`), so the
+//! rankings, clipboard history and apps of whoever runs them don't change the results
+//!
+//! [`handle_update`]: crate::app::tile::update::handle_update
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Once},
+};
+
+use iced::futures::FutureExt;
+
+use super::Headless;
+use crate::{
+    app::{ErrorKind, Message, Page, apps::App},
+    clipboard::ClipBoardContentType,
+    config::{Config, ProviderSettings},
+    providers::{Fetch, Lookup, ResultProvider, Search},
+};
+
+/// A tile with its files in the tests' directory, and no apps besides the built in ones
+fn headless(config: Config) -> Headless {
+    static CONFIG_DIR: Once = Once::new();
+    CONFIG_DIR.call_once(|| {
+        let dir = std::env::temp_dir().join(format!("rustcast-tests-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("the config directory should be created");
+        // SAFETY: nothing else in the tests reads or changes the environment
+        unsafe { std::env::set_var("RUSTCAST_CONFIG", dir) };
+    });
+    Headless::new(config)
+}
+
+/// A tile that has indexed a few apps
+fn with_apps() -> Headless {
+    let mut headless = headless(Config::default());
+    headless.index(
+        ["Safari", "Slack", "Terminal", "iTerm"]
+            .into_iter()
+            .map(App::test_app)
+            .collect(),
+    );
+    headless
+}
+
+fn names(headless: &Headless) -> Vec<&str> {
+    headless
+        .results()
+        .iter()
+        .map(|x| x.display_name.as_str())
+        .collect()
+}
+
+#[test]
+fn typing_searches_the_apps() {
+    let mut headless = with_apps();
+
+    headless.search("saf");
+    assert_eq!(names(&headless).first(), Some(&"Safari"));
+
+    headless.search("term");
+    assert_eq!(names(&headless)[..2], ["Terminal", "iTerm"]);
+}
+
+#[test]
+fn calculations_are_the_first_result() {
+    let mut headless = with_apps();
+    headless.search("2 * 21");
+    assert_eq!(names(&headless).first(), Some(&"42"));
+}
+
+#[test]
+fn disabled_providers_give_no_results() {
+    let mut headless = headless(Config {
+        providers: HashMap::from([(
+            "calculator".to_string(),
+            ProviderSettings {
+                enabled: false,
+                priority: None,
+            },
+        )]),
+        ..Config::default()
+    });
+    headless.search("2 * 21");
+    assert!(!names(&headless).contains(&"42"));
+}
+
+#[test]
+fn keyword_providers_can_be_disabled() {
+    let mut enabled = with_apps();
+    enabled.search("case Hello World");
+    assert!(names(&enabled).contains(&"hello world"));
+
+    let mut disabled = headless(Config {
+        providers: HashMap::from([(
+            "case".to_string(),
+            ProviderSettings {
+                enabled: false,
+                priority: None,
+            },
+        )]),
+        ..Config::default()
+    });
+    disabled.search("case Hello World");
+    assert!(!names(&disabled).contains(&"hello world"));
+}
+
+#[test]
+fn question_marks_open_and_close_the_shortcuts() {
+    let mut headless = with_apps();
+
+    headless.search("?");
+    assert_eq!(*headless.page(), Page::Keybindings);
+    assert!(headless.tile().query.is_empty());
+
+    headless.search("?");
+    assert_eq!(*headless.page(), Page::Main);
+}
+
+#[test]
+fn going_back_returns_to_the_page_a_page_was_opened_from() {
+    let mut headless = with_apps();
+
+    headless.send(Message::SwitchToPage(Page::ClipboardHistory));
+    headless.send(Message::SwitchToPage(Page::Settings));
+    assert_eq!(*headless.page(), Page::Settings);
+
+    headless.send(Message::PageBack);
+    assert_eq!(*headless.page(), Page::ClipboardHistory);
+    headless.send(Message::PageBack);
+    assert_eq!(*headless.page(), Page::Main);
+}
+
+#[test]
+fn copied_text_is_kept_in_the_clipboard_history() {
+    let mut headless = with_apps();
+    let copied = ClipBoardContentType::Text("headless clipboard test".to_string());
+
+    headless.send(Message::ClipboardCaptured(copied.clone(), None));
+    assert!(
+        headless
+            .tile()
+            .clipboard_content
+            .iter()
+            .any(|x| x.content == copied)
+    );
+
+    headless.send(Message::ExpireClipboardItem(copied.clone()));
+    assert!(
+        !headless
+            .tile()
+            .clipboard_content
+            .iter()
+            .any(|x| x.content == copied)
+    );
+}
+
+#[test]
+fn conv_converts_snippets() {
+    let mut headless = with_apps();
+
+    headless.search(r#"conv json yaml {"b": [1, 2], "a": "x"}"#);
+    assert_eq!(names(&headless), ["a: x b: - 1 - 2"]);
+
+    headless.search("conv yaml json a: [1, {b: c}]");
+    assert_eq!(names(&headless), [r#"{ "a": [ 1, { "b": "c" } ] }"#]);
+}
+
+/// Looks up a result named after what follows `echo`, which is kept for a minute
+struct Echo;
+
+impl ResultProvider for Echo {
+    fn name(&self) -> &'static str {
+        "echo"
+    }
+
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    fn lookup(&self, search: &Search) -> Option<(Lookup, Fetch)> {
+        let name = search.query.strip_prefix("echo ")?.to_string();
+        Some((
+            Lookup {
+                cache_seconds: 60,
+                ..Lookup::new("echo", ErrorKind::Extension)
+            },
+            async move { Ok(vec![App::test_app(&name)]) }.boxed(),
+        ))
+    }
+}
+
+#[test]
+fn lookups_add_their_results_and_are_cached() {
+    let mut headless = with_apps();
+    headless.tile.providers.push(Arc::new(Echo));
+
+    headless.search("echo Safari");
+    assert_eq!(names(&headless), ["Safari"]);
+
+    // The cached result is shown instead of looking it up again
+    headless.search("echo Slack");
+    assert_eq!(names(&headless), ["Safari"]);
+}
@@ -2,18 +2,16 @@
 use std::cmp::min;
 use std::collections::HashMap;
 use std::fs;
-use std::io::Cursor;
+use std::sync::Arc;
 use std::thread;
+use std::time::{Duration, Instant};
 
-use iced::Task;
-use iced::widget::image::Handle;
 use iced::widget::operation;
 use iced::widget::operation::AbsoluteOffset;
 use iced::window;
 use iced::window::Id;
-use log::info;
-use rayon::iter::IntoParallelRefIterator;
-use rayon::iter::ParallelIterator;
+use iced::{Animation, Task};
+use log::{error, info};
 use rayon::slice::ParallelSliceMut;
 
 use crate::app::Editable;
@@ -22,29 +20,46 @@ use crate::app::SetConfigFields;
 use crate::app::SetConfigThemeFields;
 use crate::app::ToApp;
 use crate::app::ToApps;
-use crate::app::WINDOW_WIDTH;
 use crate::app::apps::App;
 use crate::app::apps::AppCommand;
 use crate::app::default_settings;
 use crate::app::menubar::menu_builder;
 use crate::app::menubar::menu_icon;
-use crate::app::tile::AppIndex;
-use crate::app::{Message, Page, tile::Tile};
-use crate::calculator::Expr;
-use crate::commands::Function;
+use crate::app::tile::app_cache;
+use crate::app::tile::elm::emoji_index;
+use crate::app::{
+    ANIMATION_TIME, ERROR_DISPLAY_TIME, ErrorKind, Message, PAGE_KEYWORDS, Page,
+    tile::{Browsed, Tile},
+};
+use crate::calculator::Calculation;
+use crate::calendar;
+use crate::clipboard::{self, ClipBoardContentType};
+use crate::commands::{Function, ShellCommand};
 use crate::config::Config;
+use crate::config::DisplayMode;
 use crate::config::MainPage;
+use crate::contacts;
+use crate::content_search;
 use crate::debounce::DebouncePolicy;
-use crate::platform::macos::launching::Shortcut;
-use crate::platform::macos::launching::global_handler;
-use crate::platform::macos::{start_at_login, stop_at_login};
-use crate::quit::get_open_apps;
-use crate::unit_conversion;
-use crate::utils::is_valid_url;
+use crate::drag;
+use crate::extensions;
+use crate::i18n::{self, tr};
+use crate::passwords;
+use crate::path_completion;
+use crate::platform;
+use crate::platform::HapticPattern;
+use crate::platform::Shortcut;
+use crate::platform::get_installed_apps;
+use crate::projects;
+use crate::providers::{self, Fetch, Lookup};
+use crate::search::Searcher;
+use crate::timers::clock;
+use crate::utils::{config_dir, config_path, time_ago, unix_now};
 use crate::{app::ArrowKey, platform::focus_this_app};
-use crate::{app::DEFAULT_WINDOW_HEIGHT, platform::perform_haptic};
-use crate::{app::Move, platform::HapticPattern};
-use crate::{app::RUSTCAST_DESC_NAME, platform::get_installed_apps};
+use crate::{
+    app::{DEFAULT_WINDOW_HEIGHT, FULL_PAGE_LIST_HEIGHT},
+    platform::perform_haptic,
+};
 
 /// Handle the "elm" update
 pub fn handle_update(tile: &mut Tile, message: Message) -> Task<Message> {
@@ -55,17 +70,53 @@ pub fn handle_update(tile: &mut Tile, message: Message) -> Task<Message> {
             tile.focused = true;
             tile.visible = true;
 
-            if tile.page == Page::Main && tile.query_lc.is_empty() {
-                window::latest()
-                    .map(|x| x.unwrap())
-                    .map(|id| Message::SearchQueryChanged(String::new(), id))
+            // Listing the processes can take a moment with many of them, so the running apps'
+            // dots are filled in once it is done
+            let running_task = Task::perform(
+                async {
+                    tokio::task::spawn_blocking(platform::running_app_paths)
+                        .await
+                        .unwrap_or_default()
+                },
+                Message::RunningApps,
+            );
+
+            // The events are read again once they are old, so that the footer's next meeting is
+            // up to date
+            let calendar_task = if calendar::is_configured(&tile.config.calendar)
+                && unix_now().saturating_sub(tile.events_updated) >= calendar::CACHE_SECONDS
+            {
+                Task::perform(
+                    calendar::upcoming(tile.config.calendar.clone()),
+                    Message::CalendarEvents,
+                )
             } else {
                 Task::none()
-            }
+            };
+
+            let open_task = if tile.page == Page::Main && tile.query_lc.is_empty() {
+                calendar_task.chain(
+                    window::latest()
+                        .map(|x| x.unwrap())
+                        .map(|id| Message::SearchQueryChanged(String::new(), id)),
+                )
+            } else {
+                calendar_task
+            };
+            Task::batch([running_task, open_task])
         }
 
-        Message::UpdateAvailable => {
-            tile.update_available = true;
+        Message::RunningApps(paths) => {
+            tile.running = paths;
+            Task::none()
+        }
+
+        Message::UpdateAvailable(release) => {
+            if tile.available_update.as_ref() == Some(&release) {
+                return Task::none();
+            }
+            tile.available_update = Some(release);
+            // Reloading rebuilds the tray menu, which then shows the update
             Task::done(Message::ReloadConfig)
         }
 
@@ -74,7 +125,7 @@ pub fn handle_update(tile: &mut Tile, message: Message) -> Task<Message> {
                 tile.current_mode = mode.clone();
                 info!("Switched mode");
                 Task::done(Message::RunFunction(Function::RunShellCommand(
-                    command.to_owned(),
+                    ShellCommand::new(command),
                 )))
             } else {
                 info!("Switching to default mode");
@@ -86,15 +137,14 @@ pub fn handle_update(tile: &mut Tile, message: Message) -> Task<Message> {
         Message::HideTrayIcon => {
             tile.tray_icon = None;
             tile.config.show_trayicon = false;
-            let home = std::env::var("HOME").unwrap();
             let confg_str = toml::to_string(&tile.config).unwrap();
-            thread::spawn(move || fs::write(home + "/.config/rustcast/config.toml", confg_str));
+            thread::spawn(move || fs::write(config_path("config.toml"), confg_str));
             Task::none()
         }
 
         Message::SetSender(sender) => {
             tile.sender = Some(sender.clone());
-            global_handler(sender.clone());
+            platform::listen_for_shortcuts(sender.clone());
             if tile.config.show_trayicon {
                 tile.tray_icon = Some(menu_icon(tile.config.clone(), sender));
             }
@@ -102,17 +152,84 @@ pub fn handle_update(tile: &mut Tile, message: Message) -> Task<Message> {
         }
 
         Message::ToggleAutoStartup(set_to) => {
-            if set_to {
-                start_at_login();
-                tile.config.start_at_login = true
+            if let Err(error) = platform::set_autostart(set_to) {
+                return Task::done(Message::Error(ErrorKind::Autostart, error));
+            }
+            tile.config.start_at_login = set_to;
+            tile.options.insert(App::start_at_login_app(set_to));
+
+            // Show the new state in the results
+            let query = tile.query.clone();
+            window::latest()
+                .map(|x| x.unwrap())
+                .map(move |id| Message::SearchQueryChanged(query.clone(), id))
+        }
+
+        Message::ToggleKeepAwake => {
+            let task = if tile.keep_awake.take().is_some() {
+                info!("Stopped preventing sleep");
+                Task::none()
             } else {
-                stop_at_login();
-                tile.config.start_at_login = false
+                match platform::prevent_sleep() {
+                    Ok(assertion) => {
+                        info!("Preventing sleep");
+                        tile.keep_awake = Some(Arc::new(assertion));
+                        tile.keep_awake_run += 1;
+                        let run = tile.keep_awake_run;
+                        match tile.config.keep_awake_timeout {
+                            0 => Task::none(),
+                            minutes => Task::perform(
+                                tokio::time::sleep(Duration::from_secs(minutes * 60)),
+                                move |_| Message::KeepAwakeTimedOut(run),
+                            ),
+                        }
+                    }
+                    Err(error) => return Task::done(Message::Error(ErrorKind::Launch, error)),
+                }
+            };
+
+            let keeping_awake = tile.keep_awake.is_some();
+            tile.options.insert(App::keep_awake_app(keeping_awake));
+            if let Some(icon) = tile.tray_icon.as_mut()
+                && let Some(sender) = tile.sender.clone()
+            {
+                icon.set_menu(Some(Box::new(menu_builder(
+                    tile.config.clone(),
+                    sender,
+                    tile.available_update.is_some(),
+                    keeping_awake,
+                ))));
             }
-            Task::none()
+
+            // Show the new state in the results
+            let query = tile.query.clone();
+            task.chain(
+                window::latest()
+                    .map(|x| x.unwrap())
+                    .map(move |id| Message::SearchQueryChanged(query.clone(), id)),
+            )
+        }
+
+        Message::KeepAwakeTimedOut(run) => {
+            // Sleep may have been allowed (and prevented again) since this timeout started
+            if tile.keep_awake.is_none() || run != tile.keep_awake_run {
+                return Task::none();
+            }
+            Task::done(Message::ToggleKeepAwake)
         }
 
         Message::EscKeyPressed(id) => {
+            if tile.confirming.take().is_some() {
+                return Task::none();
+            }
+
+            if !tile.browsing.is_empty() {
+                if tile.query.is_empty() {
+                    return browse_back(tile, id);
+                }
+                return Task::done(Message::SearchQueryChanged(String::new(), id));
+            }
+
             if !tile.query_lc.is_empty() {
                 return Task::batch([
                     Task::done(Message::ClearSearchQuery),
@@ -121,12 +238,16 @@ pub fn handle_update(tile: &mut Tile, message: Message) -> Task<Message> {
             }
 
             match tile.page {
+                Page::Main if tile.scope.is_some() => {
+                    tile.scope = None;
+                    return execute_query(tile, id);
+                }
                 Page::Main => {}
                 Page::Settings => {
                     return Task::done(Message::WriteConfig(true));
                 }
                 _ => {
-                    return Task::done(Message::SwitchToPage(Page::Main));
+                    return Task::done(Message::PageBack);
                 }
             }
 
@@ -137,17 +258,12 @@ pub fn handle_update(tile: &mut Tile, message: Message) -> Task<Message> {
                 ])
             } else {
                 tile.page = Page::Main;
+                tile.page_stack.clear();
 
                 Task::batch(vec![
                     Task::done(Message::ClearSearchQuery),
                     Task::done(Message::ClearSearchResults),
-                    window::resize(
-                        id,
-                        iced::Size {
-                            width: WINDOW_WIDTH,
-                            height: DEFAULT_WINDOW_HEIGHT,
-                        },
-                    ),
+                    Task::done(Message::ResizeWindow(id, DEFAULT_WINDOW_HEIGHT)),
                 ])
             }
         }
@@ -155,17 +271,46 @@ pub fn handle_update(tile: &mut Tile, message: Message) -> Task<Message> {
         Message::ClearSearchQuery => {
             tile.query_lc = String::new();
             tile.query = String::new();
+            tile.history_index = None;
+            tile.browsing.clear();
             Task::none()
         }
 
+        Message::BrowseInto(id) => browse_into(tile, id),
+
+        Message::BrowseBack(id) => {
+            // ← moves the cursor while there is a search, so it only goes back without one
+            if !tile.query.is_empty() {
+                return Task::none();
+            }
+            browse_back(tile, id)
+        }
+
         Message::ChangeFocus(key, amount) => {
+            tile.confirming = None;
+            tile.pointer_moved = false;
+            tile.last_activity = Instant::now();
+            if let Some(task) = recall_query(tile, &key) {
+                return task;
+            }
+
+            // ← and → browse out of and into results, when the search bar isn't focused
+            if matches!(tile.page, Page::Main | Page::FileSearch) {
+                match key {
+                    ArrowKey::Left => {
+                        return window::latest().map(|x| Message::BrowseBack(x.unwrap()));
+                    }
+                    ArrowKey::Right => {
+                        return window::latest().map(|x| Message::BrowseInto(x.unwrap()));
+                    }
+                    _ => {}
+                }
+            }
+
             let mut return_task = Task::none();
             for _ in 0..amount {
                 let len = match tile.page {
                     Page::ClipboardHistory => tile.clipboard_content.len() as u32,
-                    Page::EmojiSearch => {
-                        tile.emoji_apps.search_prefix(&tile.query_lc).count() as u32
-                    } // or tile.results.len()
                     _ => tile.results.len() as u32,
                 };
 
@@ -201,9 +346,12 @@ pub fn handle_update(tile: &mut Tile, message: Message) -> Task<Message> {
                 };
 
                 let quantity = match tile.page {
-                    Page::Main | Page::FileSearch | Page::ClipboardHistory => 66.5,
+                    Page::Main
+                    | Page::FileSearch
+                    | Page::ClipboardHistory
+                    | Page::CalculationHistory => tile.config.theme.display_mode.scroll_step(),
                     Page::EmojiSearch => 5.,
-                    Page::Settings => 0.,
+                    Page::Settings | Page::Keybindings => 0.,
                 };
 
                 let (wrapped_up, wrapped_down) = match &key {
@@ -231,24 +379,55 @@ pub fn handle_update(tile: &mut Tile, message: Message) -> Task<Message> {
                     ),
                 ]);
             }
+            announce_focused(tile);
             return_task
         }
 
         Message::ResizeWindow(id, height) => {
             info!("Resizing rustcast window");
             tile.height = height;
+            // The frames apply the animated height, which a hidden window doesn't get
+            if tile.animates() && tile.visible {
+                tile.animated_height.go_mut(height, Instant::now());
+                return Task::none();
+            }
+            tile.animated_height = Animation::new(height).duration(ANIMATION_TIME);
             window::resize(
                 id,
                 iced::Size {
-                    width: WINDOW_WIDTH,
+                    width: tile.config.window.width,
                     height,
                 },
             )
         }
+
+        Message::AnimationFrame(now) => {
+            let Some(id) = tile.window else {
+                return Task::none();
+            };
+
+            let resize = if tile.animated_height.is_animating(now) {
+                let height = tile.animated_height.interpolate_with(|x| x, now);
+                window::resize(
+                    id,
+                    iced::Size {
+                        width: tile.config.window.width,
+                        height,
+                    },
+                )
+            } else {
+                Task::none()
+            };
+            let opacity = tile.fade.interpolate(0.0, 1.0, now);
+            Task::batch([resize, set_opacity(id, opacity)])
+        }
         Message::LoadRanking => {
             for (name, rank) in &tile.ranking {
                 tile.options.set_ranking(name, rank.to_owned());
             }
+            for (name, time) in &tile.last_used {
+                tile.options.set_last_used(name, time.to_owned());
+            }
 
             Task::none()
         }
@@ -256,27 +435,233 @@ pub fn handle_update(tile: &mut Tile, message: Message) -> Task<Message> {
         Message::SaveRanking => {
             tile.ranking = tile.options.get_rankings();
             let string_rep = toml::to_string(&tile.ranking).unwrap_or("".to_string());
-            let ranking_file_path =
-                std::env::var("HOME").unwrap_or("/".to_string()) + "/.config/rustcast/ranking.toml";
-            fs::write(ranking_file_path, string_rep).ok();
+            fs::write(config_path("ranking.toml"), string_rep).ok();
+            tile.last_used = tile.options.get_last_used();
+            let string_rep = toml::to_string(&tile.last_used).unwrap_or("".to_string());
+            fs::write(config_path("last_used.toml"), string_rep).ok();
+            let string_rep = toml::to_string(&tile.learned).unwrap_or("".to_string());
+            fs::write(config_path("learned.toml"), string_rep).ok();
             Task::none()
         }
 
-        Message::OpenFocused => Task::done(Message::OpenResult(tile.focus_id)),
+        Message::OpenFocused => match tile.confirming.take() {
+            Some((_, function)) => Task::done(Message::RunFunction(function)),
+            None => Task::done(Message::OpenResult(tile.focus_id)),
+        },
         Message::OpenResult(id) => open_result(tile, id as usize),
 
+        Message::FocusResult(id) => {
+            tile.last_activity = Instant::now();
+            if tile.focus_id != id {
+                tile.focus_id = id;
+                tile.confirming = None;
+                announce_focused(tile);
+            }
+            Task::none()
+        }
+
+        // Results that move under a pointer that stays still (like when the search changes) don't
+        // take the focus
+        Message::HoverResult(id) => {
+            if tile.pointer_moved {
+                return Task::done(Message::FocusResult(id));
+            }
+            Task::none()
+        }
+
+        Message::PointerMoved => {
+            tile.pointer_moved = true;
+            Task::none()
+        }
+
+        Message::PressResult(id) => {
+            tile.pressed = Some(id);
+            Task::done(Message::FocusResult(id))
+        }
+
+        Message::PointerReleased => {
+            tile.pressed = None;
+            Task::none()
+        }
+
+        // Leaving the result that the button is held on drags it out of the window
+        Message::LeaveResult(id) => {
+            if tile.pressed != Some(id) {
+                return Task::none();
+            }
+            tile.pressed = None;
+            drag_result(tile, id as usize)
+        }
+
+        Message::ShowActions(id, result) => {
+            if let Some(result) = result {
+                tile.focus_id = result;
+            }
+            show_actions(tile, id)
+        }
+
+        // The actions are for the result that they were shown for, so it is focused again first
+        Message::RunAction(id, message) => browse_back(tile, id).chain(Task::done(*message)),
+
+        Message::OpenWithFocused(id) => {
+            let Some(target) = open_with_target(tile) else {
+                return Task::none();
+            };
+            // Finding the apps reads the registry or every desktop entry
+            Task::perform(
+                async move {
+                    let apps = {
+                        let target = target.clone();
+                        tokio::task::spawn_blocking(move || platform::apps_for(&target))
+                            .await
+                            .unwrap_or_default()
+                    };
+                    (target, apps)
+                },
+                move |(target, apps)| Message::OpenWithApps(id, target, apps),
+            )
+        }
+
+        Message::OpenWithApps(id, target, apps) => {
+            // Another result may have been focused (or the search changed) while they were found
+            if open_with_target(tile).as_ref() != Some(&target) {
+                return Task::none();
+            }
+
+            let name = std::path::Path::new(&target)
+                .file_name()
+                .map(|x| x.to_string_lossy().to_string())
+                .filter(|_| !target.contains("://"))
+                .unwrap_or(target.clone());
+            // The results are replaced by the apps, until the search changes
+            tile.results = apps
+                .into_iter()
+                .map(|(app, path)| {
+                    Arc::new(App {
+                        ranking: 0,
+                        open_command: AppCommand::Function(Function::OpenWith(
+                            target.clone(),
+                            path,
+                        )),
+                        desc: format!("Open {name} with"),
+                        icons: None,
+                        display_name: app,
+                        search_name: String::new(),
+                        version: None,
+                        last_used: None,
+                        children: None,
+                    })
+                })
+                .collect();
+            tile.focus_id = 0;
+            resize_for_results_count(id, tile.results.len(), &tile.config)
+        }
+
+        Message::QuitFocused(force) => {
+            let Some(app) = tile
+                .results
+                .get(tile.focus_id as usize)
+                .filter(|app| tile.page == Page::Main && tile.is_running(app))
+            else {
+                return Task::none();
+            };
+            let AppCommand::Function(Function::OpenApp(path)) = &app.open_command else {
+                return Task::none();
+            };
+
+            let result = if force {
+                Function::ForceQuitAppAt(path.clone())
+            } else {
+                Function::QuitAppAt(path.clone())
+            }
+            .execute(&tile.config);
+            if let Err(error) = result {
+                return Task::done(Message::Error(ErrorKind::Launch, error));
+            }
+            let path = path.clone();
+            tile.running.retain(|x| !platform::runs_from(x, &path));
+            Task::none()
+        }
+
+        Message::UninstallFocused => {
+            let Some(app) = tile
+                .results
+                .get(tile.focus_id as usize)
+                .filter(|_| tile.page == Page::Main)
+            else {
+                return Task::none();
+            };
+            let AppCommand::Function(Function::OpenApp(path)) = &app.open_command else {
+                return Task::none();
+            };
+            if !platform::can_uninstall(path) {
+                return Task::none();
+            }
+
+            tile.confirming = Some((
+                tr!("confirm-uninstall", name = app.display_name.clone()),
+                Function::Uninstall(path.clone()),
+            ));
+            Task::none()
+        }
+
+        Message::HideFocusedResult => {
+            let Some(app) = tile
+                .results
+                .get(tile.focus_id as usize)
+                .filter(|_| tile.page == Page::Main)
+                .cloned()
+            else {
+                return Task::none();
+            };
+            if tile.options.get(&app.search_name).is_none() {
+                return Task::none();
+            }
+
+            info!("Hiding {}", app.display_name);
+            tile.options.remove(&app.search_name);
+            tile.results.remove(tile.focus_id as usize);
+            tile.focus_id = tile
+                .focus_id
+                .min(tile.results.len().saturating_sub(1) as u32);
+            tile.config.ignore_apps.push(app.display_name.clone());
+            Task::done(Message::WriteConfig(false))
+        }
+
+        Message::RevealFocused => {
+            let path = tile
+                .results
+                .get(tile.focus_id as usize)
+                .filter(|_| matches!(tile.page, Page::Main | Page::FileSearch))
+                .and_then(|app| match &app.open_command {
+                    AppCommand::Function(
+                        Function::OpenApp(path) | Function::OpenAtLine(path, _),
+                    ) => Some(path.clone()),
+                    _ => None,
+                });
+
+            // Results that aren't apps or files are opened as usual
+            match path {
+                Some(path) => Task::done(Message::RunFunction(Function::Reveal(path))),
+                None => Task::done(Message::OpenFocused),
+            }
+        }
+
         Message::ReloadConfig => {
             info!("Reloading config");
-            let new_config: Config = match toml::from_str(
-                &fs::read_to_string(
-                    std::env::var("HOME").unwrap_or("".to_owned())
-                        + "/.config/rustcast/config.toml",
-                )
-                .unwrap_or("".to_owned()),
-            ) {
+            let (mut new_config, diagnostics) = match Config::load(&config_dir()) {
                 Ok(a) => a,
-                Err(_) => return Task::none(),
+                Err(diagnostic) => {
+                    tile.config_diagnostics = vec![diagnostic.clone()];
+                    return Task::done(Message::Error(ErrorKind::Config, diagnostic.to_string()));
+                }
             };
+            tile.config_diagnostics = diagnostics;
+            let diagnostics_task = Task::batch(
+                tile.config_diagnostics
+                    .iter()
+                    .map(|x| Task::done(Message::Error(ErrorKind::Config, x.to_string()))),
+            );
 
             if let Ok(hotkey) = Shortcut::parse(&new_config.clipboard_hotkey) {
                 tile.hotkeys.clipboard_hotkey = hotkey
@@ -296,7 +681,9 @@ pub fn handle_update(tile: &mut Tile, message: Message) -> Task<Message> {
 
             tile.hotkeys.shells = shell_map;
 
-            let update_apps_task = if tile.config.shells != new_config.shells {
+            let update_apps_task = if tile.config.shells != new_config.shells
+                || tile.config.ignore_apps != new_config.ignore_apps
+            {
                 info!("App Update required");
                 Task::done(Message::UpdateApps)
             } else {
@@ -309,7 +696,8 @@ pub fn handle_update(tile: &mut Tile, message: Message) -> Task<Message> {
                 icon.set_menu(Some(Box::new(menu_builder(
                     new_config.clone(),
                     tile.sender.clone().unwrap(),
-                    tile.update_available,
+                    tile.available_update.is_some(),
+                    tile.keep_awake.is_some(),
                 ))));
             } else {
                 tile.tray_icon = Some(menu_icon(new_config.clone(), tile.sender.clone().unwrap()));
@@ -320,16 +708,25 @@ pub fn handle_update(tile: &mut Tile, message: Message) -> Task<Message> {
                     .ok();
             }
 
+            if tile.config.emoji_skin_tone != new_config.emoji_skin_tone {
+                tile.emoji_apps = emoji_index(&new_config);
+            }
+
             tile.theme = new_config.theme.to_owned().into();
+            new_config.start_at_login = platform::autostart_enabled();
+            i18n::set_language(new_config.language.as_deref());
+            tile.providers = providers::configured(&new_config);
             tile.config = new_config;
-            Task::batch([Task::done(Message::LoadRanking), update_apps_task])
+            Task::batch([
+                Task::done(Message::LoadRanking),
+                update_apps_task,
+                diagnostics_task,
+            ])
         }
 
         Message::KeyPressed(shortcut) => {
             if let Some(cmd) = tile.hotkeys.shells.get(&shortcut) {
-                return Task::done(Message::RunFunction(Function::RunShellCommand(
-                    cmd.command.clone(),
-                )));
+                return Task::done(Message::RunFunction(cmd.function(cmd.command.clone())));
             }
 
             let is_clipboard_hotkey = shortcut == tile.hotkeys.clipboard_hotkey;
@@ -348,11 +745,12 @@ pub fn handle_update(tile: &mut Tile, message: Message) -> Task<Message> {
             if is_open_hotkey || is_clipboard_hotkey {
                 if !tile.visible {
                     tile.height = if is_clipboard_hotkey {
-                        ((7 * 55) + 35 + DEFAULT_WINDOW_HEIGHT as usize) as f32
+                        full_page_height()
                     } else {
                         DEFAULT_WINDOW_HEIGHT
                     };
-                    return Task::batch([open_window(tile.height), clipboard_page_task]);
+                    let height = tile.height;
+                    return Task::batch([show_window(tile, height), clipboard_page_task]);
                 }
 
                 tile.visible = !tile.visible;
@@ -374,65 +772,133 @@ pub fn handle_update(tile: &mut Tile, message: Message) -> Task<Message> {
             }
         }
 
+        Message::ShowWindow => {
+            if tile.visible {
+                Task::done(Message::OpenWindow)
+            } else {
+                Task::done(Message::KeyPressed(tile.hotkeys.toggle.clone()))
+            }
+        }
+
         Message::OpenToSettings => {
             tile.page = Page::Settings;
-            Task::batch([
-                Task::done(Message::OpenWindow),
-                open_window(((7 * 55) + 35 + DEFAULT_WINDOW_HEIGHT as usize) as f32),
-            ])
+            tile.page_stack.clear();
+            show_window(tile, full_page_height())
         }
 
         Message::SwitchToPage(page) => {
-            let task = match &page {
-                Page::ClipboardHistory => {
-                    if !tile.config.cbhist {
-                        return Task::none();
-                    }
-                    window::latest().map(|x| {
-                        let id = x.unwrap();
-                        Message::ResizeWindow(
-                            id,
-                            ((7 * 55) + 35 + DEFAULT_WINDOW_HEIGHT as usize) as f32,
-                        )
-                    })
+            let previous = tile.page.clone();
+            let task = show_page(tile, page);
+
+            // Pages open on top of the one they were opened from, which going back returns to.
+            // Opening one that is already open under it goes back to it, and opening the main
+            // page starts over
+            if tile.page == Page::Main {
+                tile.page_stack.clear();
+            } else if let Some(i) = tile.page_stack.iter().position(|x| *x == tile.page) {
+                tile.page_stack.truncate(i);
+            } else if tile.page != previous {
+                tile.page_stack.push(previous);
+            }
+            task
+        }
+
+        Message::PageBack => {
+            let page = tile.page_stack.pop().unwrap_or(Page::Main);
+            show_page(tile, page)
+        }
+
+        Message::EnterScope(keyword, id) => {
+            if let Some((_, page)) = PAGE_KEYWORDS.iter().find(|(x, _)| *x == keyword) {
+                let task = handle_update(tile, Message::SwitchToPage(page.clone()));
+                // Switching pages can be refused, like when clipboard history is off
+                if tile.page == *page {
+                    tile.scope = Some(keyword);
                 }
-                Page::Settings => window::latest().map(|x| {
-                    let id = x.unwrap();
-                    Message::ResizeWindow(
-                        id,
-                        ((7 * 55) + 35 + DEFAULT_WINDOW_HEIGHT as usize) as f32,
-                    )
-                }),
-                _ => Task::none(),
-            };
+                return task;
+            }
 
-            tile.page = page;
+            tile.page = Page::Main;
+            tile.page_stack.clear();
+            tile.scope = Some(keyword);
+            tile.query = String::new();
+            tile.query_lc = String::new();
+            tile.focus_id = 0;
+            execute_query(tile, id)
+        }
 
-            let refresh_empty_main_query = if tile.page == Page::Main {
-                window::latest()
-                    .map(|x| x.unwrap())
-                    .map(|id| Message::SearchQueryChanged(String::new(), id))
-            } else {
-                Task::none()
-            };
+        Message::SetEmojiCategory(category) => {
+            tile.emoji_category = category;
+            tile.focus_id = 0;
+            tile.results = tile
+                .recent_emoji_apps()
+                .into_iter()
+                .chain(App::emoji_category_apps(
+                    category,
+                    tile.config.emoji_skin_tone,
+                ))
+                .map(Arc::new)
+                .collect();
+            operation::scroll_to(
+                "results",
+                AbsoluteOffset {
+                    x: None,
+                    y: Some(0.),
+                },
+            )
+        }
 
-            Task::batch([
-                Task::done(Message::ClearSearchQuery),
-                Task::done(Message::ClearSearchResults),
-                task,
-                refresh_empty_main_query,
-            ])
+        Message::RunFunction(Function::Uninstall(path)) => {
+            // Finding the uninstaller on Windows runs `reg query` for each installed app, so it is
+            // run in the background, and the window is only hidden once it started, so that an
+            // error can still be seen
+            let hide = tile.config.buffer_rules.clear_on_enter && tile.visible;
+            Task::future(async move {
+                tokio::task::spawn_blocking(move || platform::uninstall_app(&path))
+                    .await
+                    .unwrap_or_else(|e| Err(e.to_string()))
+            })
+            .then(move |result| match result {
+                Err(error) => Task::done(Message::Error(ErrorKind::Launch, error)),
+                Ok(()) if hide => window::latest()
+                    .map(|x| x.unwrap())
+                    .map(Message::HideWindow)
+                    .chain(Task::done(Message::ClearSearchQuery))
+                    .chain(Task::done(Message::ReturnFocus)),
+                Ok(()) => Task::none(),
+            })
         }
 
         Message::RunFunction(command) => {
-            command.execute(&tile.config);
+            // Keep the window open so that the error can be seen
+            if let Err(error) = command.execute(&tile.config) {
+                return Task::done(Message::Error(ErrorKind::Launch, error));
+            }
+            if tile.page == Page::EmojiSearch
+                && let Function::CopyToClipboard(ClipBoardContentType::Text(emoji)) = &command
+            {
+                tile.record_recent_emoji(emoji.to_owned());
+            }
+            if tile.page == Page::Main
+                && let Function::Calculate(expr) = &command
+                && let Some(result) = expr.eval()
+            {
+                tile.record_calculation(Calculation {
+                    expression: tile.query.trim().to_string(),
+                    result: result.to_string(),
+                });
+            }
+
             let page_task = match tile.page {
                 Page::Settings => Task::done(Message::SwitchToPage(Page::Main)),
                 _ => Task::none(),
             };
 
             let return_focus_task = match &command {
-                Function::OpenApp(_) | Function::GoogleSearch(_) => Task::none(),
+                Function::OpenApp(_)
+                | Function::Reveal(_)
+                | Function::GoogleSearch(_)
+                | Function::SearchWith(..) => Task::none(),
                 _ => Task::done(Message::ReturnFocus),
             };
 
@@ -454,11 +920,151 @@ pub fn handle_update(tile: &mut Tile, message: Message) -> Task<Message> {
             }
             info!("Hiding RustCast window");
             tile.visible = false;
+            tile.hide_timer = None;
             tile.focused = false;
+            tile.composing = false;
+            tile.command_held = false;
             tile.page = Page::Main;
+            tile.page_stack.clear();
+            tile.scope = None;
             tile.focus_id = 0;
 
-            Task::batch([window::close(a), Task::done(Message::ClearSearchResults)])
+            // The window is hidden as it finishes fading out, and is made opaque again while hidden
+            let hide = window::set_mode(a, window::Mode::Hidden).chain(set_opacity(a, 1.0));
+            let hide = if tile.animates() {
+                tile.fade.go_mut(false, Instant::now());
+                let (hide, handle) = Task::perform(tokio::time::sleep(ANIMATION_TIME), |_| ())
+                    .discard()
+                    .chain(hide)
+                    .abortable();
+                // Showing the window again before then cancels hiding it
+                tile.hide_timer = Some(handle.abort_on_drop());
+                hide
+            } else {
+                hide
+            };
+            Task::batch([hide, Task::done(Message::ClearSearchResults)])
+        }
+
+        // The window is only closed when something else closes it, so the next one is opened anew
+        Message::WindowClosed(id) => {
+            if tile.window == Some(id) {
+                tile.window = None;
+            }
+            Task::done(Message::HideWindow(id))
+        }
+
+        Message::CompleteQuery(id) => {
+            if tile.page == Page::CalculationHistory {
+                // Put the calculation back into the search, so that it can be changed
+                let Some(app) = tile.results.get(tile.focus_id as usize) else {
+                    return Task::none();
+                };
+                let expression = app.search_name.clone();
+                tile.page = Page::Main;
+                tile.page_stack.clear();
+                return Task::done(Message::SearchQueryChanged(expression, id))
+                    .chain(operation::focus("query"))
+                    .chain(operation::move_cursor_to_end("query"));
+            }
+            // Results with children are browsed into, except for paths, which Tab completes
+            if tile
+                .results
+                .get(tile.focus_id as usize)
+                .is_some_and(|x| x.children.is_some())
+                && !path_completion::is_path(&tile.query)
+            {
+                return browse_into(tile, id);
+            }
+            if tile.page != Page::Main {
+                return Task::none();
+            }
+            let Some(app) = tile.results.get(tile.focus_id as usize) else {
+                return Task::none();
+            };
+
+            // Like shell completion, leave a space after the name so arguments can be typed, or
+            // complete the path (going into folders) when browsing files
+            let completed = if path_completion::is_path(&tile.query) {
+                app.search_name.clone()
+            } else {
+                format!("{} ", app.display_name)
+            };
+            Task::done(Message::SearchQueryChanged(completed, id))
+                .chain(operation::focus("query"))
+                .chain(operation::move_cursor_to_end("query"))
+        }
+
+        Message::Error(kind, message) => {
+            error!("{kind}: {message}");
+            tile.error = Some((kind, message.clone()));
+            Task::perform(
+                async { tokio::time::sleep(ERROR_DISPLAY_TIME).await },
+                move |_| Message::DismissError(message.clone()),
+            )
+        }
+
+        Message::DismissError(message) => {
+            // A newer error may have replaced this one, and is dismissed after its own delay
+            if tile.error.as_ref().is_some_and(|(_, x)| *x == message) {
+                tile.error = None;
+            }
+            Task::none()
+        }
+
+        Message::ClearQueryHistory => {
+            tile.clear_query_history();
+            Task::none()
+        }
+
+        Message::StartTimer(duration, label) => {
+            let id = tile.timers.start(duration, label);
+            info!("Started a timer for {duration:?}");
+            Task::batch([
+                hide_window_task(),
+                Task::perform(
+                    async move { tokio::time::sleep(duration).await },
+                    move |_| Message::TimerFinished(id),
+                ),
+            ])
+        }
+
+        Message::CancelTimer(timer_id) => {
+            tile.timers.remove(timer_id);
+            let query = tile.query.clone();
+            window::latest()
+                .map(|x| x.unwrap())
+                .map(move |id| Message::SearchQueryChanged(query.clone(), id))
+        }
+
+        Message::TimerFinished(timer_id) => {
+            // Cancelled timers are already removed
+            let Some(timer) = tile.timers.remove(timer_id) else {
+                return Task::none();
+            };
+            match timer.notify(tile.config.timer_sound) {
+                Ok(()) => Task::none(),
+                Err(error) => Task::done(Message::Error(ErrorKind::Notification, error)),
+            }
+        }
+
+        Message::StartStopwatch => {
+            tile.timers.start_stopwatch();
+            hide_window_task()
+        }
+
+        Message::StopStopwatch => {
+            let Some(elapsed) = tile.timers.stop_stopwatch() else {
+                return Task::none();
+            };
+            Task::done(Message::RunFunction(Function::CopyToClipboard(
+                ClipBoardContentType::Text(clock(elapsed)),
+            )))
+        }
+
+        Message::ModifiersChanged(modifiers) => {
+            tile.command_held = modifiers.command();
+            Task::none()
         }
 
         Message::ReturnFocus => {
@@ -467,29 +1073,15 @@ pub fn handle_update(tile: &mut Tile, message: Message) -> Task<Message> {
             Task::none()
         }
 
-        Message::FocusTextInput(update_query_char) => {
-            match update_query_char {
-                Move::Forwards(query_char) => {
-                    tile.query += &query_char.clone();
-                    tile.query_lc += &query_char.clone().to_lowercase();
-                }
-                Move::Back => {
-                    tile.query.pop();
-                    tile.query_lc.pop();
-                }
-            }
+        Message::FocusTextInput => operation::focus("query"),
 
-            let updated_query = tile.query.clone();
-            Task::batch([
-                operation::focus("query"),
-                window::latest()
-                    .map(|x| x.unwrap())
-                    .map(move |x| Message::SearchQueryChanged(updated_query.clone(), x)),
-            ])
+        Message::ImeComposing(composing) => {
+            tile.composing = composing;
+            Task::none()
         }
 
         Message::ToggleFavouriteApp(app_name) => {
-            let ranking = match tile.options.by_name.get(&app_name) {
+            let ranking = match tile.options.get(&app_name) {
                 None => return Task::none(),
                 Some(app) => {
                     if app.ranking == -1 {
@@ -504,12 +1096,20 @@ pub fn handle_update(tile: &mut Tile, message: Message) -> Task<Message> {
         }
 
         Message::UpdateApps => {
-            let mut new_options = get_installed_apps(tile.config.theme.show_icons);
-            new_options.extend(tile.config.shells.iter().map(|x| x.to_app()));
-            new_options.extend(tile.config.modes.to_apps());
-            new_options.extend(App::basic_apps());
-            new_options.par_sort_by_key(|x| x.display_name.len());
-            tile.options = AppIndex::from_apps(new_options);
+            // Starting again replaces a run that is still going, whose apps are then ignored
+            tile.index_run += 1;
+            tile.indexing = true;
+            let run = tile.index_run;
+            let store_icons = tile.config.theme.show_icons;
+            info!("Indexing apps");
+            let index_task = Task::perform(
+                async move {
+                    tokio::task::spawn_blocking(move || get_installed_apps(store_icons))
+                        .await
+                        .unwrap_or_default()
+                },
+                move |apps| Message::AppsIndexed(run, apps),
+            );
 
             let mut shell_map = HashMap::new();
 
@@ -517,102 +1117,415 @@ pub fn handle_update(tile: &mut Tile, message: Message) -> Task<Message> {
                 if let Some(has_hk) = &shell.hotkey
                     && let Some(hotkey) = Shortcut::parse(has_hk).ok()
                 {
-                    shell_map.insert(hotkey, shell.clone());
+                    shell_map.insert(hotkey, shell.clone());
+                }
+            }
+
+            tile.hotkeys.shells = shell_map;
+
+            if tile.config.search_contacts {
+                return Task::batch([
+                    index_task,
+                    Task::perform(contacts::load(), Message::ContactsLoaded),
+                ]);
+            }
+            tile.contacts.clear();
+            index_task
+        }
+
+        Message::ContactsLoaded(result) => match result {
+            Ok(loaded) => {
+                info!("Read {} contacts", loaded.len());
+                tile.contacts = loaded;
+                Task::none()
+            }
+            Err(error) => Task::done(Message::Error(ErrorKind::Contacts, error)),
+        },
+
+        Message::AppsIndexed(run, installed) => {
+            if run != tile.index_run {
+                info!("Ignoring the apps from an indexing run that was replaced");
+                return Task::none();
+            }
+            info!("Indexed {} apps", installed.len());
+            tile.indexing = false;
+            app_cache::save(&installed);
+
+            let mut new_options = installed;
+            new_options.extend(tile.config.shells.iter().map(|x| x.to_app()));
+            new_options.extend(tile.config.modes.to_apps());
+            new_options.extend(App::basic_apps(
+                tile.config.start_at_login,
+                tile.keep_awake.is_some(),
+            ));
+            new_options.extend(projects::project_apps());
+            new_options.retain(|x| !tile.config.ignores_app(x));
+            new_options.par_sort_by_key(|x| x.display_name.len());
+
+            let rankings = tile.options.get_rankings();
+            let last_used = tile.options.get_last_used();
+            tile.options = Searcher::from_apps(new_options);
+            for (name, rank) in rankings {
+                tile.options.set_ranking(&name, rank);
+            }
+            for (name, time) in last_used {
+                tile.options.set_last_used(&name, time);
+            }
+
+            Task::none()
+        }
+
+        Message::ClearSearchResults => {
+            tile.results = Vec::new();
+            Task::none()
+        }
+        Message::WindowFocusChanged(wid, focused) => {
+            tile.focused = focused;
+            let grace = tile.config.window.focus_loss_grace_ms;
+            if focused && !tile.visible {
+                // The window was clicked while it fades out
+                Task::none()
+            } else if focused {
+                // Getting the focus back in time cancels hiding, and starts the idle timer again
+                tile.last_activity = Instant::now();
+                tile.hide_timer = None;
+                match tile.config.window.auto_hide_seconds {
+                    0 => Task::none(),
+                    seconds => hide_after(tile, wid, Duration::from_secs(seconds)),
+                }
+            } else if grace == 0 {
+                Task::done(Message::HideWindow(wid)).chain(Task::done(Message::ClearSearchQuery))
+            } else {
+                hide_after(tile, wid, Duration::from_millis(grace))
+            }
+        }
+
+        Message::HideTimeout(wid) => {
+            tile.hide_timer = None;
+            if !tile.visible {
+                return Task::none();
+            }
+
+            // Anything done while the idle timer ran starts it again for the time that is left
+            let idle = Duration::from_secs(tile.config.window.auto_hide_seconds);
+            let elapsed = tile.last_activity.elapsed();
+            if tile.focused && elapsed < idle {
+                return hide_after(tile, wid, idle - elapsed);
+            }
+            Task::done(Message::HideWindow(wid)).chain(Task::done(Message::ClearSearchQuery))
+        }
+
+        Message::EditClipboardHistory(action) => {
+            if !tile.clipboard_history_on() {
+                return Task::none();
+            }
+            match action {
+                Editable::Create(content) => {
+                    tile.clipboard_content
+                        .add(content, None, tile.config.clipboard_history_limit);
+                }
+                Editable::Delete(content) => tile.clipboard_content.remove(&content),
+                Editable::Update { old, new } => tile.clipboard_content.update(&old, new),
+            }
+            Task::none()
+        }
+
+        Message::ClipboardUnavailable(reason) => {
+            tile.clipboard_unavailable = Some(reason);
+            if tile.page == Page::ClipboardHistory {
+                return Task::done(Message::SwitchToPage(Page::Main));
+            }
+            Task::none()
+        }
+
+        Message::ClipboardCaptured(content, source) => {
+            if !tile.clipboard_history_on() {
+                return Task::none();
+            }
+
+            if let ClipBoardContentType::Text(text) = &content
+                && tile.secret.as_ref() == Some(text)
+            {
+                info!("Leaving the copied secret out of the clipboard history");
+                return Task::none();
+            }
+
+            let privacy = &tile.config.clipboard_privacy;
+            if let Some(source) = &source
+                && privacy.ignores(source)
+            {
+                info!("Ignoring clipboard item copied from {}", source.name);
+                return Task::none();
+            }
+
+            let expiry = Duration::from_secs(privacy.secret_expiry);
+            let expires = privacy.expire_secrets && content.looks_secret(&privacy.secret_keywords);
+
+            tile.clipboard_content.add(
+                content.to_owned(),
+                source,
+                tile.config.clipboard_history_limit,
+            );
+
+            if !expires {
+                return Task::none();
+            }
+            info!("Clipboard item looks like a secret, expiring it in {expiry:?}");
+            Task::perform(async move { tokio::time::sleep(expiry).await }, move |_| {
+                Message::ExpireClipboardItem(content.clone())
+            })
+        }
+
+        Message::ExpireClipboardItem(content) => {
+            tile.clipboard_content.expire(&content);
+            Task::none()
+        }
+
+        Message::TogglePinClipboardItem(content) => {
+            tile.clipboard_content.toggle_pin(&content);
+            if let Some(index) = tile
+                .clipboard_content
+                .iter()
+                .position(|x| x.content == content)
+            {
+                tile.focus_id = index as u32;
+            }
+            Task::none()
+        }
+
+        Message::PasteFocused(plain_text) => {
+            if tile.page != Page::ClipboardHistory {
+                return Task::none();
+            }
+            match tile.clipboard_content.iter().nth(tile.focus_id as usize) {
+                Some(entry) => Task::done(Message::PasteClipboardItem(
+                    entry.content.to_owned(),
+                    plain_text,
+                )),
+                None => Task::none(),
+            }
+        }
+
+        Message::PasteClipboardItem(content, plain_text) => {
+            // Plain text is pasted by only putting the text on the clipboard (which is all that
+            // the history keeps of text) and pasting it as usual, so images have none to paste
+            if plain_text && matches!(content, ClipBoardContentType::Image(_)) {
+                return Task::none();
+            }
+            if let Err(error) = Function::CopyToClipboard(content).execute(&tile.config) {
+                return Task::done(Message::Error(ErrorKind::Launch, error));
+            }
+
+            // The previous app needs a moment to become frontmost again before it can receive
+            // the paste keystroke
+            let paste_task = Task::perform(
+                async { tokio::time::sleep(Duration::from_millis(150)).await },
+                |_| Message::SynthesizePaste,
+            );
+
+            window::latest()
+                .map(|x| x.unwrap())
+                .map(Message::HideWindow)
+                .chain(Task::done(Message::ClearSearchQuery))
+                .chain(Task::done(Message::ReturnFocus))
+                .chain(paste_task)
+        }
+
+        Message::TransformClipboardItem(content) => {
+            if let Err(error) = Function::CopyToClipboard(content).execute(&tile.config) {
+                return Task::done(Message::Error(ErrorKind::Launch, error));
+            }
+            // `case` on its own transforms the text in the clipboard
+            tile.page = Page::Main;
+            tile.page_stack.clear();
+            window::latest()
+                .map(|x| x.unwrap())
+                .map(|id| Message::SearchQueryChanged("case".to_string(), id))
+                .chain(operation::focus("query"))
+                .chain(operation::move_cursor_to_end("query"))
+        }
+
+        Message::ExtractClipboardText(content) => {
+            let ClipBoardContentType::Image(image) = content else {
+                return Task::none();
+            };
+            info!("Extracting the text from a clipboard image");
+            Task::perform(
+                async move {
+                    tokio::task::spawn_blocking(move || clipboard::extract_text(&image))
+                        .await
+                        .unwrap_or_else(|e| Err(e.to_string()))
+                },
+                Message::ClipboardTextExtracted,
+            )
+        }
+
+        Message::ClipboardTextExtracted(result) => match result {
+            Ok(text) if text.trim().is_empty() => Task::done(Message::Error(
+                ErrorKind::TextRecognition,
+                "There is no text in the image".to_string(),
+            )),
+            Ok(text) => {
+                let content = ClipBoardContentType::Text(text);
+                if let Err(error) = Function::CopyToClipboard(content.clone()).execute(&tile.config)
+                {
+                    return Task::done(Message::Error(ErrorKind::Launch, error));
+                }
+                if tile.clipboard_history_on() {
+                    tile.clipboard_content.add(
+                        content.clone(),
+                        None,
+                        tile.config.clipboard_history_limit,
+                    );
+                    if let Some(index) = tile
+                        .clipboard_content
+                        .iter()
+                        .position(|x| x.content == content)
+                    {
+                        tile.focus_id = index as u32;
+                    }
                 }
+                Task::none()
             }
+            Err(error) => Task::done(Message::Error(ErrorKind::TextRecognition, error)),
+        },
+
+        Message::SynthesizePaste => {
+            info!("Pasting into the frontmost app");
+            // Running xdotool or wtype can take a moment
+            Task::perform(
+                async {
+                    tokio::task::spawn_blocking(platform::paste)
+                        .await
+                        .unwrap_or_else(|e| Err(e.to_string()))
+                },
+                Result::err,
+            )
+            .and_then(|e| Task::done(Message::Error(ErrorKind::Paste, e)))
+        }
 
-            tile.hotkeys.shells = shell_map;
-
+        Message::SetFileSearchSender(sender) => {
+            tile.file_search_sender = Some(sender);
             Task::none()
         }
 
-        Message::ClearSearchResults => {
-            tile.results = Vec::new();
+        Message::FileSearchResult(apps) => {
+            assert!(apps.len() <= 50, "Batch must not exceed 50 results.");
+            // The results of the search that was browsed from are kept until going back
+            if tile.page == Page::FileSearch && tile.browsing.is_empty() {
+                let prev_display_count = min(tile.config.window.max_rows, tile.results.len());
+                tile.results.extend(apps.into_iter().map(Arc::new));
+                let new_display_count = min(tile.config.window.max_rows, tile.results.len());
+                // Only resize when the visible row count changes (up to max_rows).
+                if new_display_count != prev_display_count && new_display_count > 0 {
+                    let height = results_height(new_display_count, tile.config.theme.display_mode);
+                    return window::latest()
+                        .map(move |x| Message::ResizeWindow(x.unwrap(), height));
+                }
+            }
             Task::none()
         }
-        Message::WindowFocusChanged(wid, focused) => {
-            tile.focused = focused;
-            if !focused {
-                Task::done(Message::HideWindow(wid)).chain(Task::done(Message::ClearSearchQuery))
-            } else {
-                Task::none()
+
+        Message::ExtensionResults(id, query, result) => {
+            // Results for a query that has since changed are stale
+            if tile.page != Page::Main || tile.query != query {
+                return Task::none();
+            }
+
+            match result {
+                Ok(apps) => {
+                    tile.results = apps.into_iter().map(Arc::new).collect();
+                    tile.focus_id = 0;
+                    resize_for_results_count(id, tile.results.len(), &tile.config)
+                }
+                Err(error) => Task::done(Message::Error(ErrorKind::Extension, error)),
             }
         }
 
-        Message::EditClipboardHistory(action) => {
-            if !tile.config.cbhist {
+        Message::LookupResults(id, query, lookup, result) => {
+            if let Ok(apps) = &result
+                && lookup.cache_seconds > 0
+            {
+                tile.lookup_cache
+                    .insert(lookup.key.clone(), (unix_now(), apps.clone()));
+            }
+            // Results for a search that has since changed are stale
+            if tile.page != Page::Main || tile.query != query {
                 return Task::none();
             }
-            match action {
-                Editable::Create(content) => {
-                    if !tile.clipboard_content.contains(&content) {
-                        tile.clipboard_content.insert(0, content);
-                        return Task::none();
-                    }
-
-                    let new_content_vec = tile
-                        .clipboard_content
-                        .par_iter()
-                        .filter_map(|x| {
-                            if *x == content {
-                                None
-                            } else {
-                                Some(x.to_owned())
-                            }
-                        })
-                        .collect();
 
-                    tile.clipboard_content = new_content_vec;
-                    tile.clipboard_content.insert(0, content);
-                }
-                Editable::Delete(content) => {
-                    tile.clipboard_content = tile
-                        .clipboard_content
-                        .iter()
-                        .filter_map(|x| {
-                            if *x == content {
-                                None
-                            } else {
-                                Some(x.to_owned())
-                            }
+            let apps = match (result, tile.lookup_cache.get(&lookup.key)) {
+                (Ok(apps), _) => apps,
+                // The last results are shown when they can't be looked up, like when offline
+                (Err(_), Some((time, apps))) if lookup.keep_stale => {
+                    let age = time_ago(*time);
+                    apps.iter()
+                        .cloned()
+                        .map(|mut app| {
+                            app.desc = tr!("lookup-stale", desc = app.desc, age = age.clone());
+                            app
                         })
-                        .collect();
-                }
-                Editable::Update { old, new } => {
-                    tile.clipboard_content = tile
-                        .clipboard_content
-                        .iter()
-                        .map(|x| if x == &old { new.clone() } else { x.to_owned() })
-                        .collect();
+                        .collect()
                 }
-            }
-            Task::none()
+                (Err(error), _) => return Task::done(Message::Error(lookup.error, error)),
+            };
+            tile.results
+                .extend(apps.into_iter().filter(|x| lookup.matches(x)).map(Arc::new));
+            resize_for_results_count(id, tile.results.len(), &tile.config)
         }
 
-        Message::SetFileSearchSender(sender) => {
-            tile.file_search_sender = Some(sender);
+        Message::RevealSecret(manager, item, field) => Task::perform(
+            passwords::reveal(manager, item, field),
+            Message::SecretRevealed,
+        ),
+
+        Message::SecretRevealed(result) => {
+            let secret = match result {
+                Ok(secret) => secret,
+                Err(error) => return Task::done(Message::Error(ErrorKind::Passwords, error)),
+            };
+            // Remembered before it is copied, so that the clipboard history leaves it out
+            tile.secret = Some(secret.clone());
+
+            let clear_after = Duration::from_secs(tile.config.passwords.clear_after);
+            let copied = secret.clone();
+            Task::done(Message::RunFunction(Function::CopyToClipboard(
+                ClipBoardContentType::Text(copied),
+            )))
+            .chain(Task::perform(tokio::time::sleep(clear_after), move |_| {
+                Message::ClearSecret(secret.clone())
+            }))
+        }
+
+        Message::ClearSecret(secret) => {
+            if let Err(e) = passwords::clear_clipboard(&secret) {
+                error!("Couldn't clear the secret from the clipboard: {e}");
+            }
+            if tile.secret.as_ref() == Some(&secret) {
+                tile.secret = None;
+            }
             Task::none()
         }
 
-        Message::FileSearchResult(apps) => {
-            assert!(apps.len() <= 50, "Batch must not exceed 50 results.");
-            if tile.page == Page::FileSearch {
-                let prev_display_count = std::cmp::min(5, tile.results.len());
-                tile.results.extend(apps);
-                let new_display_count = std::cmp::min(5, tile.results.len());
-                // Only resize when the visible row count changes (max 5).
-                if new_display_count != prev_display_count && new_display_count > 0 {
-                    return window::latest().map(move |x| {
-                        Message::ResizeWindow(
-                            x.unwrap(),
-                            ((new_display_count * 55) + 35 + DEFAULT_WINDOW_HEIGHT as usize) as f32,
-                        )
-                    });
+        Message::CalendarEvents(result) => {
+            match result {
+                Ok(events) => {
+                    tile.events = events;
+                    tile.events_updated = unix_now();
                 }
+                // This happens in the background, so it is only logged
+                Err(e) => error!("Couldn't read the calendar: {e}"),
             }
             Task::none()
         }
 
+        Message::ContentSearchResult(query, apps) => {
+            if tile.query_lc != query {
+                return Task::none();
+            }
+            handle_update(tile, Message::FileSearchResult(apps))
+        }
+
         Message::FileSearchClear => {
             if tile.page == Page::FileSearch {
                 tile.results.clear();
@@ -622,11 +1535,55 @@ pub fn handle_update(tile: &mut Tile, message: Message) -> Task<Message> {
 
         Message::SearchQueryChanged(input, id) => {
             tile.focus_id = 0;
+            tile.confirming = None;
+            tile.pointer_moved = false;
+            tile.last_activity = Instant::now();
 
             if tile.config.haptic_feedback {
                 perform_haptic(HapticPattern::Alignment);
             }
 
+            // Editing a recalled query stops browsing the query history
+            if tile.history_index.and_then(|i| tile.query_history.get(i)) != Some(&input) {
+                tile.history_index = None;
+            }
+
+            // While browsing, the search filters the children of what was browsed into
+            if let Some(browsed) = tile.browsing.last() {
+                tile.query_lc = input.trim().to_lowercase();
+                tile.query = input;
+                tile.results = browsed
+                    .children
+                    .iter()
+                    .filter(|x| x.matches_words(&tile.query_lc))
+                    .cloned()
+                    .collect();
+                announce_focused(tile);
+                return resize_for_results_count(id, tile.results.len(), &tile.config);
+            }
+
+            // Typing `?` into an empty search bar toggles the shortcuts page
+            if input == "?" && matches!(tile.page, Page::Main | Page::Keybindings) {
+                tile.query_lc = String::new();
+                tile.query = String::new();
+                if tile.page == Page::Keybindings {
+                    return Task::done(Message::PageBack);
+                }
+                return Task::done(Message::SwitchToPage(Page::Keybindings));
+            }
+            // Typing on the shortcuts page searches the page it was opened from
+            if tile.page == Page::Keybindings {
+                tile.page = tile.page_stack.pop().unwrap_or(Page::Main);
+            }
+
+            // Typing a keyword and then a space scopes the search to its page or extension
+            if tile.page == Page::Main
+                && tile.scope.is_none()
+                && let Some(keyword) = input.strip_suffix(' ').and_then(|x| tile.scope_keyword(x))
+            {
+                return Task::done(Message::EnterScope(keyword, id));
+            }
+
             tile.query_lc = input.trim().to_lowercase();
             tile.query = input.clone();
 
@@ -636,7 +1593,7 @@ pub fn handle_update(tile: &mut Tile, message: Message) -> Task<Message> {
 
             // Return a task that waits for the debounce delay before executing search
             if let Some(delay) = tile.page.debounce_delay(&tile.config) {
-                tile.debouncer.reset();
+                tile.debouncer.reset(delay);
                 Task::perform(
                     async move {
                         tokio::time::sleep(delay).await;
@@ -645,15 +1602,15 @@ pub fn handle_update(tile: &mut Tile, message: Message) -> Task<Message> {
                     Message::DebouncedSearch,
                 )
             } else {
-                execute_query(tile, id)
+                let task = execute_query(tile, id);
+                announce_focused(tile);
+                task
             }
         }
 
         Message::OpenFileDialogue(mode_name) => rfd::FileDialog::new()
             .add_filter("shell", &["sh", "bash", "zsh"])
-            .set_directory(
-                std::env::var("HOME").unwrap_or("".to_string()) + "/.config/rustcast/config.toml",
-            )
+            .set_directory(config_dir())
             .pick_file()
             .and_then(|path| {
                 path.to_str().map(|path_str| {
@@ -727,7 +1684,7 @@ pub fn handle_update(tile: &mut Tile, message: Message) -> Task<Message> {
 
                 SetConfigFields::ShellCommands(Editable::Create(shell_command)) => {
                     if !final_config.shells.contains(&shell_command) {
-                        final_config.shells.push(shell_command);
+                        final_config.shells.push(*shell_command);
                     }
                 }
 
@@ -736,7 +1693,7 @@ pub fn handle_update(tile: &mut Tile, message: Message) -> Task<Message> {
                         .shells
                         .iter()
                         .filter_map(|shell| {
-                            if &shell_command != shell {
+                            if *shell_command != *shell {
                                 Some(shell.to_owned())
                             } else {
                                 None
@@ -750,8 +1707,8 @@ pub fn handle_update(tile: &mut Tile, message: Message) -> Task<Message> {
                         .shells
                         .iter()
                         .map(|shell| {
-                            if shell == &old {
-                                new.clone()
+                            if *shell == *old {
+                                *new.clone()
                             } else {
                                 shell.to_owned()
                             }
@@ -763,6 +1720,7 @@ pub fn handle_update(tile: &mut Tile, message: Message) -> Task<Message> {
                 SetConfigFields::PlaceHolder(placeholder) => final_config.placeholder = placeholder,
                 SetConfigFields::SetPage(page) => final_config.main_page = page,
                 SetConfigFields::DebounceDelay(delay) => final_config.debounce_delay = delay,
+                SetConfigFields::EmojiSkinTone(tone) => final_config.emoji_skin_tone = tone,
                 SetConfigFields::HapticFeedback(haptic_feedback) => {
                     final_config.haptic_feedback = haptic_feedback
                 }
@@ -776,6 +1734,9 @@ pub fn handle_update(tile: &mut Tile, message: Message) -> Task<Message> {
                 SetConfigFields::SetThemeFields(SetConfigThemeFields::ShowIcons(icns)) => {
                     final_config.theme.show_icons = icns
                 }
+                SetConfigFields::SetThemeFields(SetConfigThemeFields::DisplayMode(mode)) => {
+                    final_config.theme.display_mode = mode
+                }
                 SetConfigFields::SetThemeFields(SetConfigThemeFields::ShowScrollBar(show)) => {
                     final_config.theme.show_scroll_bar = show
                 }
@@ -793,13 +1754,13 @@ pub fn handle_update(tile: &mut Tile, message: Message) -> Task<Message> {
                 }
             };
 
+            tile.providers = providers::configured(&final_config);
             tile.config = final_config;
             Task::none()
         }
 
         Message::WriteConfig(page_switch) => {
-            let config_file_path =
-                std::env::var("HOME").unwrap_or("".to_string()) + "/.config/rustcast/config.toml";
+            let config_file_path = config_path("config.toml");
 
             tile.config.aliases.remove("");
             tile.config.modes.remove("");
@@ -823,7 +1784,7 @@ pub fn handle_update(tile: &mut Tile, message: Message) -> Task<Message> {
             Task::batch([
                 Task::done(Message::ReloadConfig),
                 if page_switch {
-                    Task::done(Message::SwitchToPage(Page::Main))
+                    Task::done(Message::PageBack)
                 } else {
                     Task::none()
                 },
@@ -841,59 +1802,459 @@ pub fn handle_update(tile: &mut Tile, message: Message) -> Task<Message> {
                 return Task::none();
             }
 
-            execute_query(tile, id)
+            let task = execute_query(tile, id);
+            announce_focused(tile);
+            task
         }
     }
 }
 
-/// helper function for the tasks needed to open a window
-fn open_window(height: f32) -> Task<Message> {
+/// Have the screen reader read out the focused result, with where it is in the results
+fn announce_focused(tile: &Tile) {
+    let (focused, count) = match tile.page {
+        Page::ClipboardHistory => (
+            tile.clipboard_content
+                .iter()
+                .nth(tile.focus_id as usize)
+                .map(|x| x.to_app()),
+            tile.clipboard_content.len(),
+        ),
+        _ => (
+            tile.results
+                .get(tile.focus_id as usize)
+                .map(|x| App::clone(x)),
+            tile.results.len(),
+        ),
+    };
+
+    let text = match focused {
+        Some(app) => tr!(
+            "result-focused",
+            name = app.display_name,
+            desc = app.desc,
+            position = tile.focus_id + 1,
+            count = count
+        ),
+        None if tile.query.is_empty() => return,
+        None => tr!("results-none"),
+    };
+    platform::announce(&text);
+}
+
+/// Show a page, clearing the search
+fn show_page(tile: &mut Tile, page: Page) -> Task<Message> {
+    tile.scope = None;
+    tile.content_search = None;
+    let task = match &page {
+        Page::ClipboardHistory => {
+            if !tile.config.cbhist {
+                return Task::none();
+            }
+            if let Some(reason) = &tile.clipboard_unavailable {
+                return Task::done(Message::Error(ErrorKind::Clipboard, reason.clone()));
+            }
+            window::latest().map(|x| {
+                let id = x.unwrap();
+                Message::ResizeWindow(id, full_page_height())
+            })
+        }
+        Page::Settings | Page::Keybindings => window::latest().map(|x| {
+            let id = x.unwrap();
+            Message::ResizeWindow(id, full_page_height())
+        }),
+        // Going back to the file search from a full page shrinks the window to its empty search
+        Page::FileSearch => window::latest().map(|x| {
+            let id = x.unwrap();
+            Message::ResizeWindow(id, DEFAULT_WINDOW_HEIGHT)
+        }),
+        _ => Task::none(),
+    };
+
+    tile.page = page;
+
+    let refresh_empty_query = if matches!(
+        tile.page,
+        Page::Main | Page::EmojiSearch | Page::CalculationHistory
+    ) {
+        window::latest()
+            .map(|x| x.unwrap())
+            .map(|id| Message::SearchQueryChanged(String::new(), id))
+    } else {
+        Task::none()
+    };
+
+    Task::batch([
+        Task::done(Message::ClearSearchQuery),
+        Task::done(Message::ClearSearchResults),
+        task,
+        refresh_empty_query,
+    ])
+}
+
+/// Browse into the focused result's children, keeping the search to go back to
+fn browse_into(tile: &mut Tile, id: Id) -> Task<Message> {
+    if !matches!(tile.page, Page::Main | Page::FileSearch) {
+        return Task::none();
+    }
+    let Some(app) = tile.results.get(tile.focus_id as usize) else {
+        return Task::none();
+    };
+    let Some(children) = &app.children else {
+        return Task::none();
+    };
+
+    let title = app.display_name.trim_end_matches('/').to_string();
+    let children = children.apps();
+    browse(tile, id, title, children)
+}
+
+/// The file or url that the focused result opens, for "Open With"
+fn open_with_target(tile: &Tile) -> Option<String> {
+    let app = tile
+        .results
+        .get(tile.focus_id as usize)
+        .filter(|_| matches!(tile.page, Page::Main | Page::FileSearch))?;
+    match &app.open_command {
+        AppCommand::Function(Function::OpenApp(path)) => Some(path.clone()),
+        AppCommand::Function(Function::OpenWebsite(url)) if url.contains("://") => {
+            Some(url.clone())
+        }
+        AppCommand::Function(Function::OpenWebsite(url)) => Some(format!("https://{url}")),
+        _ => None,
+    }
+}
+
+/// Show the actions for a result, like opening it with another app, as results that are browsed
+/// into, so that going back returns to it
+fn show_actions(tile: &mut Tile, id: Id) -> Task<Message> {
+    if !matches!(tile.page, Page::Main | Page::FileSearch) {
+        return Task::none();
+    }
+    let Some(app) = tile.results.get(tile.focus_id as usize).cloned() else {
+        return Task::none();
+    };
+
+    let action = |name: String, message: Message| App {
+        ranking: 0,
+        open_command: AppCommand::Message(Message::RunAction(id, Box::new(message))),
+        desc: app.display_name.clone(),
+        icons: None,
+        display_name: name,
+        search_name: String::new(),
+        version: None,
+        last_used: None,
+        children: None,
+    };
+    let copy = |text: &str| {
+        Message::RunFunction(Function::CopyToClipboard(ClipBoardContentType::Text(
+            text.to_string(),
+        )))
+    };
+
+    let mut actions = vec![];
+    if !matches!(app.open_command, AppCommand::Display) {
+        actions.push(action(tr!("action-open"), Message::OpenFocused));
+    }
+    if app.children.is_some() {
+        actions.push(action(tr!("action-browse-into"), Message::BrowseInto(id)));
+    }
+    match &app.open_command {
+        AppCommand::Function(Function::OpenApp(path) | Function::OpenAtLine(path, _)) => {
+            actions.push(action(tr!("action-reveal"), Message::RevealFocused));
+            actions.push(action(
+                tr!("action-open-with"),
+                Message::OpenWithFocused(id),
+            ));
+            actions.push(action(tr!("action-copy-path"), copy(path)));
+        }
+        AppCommand::Function(Function::OpenWebsite(url)) => {
+            actions.push(action(
+                tr!("action-open-with"),
+                Message::OpenWithFocused(id),
+            ));
+            actions.push(action(tr!("action-copy-link"), copy(url)));
+        }
+        _ => {}
+    }
+    if tile.page == Page::Main && tile.is_running(&app) {
+        actions.push(action(tr!("action-quit"), Message::QuitFocused(false)));
+        actions.push(action(tr!("action-force-quit"), Message::QuitFocused(true)));
+    }
+    if tile.page == Page::Main && tile.options.get(&app.search_name).is_some() {
+        let favourite = if app.ranking == -1 {
+            tr!("action-remove-favourite")
+        } else {
+            tr!("action-add-favourite")
+        };
+        let toggle = Message::ToggleFavouriteApp(app.search_name.clone());
+        actions.push(action(favourite, toggle));
+        actions.push(action(tr!("action-hide"), Message::HideFocusedResult));
+        if let AppCommand::Function(Function::OpenApp(path)) = &app.open_command
+            && platform::can_uninstall(path)
+        {
+            actions.push(action(tr!("action-uninstall"), Message::UninstallFocused));
+        }
+    }
+    actions.push(action(tr!("action-copy-name"), copy(&app.display_name)));
+
+    browse(tile, id, tr!("actions"), actions)
+}
+
+/// Browse into some results, keeping the search to go back to
+fn browse(tile: &mut Tile, id: Id, title: String, children: Vec<App>) -> Task<Message> {
+    let children: Vec<Arc<App>> = children.into_iter().map(Arc::new).collect();
+    tile.browsing.push(Browsed {
+        title,
+        children: children.clone(),
+        query: std::mem::take(&mut tile.query),
+        results: std::mem::replace(&mut tile.results, children),
+        focus_id: tile.focus_id,
+    });
+    tile.query_lc = String::new();
+    tile.focus_id = 0;
+    tile.confirming = None;
+    tile.history_index = None;
+    announce_focused(tile);
+    resize_for_results_count(id, tile.results.len(), &tile.config)
+}
+
+/// Go back out of the innermost result that was browsed into, to the search and result that it
+/// was browsed into from
+fn browse_back(tile: &mut Tile, id: Id) -> Task<Message> {
+    let Some(browsed) = tile.browsing.pop() else {
+        return Task::none();
+    };
+
+    tile.query_lc = browsed.query.trim().to_lowercase();
+    tile.query = browsed.query;
+    tile.results = browsed.results;
+    tile.focus_id = browsed.focus_id;
+    tile.confirming = None;
+    announce_focused(tile);
+    Task::batch([
+        resize_for_results_count(id, tile.results.len(), &tile.config),
+        operation::move_cursor_to_end("query"),
+    ])
+}
+
+/// Recall a previous query with the arrow keys, like a shell does
+///
+/// Pressing up on the first result of an empty search starts browsing the query history, and
+/// while browsing, up and down move through the history instead of through the results
+fn recall_query(tile: &mut Tile, key: &ArrowKey) -> Option<Task<Message>> {
+    if tile.page != Page::Main || tile.query_history.is_empty() {
+        return None;
+    }
+
+    let index = match (key, tile.history_index) {
+        (ArrowKey::Up, None) if tile.query.is_empty() && tile.focus_id == 0 => Some(0),
+        (ArrowKey::Up, Some(i)) => Some(min(i + 1, tile.query_history.len() - 1)),
+        (ArrowKey::Down, Some(0)) => None,
+        (ArrowKey::Down, Some(i)) => Some(i - 1),
+        _ => return None,
+    };
+
+    tile.history_index = index;
+    let query = index
+        .and_then(|i| tile.query_history.get(i).cloned())
+        .unwrap_or_default();
+
+    Some(
+        window::latest()
+            .map(move |id| Message::SearchQueryChanged(query.clone(), id.unwrap()))
+            .chain(operation::move_cursor_to_end("query")),
+    )
+}
+
+/// Show the window again where a new one would open (like on the monitor with the pointer) when
+/// `follow_active_display` is on, or where it was otherwise, or open one if there isn't one to
+/// reuse
+fn show_window(tile: &mut Tile, height: f32) -> Task<Message> {
+    let Some(id) = tile.window else {
+        let (id, open) = open_window(&tile.config, height);
+        tile.window = Some(id);
+        return open;
+    };
+
+    // The window fades in from where it is, like partly faded out when it was hidden just now
+    tile.hide_timer = None;
+    let now = Instant::now();
+    let fade = if !tile.animates() {
+        tile.fade = Animation::new(true).duration(ANIMATION_TIME);
+        Task::none()
+    } else if tile.fade.is_animating(now) {
+        tile.fade.go_mut(true, now);
+        Task::none()
+    } else {
+        tile.fade = Animation::new(false).duration(ANIMATION_TIME).go(true, now);
+        set_opacity(id, 0.0)
+    };
+
+    let config = &tile.config.window;
+    let size = iced::Size {
+        width: config.width,
+        height: DEFAULT_WINDOW_HEIGHT,
+    };
+    let position = if config.follow_active_display {
+        config
+            .position
+            .on_monitor(platform::monitor_frame(config), size)
+    } else {
+        window::Position::Default
+    };
+    let move_window = match position {
+        window::Position::Specific(point) => window::move_to(id, point),
+        // The platform only places windows as they open, so the window stays where it was
+        _ => Task::none(),
+    };
     Task::batch([
-        window::open(default_settings())
-            .1
-            .map(move |id| Message::ResizeWindow(id, height)),
+        fade.chain(move_window)
+            .chain(window::set_mode(id, window::Mode::Windowed))
+            .chain(window::gain_focus(id))
+            .chain(Task::done(Message::ResizeWindow(id, height))),
         Task::done(Message::OpenWindow),
         operation::focus("query"),
     ])
 }
 
-/// A helper function for resizing rustcast when only one result is found
-fn single_item_resize_task(id: Id) -> Task<Message> {
-    resize_task(id, 1)
+/// Set how opaque the window is
+fn set_opacity(id: Id, opacity: f32) -> Task<Message> {
+    window::run(id, move |handle| {
+        if let Ok(handle) = handle.window_handle() {
+            platform::set_opacity(&handle, opacity);
+        }
+    })
+    .discard()
 }
 
-/// A helper function for resizing rustcast when zero results are found
-fn zero_item_resize_task(id: Id) -> Task<Message> {
-    resize_task(id, 0)
+/// helper function for the tasks needed to open a window
+fn open_window(config: &Config, height: f32) -> (Id, Task<Message>) {
+    let (id, open) = window::open(default_settings(&config.window));
+    let hide_from_screen_sharing = config.window.hide_from_screen_sharing;
+    let theme = config.theme.clone();
+    let task = Task::batch([
+        open.discard()
+            .chain(
+                window::run(id, move |handle| {
+                    if let Ok(handle) = handle.window_handle() {
+                        platform::set_hidden_from_capture(&handle, hide_from_screen_sharing);
+                        platform::set_blur(&handle, &theme);
+                    }
+                })
+                .discard(),
+            )
+            .chain(Task::done(Message::ResizeWindow(id, height))),
+        Task::done(Message::OpenWindow),
+        operation::focus("query"),
+    ]);
+    (id, task)
 }
 
-fn resize_task(id: Id, count: u32) -> Task<Message> {
+/// A helper function for resizing rustcast when only one result is found
+fn single_item_resize_task(id: Id, display_mode: DisplayMode) -> Task<Message> {
     Task::done(Message::ResizeWindow(
         id,
-        (55 * count) as f32 + DEFAULT_WINDOW_HEIGHT,
+        display_mode.window_row_height() + DEFAULT_WINDOW_HEIGHT,
     ))
 }
 
-fn resize_for_results_count(id: Id, count: usize) -> Task<Message> {
+/// A helper function for resizing rustcast when zero results are found
+fn zero_item_resize_task(id: Id) -> Task<Message> {
+    Task::done(Message::ResizeWindow(id, DEFAULT_WINDOW_HEIGHT))
+}
+
+/// The height of the window on the pages that fill it, like clipboard history and settings
+fn full_page_height() -> f32 {
+    FULL_PAGE_LIST_HEIGHT + 35. + DEFAULT_WINDOW_HEIGHT
+}
+
+/// The height of the window when it shows this many result rows
+fn results_height(rows: usize, display_mode: DisplayMode) -> f32 {
+    (rows as f32 * display_mode.window_row_height()) + 35. + DEFAULT_WINDOW_HEIGHT
+}
+
+fn resize_for_results_count(id: Id, count: usize, config: &Config) -> Task<Message> {
     if count == 0 {
         return zero_item_resize_task(id);
     }
     if count == 1 {
-        return single_item_resize_task(id);
+        return single_item_resize_task(id, config.theme.display_mode);
     }
 
-    let max_elem = min(5, count);
+    let max_elem = min(config.window.max_rows, count);
     Task::done(Message::ResizeWindow(
         id,
-        ((max_elem * 55) + 35 + DEFAULT_WINDOW_HEIGHT as usize) as f32,
+        results_height(max_elem, config.theme.display_mode),
     ))
 }
 
+/// Hide the window and give focus back to the app that had it, for commands that are done with
+/// the window
+fn hide_window_task() -> Task<Message> {
+    window::latest()
+        .map(|x| x.unwrap())
+        .map(Message::HideWindow)
+        .chain(Task::done(Message::ClearSearchQuery))
+        .chain(Task::done(Message::ReturnFocus))
+}
+
+/// Hide the window after a delay, unless the timer is cancelled (or replaced) before then
+fn hide_after(tile: &mut Tile, id: Id, delay: Duration) -> Task<Message> {
+    let (task, handle) =
+        Task::perform(tokio::time::sleep(delay), move |_| Message::HideTimeout(id)).abortable();
+    // Replacing the handle cancels the timer that was running
+    tile.hide_timer = Some(handle.abort_on_drop());
+    task
+}
+
+/// Drag a result out of the window, to drop it onto another app
+fn drag_result(tile: &Tile, id: usize) -> Task<Message> {
+    let app = if tile.page == Page::ClipboardHistory {
+        tile.clipboard_content
+            .iter()
+            .nth(id)
+            .map(|x| x.content.to_app())
+    } else {
+        tile.results.get(id).map(|x| (**x).clone())
+    };
+    let Some(app) = app else {
+        return Task::none();
+    };
+
+    let dragged = match drag::dragged(&app) {
+        Ok(Some(dragged)) => dragged,
+        Ok(None) => return Task::none(),
+        Err(e) => return Task::done(Message::Error(ErrorKind::Drag, e)),
+    };
+    window::latest().and_then(move |id| {
+        let dragged = dragged.clone();
+        window::run(id, move |handle| {
+            handle
+                .window_handle()
+                .map_err(|e| e.to_string())
+                .and_then(|handle| platform::start_drag(&handle, &dragged))
+                .err()
+        })
+        .and_then(|e| Task::done(Message::Error(ErrorKind::Drag, e)))
+    })
+}
+
 fn open_result(tile: &mut Tile, id: usize) -> Task<Message> {
+    if tile.page == Page::Keybindings {
+        return Task::none();
+    }
+    if tile.page == Page::Main {
+        tile.record_query();
+        if let Some(app) = tile.results.get(id) {
+            let search_name = app.search_name.clone();
+            tile.learn_selection(&search_name);
+        }
+    }
+
     let results = if tile.page == Page::ClipboardHistory {
         tile.clipboard_content
             .iter()
-            .map(|x| x.to_app().to_owned())
+            .map(|x| Arc::new(x.content.to_app()))
             .collect()
     } else {
         tile.results.clone()
@@ -905,31 +2266,24 @@ fn open_result(tile: &mut Tile, id: usize) -> Task<Message> {
 
     let search_name = app.search_name.clone();
 
-    match app.open_command {
+    match &app.open_command {
         AppCommand::Function(func) => {
             info!("Updating ranking for: {search_name}");
             tile.options.update_ranking(&search_name);
-            Task::done(Message::RunFunction(func))
+            if let Function::OpenApp(_) = func {
+                tile.options.set_last_used(&search_name, unix_now());
+            }
+            Task::done(Message::RunFunction(func.clone()))
         }
         AppCommand::Message(msg) => {
             info!("Updating ranking for: {search_name}");
             tile.options.update_ranking(&search_name);
-            Task::done(msg)
+            Task::done(msg.clone())
         }
         AppCommand::Display => Task::done(Message::ReturnFocus),
     }
 }
 
-/// Handling the lemon easter egg icon
-fn lemon_icon_handle() -> Option<Handle> {
-    image::ImageReader::new(Cursor::new(include_bytes!("../../../docs/lemon.png")))
-        .with_guessed_format()
-        .unwrap()
-        .decode()
-        .ok()
-        .map(|img| Handle::from_rgba(img.width(), img.height(), img.into_bytes()))
-}
-
 fn execute_query(tile: &mut Tile, id: Id) -> Task<Message> {
     let mut task = Task::none();
     let prev_size = tile.results.len();
@@ -943,13 +2297,46 @@ fn execute_query(tile: &mut Tile, id: Id) -> Task<Message> {
         _ => {}
     }
 
+    if tile.page == Page::CalculationHistory && tile.query_lc != "main" {
+        tile.results = tile.calculation_apps().into_iter().map(Arc::new).collect();
+        return resize_for_results_count(id, tile.results.len(), &tile.config);
+    }
+
+    if let Some(extension) = tile.scoped_extension() {
+        let query = tile.query.clone();
+        return Task::perform(
+            extensions::run(extension.clone(), query.trim().to_string()),
+            move |result| Message::ExtensionResults(id, query.clone(), result),
+        );
+    }
+
+    if tile.page == Page::EmojiSearch && tile.query_lc.is_empty() {
+        tile.results = tile
+            .recent_emoji_apps()
+            .into_iter()
+            .chain(App::emoji_category_apps(
+                tile.emoji_category,
+                tile.config.emoji_skin_tone,
+            ))
+            .map(Arc::new)
+            .collect();
+        return resize_for_results_count(id, tile.results.len(), &tile.config);
+    }
+
     if tile.page == Page::Main && tile.query_lc.is_empty() {
-        tile.results = match tile.config.main_page {
+        tile.results = tile
+            .update_apps()
+            .into_iter()
+            .chain(tile.clipboard_status_apps())
+            .chain(tile.config_diagnostic_apps())
+            .map(Arc::new)
+            .collect();
+        tile.results.extend(match tile.config.main_page {
             MainPage::FrequentlyUsed => tile.frequent_results(),
             MainPage::Blank => vec![],
             MainPage::Favourites => tile.options.get_favourites(),
-        };
-        return resize_for_results_count(id, tile.results.len());
+        });
+        return resize_for_results_count(id, tile.results.len(), &tile.config);
     }
 
     if tile.query_lc.is_empty()
@@ -959,49 +2346,9 @@ fn execute_query(tile: &mut Tile, id: Id) -> Task<Message> {
         return zero_item_resize_task(id);
     };
 
-    let quittables = if tile.query_lc.starts_with("quit") {
-        get_open_apps(tile.config.theme.show_icons)
-    } else {
-        vec![]
-    };
-
     match tile.query_lc.as_str() {
-        "randomvar" => {
-            let rand_num = rand::random_range(0..100);
-            tile.results = vec![App {
-                ranking: 0,
-                open_command: AppCommand::Function(Function::RandomVar(rand_num)),
-                desc: "Easter egg".to_string(),
-                icons: None,
-                display_name: rand_num.to_string(),
-                search_name: String::new(),
-            }];
-            return single_item_resize_task(id);
-        }
-        "lemon" => {
-            tile.results = vec![App {
-                ranking: 0,
-                open_command: AppCommand::Display,
-                desc: "Easter Egg".to_string(),
-                icons: lemon_icon_handle(),
-                display_name: "Lemon".to_string(),
-                search_name: "".to_string(),
-            }];
-            return single_item_resize_task(id);
-        }
-        "67" => {
-            tile.results = vec![App {
-                ranking: 0,
-                open_command: AppCommand::Function(Function::RandomVar(67)),
-                desc: "Easter egg".to_string(),
-                icons: None,
-                display_name: 67.to_string(),
-                search_name: String::new(),
-            }];
-            return single_item_resize_task(id);
-        }
         "cbhist" => {
-            task = task.chain(Task::done(Message::SwitchToPage(Page::ClipboardHistory)));
+            return Task::done(Message::EnterScope("cbhist".to_string(), id));
         }
         "main" => {
             if tile.page != Page::Main {
@@ -1011,26 +2358,60 @@ fn execute_query(tile: &mut Tile, id: Id) -> Task<Message> {
         }
         "fav" => {
             tile.results = tile.options.get_favourites();
-            return resize_for_results_count(id, tile.results.len());
+            return resize_for_results_count(id, tile.results.len(), &tile.config);
         }
         query => 'a: {
             if !query.starts_with(">") || tile.page != Page::Main {
                 break 'a;
             }
             let command = tile.query.strip_prefix(">").unwrap_or("");
-            tile.results = vec![App {
+            tile.results = vec![Arc::new(App {
                 ranking: 20,
-                open_command: AppCommand::Function(Function::RunShellCommand(command.to_string())),
+                open_command: AppCommand::Function(Function::RunShellCommand(ShellCommand::new(
+                    command,
+                ))),
                 display_name: format!("Shell Command: {}", command),
                 icons: None,
                 search_name: "".to_string(),
                 desc: "Shell Command".to_string(),
-            }];
-            return single_item_resize_task(id);
+                version: None,
+                last_used: None,
+                children: None,
+            })];
+            return single_item_resize_task(id, tile.config.theme.display_mode);
         }
     }
 
+    if tile.page == Page::Main && tile.scope.is_none() && path_completion::is_path(&tile.query) {
+        tile.results = path_completion::completions(&tile.query)
+            .into_iter()
+            .map(Arc::new)
+            .collect();
+        tile.focus_id = 0;
+        return task.chain(resize_for_results_count(
+            id,
+            tile.results.len(),
+            &tile.config,
+        ));
+    }
+
     match tile.page {
+        Page::FileSearch if tile.scope.as_deref() == Some("grep") => {
+            tile.results.clear();
+            let query = tile.query_lc.clone();
+            let (search, handle) = Task::run(
+                content_search::search(
+                    query.clone(),
+                    tile.config.search_dirs.clone(),
+                    tile.config.content_search.clone(),
+                ),
+                move |apps| Message::ContentSearchResult(query.clone(), apps),
+            )
+            .abortable();
+            // Replacing the handle stops the search that was running
+            tile.content_search = Some(handle.abort_on_drop());
+            return task.chain(search);
+        }
         Page::FileSearch => {
             if let Some(ref sender) = tile.file_search_sender {
                 tile.results.clear();
@@ -1045,71 +2426,95 @@ fn execute_query(tile: &mut Tile, id: Id) -> Task<Message> {
     }
 
     tile.handle_search_query_changed();
-    if tile.query_lc.starts_with("quit") {
-        let query = tile.query_lc.clone();
-        tile.results.extend(quittables.iter().filter_map(move |x| {
-            if x.search_name.starts_with(&query) {
-                Some(x.to_owned())
-            } else {
-                None
-            }
-        }))
+    // The lookups run alongside the rest, since the window shouldn't wait for them to resize
+    let mut lookups = Task::none();
+    if tile.page == Page::Main {
+        let search = tile.search();
+        let results = providers::provide(&tile.providers, &search);
+        let started = providers::lookups(&tile.providers, &search);
+        tile.results.extend(results.into_iter().map(Arc::new));
+
+        // Replacing the lookups stops the ones that were running, since their search changed
+        tile.lookups.clear();
+        lookups = Task::batch(
+            started
+                .into_iter()
+                .map(|(name, lookup, fetch)| start_lookup(tile, id, name, lookup, fetch))
+                .collect::<Vec<_>>(),
+        );
     }
 
     if !tile.results.is_empty() {
         tile.results.par_sort_by_key(|x| -x.ranking);
 
         let new_length = tile.results.len();
-        let max_elem = min(5, new_length);
+        let max_elem = min(tile.config.window.max_rows, new_length);
 
         if prev_size == new_length {
-            return task;
+            return Task::batch([task, lookups]);
         }
 
-        return task.chain(Task::batch([
-            Task::done(Message::ResizeWindow(
-                id,
-                ((max_elem * 55) + 35 + DEFAULT_WINDOW_HEIGHT as usize) as f32,
-            )),
-            Task::done(Message::ChangeFocus(ArrowKey::Left, 1)),
-        ]));
+        return Task::batch([
+            task.chain(Task::batch([
+                Task::done(Message::ResizeWindow(
+                    id,
+                    results_height(max_elem, tile.config.theme.display_mode),
+                )),
+                Task::done(Message::ChangeFocus(ArrowKey::Left, 1)),
+            ])),
+            lookups,
+        ]);
     }
 
-    if is_valid_url(&tile.query) {
-        tile.results.push(App {
-            ranking: 0,
-            open_command: AppCommand::Function(Function::OpenWebsite(tile.query.clone())),
-            desc: "Web Browsing".to_string(),
-            icons: None,
-            display_name: "Open Website: ".to_string() + &tile.query,
-            search_name: String::new(),
-        });
-    } else if let Some(conversions) = unit_conversion::convert_query(&tile.query) {
-        tile.results = conversions
-            .into_iter()
-            .map(|conversion| conversion.to_app())
-            .collect();
-        return single_item_resize_task(id);
-    } else if let Ok(res) = Expr::from_str(&tile.query) {
-        tile.results.push(App {
-            ranking: 0,
-            open_command: AppCommand::Function(Function::Calculate(res.clone())),
-            desc: RUSTCAST_DESC_NAME.to_string(),
-            icons: None,
-            display_name: res.eval().map(|x| x.to_string()).unwrap_or("".to_string()),
-            search_name: "".to_string(),
-        });
-        return single_item_resize_task(id);
-    } else if tile.query.ends_with("?") || tile.query.split_whitespace().nth(2).is_some() {
-        tile.results = vec![App {
-            ranking: 0,
-            open_command: AppCommand::Function(Function::GoogleSearch(tile.query.clone())),
-            icons: None,
-            desc: "Web Search".to_string(),
-            display_name: format!("Search for: {}", tile.query),
-            search_name: String::new(),
-        }];
-        return single_item_resize_task(id);
+    tile.results = providers::fallback(&tile.providers, &tile.search())
+        .into_iter()
+        .map(Arc::new)
+        .collect();
+    if tile.results.is_empty() {
+        return Task::batch([task, lookups]);
+    }
+    Task::batch([
+        resize_for_results_count(id, tile.results.len(), &tile.config),
+        lookups,
+    ])
+}
+
+/// Show the results of a lookup from the lookup cache, unless they are old, or start looking them
+/// up, which replaces the provider's lookup that was running
+fn start_lookup(
+    tile: &mut Tile,
+    id: Id,
+    name: &'static str,
+    lookup: Lookup,
+    fetch: Fetch,
+) -> Task<Message> {
+    if let Some((time, apps)) = tile.lookup_cache.get(&lookup.key)
+        && unix_now().saturating_sub(*time) < lookup.cache_seconds
+    {
+        tile.results.extend(
+            apps.iter()
+                .filter(|x| lookup.matches(x))
+                .cloned()
+                .map(Arc::new),
+        );
+        return Task::none();
     }
+
+    // Debounced lookups only start once the search hasn't changed for the debounce delay
+    let delay = if lookup.debounce {
+        Duration::from_millis(tile.config.debounce_delay)
+    } else {
+        Duration::ZERO
+    };
+    let query = tile.query.clone();
+    let (task, handle) = Task::perform(
+        async move {
+            tokio::time::sleep(delay).await;
+            fetch.await
+        },
+        move |result| Message::LookupResults(id, query.clone(), lookup.clone(), result),
+    )
+    .abortable();
+    tile.lookups.insert(name, handle.abort_on_drop());
     task
 }
@@ -5,6 +5,9 @@
 //! - ln(x)
 //! - log(x) (base 10)
 //! - log(base, x)
+//! - percentages, where x% is x/100, "x% of y" is x/100 * y, and "y + x%" / "y - x%" add or
+//!   subtract x percent of y
+//! - thousands separators (1,500), outside of function arguments
 //!
 //! Examples:
 //!   "2 + 3*4"        => 14
@@ -13,6 +16,18 @@
 //!   "ln(2.7182818)"  => ~1
 //!   "log(100)"       => 2
 //!   "log(2, 8)"      => 3
+//!   "15% of 230"     => 34.5
+//!   "230 + 15%"      => 264.5
+//!   "1,500*3"        => 4500
+
+use crate::{
+    app::{
+        ToApp,
+        apps::{App, AppCommand},
+    },
+    clipboard::ClipBoardContentType,
+    commands::Function,
+};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Expr {
@@ -30,6 +45,7 @@ pub enum Expr {
         name: String,
         args: Vec<Expr>,
     },
+    Percent(Box<Expr>),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -47,6 +63,17 @@ pub enum BinOp {
     Pow,
 }
 
+impl std::str::FromStr for Expr {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Expr, String> {
+        let mut p = Parser::new(s);
+        let expr = p.parse_expr()?;
+        p.expect(Token::End)?;
+        Ok(expr)
+    }
+}
+
 impl Expr {
     pub fn eval(&self) -> Option<f64> {
         use BinOp::*;
@@ -62,6 +89,17 @@ impl Expr {
                 })
             }
 
+            // "y + x%" means y plus x percent of y
+            Expr::Binary {
+                op: op @ (Add | Sub),
+                lhs,
+                rhs,
+            } if matches!(**rhs, Expr::Percent(_)) => {
+                let a = lhs.eval()?;
+                let b = a * rhs.eval()?;
+                Some(if *op == Add { a + b } else { a - b })
+            }
+
             Expr::Binary { op, lhs, rhs } => {
                 let a = lhs.eval()?;
                 let b = rhs.eval()?;
@@ -95,14 +133,152 @@ impl Expr {
                     _ => None,
                 }
             }
+
+            Expr::Percent(rhs) => Some(rhs.eval()? / 100.),
         }
     }
 
-    pub fn from_str(s: &str) -> Result<Expr, String> {
-        let mut p = Parser::new(s);
-        let expr = p.parse_expr()?;
-        p.expect(Token::End)?;
-        Ok(expr)
+    /// The expression with brackets around every operation inside it, so that the order it is
+    /// worked out in is clear, like `2 + (3 * 4)`
+    pub fn explicit(&self) -> String {
+        match self {
+            Expr::Binary { op, lhs, rhs } => {
+                format!("{} {} {}", lhs.bracketed(), op.symbol(), rhs.bracketed())
+            }
+            _ => self.bracketed(),
+        }
+    }
+
+    fn bracketed(&self) -> String {
+        match self {
+            Expr::Number(x) => x.to_string(),
+            Expr::Unary {
+                op: UnaryOp::Plus,
+                rhs,
+            } => format!("+{}", rhs.bracketed()),
+            Expr::Unary {
+                op: UnaryOp::Minus,
+                rhs,
+            } => format!("-{}", rhs.bracketed()),
+            Expr::Binary { .. } => format!("({})", self.explicit()),
+            Expr::Func { name, args } => format!(
+                "{name}({})",
+                args.iter()
+                    .map(|x| x.explicit())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            Expr::Percent(rhs) => format!("{}%", rhs.bracketed()),
+        }
+    }
+
+    /// Each operation in the order that it is worked out, with its result, like `3 * 4 = 12` and
+    /// then `2 + 12 = 14`
+    pub fn steps(&self) -> Vec<String> {
+        let mut steps = vec![];
+        self.push_steps(&mut steps);
+        steps
+    }
+
+    fn push_steps(&self, steps: &mut Vec<String>) {
+        match self {
+            Expr::Number(_) => {}
+            Expr::Unary { rhs, .. } | Expr::Percent(rhs) => rhs.push_steps(steps),
+            Expr::Binary { op, lhs, rhs } => {
+                lhs.push_steps(steps);
+                rhs.push_steps(steps);
+                steps.push(format!(
+                    "{} {} {} = {}",
+                    lhs.operand(),
+                    op.symbol(),
+                    rhs.operand(),
+                    self.value()
+                ));
+            }
+            Expr::Func { name, args } => {
+                for arg in args {
+                    arg.push_steps(steps);
+                }
+                let args = args
+                    .iter()
+                    .map(|x| x.operand())
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                steps.push(format!("{name}({args}) = {}", self.value()));
+            }
+        }
+    }
+
+    /// How the expression is shown as part of a step, which is its value unless it is a number
+    /// or a percentage of one
+    fn operand(&self) -> String {
+        match self {
+            Expr::Number(x) => x.to_string(),
+            Expr::Percent(rhs) if matches!(**rhs, Expr::Number(_)) => self.bracketed(),
+            _ => self.value(),
+        }
+    }
+
+    fn value(&self) -> String {
+        self.eval()
+            .map(|x| x.to_string())
+            .unwrap_or("?".to_string())
+    }
+}
+
+impl BinOp {
+    fn symbol(&self) -> &'static str {
+        match self {
+            BinOp::Add => "+",
+            BinOp::Sub => "-",
+            BinOp::Mul => "*",
+            BinOp::Div => "/",
+            BinOp::Pow => "^",
+        }
+    }
+}
+
+/* ---------------- History ---------------- */
+
+/// A calculation that was copied from the search results, shown on the calculation history page
+#[derive(Debug, Clone, PartialEq)]
+pub struct Calculation {
+    pub expression: String,
+    pub result: String,
+}
+
+impl Calculation {
+    /// Parses a line of the saved calculation history, which is the expression and result
+    /// separated by a tab
+    pub fn from_line(line: &str) -> Option<Calculation> {
+        let (expression, result) = line.split_once('\t')?;
+        Some(Calculation {
+            expression: expression.to_string(),
+            result: result.to_string(),
+        })
+    }
+
+    /// The calculation as a line of the saved calculation history
+    pub fn to_line(&self) -> String {
+        format!("{}\t{}", self.expression, self.result)
+    }
+}
+
+impl ToApp for Calculation {
+    fn to_app(&self) -> App {
+        App {
+            ranking: 0,
+            open_command: AppCommand::Function(Function::CopyToClipboard(
+                ClipBoardContentType::Text(self.result.clone()),
+            )),
+            desc: "Calculation".to_string(),
+            icons: None,
+            display_name: format!("{} = {}", self.expression, self.result),
+            search_name: self.expression.clone(),
+            version: None,
+            last_used: None,
+            children: None,
+        }
     }
 }
 
@@ -117,6 +293,7 @@ enum Token {
     Star,
     Slash,
     Caret,
+    Percent,
     LParen,
     RParen,
     Comma,
@@ -126,11 +303,18 @@ enum Token {
 struct Lexer<'a> {
     input: &'a str,
     i: usize,
+    /// How many function calls the lexer is inside of, where commas separate the arguments
+    /// instead of thousands
+    call_depth: usize,
 }
 
 impl<'a> Lexer<'a> {
     fn new(input: &'a str) -> Self {
-        Self { input, i: 0 }
+        Self {
+            input,
+            i: 0,
+            call_depth: 0,
+        }
     }
 
     fn peek_char(&self) -> Option<char> {
@@ -178,6 +362,10 @@ impl<'a> Lexer<'a> {
                 self.bump_char();
                 Token::Caret
             }
+            '%' => {
+                self.bump_char();
+                Token::Percent
+            }
             '(' => {
                 self.bump_char();
                 Token::LParen
@@ -208,9 +396,15 @@ impl<'a> Lexer<'a> {
         // Simple float lexer: digits/./e/E/+/- in exponent
         let start = self.i;
         let mut seen_e = false;
+        let mut seen_dot = false;
 
         while let Some(c) = self.peek_char() {
             if c.is_ascii_digit() || c == '.' {
+                seen_dot |= c == '.';
+                self.bump_char();
+                continue;
+            }
+            if c == ',' && !seen_e && !seen_dot && self.call_depth == 0 && self.thousands_follow() {
                 self.bump_char();
                 continue;
             }
@@ -226,13 +420,21 @@ impl<'a> Lexer<'a> {
             break;
         }
 
-        let s = &self.input[start..self.i];
+        let s = self.input[start..self.i].replace(',', "");
         let n = s
             .parse::<f64>()
             .map_err(|_| format!("Invalid number: {s}"))?;
         Ok(Token::Number(n))
     }
 
+    /// Whether the comma at the current position is followed by a group of exactly three digits
+    fn thousands_follow(&self) -> bool {
+        let rest = &self.input.as_bytes()[self.i..];
+        rest.len() >= 4
+            && rest[1..4].iter().all(u8::is_ascii_digit)
+            && !rest.get(4).is_some_and(u8::is_ascii_digit)
+    }
+
     fn lex_ident(&mut self) -> Result<Token, String> {
         let start = self.i;
         while let Some(c) = self.peek_char() {
@@ -294,12 +496,13 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_term(&mut self) -> Result<Expr, String> {
-        // term = power (('*'|'/') power)*
+        // term = power (('*'|'/'|'of') power)*
         let mut node = self.parse_power()?;
         loop {
-            let op = match self.cur {
+            let op = match &self.cur {
                 Token::Star => BinOp::Mul,
                 Token::Slash => BinOp::Div,
+                Token::Ident(name) if name == "of" => BinOp::Mul,
                 _ => break,
             };
             self.bump()?;
@@ -330,7 +533,7 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_unary(&mut self) -> Result<Expr, String> {
-        // unary = ('+'|'-')* primary
+        // unary = ('+'|'-')* percent
         match self.cur {
             Token::Plus => {
                 self.bump()?;
@@ -346,7 +549,18 @@ impl<'a> Parser<'a> {
                     rhs: Box::new(self.parse_unary()?),
                 })
             }
-            _ => self.parse_primary(),
+            _ => self.parse_percent(),
+        }
+    }
+
+    fn parse_percent(&mut self) -> Result<Expr, String> {
+        // percent = primary '%'?
+        let node = self.parse_primary()?;
+        if self.cur == Token::Percent {
+            self.bump()?;
+            Ok(Expr::Percent(Box::new(node)))
+        } else {
+            Ok(node)
         }
     }
 
@@ -367,6 +581,8 @@ impl<'a> Parser<'a> {
                 let name = name.clone();
                 self.bump()?;
                 // function call must be ident '(' ...
+                // (the lexer is already past the '(', so the arguments are lexed as arguments)
+                self.lex.call_depth += 1;
                 self.expect(Token::LParen)?;
                 let mut args = Vec::new();
                 if self.cur != Token::RParen {
@@ -379,6 +595,7 @@ impl<'a> Parser<'a> {
                         break;
                     }
                 }
+                self.lex.call_depth -= 1;
                 self.expect(Token::RParen)?;
                 Ok(Expr::Func { name, args })
             }
@@ -0,0 +1,255 @@
+//! Upcoming events, for searches for `cal` (or `calendar`), and the next meeting in the footer
+//!
+//! The events come from the system calendar (Calendar through EventKit on macOS, and Outlook on
+//! Windows) when `system_calendar` is turned on, and from the ICS calendars in `ics_urls`. Events
+//! with a Zoom, Google Meet, Teams or Webex link in their location or notes get a result that joins
+//! the meeting. The tile keeps the events for [`CACHE_SECONDS`], and reads them again when the
+//! window opens after that, so the footer's next meeting stays up to date
+
+mod ics;
+
+use jiff::{SignedDuration, Timestamp, tz::TimeZone};
+use log::error;
+
+use crate::{
+    app::apps::{App, AppCommand},
+    commands::Function,
+    config::Calendar,
+    platform,
+};
+
+/// How long the events are kept before they are read again
+pub const CACHE_SECONDS: u64 = 5 * 60;
+
+/// How far ahead the footer looks for the next meeting
+const NEXT_MEETING_HOURS: i64 = 12;
+
+/// The parts of meeting links that tell which service they are for
+const CONFERENCE_SERVICES: [(&str, &str); 10] = [
+    ("zoom.us/j/", "Zoom"),
+    ("zoom.us/my/", "Zoom"),
+    ("zoom.us/w/", "Zoom"),
+    ("meet.google.com/", "Google Meet"),
+    ("teams.microsoft.com/l/meetup-join", "Teams"),
+    ("teams.live.com/meet", "Teams"),
+    ("webex.com/", "Webex"),
+    ("meet.jit.si/", "Jitsi"),
+    ("whereby.com/", "Whereby"),
+    ("chime.aws/", "Chime"),
+];
+
+/// An event, or one occurrence of a recurring one
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    pub title: String,
+    pub start: Timestamp,
+    pub end: Timestamp,
+    pub all_day: bool,
+    pub location: String,
+    /// The event's notes (or description), with its url if it has one
+    pub notes: String,
+    /// The name of the calendar it is in
+    pub calendar: String,
+}
+
+impl Event {
+    /// The service and the link that join the meeting, if its location or notes have one
+    pub fn join_link(&self) -> Option<(&'static str, String)> {
+        [&self.location, &self.notes]
+            .into_iter()
+            .flat_map(|x| links(x))
+            .find_map(|link| {
+                let (_, service) = CONFERENCE_SERVICES
+                    .iter()
+                    .find(|(pattern, _)| link.contains(pattern))?;
+                Some((*service, link))
+            })
+    }
+}
+
+/// Whether there is a calendar to read events from
+pub fn is_configured(settings: &Calendar) -> bool {
+    settings.system_calendar || !settings.ics_urls.is_empty()
+}
+
+/// The filter of a `cal` search, or `None` if the search isn't one
+pub fn matching(query: &str) -> Option<String> {
+    let mut words = query.split_whitespace();
+    let keyword = words.next()?;
+    if !keyword.eq_ignore_ascii_case("cal") && !keyword.eq_ignore_ascii_case("calendar") {
+        return None;
+    }
+    Some(words.collect::<Vec<&str>>().join(" "))
+}
+
+/// Read the events from now until `days` from now, from every configured calendar
+///
+/// Calendars that can't be read are logged and left out, unless none of them can be read
+pub async fn upcoming(settings: Calendar) -> Result<Vec<Event>, String> {
+    tokio::task::spawn_blocking(move || {
+        let from = Timestamp::now();
+        let to = from
+            .checked_add(SignedDuration::from_hours(24 * settings.days.max(1) as i64))
+            .map_err(|e| e.to_string())?;
+
+        let mut events = vec![];
+        let mut errors = vec![];
+        if settings.system_calendar {
+            match platform::calendar_events(from, to) {
+                Ok(found) => events.extend(found),
+                Err(e) => errors.push(e),
+            }
+        }
+        for url in &settings.ics_urls {
+            match read_ics(url).and_then(|text| ics::events(&text, from, to)) {
+                Ok(found) => events.extend(found),
+                Err(e) => errors.push(format!("{url}: {e}")),
+            }
+        }
+
+        if events.is_empty() && !errors.is_empty() {
+            return Err(errors.join(", "));
+        }
+        for e in errors {
+            error!("Couldn't read a calendar: {e}");
+        }
+        events.sort_by_key(|x| x.start);
+        Ok(events)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// The text of an ICS calendar, from a url (`webcal://` ones are read over https) or a file
+fn read_ics(url: &str) -> Result<String, String> {
+    let url = url.trim();
+    let url = match url.strip_prefix("webcal://") {
+        Some(rest) => format!("https://{rest}"),
+        None => url.to_string(),
+    };
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        let home = std::env::var("HOME").unwrap_or("/".to_string());
+        return std::fs::read_to_string(url.replacen("~", &home, 1)).map_err(|e| e.to_string());
+    }
+
+    let response = minreq::get(url)
+        .with_header("User-Agent", "rustcast")
+        .with_timeout(10)
+        .send()
+        .map_err(|e| e.to_string())?;
+    if response.status_code != 200 {
+        return Err(format!(
+            "The calendar responded with {} {}",
+            response.status_code, response.reason_phrase
+        ));
+    }
+    response
+        .as_str()
+        .map(str::to_string)
+        .map_err(|e| e.to_string())
+}
+
+/// A result for each event that hasn't ended, which joins the meeting if it has a link
+pub fn calendar_apps(events: &[Event], filter: &str) -> Vec<App> {
+    let now = Timestamp::now();
+    events
+        .iter()
+        .filter(|x| x.end > now)
+        .map(|event| {
+            let mut desc = vec![when(event, now)];
+            if !event.location.is_empty() && !event.location.starts_with("http") {
+                desc.push(event.location.clone());
+            }
+            desc.push(event.calendar.clone());
+
+            let (display_name, open_command) = match event.join_link() {
+                Some((service, link)) => (
+                    format!("Join {service}: {}", event.title),
+                    AppCommand::Function(Function::OpenWebsite(link)),
+                ),
+                None => (event.title.clone(), AppCommand::Display),
+            };
+            App {
+                ranking: 20,
+                open_command,
+                desc: desc.join(" · "),
+                icons: None,
+                display_name,
+                search_name: String::new(),
+                version: None,
+                last_used: None,
+                children: None,
+            }
+        })
+        .filter(|x| x.matches_words(filter))
+        .collect()
+}
+
+/// The next meeting (that isn't all day) in the next few hours, like "Standup in 25 min", for the
+/// footer
+pub fn next_meeting(events: &[Event]) -> Option<String> {
+    let now = Timestamp::now();
+    let until = now
+        .checked_add(SignedDuration::from_hours(NEXT_MEETING_HOURS))
+        .ok()?;
+    let event = events
+        .iter()
+        .find(|x| !x.all_day && x.end > now && x.start < until)?;
+
+    let title: String = if event.title.chars().count() > 30 {
+        format!("{}…", event.title.chars().take(29).collect::<String>())
+    } else {
+        event.title.clone()
+    };
+    let minutes = (event.start.duration_since(now).as_secs() + 59) / 60;
+    Some(match minutes {
+        ..=0 => format!("{title} now"),
+        1..60 => format!("{title} in {minutes} min"),
+        _ => format!(
+            "{title} at {}",
+            event.start.to_zoned(TimeZone::system()).strftime("%H:%M")
+        ),
+    })
+}
+
+/// When an event is, like "Today 14:00–15:00" or "Now until 15:00"
+fn when(event: &Event, now: Timestamp) -> String {
+    let tz = TimeZone::system();
+    let start = event.start.to_zoned(tz.clone());
+    let end = event.end.to_zoned(tz.clone());
+    let today = now.to_zoned(tz).date();
+
+    let day = if start.date() == today {
+        "Today".to_string()
+    } else if today.tomorrow().is_ok_and(|x| x == start.date()) {
+        "Tomorrow".to_string()
+    } else {
+        start.strftime("%a %-d %b").to_string()
+    };
+
+    if event.all_day {
+        format!("{day}, all day")
+    } else if event.start <= now {
+        format!("Now until {}", end.strftime("%H:%M"))
+    } else {
+        format!(
+            "{day} {}–{}",
+            start.strftime("%H:%M"),
+            end.strftime("%H:%M")
+        )
+    }
+}
+
+/// The https links in some text, which ends at a space or anything that usually surrounds one
+fn links(text: &str) -> Vec<String> {
+    text.match_indices("https://")
+        .map(|(i, _)| {
+            text[i..]
+                .split(|c: char| c.is_whitespace() || "<>\"'()[]".contains(c))
+                .next()
+                .unwrap_or_default()
+                .trim_end_matches(['.', ',', ';'])
+                .to_string()
+        })
+        .collect()
+}
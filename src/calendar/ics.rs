@@ -0,0 +1,207 @@
+//! Reading the events of an ICS (iCalendar) file, like the ones calendar apps publish at a secret
+//! address
+//!
+//! The file is read with `icalendar`, and recurring events are repeated with `rrule`, from their
+//! `RRULE`, `RDATE`s and `EXDATE`s. Occurrences that were moved (with a `RECURRENCE-ID`) are
+//! replaced, and events whose rule can't be repeated only give their first occurrence
+
+use chrono::TimeZone as _;
+use icalendar::{
+    CalendarComponent, CalendarDateTime, Component, DatePerhapsTime, EventLike, EventStatus,
+    Property,
+};
+use jiff::{SignedDuration, Span, Timestamp};
+use log::warn;
+use rrule::{RRule, RRuleSet, Tz, Unvalidated};
+
+use super::Event;
+
+/// The most occurrences of a recurring event between two times, so that a rule that repeats every
+/// second still stops
+const MAX_OCCURRENCES: u16 = 1000;
+
+/// The events in an ICS file that happen (at least partly) between two times
+pub fn events(text: &str, from: Timestamp, to: Timestamp) -> Result<Vec<Event>, String> {
+    let calendar: icalendar::Calendar = text.parse()?;
+    let calendar_name = calendar.get_name().unwrap_or("Calendar").to_string();
+    let raw_events: Vec<&icalendar::Event> = calendar
+        .components
+        .iter()
+        .filter_map(|x| match x {
+            CalendarComponent::Event(event) => Some(event),
+            _ => None,
+        })
+        .collect();
+
+    // Occurrences of recurring events that were moved, which are events of their own
+    let moved: Vec<(&str, Timestamp)> = raw_events
+        .iter()
+        .filter_map(|x| Some((x.get_uid()?, timestamp(&time(x.get_recurrence_id()?)?.0))))
+        .collect();
+
+    let mut events = vec![];
+    for raw in raw_events {
+        if raw.get_status() == Some(EventStatus::Cancelled) {
+            continue;
+        }
+        let Some((start, all_day)) = raw.get_start().and_then(time) else {
+            continue;
+        };
+        let length = length(raw, &start, all_day);
+        let uid = raw.get_uid().unwrap_or_default();
+
+        let occurrences = match raw.property_value("RRULE") {
+            Some(rule) if raw.get_recurrence_id().is_none() => {
+                let after = from.checked_sub(length).unwrap_or(from);
+                repeat(raw, &start, rule, after, to)
+                    .into_iter()
+                    .filter(|x| !moved.iter().any(|(id, time)| *id == uid && time == x))
+                    .collect()
+            }
+            _ => vec![timestamp(&start)],
+        };
+
+        let mut notes = raw.get_description().unwrap_or_default().to_string();
+        if let Some(url) = raw.get_url().filter(|x| !x.is_empty()) {
+            notes = format!("{url}\n{notes}");
+        }
+        for occurrence in occurrences {
+            let Ok(end) = occurrence.checked_add(length) else {
+                continue;
+            };
+            if end > from && occurrence < to {
+                events.push(Event {
+                    title: raw.get_summary().unwrap_or_default().to_string(),
+                    start: occurrence,
+                    end,
+                    all_day,
+                    location: raw.get_location().unwrap_or_default().to_string(),
+                    notes: notes.clone(),
+                    calendar: calendar_name.clone(),
+                });
+            }
+        }
+    }
+    Ok(events)
+}
+
+/// A date or time in its own timezone, so that repeating it keeps its local time, and whether it
+/// is a whole day
+///
+/// Timezones that aren't in the database (like Outlook's Windows names) are taken as the local one
+fn time(value: DatePerhapsTime) -> Option<(chrono::DateTime<Tz>, bool)> {
+    let (datetime, tz, all_day) = match value {
+        DatePerhapsTime::Date(date) => (date.and_hms_opt(0, 0, 0)?, Tz::LOCAL, true),
+        DatePerhapsTime::DateTime(CalendarDateTime::Utc(utc)) => {
+            return Some((utc.with_timezone(&Tz::UTC), false));
+        }
+        DatePerhapsTime::DateTime(CalendarDateTime::Floating(datetime)) => {
+            (datetime, Tz::LOCAL, false)
+        }
+        DatePerhapsTime::DateTime(CalendarDateTime::WithTimezone { date_time, tzid }) => {
+            let tz = tzid
+                .trim_matches('"')
+                .parse::<chrono_tz::Tz>()
+                .map_or(Tz::LOCAL, Tz::from);
+            (date_time, tz, false)
+        }
+    };
+    // Times skipped by a daylight saving change don't exist, and ones repeated by it are taken
+    // the first time
+    Some((tz.from_local_datetime(&datetime).earliest()?, all_day))
+}
+
+fn timestamp(time: &chrono::DateTime<Tz>) -> Timestamp {
+    Timestamp::new(time.timestamp(), time.timestamp_subsec_nanos() as i32)
+        .unwrap_or(Timestamp::UNIX_EPOCH)
+}
+
+fn chrono_time(timestamp: Timestamp) -> Option<chrono::DateTime<Tz>> {
+    Tz::UTC
+        .timestamp_opt(timestamp.as_second(), timestamp.subsec_nanosecond() as u32)
+        .single()
+}
+
+/// How long an event is, from its end or its duration
+fn length(raw: &icalendar::Event, start: &chrono::DateTime<Tz>, all_day: bool) -> SignedDuration {
+    let start_timestamp = timestamp(start);
+    if let Some((end, _)) = raw.get_end().and_then(time) {
+        return timestamp(&end).duration_since(start_timestamp);
+    }
+    // Durations like `P1D` are a calendar day in the start's timezone, which can be 23 or 25
+    // hours long
+    let end = raw
+        .property_value("DURATION")
+        .and_then(|x| x.trim().parse::<Span>().ok())
+        .and_then(|x| {
+            let tz = jiff::tz::TimeZone::get(start.timezone().name())
+                .unwrap_or_else(|_| jiff::tz::TimeZone::system());
+            start_timestamp.to_zoned(tz).checked_add(x).ok()
+        });
+    match end {
+        Some(end) => end.timestamp().duration_since(start_timestamp),
+        None if all_day => SignedDuration::from_hours(24),
+        None => SignedDuration::ZERO,
+    }
+}
+
+/// The times a recurring event happens between two times, from its `RRULE`, `RDATE`s and
+/// `EXDATE`s, or only its start if its rule can't be repeated
+fn repeat(
+    raw: &icalendar::Event,
+    start: &chrono::DateTime<Tz>,
+    rule: &str,
+    from: Timestamp,
+    to: Timestamp,
+) -> Vec<Timestamp> {
+    let set = match rule
+        .parse::<RRule<Unvalidated>>()
+        .and_then(|x| x.build(*start))
+    {
+        Ok(set) => set,
+        Err(e) => {
+            warn!(
+                "Couldn't repeat {:?} ({rule}): {e}",
+                raw.get_summary().unwrap_or_default()
+            );
+            return vec![timestamp(start)];
+        }
+    };
+    let set = dates(raw, "RDATE").fold(set, RRuleSet::rdate);
+    let mut set = dates(raw, "EXDATE").fold(set, RRuleSet::exdate);
+    if let Some(from) = chrono_time(from) {
+        set = set.after(from);
+    }
+    if let Some(to) = chrono_time(to) {
+        set = set.before(to);
+    }
+    set.all(MAX_OCCURRENCES)
+        .dates
+        .iter()
+        .map(timestamp)
+        .collect()
+}
+
+/// The dates and times in each of the properties with a name, which can each have several,
+/// separated by commas
+fn dates<'a>(
+    raw: &'a icalendar::Event,
+    name: &str,
+) -> impl Iterator<Item = chrono::DateTime<Tz>> + 'a {
+    raw.multi_properties()
+        .get(name)
+        .into_iter()
+        .flatten()
+        .flat_map(|property| {
+            property.value().split(',').filter_map(|value| {
+                let mut single = Property::new(property.key(), value.trim());
+                for param in property.params().values() {
+                    single.append_parameter(param.clone());
+                }
+                time(DatePerhapsTime::from_property(&single)?).map(|(x, _)| x)
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,147 @@
+//! The events read from small ICS files, for the parts of the format that calendar apps get wrong
+//! most often
+
+use jiff::Timestamp;
+
+use super::events;
+
+fn at(time: &str) -> Timestamp {
+    time.parse().unwrap()
+}
+
+/// A calendar with the events, between the lines of its header and footer
+fn calendar(events: &str) -> String {
+    format!(
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//rustcast//tests//EN\r\nX-WR-CALNAME:Work\r\n\
+         {events}END:VCALENDAR\r\n"
+    )
+}
+
+/// The start times of the events in the calendar, in January 2026
+fn starts(events_text: &str) -> Vec<String> {
+    let mut events = events(
+        &calendar(events_text),
+        at("2026-01-01T00:00:00Z"),
+        at("2026-02-01T00:00:00Z"),
+    )
+    .unwrap();
+    events.sort_by_key(|x| x.start);
+    events.iter().map(|x| x.start.to_string()).collect()
+}
+
+#[test]
+fn folded_lines_are_put_back_together() {
+    let events = events(
+        &calendar(
+            "BEGIN:VEVENT\r\nUID:1\r\nDTSTART:20260105T090000Z\r\nDTEND:20260105T100000Z\r\n\
+             SUMMARY:Planning for the\r\n  next quarter\r\nDESCRIPTION:Join at https://meet.go\r\n\
+             \togle.com/abc-defg-hij\r\nEND:VEVENT\r\n",
+        ),
+        at("2026-01-01T00:00:00Z"),
+        at("2026-02-01T00:00:00Z"),
+    )
+    .unwrap();
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].title, "Planning for the next quarter");
+    assert_eq!(
+        events[0].notes,
+        "Join at https://meet.google.com/abc-defg-hij"
+    );
+    assert_eq!(events[0].calendar, "Work");
+}
+
+#[test]
+fn escaped_text_is_unescaped() {
+    let events = events(
+        &calendar(
+            "BEGIN:VEVENT\r\nUID:1\r\nDTSTART:20260105T090000Z\r\n\
+             SUMMARY:Lunch\\, then a walk\\; maybe\r\nLOCATION:Room 1\\\\2\r\n\
+             DESCRIPTION:First line\\nSecond line\r\nEND:VEVENT\r\n",
+        ),
+        at("2026-01-01T00:00:00Z"),
+        at("2026-02-01T00:00:00Z"),
+    )
+    .unwrap();
+
+    assert_eq!(events[0].title, "Lunch, then a walk; maybe");
+    assert_eq!(events[0].location, "Room 1\\2");
+    assert_eq!(events[0].notes, "First line\nSecond line");
+}
+
+#[test]
+fn times_are_read_in_their_timezone() {
+    let events = events(
+        &calendar(
+            "BEGIN:VEVENT\r\nUID:1\r\nDTSTART;TZID=Europe/Berlin:20260105T090000\r\n\
+             DTEND;TZID=America/New_York:20260105T040000\r\nSUMMARY:Standup\r\nEND:VEVENT\r\n",
+        ),
+        at("2026-01-01T00:00:00Z"),
+        at("2026-02-01T00:00:00Z"),
+    )
+    .unwrap();
+
+    assert_eq!(events[0].start, at("2026-01-05T08:00:00Z"));
+    assert_eq!(events[0].end, at("2026-01-05T09:00:00Z"));
+}
+
+#[test]
+fn weekly_rules_repeat_on_their_days_and_keep_the_local_time() {
+    assert_eq!(
+        starts(
+            "BEGIN:VEVENT\r\nUID:1\r\nDTSTART;TZID=Europe/Berlin:20251229T090000\r\n\
+             DURATION:PT30M\r\nRRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH;UNTIL=20260125T000000Z\r\n\
+             SUMMARY:Sync\r\nEND:VEVENT\r\n"
+        ),
+        [
+            "2026-01-01T08:00:00Z",
+            "2026-01-12T08:00:00Z",
+            "2026-01-15T08:00:00Z",
+        ]
+    );
+}
+
+#[test]
+fn monthly_rules_repeat_on_their_weekday_of_the_month() {
+    assert_eq!(
+        starts(
+            "BEGIN:VEVENT\r\nUID:1\r\nDTSTART:20251111T170000Z\r\nDTEND:20251111T180000Z\r\n\
+             RRULE:FREQ=MONTHLY;BYDAY=2TU\r\nSUMMARY:Meetup\r\nEND:VEVENT\r\n"
+        ),
+        ["2026-01-13T17:00:00Z"]
+    );
+    // Months without a 31st are skipped
+    assert_eq!(
+        starts(
+            "BEGIN:VEVENT\r\nUID:2\r\nDTSTART:20251031T170000Z\r\nRRULE:FREQ=MONTHLY;COUNT=4\r\n\
+             SUMMARY:Invoices\r\nEND:VEVENT\r\n"
+        ),
+        ["2026-01-31T17:00:00Z"]
+    );
+}
+
+#[test]
+fn excluded_and_moved_occurrences_are_left_out() {
+    assert_eq!(
+        starts(
+            "BEGIN:VEVENT\r\nUID:1\r\nDTSTART;TZID=Europe/Berlin:20260105T090000\r\n\
+             RRULE:FREQ=WEEKLY;COUNT=4\r\n\
+             EXDATE;TZID=Europe/Berlin:20260112T090000,20260119T090000\r\n\
+             SUMMARY:Standup\r\nEND:VEVENT\r\n\
+             BEGIN:VEVENT\r\nUID:1\r\nRECURRENCE-ID;TZID=Europe/Berlin:20260126T090000\r\n\
+             DTSTART;TZID=Europe/Berlin:20260127T100000\r\nSUMMARY:Standup\r\nEND:VEVENT\r\n"
+        ),
+        ["2026-01-05T08:00:00Z", "2026-01-27T09:00:00Z"]
+    );
+}
+
+#[test]
+fn cancelled_events_are_left_out() {
+    assert!(
+        starts(
+            "BEGIN:VEVENT\r\nUID:1\r\nDTSTART:20260105T090000Z\r\nSTATUS:CANCELLED\r\n\
+             SUMMARY:Standup\r\nEND:VEVENT\r\n"
+        )
+        .is_empty()
+    );
+}
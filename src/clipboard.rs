@@ -1,9 +1,21 @@
 //! This has all the logic regarding the cliboard history
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fs,
+    hash::{DefaultHasher, Hash, Hasher},
+    path::{Path, PathBuf},
+};
+
 use arboard::ImageData;
+use log::error;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     app::{ToApp, apps::App},
     commands::Function,
+    platform,
+    utils::config_path,
 };
 
 /// The kinds of clipboard content that rustcast can handle and their contents
@@ -37,6 +49,9 @@ impl ToApp for ClipBoardContentType {
             icons: None,
             display_name,
             search_name,
+            version: None,
+            last_used: None,
+            children: None,
         }
     }
 }
@@ -56,3 +71,337 @@ impl PartialEq for ClipBoardContentType {
         false
     }
 }
+
+impl ClipBoardContentType {
+    /// Whether the content looks like a secret that shouldn't stay in the clipboard history
+    ///
+    /// This matches one time passcodes, short lines mentioning one of the keywords (such as
+    /// "password") and long random looking tokens such as API keys
+    pub fn looks_secret(&self, keywords: &[String]) -> bool {
+        let ClipBoardContentType::Text(text) = self else {
+            return false;
+        };
+        let text = text.trim();
+
+        if text.contains(char::is_whitespace) {
+            let text_lc = text.to_lowercase();
+            return !text.contains('\n')
+                && text.len() <= 100
+                && keywords
+                    .iter()
+                    .any(|keyword| text_lc.contains(&keyword.to_lowercase()));
+        }
+
+        let is_otp = (6..=8).contains(&text.len()) && text.chars().all(|c| c.is_ascii_digit());
+        is_otp || is_random_token(text)
+    }
+}
+
+/// Save an image from the clipboard as a file, in the format that its extension is for
+pub fn save_image(image: &ImageData, path: &Path) -> Result<(), String> {
+    image::RgbaImage::from_raw(
+        image.width as u32,
+        image.height as u32,
+        image.bytes.to_vec(),
+    )
+    .ok_or("The image is missing some of its pixels")?
+    .save(path)
+    .map_err(|e| format!("Could not save the image: {e}"))
+}
+
+/// The text in an image, which is saved to a temporary file for [`platform::recognize_text`] to
+/// read
+pub fn extract_text(image: &ImageData) -> Result<String, String> {
+    let path = std::env::temp_dir().join("rustcast-extract-text.png");
+    save_image(image, &path)?;
+
+    let text = platform::recognize_text(&path);
+    fs::remove_file(&path).ok();
+    text
+}
+
+/// Whether a single word is long, mixes character classes and has a high entropy, like generated
+/// passwords and API keys do
+fn is_random_token(text: &str) -> bool {
+    if !(16..=256).contains(&text.len())
+        || text.contains("://")
+        || text.starts_with('/')
+        || text.starts_with('~')
+    {
+        return false;
+    }
+
+    let classes = [
+        text.chars().any(|c| c.is_ascii_lowercase()),
+        text.chars().any(|c| c.is_ascii_uppercase()),
+        text.chars().any(|c| c.is_ascii_digit()),
+        text.chars().any(|c| !c.is_alphanumeric()),
+    ];
+    if classes.iter().filter(|x| **x).count() < 3 {
+        return false;
+    }
+
+    let mut counts = HashMap::new();
+    for c in text.chars() {
+        *counts.entry(c).or_insert(0) += 1;
+    }
+    let len = text.chars().count() as f64;
+    let entropy: f64 = counts
+        .values()
+        .map(|count| {
+            let p = *count as f64 / len;
+            -p * p.log2()
+        })
+        .sum();
+
+    entropy >= 3.5
+}
+
+/// The app that was frontmost when something was copied
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClipboardSource {
+    pub name: String,
+    pub bundle_id: Option<String>,
+}
+
+/// A single item in the clipboard history, along with its metadata
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClipboardEntry {
+    pub content: ClipBoardContentType,
+    pub pinned: bool,
+    pub source: Option<ClipboardSource>,
+}
+
+impl ClipboardEntry {
+    pub fn new(content: ClipBoardContentType, source: Option<ClipboardSource>) -> Self {
+        Self {
+            content,
+            pinned: false,
+            source,
+        }
+    }
+}
+
+impl ToApp for ClipboardEntry {
+    /// Pinned entries have a pin in front of their display name
+    fn to_app(&self) -> App {
+        let mut app = self.content.to_app();
+        if let Some(source) = &self.source {
+            app.desc = format!("Copied from {}", source.name);
+        }
+        if self.pinned {
+            app.display_name = format!("📌 {}", app.display_name);
+            app.desc = "Pinned Clipboard Item".to_string();
+        }
+        app
+    }
+}
+
+/// The clipboard history, with pinned entries always kept at the top
+///
+/// Pinned entries are never evicted when the history grows past its limit, and are saved to disk so
+/// that they survive restarts
+#[derive(Debug, Clone, Default)]
+pub struct ClipboardHistory {
+    entries: Vec<ClipboardEntry>,
+}
+
+impl ClipboardHistory {
+    /// Load the clipboard history, containing only the pinned entries that were saved to disk
+    ///
+    /// Pinned images whose files can't be read are left out
+    pub fn load() -> Self {
+        let entries = fs::read_to_string(pinned_file_path())
+            .ok()
+            .and_then(|x| serde_json::from_str::<Vec<Pinned>>(&x).ok())
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|pinned| {
+                let content = match pinned {
+                    Pinned::Text(text) => ClipBoardContentType::Text(text),
+                    Pinned::Image { image } => ClipBoardContentType::Image(load_image(&image)?),
+                };
+                Some(ClipboardEntry {
+                    content,
+                    pinned: true,
+                    source: None,
+                })
+            })
+            .collect();
+
+        Self { entries }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ClipboardEntry> {
+        self.entries.iter()
+    }
+
+    /// Add new content to the history
+    ///
+    /// If the content is already in the history, it is moved to the top of the unpinned entries
+    /// (or stays where it is if it is pinned). Unpinned entries past the limit are evicted.
+    pub fn add(
+        &mut self,
+        content: ClipBoardContentType,
+        source: Option<ClipboardSource>,
+        limit: usize,
+    ) {
+        if self
+            .entries
+            .iter()
+            .any(|x| x.pinned && x.content == content)
+        {
+            return;
+        }
+
+        self.entries.retain(|x| x.content != content);
+        let first_unpinned = self.entries.iter().filter(|x| x.pinned).count();
+        self.entries
+            .insert(first_unpinned, ClipboardEntry::new(content, source));
+
+        let mut unpinned = 0;
+        self.entries.retain(|x| {
+            if x.pinned {
+                return true;
+            }
+            unpinned += 1;
+            unpinned <= limit
+        });
+    }
+
+    pub fn remove(&mut self, content: &ClipBoardContentType) {
+        let was_pinned = self
+            .entries
+            .iter()
+            .any(|x| x.pinned && x.content == *content);
+        self.entries.retain(|x| x.content != *content);
+
+        if was_pinned {
+            self.save_pinned();
+        }
+    }
+
+    pub fn update(&mut self, old: &ClipBoardContentType, new: ClipBoardContentType) {
+        let mut pinned_changed = false;
+        for entry in self.entries.iter_mut() {
+            if entry.content == *old {
+                entry.content = new.clone();
+                pinned_changed |= entry.pinned;
+            }
+        }
+
+        if pinned_changed {
+            self.save_pinned();
+        }
+    }
+
+    /// Pin or unpin an entry, moving it to the top of the history when it is pinned
+    pub fn toggle_pin(&mut self, content: &ClipBoardContentType) {
+        let Some(index) = self.entries.iter().position(|x| x.content == *content) else {
+            return;
+        };
+
+        let mut entry = self.entries.remove(index);
+        entry.pinned = !entry.pinned;
+
+        let first_unpinned = self.entries.iter().filter(|x| x.pinned).count();
+        if entry.pinned {
+            self.entries.insert(0, entry);
+        } else {
+            self.entries.insert(first_unpinned, entry);
+        }
+
+        self.save_pinned();
+    }
+
+    /// Remove an entry unless it has been pinned since it was added
+    pub fn expire(&mut self, content: &ClipBoardContentType) {
+        self.entries.retain(|x| x.pinned || x.content != *content);
+    }
+
+    /// Clear all the entries that aren't pinned
+    pub fn clear(&mut self) {
+        self.entries.retain(|x| x.pinned);
+    }
+
+    /// Save the pinned entries to disk, with the images as PNG files in a folder next to the list,
+    /// and remove the files of images that aren't pinned anymore
+    fn save_pinned(&self) {
+        let images_dir = pinned_images_dir();
+        let pinned: Vec<Pinned> = self
+            .entries
+            .iter()
+            .filter(|x| x.pinned)
+            .filter_map(|x| match &x.content {
+                ClipBoardContentType::Text(text) => Some(Pinned::Text(text.clone())),
+                ClipBoardContentType::Image(image) => {
+                    // The file is named after the image's pixels, so it is only written once
+                    let mut hasher = DefaultHasher::new();
+                    image.bytes.hash(&mut hasher);
+                    let name = format!("{:016x}.png", hasher.finish());
+                    let path = images_dir.join(&name);
+                    if !path.exists() {
+                        fs::create_dir_all(&images_dir).ok();
+                        if let Err(e) = save_image(image, &path) {
+                            error!("Couldn't save a pinned image: {e}");
+                            return None;
+                        }
+                    }
+                    Some(Pinned::Image { image: name })
+                }
+            })
+            .collect();
+
+        for file in fs::read_dir(&images_dir).into_iter().flatten().flatten() {
+            let name = file.file_name().to_string_lossy().to_string();
+            if !pinned
+                .iter()
+                .any(|x| matches!(x, Pinned::Image { image } if *image == name))
+            {
+                fs::remove_file(file.path()).ok();
+            }
+        }
+
+        if let Ok(json) = serde_json::to_string(&pinned) {
+            fs::write(pinned_file_path(), json).ok();
+        }
+    }
+}
+
+/// A pinned entry as it is saved, where text is a string (which is all that older versions saved)
+/// and an image is the name of its file in [`pinned_images_dir`]
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum Pinned {
+    Text(String),
+    Image { image: String },
+}
+
+/// A pinned image from its file in [`pinned_images_dir`]
+fn load_image(name: &str) -> Option<ImageData<'static>> {
+    let image = image::open(pinned_images_dir().join(name))
+        .inspect_err(|e| error!("Couldn't read the pinned image {name}: {e}"))
+        .ok()?
+        .into_rgba8();
+    Some(ImageData {
+        width: image.width() as usize,
+        height: image.height() as usize,
+        bytes: Cow::Owned(image.into_raw()),
+    })
+}
+
+fn pinned_file_path() -> PathBuf {
+    config_path("pinned_clipboard.json")
+}
+
+fn pinned_images_dir() -> PathBuf {
+    config_path("pinned_clipboard")
+}
@@ -1,114 +1,235 @@
 //! This handles all the different commands that rustcast can perform, such as opening apps,
 //! copying to clipboard, etc.
-use std::{process::Command, thread};
+use std::{collections::HashMap, process::Command};
 
 use arboard::Clipboard;
-use objc2_app_kit::NSWorkspace;
-use objc2_foundation::NSURL;
 
 use crate::{
-    app::apps::{App, AppCommand},
+    app::apps::{App, AppCommand, Children},
     calculator::Expr,
     clipboard::ClipBoardContentType,
-    config::Config,
-    quit::{terminate_all_apps, terminate_app},
+    config::{Config, SearchEngine, Shell},
+    logging,
+    media::MediaAction,
+    notes, notifications, platform,
 };
 
 /// The different functions that rustcast can perform
 #[derive(Debug, Clone, PartialEq)]
 pub enum Function {
     OpenApp(String),
+    OpenAtLine(String, usize),
+    Reveal(String),
+    OpenWith(String, String),
+    /// Quit an app by its name, which only macOS lists (see [`crate::platform::open_apps`])
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
     QuitApp(String),
     QuitAllApps,
-    RunShellCommand(String),
+    QuitAppAt(String),
+    ForceQuitAppAt(String),
+    Uninstall(String),
+    KillProcess(u32),
+    ToggleWifi,
+    ToggleBluetooth,
+    ToggleDarkMode,
+    ToggleNightShift,
+    ToggleTrueTone,
+    MediaControl(MediaAction),
+    RunShellCommand(ShellCommand),
+    RunInTerminal(ShellCommand),
     OpenWebsite(String),
-    RandomVar(i32), // Easter egg function
     CopyToClipboard(ClipBoardContentType),
     GoogleSearch(String),
+    SearchWith(SearchEngine, String),
     Calculate(Expr),
+    AddNote(String),
+    Notify(String, String),
+    OpenLogFile,
     Quit,
 }
 
 impl Function {
-    /// Run the command
-    pub fn execute(&self, config: &Config) {
+    /// Run the command, returning a message saying what went wrong if it couldn't be run
+    pub fn execute(&self, config: &Config) -> Result<(), String> {
         match self {
-            Function::OpenApp(path) => {
-                let path = path.to_owned();
-                thread::spawn(move || {
-                    NSWorkspace::new().openURL(&NSURL::fileURLWithPath(
-                        &objc2_foundation::NSString::from_str(&path),
-                    ));
-                });
-            }
+            Function::OpenApp(path) => platform::open_path(path)?,
+            Function::OpenAtLine(path, line) => match &config.content_search.open_at_line {
+                Some(command) => open_at_line(command, path, *line)?,
+                None => platform::open_path(path)?,
+            },
+            Function::Reveal(path) => platform::reveal_path(path)?,
+            Function::OpenWith(target, app) => platform::open_with(target, app)?,
             Function::RunShellCommand(command) => {
-                Command::new("sh").arg("-c").arg(command).spawn().ok();
-            }
-            Function::RandomVar(var) => {
-                Clipboard::new()
-                    .unwrap()
-                    .set_text(var.to_string())
-                    .unwrap_or(());
+                command
+                    .process()
+                    .spawn()
+                    .map_err(|e| format!("Could not run `{}`: {e}", command.command))?;
             }
+            Function::RunInTerminal(command) => platform::run_in_terminal(command)?,
 
-            Function::QuitAllApps => {
-                terminate_all_apps();
-            }
+            Function::QuitAllApps => platform::terminate_all_apps()?,
 
-            Function::QuitApp(name) => {
-                terminate_app(name.to_owned());
-            }
+            Function::QuitApp(name) => platform::terminate_app(name)?,
+            Function::QuitAppAt(path) => platform::quit_app_at(path, false)?,
+            Function::ForceQuitAppAt(path) => platform::quit_app_at(path, true)?,
+            Function::Uninstall(path) => platform::uninstall_app(path)?,
+            Function::KillProcess(pid) => platform::kill_process(*pid)?,
+            Function::ToggleWifi => platform::toggle_wifi()?,
+            Function::ToggleBluetooth => platform::toggle_bluetooth()?,
+            Function::ToggleDarkMode => platform::toggle_dark_mode()?,
+            Function::ToggleNightShift => platform::toggle_night_shift()?,
+            Function::ToggleTrueTone => platform::toggle_true_tone()?,
+            Function::MediaControl(action) => platform::media_control(*action)?,
 
             Function::GoogleSearch(query_string) => {
-                let query_args = query_string.replace(" ", "+");
-                let query = config.search_url.replace("%s", &query_args);
-                let query = query.strip_suffix("?").unwrap_or(&query).to_string();
-                thread::spawn(move || {
-                    NSWorkspace::new().openURL(
-                        &NSURL::URLWithString_relativeToURL(
-                            &objc2_foundation::NSString::from_str(&query),
-                            None,
-                        )
-                        .unwrap(),
-                    );
-                });
+                platform::open_url(&search_url(&config.search_url, query_string))?;
+            }
+
+            Function::SearchWith(engine, query_string) => {
+                platform::open_url(&search_url(&engine.url, query_string))?;
             }
 
             Function::OpenWebsite(url) => {
-                let open = if url.starts_with("http") {
-                    url.to_owned()
-                } else {
-                    format!("https://{}", url)
-                };
-                thread::spawn(move || {
-                    NSWorkspace::new().openURL(
-                        &NSURL::URLWithString_relativeToURL(
-                            &objc2_foundation::NSString::from_str(&open),
-                            None,
-                        )
-                        .unwrap(),
-                    );
-                });
+                // Links with their own scheme (like `mailto:`) are opened as they are
+                let open =
+                    if url.starts_with("http") || url.starts_with("mailto:") || url.contains("://")
+                    {
+                        url.to_owned()
+                    } else {
+                        format!("https://{}", url)
+                    };
+                platform::open_url(&open)?;
             }
 
             Function::Calculate(expr) => {
-                Clipboard::new()
-                    .unwrap()
-                    .set_text(expr.eval().map(|x| x.to_string()).unwrap_or("".to_string()))
-                    .unwrap_or(());
+                copy_text(expr.eval().map(|x| x.to_string()).unwrap_or("".to_string()))?
+            }
+
+            Function::AddNote(text) => notes::capture(&config.notes, text)?,
+
+            Function::Notify(title, body) => notifications::notify(title, body, true)?,
+            Function::OpenLogFile => {
+                platform::open_path(&logging::log_file(&config.logging).to_string_lossy())?
             }
 
             Function::CopyToClipboard(clipboard_content) => match clipboard_content {
-                ClipBoardContentType::Text(text) => {
-                    Clipboard::new().unwrap().set_text(text).ok();
-                }
+                ClipBoardContentType::Text(text) => copy_text(text.to_owned())?,
                 ClipBoardContentType::Image(img) => {
-                    Clipboard::new().unwrap().set_image(img.to_owned_img()).ok();
+                    Clipboard::new()
+                        .and_then(|mut clipboard| clipboard.set_image(img.to_owned_img()))
+                        .map_err(|e| format!("Could not copy the image: {e}"))?;
                 }
             },
 
             Function::Quit => std::process::exit(0),
         }
+        Ok(())
+    }
+}
+
+/// The url for a web search, made by putting the search into the `%s` in the engine's url
+fn search_url(engine_url: &str, query: &str) -> String {
+    let query_args = query.replace(" ", "+");
+    let url = engine_url.replace("%s", &query_args);
+    url.strip_suffix("?").unwrap_or(&url).to_string()
+}
+
+/// Open a file at a line with the `open_at_line` command, which has `{path}` and `{line}` in it
+fn open_at_line(command: &str, path: &str, line: usize) -> Result<(), String> {
+    let mut args = command.split_whitespace().map(|arg| {
+        arg.replace("{path}", path)
+            .replace("{line}", &line.to_string())
+    });
+    let program = args.next().ok_or("`open_at_line` is empty")?;
+    Command::new(&program)
+        .args(args)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Could not run {program}: {e}"))
+}
+
+/// Copy text to the clipboard
+fn copy_text(text: String) -> Result<(), String> {
+    Clipboard::new()
+        .and_then(|mut clipboard| clipboard.set_text(text))
+        .map_err(|e| format!("Could not copy to the clipboard: {e}"))
+}
+
+/// A shell command, along with the shell, working directory and environment to run it with
+///
+/// Without a shell set, commands run with [`Shell::native`] in the background, and with the
+/// terminal's own shell in a terminal
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ShellCommand {
+    pub command: String,
+    pub shell: Option<Shell>,
+    pub cwd: Option<String>,
+    pub env: HashMap<String, String>,
+}
+
+impl ShellCommand {
+    /// A command that runs with the default shell, working directory and environment
+    pub fn new(command: impl Into<String>) -> Self {
+        ShellCommand {
+            command: command.into(),
+            ..Default::default()
+        }
+    }
+
+    /// The working directory, with `~` expanded to the home directory
+    pub fn cwd(&self) -> Option<String> {
+        let home = std::env::var("HOME").unwrap_or("/".to_string());
+        self.cwd.as_ref().map(|x| x.replace("~", &home))
+    }
+
+    /// The process that runs the command in the background
+    pub fn process(&self) -> Command {
+        let shell = self.shell.unwrap_or(Shell::native());
+        let mut process = Command::new(shell.program());
+        process
+            .arg(shell.command_flag())
+            .arg(&self.command)
+            .envs(&self.env);
+        if let Some(cwd) = self.cwd() {
+            process.current_dir(cwd);
+        }
+        process
+    }
+
+    /// The command as a single line for a POSIX shell, that changes to the working directory and
+    /// sets the environment before running the command, for typing into a terminal
+    #[cfg(not(target_os = "windows"))]
+    pub fn script(&self) -> String {
+        let quote = |x: &str| format!("'{}'", x.replace('\'', "'\\''"));
+
+        let mut script = String::new();
+        if let Some(cwd) = self.cwd() {
+            script += &format!("cd {} && ", quote(&cwd));
+        }
+
+        let mut env = self
+            .env
+            .iter()
+            .map(|(key, value)| quote(&format!("{key}={value}")))
+            .collect::<Vec<String>>();
+        env.sort();
+        if !env.is_empty() {
+            script += &format!("env {} ", env.join(" "));
+        }
+
+        match self.shell {
+            Some(shell) => {
+                script += &format!(
+                    "{} {} {}",
+                    shell.program(),
+                    shell.command_flag(),
+                    quote(&self.command)
+                )
+            }
+            None if env.is_empty() => script += &self.command,
+            None => script += &format!("sh -c {}", quote(&self.command)),
+        }
+        script
     }
 }
 
@@ -127,6 +248,12 @@ pub fn path_to_app(absolute_path: &str, home_dir: &str) -> Option<App> {
         return None;
     }
 
+    // Folders can be browsed into, but apps (which are folders too) are only opened
+    let is_folder = std::path::Path::new(path).is_dir()
+        && std::path::Path::new(path)
+            .extension()
+            .is_none_or(|x| x != "app");
+
     let display_path = if let Some(suffix) = path.strip_prefix(home_dir) {
         format!("~{suffix}")
     } else {
@@ -140,5 +267,8 @@ pub fn path_to_app(absolute_path: &str, home_dir: &str) -> Option<App> {
         icons: None,
         display_name: filename.to_string(),
         search_name: filename.to_lowercase(),
+        version: None,
+        last_used: None,
+        children: is_folder.then(|| Children::Folder(path.to_string())),
     })
 }
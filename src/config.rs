@@ -1,7 +1,17 @@
 //! This is the config file type definitions for rustcast
-use std::{collections::HashMap, path::Path, sync::Arc};
+pub mod doctor;
 
-use iced::{Font, font::Family, theme::Custom, widget::image::Handle};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use iced::{
+    Font, Point, Rectangle, Size, font::Family, theme::Custom, widget::image::Handle,
+    window::Position,
+};
 use serde::{Deserialize, Serialize};
 
 use crate::{
@@ -9,8 +19,12 @@ use crate::{
         ToApp,
         apps::{App, AppCommand},
     },
-    commands::Function,
-    utils::handle_from_icns,
+    clipboard::ClipboardSource,
+    commands::{Function, ShellCommand},
+    i18n,
+    platform::Shortcut,
+    providers,
+    utils::{handle_from_icns, hostname},
 };
 
 /// The main config struct (effectively the config file's "schema")
@@ -20,20 +34,51 @@ pub struct Config {
     pub toggle_hotkey: String,
     pub clipboard_hotkey: String,
     pub buffer_rules: Buffer,
+    pub window: WindowConfig,
     pub main_page: MainPage,
     pub start_at_login: bool,
     pub theme: Theme,
     pub placeholder: String,
+    pub language: Option<String>,
     pub search_url: String,
+    pub search_engines: Vec<SearchEngine>,
     pub haptic_feedback: bool,
+    pub reduce_motion: bool,
     pub cbhist: bool,
+    pub clipboard_history_limit: usize,
+    pub clipboard_privacy: ClipboardPrivacy,
     pub show_trayicon: bool,
     pub shells: Vec<Shelly>,
+    pub extensions: Vec<Extension>,
+    pub providers: HashMap<String, ProviderSettings>,
     pub modes: HashMap<String, String>,
     pub aliases: HashMap<String, String>,
     pub search_dirs: Vec<String>,
-    pub log_path: String,
+    pub content_search: ContentSearch,
+    pub containers: Containers,
+    pub random: Random,
+    pub weather: Weather,
+    pub markets: Markets,
+    pub github: GitHub,
+    pub issue_tracker: IssueTracker,
+    pub calendar: Calendar,
+    pub passwords: Passwords,
+    pub ignore_apps: Vec<String>,
+    pub scoring: Scoring,
+    pub logging: Logging,
     pub debounce_delay: u64,
+    pub search_debounce_delay: u64,
+    pub query_history_limit: usize,
+    pub save_calculation_history: bool,
+    pub world_clock: WorldClock,
+    pub timer_sound: bool,
+    pub notes: Notes,
+    pub check_for_updates: bool,
+    pub lookup_public_ip: bool,
+    pub expand_short_urls: bool,
+    pub search_contacts: bool,
+    pub keep_awake_timeout: u64,
+    pub emoji_skin_tone: SkinTone,
 }
 
 impl Default for Config {
@@ -43,24 +88,357 @@ impl Default for Config {
             toggle_hotkey: "ALT+SPACE".to_string(),
             clipboard_hotkey: "SUPER+SHIFT+C".to_string(),
             buffer_rules: Buffer::default(),
+            window: WindowConfig::default(),
             theme: Theme::default(),
             start_at_login: true,
             placeholder: String::from("Time to be productive!"),
+            language: None,
             search_url: "https://duckduckgo.com/search?q=%s".to_string(),
+            search_engines: SearchEngine::defaults(),
             cbhist: true,
+            clipboard_history_limit: 500,
+            clipboard_privacy: ClipboardPrivacy::default(),
             haptic_feedback: false,
+            reduce_motion: false,
             show_trayicon: true,
             main_page: MainPage::default(),
             search_dirs: vec!["~".to_string()],
-            log_path: "/tmp/rustcast.log".to_string(),
+            content_search: ContentSearch::default(),
+            containers: Containers::default(),
+            random: Random::default(),
+            weather: Weather::default(),
+            markets: Markets::default(),
+            github: GitHub::default(),
+            issue_tracker: IssueTracker::default(),
+            calendar: Calendar::default(),
+            passwords: Passwords::default(),
+            ignore_apps: vec![],
+            scoring: Scoring::default(),
+            logging: Logging::default(),
             modes: HashMap::new(),
             aliases: HashMap::new(),
             shells: vec![],
+            extensions: vec![],
+            providers: HashMap::new(),
             debounce_delay: 300,
+            search_debounce_delay: 0,
+            query_history_limit: 100,
+            save_calculation_history: false,
+            world_clock: WorldClock::default(),
+            timer_sound: true,
+            notes: Notes::default(),
+            check_for_updates: false,
+            lookup_public_ip: false,
+            expand_short_urls: false,
+            search_contacts: false,
+            keep_awake_timeout: 0,
+            emoji_skin_tone: SkinTone::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Whether a result is hidden by `ignore_apps`, which has the names of the results to hide in
+    /// any case
+    pub fn ignores_app(&self, app: &App) -> bool {
+        self.ignore_apps
+            .iter()
+            .any(|name| name.trim().eq_ignore_ascii_case(&app.display_name))
+    }
+
+    /// Loads `config.toml` from the config directory, with the fragments in `config.d` (in order of
+    /// their file names) and then the active profile merged over it
+    ///
+    /// Returns the problems found along with the config, or the problem that stopped it from
+    /// loading
+    pub fn load(dir: &Path) -> Result<(Config, Vec<ConfigDiagnostic>), ConfigDiagnostic> {
+        let files = config_files(dir)?;
+
+        let mut merged = toml::Table::new();
+        for (file, contents) in &files {
+            // Parsing each file on its own gives errors with the line they are on
+            Config::parse(file, contents)?;
+            if let Ok(table) = toml::from_str(contents) {
+                merge_tables(&mut merged, table);
+            }
+        }
+
+        if let Some(profile) = active_profile(&merged) {
+            merge_tables(&mut merged, profile);
+        }
+        merged.remove("profiles");
+
+        let config: Config = merged.try_into().map_err(|e| ConfigDiagnostic {
+            file: "profiles".to_string(),
+            position: None,
+            key: None,
+            message: e.message().trim().to_string(),
+        })?;
+        let diagnostics = config.validate(&files);
+        Ok((config, diagnostics))
+    }
+
+    /// Parses the contents of a config file, returning where and why it is invalid if it can't be
+    /// parsed
+    fn parse(file: &str, contents: &str) -> Result<Config, ConfigDiagnostic> {
+        toml::from_str(contents).map_err(|e| {
+            let position = e.span().map(|span| position_of(contents, span.start));
+            ConfigDiagnostic {
+                file: file.to_string(),
+                position,
+                key: position.and_then(|(line, _)| key_at_line(contents, line)),
+                message: e.message().trim().to_string(),
+            }
+        })
+    }
+
+    /// Checks the parts of the config that parse, but are still invalid and so get ignored when the
+    /// config is applied, like hotkeys that aren't real key combinations
+    ///
+    /// `files` are the names and contents of the files that the config was loaded from
+    fn validate(&self, files: &[(String, String)]) -> Vec<ConfigDiagnostic> {
+        let hotkeys = [
+            ("toggle_hotkey", Some(&self.toggle_hotkey)),
+            ("clipboard_hotkey", Some(&self.clipboard_hotkey)),
+        ]
+        .into_iter()
+        .chain(self.shells.iter().map(|x| ("hotkey", x.hotkey.as_ref())))
+        .filter_map(|(key, hotkey)| {
+            let hotkey = hotkey.filter(|x| !x.is_empty())?;
+            let error = Shortcut::parse(hotkey).err()?;
+            Some(diagnostic_for_value(
+                files,
+                key,
+                hotkey,
+                format!("`{hotkey}` is not a valid hotkey ({error})"),
+            ))
+        });
+
+        let timezones = self.world_clock.cities.iter().filter_map(|city| {
+            let error = jiff::tz::TimeZone::get(&city.timezone).err()?;
+            Some(diagnostic_for_value(
+                files,
+                "timezone",
+                &city.timezone,
+                format!("`{}` is not a valid timezone ({error})", city.timezone),
+            ))
+        });
+
+        let background_image = self
+            .theme
+            .background_image_path()
+            .filter(|path| !path.exists())
+            .map(|path| {
+                diagnostic_for_value(
+                    files,
+                    "background_image",
+                    self.theme.background_image.as_deref().unwrap_or_default(),
+                    format!("The background image {} does not exist", path.display()),
+                )
+            });
+
+        let language = self
+            .language
+            .as_deref()
+            .filter(|language| i18n::supported(language).is_none())
+            .map(|language| {
+                diagnostic_for_value(
+                    files,
+                    "language",
+                    language,
+                    format!(
+                        "RustCast hasn't been translated to `{language}` (the languages are {})",
+                        i18n::languages().join(", ")
+                    ),
+                )
+            });
+
+        let extensions = self
+            .extensions
+            .iter()
+            .filter(|extension| !extension.path().exists())
+            .map(|extension| {
+                diagnostic_for_value(
+                    files,
+                    "path",
+                    &extension.path,
+                    format!(
+                        "The extension {} does not exist",
+                        extension.path().display()
+                    ),
+                )
+            });
+
+        let providers = self
+            .providers
+            .keys()
+            .filter(|name| !providers::names().contains(&name.as_str()))
+            .map(|name| {
+                diagnostic_for_value(
+                    files,
+                    "[providers.",
+                    name,
+                    format!(
+                        "`{name}` is not a provider (the providers are {})",
+                        providers::names().join(", ")
+                    ),
+                )
+            });
+
+        hotkeys
+            .chain(timezones)
+            .chain(background_image)
+            .chain(language)
+            .chain(extensions)
+            .chain(providers)
+            .collect()
+    }
+}
+
+/// A diagnostic for an invalid value, pointing at the line that sets `key` to it
+///
+/// The value is searched for as well as the key, to find the right one of several entries with the
+/// same key (like the hotkeys of shell commands)
+fn diagnostic_for_value(
+    files: &[(String, String)],
+    key: &str,
+    value: &str,
+    message: String,
+) -> ConfigDiagnostic {
+    let found = files.iter().find_map(|(file, contents)| {
+        let line = contents.lines().position(|line| {
+            let line = line.trim_start();
+            (line.starts_with(key) || line.contains(&format!(" {key} ="))) && line.contains(value)
+        })?;
+        Some((file, contents, line + 1))
+    });
+
+    ConfigDiagnostic {
+        file: found.map_or("config.toml".to_string(), |(file, _, _)| file.to_owned()),
+        position: found.map(|(_, _, line)| (line, 1)),
+        key: found.and_then(|(_, contents, line)| key_at_line(contents, line)),
+        message,
+    }
+}
+
+/// The names and contents of the files that make up the config, which are `config.toml` and then
+/// the fragments in `config.d` in order of their file names
+pub fn config_files(dir: &Path) -> Result<Vec<(String, String)>, ConfigDiagnostic> {
+    let mut fragments: Vec<String> = fs::read_dir(dir.join("config.d"))
+        .map(|entries| {
+            entries
+                .filter_map(|x| x.ok()?.file_name().into_string().ok())
+                .filter(|x| x.ends_with(".toml"))
+                .collect()
+        })
+        .unwrap_or_default();
+    fragments.sort();
+
+    std::iter::once("config.toml".to_string())
+        .chain(fragments.into_iter().map(|x| format!("config.d/{x}")))
+        .map(|file| {
+            fs::read_to_string(dir.join(&file))
+                .map(|contents| (file.clone(), contents))
+                .map_err(|e| ConfigDiagnostic {
+                    file,
+                    position: None,
+                    key: None,
+                    message: e.to_string(),
+                })
+        })
+        .collect()
+}
+
+/// Merges `other` over `base`, merging tables key by key and replacing everything else (including
+/// lists, such as `shells`)
+fn merge_tables(base: &mut toml::Table, other: toml::Table) {
+    for (key, value) in other {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(other)) => {
+                merge_tables(base, other)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// The profile to apply from the `[profiles]` table, which is the one named by the
+/// `RUSTCAST_PROFILE` environment variable, or otherwise the one named after this machine's
+/// hostname (with or without `.local`)
+fn active_profile(config: &toml::Table) -> Option<toml::Table> {
+    let profiles = config.get("profiles")?.as_table()?;
+    let name = std::env::var("RUSTCAST_PROFILE").ok().or_else(hostname)?;
+    let short_name = name.strip_suffix(".local").unwrap_or(&name);
+
+    profiles.iter().find_map(|(profile, table)| {
+        (profile.eq_ignore_ascii_case(&name) || profile.eq_ignore_ascii_case(short_name))
+            .then(|| table.as_table().cloned())?
+    })
+}
+
+/// A problem with the config file, and where in the file it is
+///
+/// The file is relative to the config directory, the position is the line and column (both
+/// starting from 1), and the key is the full name of the key on that line, e.g.
+/// `theme.display_mode`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigDiagnostic {
+    pub file: String,
+    pub position: Option<(usize, usize)>,
+    pub key: Option<String>,
+    pub message: String,
+}
+
+impl ConfigDiagnostic {
+    /// Where the problem is, e.g. "config.toml, line 3, column 8 (theme.display_mode)"
+    pub fn location(&self) -> String {
+        let file = &self.file;
+        match (self.position, &self.key) {
+            (Some((line, column)), Some(key)) => {
+                format!("{file}, line {line}, column {column} ({key})")
+            }
+            (Some((line, column)), None) => format!("{file}, line {line}, column {column}"),
+            (None, Some(key)) => format!("{file} ({key})"),
+            (None, None) => file.to_owned(),
         }
     }
 }
 
+impl std::fmt::Display for ConfigDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.location(), self.message)
+    }
+}
+
+/// The line and column of a byte offset into the contents of a file
+fn position_of(contents: &str, offset: usize) -> (usize, usize) {
+    let before = contents.get(..offset).unwrap_or(contents);
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+    (line, column)
+}
+
+/// The full name of the key set on a line, including the table that it is in
+fn key_at_line(contents: &str, line: usize) -> Option<String> {
+    let lines: Vec<&str> = contents.lines().take(line).collect();
+    let key = lines
+        .last()?
+        .split_once('=')
+        .map(|(key, _)| key.trim().to_string());
+    let table = lines.iter().rev().find_map(|line| {
+        let line = line.trim();
+        line.starts_with('[')
+            .then(|| line.trim_matches(['[', ']']).to_string())
+    });
+
+    match (table, key) {
+        (Some(table), Some(key)) => Some(format!("{table}.{key}")),
+        (table, key) => key.or(table),
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default, Eq, Copy)]
 #[serde(rename_all = "lowercase")]
 pub enum MainPage {
@@ -80,16 +458,144 @@ impl std::fmt::Display for MainPage {
     }
 }
 
+/// The skin tone that is applied to emojis that support one when they are copied
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default, Eq, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum SkinTone {
+    #[default]
+    Default,
+    Light,
+    MediumLight,
+    Medium,
+    MediumDark,
+    Dark,
+}
+
+impl SkinTone {
+    /// All the skin tones, in the order they are shown in the settings page
+    pub const ALL: [SkinTone; 6] = [
+        SkinTone::Default,
+        SkinTone::Light,
+        SkinTone::MediumLight,
+        SkinTone::Medium,
+        SkinTone::MediumDark,
+        SkinTone::Dark,
+    ];
+
+    /// Apply the skin tone to an emoji, returning the emoji itself if it doesn't support skin tones
+    pub fn apply(&self, emoji: &'static emojis::Emoji) -> &'static emojis::Emoji {
+        let tone = match self {
+            SkinTone::Default => return emoji,
+            SkinTone::Light => emojis::SkinTone::Light,
+            SkinTone::MediumLight => emojis::SkinTone::MediumLight,
+            SkinTone::Medium => emojis::SkinTone::Medium,
+            SkinTone::MediumDark => emojis::SkinTone::MediumDark,
+            SkinTone::Dark => emojis::SkinTone::Dark,
+        };
+        emoji.with_skin_tone(tone).unwrap_or(emoji)
+    }
+}
+
+/// How dense the results are, which sets the row height, icon size and font sizes
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default, Eq, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum DisplayMode {
+    Compact,
+    #[default]
+    Comfortable,
+    Large,
+}
+
+impl std::fmt::Display for DisplayMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            DisplayMode::Compact => "Compact",
+            DisplayMode::Comfortable => "Comfortable",
+            DisplayMode::Large => "Large",
+        })
+    }
+}
+
+impl DisplayMode {
+    pub const ALL: [DisplayMode; 3] = [
+        DisplayMode::Compact,
+        DisplayMode::Comfortable,
+        DisplayMode::Large,
+    ];
+
+    /// The height of a result row's content
+    pub fn row_height(&self) -> f32 {
+        match self {
+            DisplayMode::Compact => 40.,
+            DisplayMode::Comfortable => 50.,
+            DisplayMode::Large => 60.,
+        }
+    }
+
+    /// The height each result adds to the window
+    pub fn window_row_height(&self) -> f32 {
+        self.row_height() + 5.
+    }
+
+    /// The height each result adds to the results list
+    pub fn list_row_height(&self) -> f32 {
+        self.row_height() + 10.
+    }
+
+    /// How far the results list scrolls for each result, including the row's padding
+    pub fn scroll_step(&self) -> f32 {
+        self.row_height() + 16.5
+    }
+
+    pub fn icon_size(&self) -> f32 {
+        self.row_height() - 10.
+    }
+
+    /// The font size of a result's name
+    pub fn title_size(&self) -> f32 {
+        match self {
+            DisplayMode::Compact => 14.,
+            DisplayMode::Comfortable => 16.,
+            DisplayMode::Large => 19.,
+        }
+    }
+
+    /// The font size of a result's description
+    pub fn desc_size(&self) -> f32 {
+        self.title_size() - 3.
+    }
+}
+
+/// The system backdrop that is used to blur behind the window on Windows
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default, Eq, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum WindowsBackdrop {
+    #[default]
+    Acrylic,
+    Mica,
+}
+
 /// The settings you can set for the theme
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 #[serde(default)]
 pub struct Theme {
     pub text_color: (f32, f32, f32),
     pub background_color: (f32, f32, f32),
+    pub background_opacity: f32,
+    pub background_image: Option<String>,
+    pub background_gradient: Option<[(f32, f32, f32); 2]>,
+    pub background_dim: f32,
     pub blur: bool,
+    pub windows_backdrop: WindowsBackdrop,
     pub show_icons: bool,
     pub show_scroll_bar: bool,
     pub font: Option<String>,
+    pub monospace_font: Option<String>,
+    pub font_size: f32,
+    pub result_title_size: Option<f32>,
+    pub result_subtitle_size: Option<f32>,
+    pub display_mode: DisplayMode,
+    pub advanced: ThemeAdvanced,
 }
 
 impl Default for Theme {
@@ -97,10 +603,21 @@ impl Default for Theme {
         Self {
             text_color: (0.95, 0.95, 0.96),
             background_color: (0.0, 0.0, 0.0),
-            blur: false,
+            background_opacity: 0.,
+            background_image: None,
+            background_gradient: None,
+            background_dim: 0.,
+            blur: true,
+            windows_backdrop: WindowsBackdrop::default(),
             show_icons: true,
             show_scroll_bar: false,
             font: None,
+            monospace_font: None,
+            font_size: 16.,
+            result_title_size: None,
+            result_subtitle_size: None,
+            display_mode: DisplayMode::default(),
+            advanced: ThemeAdvanced::default(),
         }
     }
 }
@@ -157,22 +674,136 @@ impl Theme {
             r: self.background_color.0,
             g: self.background_color.1,
             b: self.background_color.2,
-            a: 0.,
+            a: self.background_opacity.clamp(0., 1.),
         }
     }
 
+    /// The path of the background image, with `~` expanded
+    pub fn background_image_path(&self) -> Option<PathBuf> {
+        let image = self.background_image.as_ref().filter(|x| !x.is_empty())?;
+        let home = dirs::home_dir().unwrap_or(PathBuf::from("/"));
+        Some(PathBuf::from(image.replacen(
+            "~",
+            &home.to_string_lossy(),
+            1,
+        )))
+    }
+
+    /// The background gradient from the first color at the top to the second at the bottom,
+    /// darkened by the dim factor
+    pub fn gradient(&self) -> Option<iced::Gradient> {
+        let [from, to] = self.background_gradient?;
+        let brightness = 1. - self.background_dim.clamp(0., 1.);
+        let color = |(r, g, b): (f32, f32, f32)| {
+            iced::Color::from_rgba(
+                r * brightness,
+                g * brightness,
+                b * brightness,
+                self.background_opacity.clamp(0., 1.),
+            )
+        };
+        Some(
+            iced::gradient::Linear::new(iced::Degrees(180.))
+                .add_stop(0., color(from))
+                .add_stop(1., color(to))
+                .into(),
+        )
+    }
+
     /// Return the font in the theme config of type [`iced::Font`]
     pub fn font(&self) -> Font {
-        let opt_font_name = self.font.clone();
-        match opt_font_name {
-            Some(font_name) => Font {
-                family: Family::Name(font_name.leak()),
-                ..Default::default()
-            },
-            None => Font {
-                family: Family::SansSerif,
-                ..Default::default()
-            },
+        font_named(self.font.as_deref(), Family::SansSerif)
+    }
+
+    /// The font for calculations and shell commands, which is the system's monospace font if
+    /// `monospace_font` isn't set
+    pub fn monospace_font(&self) -> Font {
+        font_named(self.monospace_font.as_deref(), Family::Monospace)
+    }
+
+    /// The font size of a result's name, which depends on the display mode unless it is set
+    pub fn title_size(&self) -> f32 {
+        self.result_title_size
+            .unwrap_or(self.display_mode.title_size())
+    }
+
+    /// The font size of a result's description, which depends on the display mode unless it is set
+    pub fn desc_size(&self) -> f32 {
+        self.result_subtitle_size
+            .unwrap_or(self.display_mode.desc_size())
+    }
+}
+
+/// The font with the given name, or the generic family if there isn't one
+///
+/// Font names have to be `'static`, so each name is leaked the first time it is used and reused
+/// after that
+fn font_named(name: Option<&str>, fallback: Family) -> Font {
+    static NAMES: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+
+    let family = match name {
+        Some(name) => {
+            let mut names = NAMES.lock().unwrap_or_else(|x| x.into_inner());
+            let name = match names.iter().find(|x| **x == name) {
+                Some(leaked) => *leaked,
+                None => {
+                    let leaked: &'static str = name.to_string().leak();
+                    names.push(leaked);
+                    leaked
+                }
+            };
+            Family::Name(name)
+        }
+        None => fallback,
+    };
+
+    Font {
+        family,
+        ..Default::default()
+    }
+}
+
+/// The finer details of the styling, for restyling rustcast beyond the colors and font
+/// - Tint and focused_tint are how much white is mixed into the background of the results, search
+///   bar and footer, and of the selected result
+/// - Surface_opacity and focused_surface_opacity are how opaque those backgrounds are
+/// - Border_opacity and focused_border_opacity are how opaque their borders are
+/// - Border_width is the width of the border around the window
+/// - Window_radius is how rounded the window's corners are, and row_radius is how rounded each
+///   result's corners are
+/// - Focused_row_color is the background color of the selected result, instead of the tinted
+///   background color
+/// - Selection_color is the color of selected text, instead of the text color
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct ThemeAdvanced {
+    pub tint: f32,
+    pub focused_tint: f32,
+    pub surface_opacity: f32,
+    pub focused_surface_opacity: f32,
+    pub border_opacity: f32,
+    pub focused_border_opacity: f32,
+    pub border_width: f32,
+    pub window_radius: f32,
+    pub row_radius: f32,
+    pub focused_row_color: Option<(f32, f32, f32)>,
+    pub selection_color: Option<(f32, f32, f32)>,
+}
+
+impl Default for ThemeAdvanced {
+    fn default() -> Self {
+        Self {
+            tint: 0.06,
+            focused_tint: 0.2,
+            surface_opacity: 0.58,
+            focused_surface_opacity: 0.9,
+            border_opacity: 0.22,
+            focused_border_opacity: 0.35,
+            border_width: 0.4,
+            window_radius: 14.,
+            row_radius: 0.,
+            focused_row_color: None,
+            selection_color: None,
         }
     }
 }
@@ -198,9 +829,619 @@ impl Default for Buffer {
     }
 }
 
+/// The size and placement of the rustcast window
+///
+/// - width is the width of the window in pixels
+/// - max_rows is the number of results shown before the results start scrolling, which the window
+///   grows to fit (it can also be set as `max_visible_results`)
+/// - position is where the window opens on the monitor
+/// - open_on is which monitor the window opens on
+/// - hide_from_screen_sharing keeps the window out of screenshots, screen recordings and shared
+///   screens in video calls
+/// - focus_loss_grace_ms is how long the window can be unfocused (like while a permission dialog
+///   is shown) before it hides, so that getting the focus back in time keeps it open
+/// - auto_hide_seconds hides the window after it is left alone for that long (0 never does)
+/// - follow_active_display moves the window onto the monitor from open_on every time it is shown,
+///   instead of showing it where it was first opened
+/// - compositor_ipc lets rustcast ask Hyprland or Sway (on Linux) for the focused output, float its
+///   window and focus the previous window again when it hides
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct WindowConfig {
+    pub width: f32,
+    #[serde(alias = "max_visible_results")]
+    pub max_rows: usize,
+    pub position: WindowPosition,
+    pub open_on: OpenOn,
+    pub hide_from_screen_sharing: bool,
+    pub focus_loss_grace_ms: u64,
+    pub auto_hide_seconds: u64,
+    pub follow_active_display: bool,
+    pub compositor_ipc: bool,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        WindowConfig {
+            width: 500.,
+            max_rows: 5,
+            position: WindowPosition::default(),
+            open_on: OpenOn::default(),
+            hide_from_screen_sharing: false,
+            focus_loss_grace_ms: 250,
+            auto_hide_seconds: 0,
+            follow_active_display: true,
+            compositor_ipc: true,
+        }
+    }
+}
+
+/// Where the window opens, either anchored to a spot on the screen or at exact coordinates
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
+#[serde(untagged)]
+pub enum WindowPosition {
+    Anchor(WindowAnchor),
+    Exact { x: f32, y: f32 },
+}
+
+impl Default for WindowPosition {
+    fn default() -> Self {
+        WindowPosition::Anchor(WindowAnchor::Center)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Default, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum WindowAnchor {
+    #[default]
+    Center,
+    TopThird,
+}
+
+impl WindowPosition {
+    /// Where a window of the given size should open on the monitor, or on whichever monitor the
+    /// platform picks if the monitor isn't known
+    pub fn on_monitor(self, monitor: Option<Rectangle>, size: Size) -> Position {
+        let Some(monitor) = monitor else {
+            return match self {
+                WindowPosition::Anchor(WindowAnchor::Center) => Position::Centered,
+                WindowPosition::Anchor(WindowAnchor::TopThird) => {
+                    Position::SpecificWith(|window, monitor| {
+                        Point::new(
+                            (monitor.width - window.width) / 2.,
+                            (monitor.height - window.height) / 3.,
+                        )
+                    })
+                }
+                WindowPosition::Exact { x, y } => Position::Specific(Point::new(x, y)),
+            };
+        };
+
+        let centered_x = monitor.x + (monitor.width - size.width) / 2.;
+        Position::Specific(match self {
+            WindowPosition::Anchor(WindowAnchor::Center) => {
+                Point::new(centered_x, monitor.y + (monitor.height - size.height) / 2.)
+            }
+            WindowPosition::Anchor(WindowAnchor::TopThird) => {
+                Point::new(centered_x, monitor.y + (monitor.height - size.height) / 3.)
+            }
+            WindowPosition::Exact { x, y } => Point::new(monitor.x + x, monitor.y + y),
+        })
+    }
+}
+
+/// Which monitor the window opens on
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Default, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OpenOn {
+    #[default]
+    CursorMonitor,
+    Primary,
+    ActiveWindowMonitor,
+}
+
+/// The rules for keeping sensitive content out of the clipboard history
+///
+/// - ignored_apps are the names or bundle identifiers of apps whose copies are never recorded
+/// - expire_secrets is whether entries that look like secrets (passwords, tokens, OTP codes) are
+///   removed from the history after secret_expiry seconds
+/// - secret_keywords are words that mark a short single line of text as a secret
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct ClipboardPrivacy {
+    pub ignored_apps: Vec<String>,
+    pub expire_secrets: bool,
+    pub secret_expiry: u64,
+    pub secret_keywords: Vec<String>,
+}
+
+impl Default for ClipboardPrivacy {
+    fn default() -> Self {
+        ClipboardPrivacy {
+            ignored_apps: [
+                "1Password",
+                "Bitwarden",
+                "Dashlane",
+                "KeePassXC",
+                "Keychain Access",
+                "LastPass",
+                "Passwords",
+            ]
+            .map(String::from)
+            .to_vec(),
+            expire_secrets: true,
+            secret_expiry: 60,
+            secret_keywords: ["password", "passwd", "secret", "api_key", "apikey"]
+                .map(String::from)
+                .to_vec(),
+        }
+    }
+}
+
+impl ClipboardPrivacy {
+    /// Whether copies from this app should be left out of the clipboard history
+    pub fn ignores(&self, source: &ClipboardSource) -> bool {
+        self.ignored_apps.iter().any(|app| {
+            app.eq_ignore_ascii_case(&source.name)
+                || source
+                    .bundle_id
+                    .as_ref()
+                    .is_some_and(|id| app.eq_ignore_ascii_case(id))
+        })
+    }
+}
+
+/// A search engine that web searches can also be made with, besides the `search_url`
+/// - Name is what the engine is called in the results
+/// - Url is the engine's search url, with a %s to mark where the search goes
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct SearchEngine {
+    pub name: String,
+    pub url: String,
+}
+
+impl SearchEngine {
+    pub fn defaults() -> Vec<SearchEngine> {
+        [
+            ("Google", "https://google.com/search?q=%s"),
+            ("DuckDuckGo", "https://duckduckgo.com/search?q=%s"),
+            ("Kagi", "https://kagi.com/search?q=%s"),
+        ]
+        .into_iter()
+        .map(|(name, url)| SearchEngine {
+            name: name.to_string(),
+            url: url.to_string(),
+        })
+        .collect()
+    }
+}
+
+/// Searching the contents of files with `grep` (see [`crate::content_search`])
+/// - Extensions are the extensions of the files that are searched
+/// - Max file size is the size of the biggest file that is searched, in bytes
+/// - Open at line is the command that opens a file at a line, with `{path}` and `{line}` in it
+///   (like `code --goto {path}:{line}`), and files are opened with their default app without it
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct ContentSearch {
+    pub extensions: Vec<String>,
+    pub max_file_size: u64,
+    pub open_at_line: Option<String>,
+}
+
+impl Default for ContentSearch {
+    fn default() -> Self {
+        ContentSearch {
+            extensions: ["txt", "md", "toml", "json", "yaml", "yml", "csv", "log"]
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+            max_file_size: 1_000_000,
+            open_at_line: None,
+        }
+    }
+}
+
+impl ContentSearch {
+    /// Whether a file has one of the extensions that are searched
+    pub fn allows(&self, path: &Path) -> bool {
+        path.extension().is_some_and(|extension| {
+            self.extensions
+                .iter()
+                .any(|x| extension.eq_ignore_ascii_case(x.trim_start_matches('.')))
+        })
+    }
+}
+
+/// Controlling containers by searching for a keyword (see [`crate::containers`])
+/// - Enabled is whether the containers are listed, which is off by default
+/// - Keyword is what searches start with to list them
+/// - Command is the CLI that lists and controls them, like `docker` or `podman`
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct Containers {
+    pub enabled: bool,
+    pub keyword: String,
+    pub command: String,
+}
+
+impl Default for Containers {
+    fn default() -> Self {
+        Containers {
+            enabled: false,
+            keyword: "docker".to_string(),
+            command: "docker".to_string(),
+        }
+    }
+}
+
+/// The random data from `rand` searches (see [`crate::random`])
+/// - Max is the largest number that `rand` on its own picks (from 1)
+/// - Lorem paragraphs is how many paragraphs of lorem ipsum `rand lorem` gives
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct Random {
+    pub max: i64,
+    pub lorem_paragraphs: usize,
+}
+
+impl Default for Random {
+    fn default() -> Self {
+        Random {
+            max: 100,
+            lorem_paragraphs: 3,
+        }
+    }
+}
+
+/// The weather shown when searching for `weather` (see [`crate::weather`])
+/// - Location is the place that `weather` on its own shows the weather at, which has to be set for
+///   it to show anything
+/// - Units are metric (°C and km/h) or imperial (°F and mph)
+/// - Geocoding url and forecast url are the Open-Meteo APIs (or compatible ones) that the place
+///   and its weather are looked up with
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct Weather {
+    pub location: String,
+    pub units: WeatherUnits,
+    pub geocoding_url: String,
+    pub forecast_url: String,
+}
+
+impl Default for Weather {
+    fn default() -> Self {
+        Weather {
+            location: String::new(),
+            units: WeatherUnits::default(),
+            geocoding_url: "https://geocoding-api.open-meteo.com/v1/search".to_string(),
+            forecast_url: "https://api.open-meteo.com/v1/forecast".to_string(),
+        }
+    }
+}
+
+/// The units the weather is shown in
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default, Eq, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum WeatherUnits {
+    #[default]
+    Metric,
+    Imperial,
+}
+
+/// The prices shown when searching for `stock <symbol>` or `crypto <symbol>` (see
+/// [`crate::markets`])
+/// - Chart url is the API that works like Yahoo Finance's chart API, which the prices are looked
+///   up with
+/// - Crypto currency is the currency that crypto prices are in
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct Markets {
+    pub chart_url: String,
+    pub crypto_currency: String,
+}
+
+impl Default for Markets {
+    fn default() -> Self {
+        Markets {
+            chart_url: "https://query1.finance.yahoo.com/v8/finance/chart".to_string(),
+            crypto_currency: "USD".to_string(),
+        }
+    }
+}
+
+/// The repositories, issues and pull requests shown when searching for `gh` (see
+/// [`crate::github`])
+/// - Token is a personal access token, which `$GITHUB_TOKEN` is used instead of if it isn't set.
+///   Without either, `gh` searches don't show anything
+/// - Api url is GitHub's API, which is different for GitHub Enterprise
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct GitHub {
+    pub token: String,
+    pub api_url: String,
+}
+
+impl Default for GitHub {
+    fn default() -> Self {
+        GitHub {
+            token: String::new(),
+            api_url: "https://api.github.com".to_string(),
+        }
+    }
+}
+
+/// The Jira or Linear issues that issue keys like `PROJ-123` open, and that `issues` lists (see
+/// [`crate::issue_tracker`])
+/// - Kind is Jira or Linear
+/// - Base url is the Jira site (like `https://example.atlassian.net`), or the Linear workspace
+///   (like `https://linear.app/example`). Nothing is shown if it isn't set
+/// - Token is a Jira API token (or personal access token on Jira Data Center), or a Linear API key
+/// - Email is the Jira Cloud account's email, which is only needed for Jira Cloud
+/// - Projects are the project keys that open issues, so that searches like `utf-8` don't. Any key
+///   opens an issue if there aren't any
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default)]
+#[serde(default)]
+pub struct IssueTracker {
+    pub kind: IssueTrackerKind,
+    pub base_url: String,
+    pub token: String,
+    pub email: Option<String>,
+    pub projects: Vec<String>,
+}
+
+/// The issue trackers that issues can be opened in
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default, Eq, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueTrackerKind {
+    #[default]
+    Jira,
+    Linear,
+}
+
+impl IssueTrackerKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            IssueTrackerKind::Jira => "Jira",
+            IssueTrackerKind::Linear => "Linear",
+        }
+    }
+}
+
+/// The events shown when searching for `cal` (see [`crate::calendar`])
+/// - System calendar is whether to read the events from Calendar on macOS (which asks for
+///   permission) or Outlook on Windows
+/// - Ics urls are the addresses (or paths) of ICS calendars to read events from too, like a Google
+///   calendar's secret address
+/// - Days is how many days ahead `cal` shows the events of
+/// - Show next meeting is whether the footer shows the next meeting when the window opens
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct Calendar {
+    pub system_calendar: bool,
+    pub ics_urls: Vec<String>,
+    pub days: u32,
+    pub show_next_meeting: bool,
+}
+
+impl Default for Calendar {
+    fn default() -> Self {
+        Calendar {
+            system_calendar: false,
+            ics_urls: vec![],
+            days: 2,
+            show_next_meeting: true,
+        }
+    }
+}
+
+/// Copying logins from a password manager by searching for a keyword (see [`crate::passwords`])
+/// - Enabled is whether the vault is searched, which is off by default
+/// - Keyword is what searches start with to search it
+/// - Manager is 1Password (with the `op` CLI) or Bitwarden (with the `bw` CLI, which has to be
+///   unlocked with `$BW_SESSION` set)
+/// - Clear after is how many seconds a copied secret stays on the clipboard
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct Passwords {
+    pub enabled: bool,
+    pub keyword: String,
+    pub manager: PasswordManager,
+    pub clear_after: u64,
+}
+
+impl Default for Passwords {
+    fn default() -> Self {
+        Passwords {
+            enabled: false,
+            keyword: "pw".to_string(),
+            manager: PasswordManager::default(),
+            clear_after: 30,
+        }
+    }
+}
+
+/// The password managers whose CLIs logins can be copied with
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default, Eq, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum PasswordManager {
+    #[default]
+    OnePassword,
+    Bitwarden,
+}
+
+impl PasswordManager {
+    pub fn name(self) -> &'static str {
+        match self {
+            PasswordManager::OnePassword => "1Password",
+            PasswordManager::Bitwarden => "Bitwarden",
+        }
+    }
+
+    /// The CLI's executable
+    pub fn command(self) -> &'static str {
+        match self {
+            PasswordManager::OnePassword => "op",
+            PasswordManager::Bitwarden => "bw",
+        }
+    }
+}
+
+/// The cities shown when searching for `time`
+/// - Cities are the cities' names, and their IANA timezones (like "Europe/London")
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct WorldClock {
+    pub cities: Vec<City>,
+}
+
+impl Default for WorldClock {
+    fn default() -> Self {
+        WorldClock {
+            cities: [
+                ("London", "Europe/London"),
+                ("New York", "America/New_York"),
+                ("Tokyo", "Asia/Tokyo"),
+            ]
+            .into_iter()
+            .map(|(name, timezone)| City {
+                name: name.to_string(),
+                timezone: timezone.to_string(),
+            })
+            .collect(),
+        }
+    }
+}
+
+/// A city on the world clock
+/// - Name is what the city is called in the results
+/// - Timezone is the IANA name of the city's timezone
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct City {
+    pub name: String,
+    pub timezone: String,
+}
+
+/// Where the notes captured with `note <text>` go
+/// - Destination is either a markdown file, or the Reminders app (on macOS)
+/// - File is the markdown file that notes are added to, which is `notes.md` in the config
+///   directory if it isn't set
+/// - Reminders_list is the list that reminders are added to, which is the default list if it
+///   isn't set
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default)]
+#[serde(default)]
+pub struct Notes {
+    pub destination: NoteDestination,
+    pub file: Option<String>,
+    pub reminders_list: Option<String>,
+}
+
+/// Points added to (or taken from) a search result's score, on top of how often it was opened,
+/// which the results are ordered by
+/// - Prefix, word prefix, initials, substring and words are for how the result's name matched the search (see
+///   [`crate::search::MatchKind`])
+/// - Apps, shell commands and files are for what the result is
+/// - Long name penalty is taken away for every character of the result's name
+///
+/// They are all 0 by default, so results are only ordered by how often they were opened and then
+/// by how they matched
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default)]
+#[serde(default)]
+pub struct Scoring {
+    pub prefix: f32,
+    pub word_prefix: f32,
+    pub initials: f32,
+    pub substring: f32,
+    pub words: f32,
+    pub apps: f32,
+    pub shell_commands: f32,
+    pub files: f32,
+    pub long_name_penalty: f32,
+}
+
+/// The settings of one of the providers of the results besides the apps (see
+/// [`crate::providers`]), which are set by its name
+/// - Enabled is whether it gives results at all
+/// - Priority replaces its default priority, and providers with higher priorities are asked for
+///   results first, or have their results shown first when they rank the same
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct ProviderSettings {
+    pub enabled: bool,
+    pub priority: Option<i32>,
+}
+
+impl Default for ProviderSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            priority: None,
+        }
+    }
+}
+
+/// Where notes are captured to
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default, Eq, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum NoteDestination {
+    #[default]
+    File,
+    Reminders,
+}
+
+/// Where rustcast's logs go, and how much is logged
+/// - Level is the most detailed level that is logged, which `$RUSTCAST_LOG` overrides
+/// - Dir is the directory the log files are kept in, which is the platform's log or state
+///   directory if it isn't set
+/// - Keep_days is how many days of log files are kept, as a new file is started every day
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct Logging {
+    pub level: LogLevel,
+    pub dir: Option<String>,
+    pub keep_days: usize,
+}
+
+impl Default for Logging {
+    fn default() -> Self {
+        Self {
+            level: LogLevel::default(),
+            dir: None,
+            keep_days: 7,
+        }
+    }
+}
+
+/// How detailed the logs are, from only errors to everything
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default, Eq, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+            LogLevel::Trace => "trace",
+        }
+    }
+}
+
 /// Command is the command it will run when the button is clicked
 /// Icon_path is the path to an icon, but this is optional
 /// Alias is the text that is used to call this command / search for it
+/// Run_in_terminal opens the command in a terminal window, for commands whose output matters
+/// Cwd, env and shell are the working directory, extra environment variables and shell to run
+/// the command with, which are all optional
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default)]
 pub struct Shelly {
     pub command: String,
@@ -208,6 +1449,78 @@ pub struct Shelly {
     pub alias: String,
     pub alias_lc: String,
     pub hotkey: Option<String>,
+    #[serde(default)]
+    pub run_in_terminal: bool,
+    pub cwd: Option<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    pub shell: Option<Shell>,
+}
+
+/// An executable that gives the results for searches that start with its keyword (see
+/// [`crate::extensions`])
+///
+/// Args are passed before the search, and cwd and env are the working directory and extra
+/// environment variables to run it with
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default)]
+pub struct Extension {
+    pub keyword: String,
+    pub path: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    pub cwd: Option<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+}
+
+impl Extension {
+    /// The path of the executable, with `~` expanded
+    pub fn path(&self) -> PathBuf {
+        let home = dirs::home_dir().unwrap_or(PathBuf::from("/"));
+        PathBuf::from(self.path.replacen("~", &home.to_string_lossy(), 1))
+    }
+}
+
+/// The shell that a shell command is run with
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Shell {
+    Sh,
+    Bash,
+    Zsh,
+    Fish,
+    Powershell,
+}
+
+impl Shell {
+    /// The shell that commands run with when none is set
+    pub fn native() -> Shell {
+        if cfg!(target_os = "windows") {
+            Shell::Powershell
+        } else {
+            Shell::Sh
+        }
+    }
+
+    /// The program to run the shell with
+    pub fn program(&self) -> &'static str {
+        match self {
+            Shell::Sh => "sh",
+            Shell::Bash => "bash",
+            Shell::Zsh => "zsh",
+            Shell::Fish => "fish",
+            Shell::Powershell if cfg!(target_os = "windows") => "powershell",
+            Shell::Powershell => "pwsh",
+        }
+    }
+
+    /// The flag that makes the shell run the argument after it as a command
+    pub fn command_flag(&self) -> &'static str {
+        match self {
+            Shell::Powershell => "-Command",
+            _ => "-c",
+        }
+    }
 }
 
 impl ToApp for Shelly {
@@ -223,11 +1536,55 @@ impl ToApp for Shelly {
         });
         App {
             ranking: 0,
-            open_command: AppCommand::Function(Function::RunShellCommand(self_clone.command)),
+            open_command: AppCommand::Function(self.function(self_clone.command)),
             desc: "Shell Command".to_string(),
             icons: icon,
             display_name: self_clone.alias,
             search_name: self_clone.alias_lc,
+            version: None,
+            last_used: None,
+            children: None,
         }
     }
 }
+
+impl Shelly {
+    /// The function that runs the given command the way this shell command is set up to
+    pub fn function(&self, command: String) -> Function {
+        let command = ShellCommand {
+            command,
+            shell: self.shell,
+            cwd: self.cwd.clone(),
+            env: self.env.clone(),
+        };
+        if self.run_in_terminal {
+            Function::RunInTerminal(command)
+        } else {
+            Function::RunShellCommand(command)
+        }
+    }
+
+    /// The result for a query that starts with this command's alias and then has more text, which
+    /// runs the command with that text appended as its argument
+    ///
+    /// The argument is shown in the description so that it is clear what will be run
+    pub fn with_argument(&self, query: &str) -> Option<App> {
+        let alias = query.get(..self.alias_lc.len())?;
+        if self.alias_lc.is_empty() || !alias.eq_ignore_ascii_case(&self.alias_lc) {
+            return None;
+        }
+
+        let argument = query[self.alias_lc.len()..].strip_prefix(' ')?.trim();
+        if argument.is_empty() {
+            return None;
+        }
+
+        let mut app = self.to_app();
+        app.ranking = 20;
+        app.open_command =
+            AppCommand::Function(self.function(format!("{} {argument}", self.command.trim_end())));
+        app.desc = format!("Shell Command, with argument: {argument}");
+        app.search_name = String::new();
+        Some(app)
+    }
+}
@@ -0,0 +1,125 @@
+//! `rustcast config doctor`, which explains what rustcast makes of the config files
+//!
+//! Everything here is worked out from the serde definitions of [`Config`], so it can't drift
+//! from what rustcast actually reads
+use std::{collections::HashSet, fmt::Write, path::Path};
+
+use crate::config::{Config, config_files};
+
+/// Checks the config in `dir`, and reports any problems with it, the sections that aren't set
+/// (and so use their defaults), and the config that rustcast ends up using
+pub fn doctor(dir: &Path) -> String {
+    let mut report = format!("Config directory: {}\n", dir.display());
+
+    let files = match config_files(dir) {
+        Ok(files) => files,
+        Err(diagnostic) => {
+            writeln!(report, "\n✗ {diagnostic}").ok();
+            return report;
+        }
+    };
+    writeln!(
+        report,
+        "Files: {}",
+        files
+            .iter()
+            .map(|(file, _)| file.as_str())
+            .collect::<Vec<&str>>()
+            .join(", ")
+    )
+    .ok();
+
+    let mut problems: Vec<String> = files
+        .iter()
+        .flat_map(|(file, contents)| {
+            unknown_keys(contents)
+                .into_iter()
+                .map(move |key| format!("{file}: unknown key `{key}`"))
+        })
+        .collect();
+
+    let config = match Config::load(dir) {
+        Ok((config, diagnostics)) => {
+            problems.extend(diagnostics.iter().map(|x| x.to_string()));
+            Some(config)
+        }
+        Err(diagnostic) => {
+            problems.push(diagnostic.to_string());
+            None
+        }
+    };
+
+    if problems.is_empty() {
+        writeln!(report, "\n✓ No problems found").ok();
+    } else {
+        writeln!(report, "\nProblems:").ok();
+        for problem in problems {
+            writeln!(report, "  ✗ {problem}").ok();
+        }
+    }
+
+    let set_keys: HashSet<String> = files
+        .iter()
+        .filter_map(|(_, contents)| toml::from_str::<toml::Table>(contents).ok())
+        .flat_map(|table| table.into_iter().map(|(key, _)| key))
+        .collect();
+    if let Ok(toml::Value::Table(defaults)) = toml::Value::try_from(Config::default()) {
+        for (section, value) in defaults {
+            if !value.is_table() || set_keys.contains(&section) {
+                continue;
+            }
+
+            let mut table = toml::Table::new();
+            table.insert(section.clone(), value);
+            writeln!(
+                report,
+                "\n[{section}] isn't set, so its defaults are used:\n{}",
+                toml::to_string(&table).unwrap_or_default().trim_end()
+            )
+            .ok();
+        }
+    }
+
+    match config {
+        Some(config) => writeln!(
+            report,
+            "\nThe config that is used:\n{}",
+            toml::to_string_pretty(&config).unwrap_or_else(|e| e.to_string())
+        ),
+        None => writeln!(
+            report,
+            "\nThe config couldn't be loaded, so the default config is used"
+        ),
+    }
+    .ok();
+
+    report
+}
+
+/// The keys in a config file that aren't part of the config, which are the ones that serde ignores
+/// when deserializing it
+///
+/// Profiles are checked on their own, as the `[profiles]` table isn't part of [`Config`]
+fn unknown_keys(contents: &str) -> Vec<String> {
+    // Files that can't be parsed are reported by `Config::load`
+    let Ok(mut table) = toml::from_str::<toml::Table>(contents) else {
+        return vec![];
+    };
+    let profiles = table.remove("profiles");
+
+    let mut unknown = vec![];
+    let _: Result<Config, _> = serde_ignored::deserialize(toml::Value::Table(table), |path| {
+        unknown.push(path.to_string())
+    });
+
+    for (name, profile) in profiles
+        .and_then(|x| x.as_table().cloned())
+        .unwrap_or_default()
+    {
+        let _: Result<Config, _> = serde_ignored::deserialize(profile, |path| {
+            unknown.push(format!("profiles.{name}.{path}"))
+        });
+    }
+
+    unknown
+}
@@ -0,0 +1,100 @@
+//! Searching the people in Contacts, when `search_contacts` is turned on
+//!
+//! The contacts are read (which macOS asks permission for the first time) whenever the apps are
+//! indexed, and searching for the start of a person's name (or any of their names) gives results
+//! that copy their email address or phone number, write them an email, or open them in Contacts
+
+use crate::{
+    app::apps::{App, AppCommand},
+    clipboard::ClipBoardContentType,
+    commands::Function,
+    platform,
+};
+
+/// The most people that are shown for a search
+const MAX_CONTACTS: usize = 3;
+
+/// A person in Contacts
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contact {
+    /// Contacts' id for the person, which opens them with an `addressbook://` url
+    pub id: String,
+    pub name: String,
+    pub emails: Vec<String>,
+    pub phones: Vec<String>,
+}
+
+/// Read the people in Contacts
+pub async fn load() -> Result<Vec<Contact>, String> {
+    tokio::task::spawn_blocking(platform::contacts)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// The actions for the people whose names (or any of them) start with the words of the search
+pub fn contact_apps(contacts: &[Contact], query: &str) -> Vec<App> {
+    let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    if words.is_empty() || query.trim().chars().count() < 2 {
+        return vec![];
+    }
+
+    contacts
+        .iter()
+        .filter(|contact| {
+            let name = contact.name.to_lowercase();
+            let names: Vec<&str> = name.split_whitespace().collect();
+            words
+                .iter()
+                .all(|word| names.iter().any(|x| x.starts_with(word.as_str())))
+        })
+        .take(MAX_CONTACTS)
+        .flat_map(person_apps)
+        .collect()
+}
+
+/// Copying the person's first email address and phone number, writing them an email, and opening
+/// them in Contacts
+fn person_apps(contact: &Contact) -> Vec<App> {
+    let app = |display_name: String, desc: String, function: Function| App {
+        ranking: 20,
+        open_command: AppCommand::Function(function),
+        desc,
+        icons: None,
+        display_name,
+        search_name: String::new(),
+        version: None,
+        last_used: None,
+        children: None,
+    };
+    let copy = |text: &str| Function::CopyToClipboard(ClipBoardContentType::Text(text.to_string()));
+    let name = &contact.name;
+
+    let mut apps = vec![];
+    if let Some(email) = contact.emails.first() {
+        apps.push(app(
+            format!("Copy {name}'s email"),
+            email.clone(),
+            copy(email),
+        ));
+    }
+    if let Some(phone) = contact.phones.first() {
+        apps.push(app(
+            format!("Copy {name}'s phone number"),
+            phone.clone(),
+            copy(phone),
+        ));
+    }
+    if let Some(email) = contact.emails.first() {
+        apps.push(app(
+            format!("Email {name}"),
+            email.clone(),
+            Function::OpenWebsite(format!("mailto:{email}")),
+        ));
+    }
+    apps.push(app(
+        format!("Open {name} in Contacts"),
+        "Contacts".to_string(),
+        Function::OpenWebsite(format!("addressbook://{}", contact.id)),
+    ));
+    apps
+}
@@ -0,0 +1,140 @@
+//! Controlling Docker (or Podman) containers, for searches that start with the containers' keyword
+//!
+//! The containers are listed with the CLI's `ps`, and the ones whose name contains the rest of
+//! the search give results: running containers can be stopped, have their logs followed, or have
+//! a shell opened in them (both in a terminal), and stopped containers can be started
+
+use std::{process::Stdio, time::Duration};
+
+use serde::Deserialize;
+
+use crate::{
+    app::apps::{App, AppCommand},
+    commands::{Function, ShellCommand},
+    config::Containers,
+};
+
+/// How long the CLI can take to list the containers before it is stopped
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A container, as printed by `ps --format '{{json .}}'`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Container {
+    names: Names,
+    #[serde(default)]
+    image: String,
+    #[serde(default)]
+    state: String,
+    #[serde(default)]
+    status: String,
+}
+
+/// Docker prints the names joined with commas, and Podman prints them as a list
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Names {
+    Joined(String),
+    List(Vec<String>),
+}
+
+impl Container {
+    fn name(&self) -> String {
+        match &self.names {
+            Names::Joined(names) => names.split(',').next().unwrap_or_default().to_string(),
+            Names::List(names) => names.first().cloned().unwrap_or_default(),
+        }
+    }
+}
+
+/// The text after the keyword, if the search starts with it
+pub fn matching<'a>(settings: &Containers, query: &'a str) -> Option<&'a str> {
+    let keyword = settings.keyword.trim();
+    let rest = query
+        .get(..keyword.len())
+        .filter(|x| !keyword.is_empty() && x.eq_ignore_ascii_case(keyword))
+        .map(|_| &query[keyword.len()..])?;
+
+    if rest.is_empty() {
+        Some(rest)
+    } else {
+        Some(rest.strip_prefix(' ')?.trim())
+    }
+}
+
+/// The actions for the containers whose name contains the filter, or why they couldn't be listed
+pub async fn list(settings: Containers, filter: String) -> Result<Vec<App>, String> {
+    let output = tokio::time::timeout(
+        TIMEOUT,
+        tokio::process::Command::new(&settings.command)
+            .args(["ps", "--all", "--format", "{{json .}}"])
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .map_err(|_| format!("`{} ps` took too long to respond", settings.command))?
+    .map_err(|e| format!("Could not run {}: {e}", settings.command))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "`{} ps` failed: {}",
+            settings.command,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let filter = filter.to_lowercase();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut containers: Vec<Container> = stdout
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .filter(|x: &Container| x.name().to_lowercase().contains(&filter))
+        .collect();
+    // Running containers first
+    containers.sort_by_key(|x| (x.state != "running", x.name()));
+
+    Ok(containers
+        .iter()
+        .flat_map(|x| container_apps(x, &settings.command))
+        .collect())
+}
+
+/// The actions for a container
+fn container_apps(container: &Container, cli: &str) -> Vec<App> {
+    let name = container.name();
+    let desc = format!("{} · {}", container.image, container.status);
+    let app = |display_name: String, function: Function| App {
+        ranking: 0,
+        open_command: AppCommand::Function(function),
+        desc: desc.clone(),
+        icons: None,
+        display_name,
+        search_name: String::new(),
+        version: None,
+        last_used: None,
+        children: None,
+    };
+
+    if container.state != "running" {
+        return vec![app(
+            format!("Start {name}"),
+            Function::RunShellCommand(ShellCommand::new(format!("{cli} start {name}"))),
+        )];
+    }
+
+    vec![
+        app(
+            format!("Stop {name}"),
+            Function::RunShellCommand(ShellCommand::new(format!("{cli} stop {name}"))),
+        ),
+        app(
+            format!("Follow the logs of {name}"),
+            Function::RunInTerminal(ShellCommand::new(format!("{cli} logs --follow {name}"))),
+        ),
+        app(
+            format!("Open a shell in {name}"),
+            Function::RunInTerminal(ShellCommand::new(format!("{cli} exec -it {name} sh"))),
+        ),
+    ]
+}
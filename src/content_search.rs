@@ -0,0 +1,122 @@
+//! Searching the contents of small text files, for searches that are scoped with `grep`
+//!
+//! The search dirs are walked (skipping hidden files and folders, and the [`SKIPPED_DIRS`]), and
+//! the files with an allowed extension that are small enough are read. Each line that contains the
+//! pattern (in any case) is a result, which opens the file at that line. The results are sent in
+//! batches as they are found, like the file search's results
+
+use std::path::{Path, PathBuf};
+
+use iced::futures::{SinkExt, Stream};
+use iced::stream;
+
+use crate::{
+    app::{
+        FILE_SEARCH_BATCH_SIZE, FILE_SEARCH_MAX_RESULTS,
+        apps::{App, AppCommand},
+    },
+    commands::Function,
+    config::ContentSearch,
+};
+
+/// Folders that are full of files that aren't worth searching, and take a long time to walk
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "Library"];
+
+/// How much of a matching line is shown as its result's name
+const MAX_LINE_LENGTH: usize = 120;
+
+/// The lines containing a pattern in the files under some dirs, in batches
+pub fn search(
+    pattern: String,
+    dirs: Vec<String>,
+    settings: ContentSearch,
+) -> impl Stream<Item = Vec<App>> {
+    stream::channel(100, async move |mut output| {
+        let home_dir = std::env::var("HOME").unwrap_or("/".to_string());
+        let pattern = pattern.to_lowercase();
+        let mut pending: Vec<PathBuf> = dirs
+            .iter()
+            .map(|dir| PathBuf::from(dir.replacen("~", &home_dir, 1)))
+            .collect();
+
+        let mut batch = Vec::with_capacity(FILE_SEARCH_BATCH_SIZE as usize);
+        let mut total_sent: u32 = 0;
+
+        while let Some(dir) = pending.pop() {
+            let Ok(mut entries) = tokio::fs::read_dir(&dir).await else {
+                continue;
+            };
+
+            while let Ok(Some(entry)) = entries.next_entry().await {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let Ok(file_type) = entry.file_type().await else {
+                    continue;
+                };
+                if name.starts_with('.') || file_type.is_symlink() {
+                    continue;
+                }
+                if file_type.is_dir() {
+                    if !SKIPPED_DIRS.contains(&name.as_str()) {
+                        pending.push(entry.path());
+                    }
+                    continue;
+                }
+
+                let path = entry.path();
+                if !settings.allows(&path)
+                    || entry
+                        .metadata()
+                        .await
+                        .is_ok_and(|x| x.len() > settings.max_file_size)
+                {
+                    continue;
+                }
+                let Ok(contents) = tokio::fs::read_to_string(&path).await else {
+                    continue;
+                };
+
+                for (i, line) in contents.lines().enumerate() {
+                    if !line.to_lowercase().contains(&pattern) {
+                        continue;
+                    }
+
+                    batch.push(line_app(&path, i + 1, line, &home_dir));
+                    total_sent += 1;
+                    if batch.len() as u32 >= FILE_SEARCH_BATCH_SIZE
+                        || total_sent >= FILE_SEARCH_MAX_RESULTS
+                    {
+                        output.send(std::mem::take(&mut batch)).await.ok();
+                    }
+                    if total_sent >= FILE_SEARCH_MAX_RESULTS {
+                        return;
+                    }
+                }
+            }
+        }
+
+        if !batch.is_empty() {
+            output.send(batch).await.ok();
+        }
+    })
+}
+
+/// The result for a line of a file, named after the line and described by where it is
+fn line_app(path: &Path, line_number: usize, line: &str, home_dir: &str) -> App {
+    let path_str = path.to_string_lossy().to_string();
+    let display_path = match path_str.strip_prefix(home_dir) {
+        Some(suffix) => format!("~{suffix}"),
+        None => path_str.clone(),
+    };
+
+    App {
+        ranking: 0,
+        open_command: AppCommand::Function(Function::OpenAtLine(path_str, line_number)),
+        desc: format!("{display_path}:{line_number}"),
+        icons: None,
+        display_name: line.trim().chars().take(MAX_LINE_LENGTH).collect(),
+        search_name: String::new(),
+        version: None,
+        last_used: None,
+        children: None,
+    }
+}
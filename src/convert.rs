@@ -0,0 +1,155 @@
+//! Converting config snippets between JSON, YAML and TOML, for searches that start with `conv`
+//!
+//! `conv json yaml` followed by some text converts it from JSON to YAML, and `conv json` followed
+//! by some text checks that it is valid JSON (and formats it). Without any text, the text in the
+//! clipboard is used. Opening a result copies it, and a snippet that doesn't parse gives a result
+//! with the syntax error instead
+//!
+//! Each format is read into a [`serde_json::Value`] and written back out from it, so the keys of
+//! tables come out sorted
+
+use arboard::Clipboard;
+use serde_json::Value;
+
+use crate::{
+    app::apps::{App, AppCommand},
+    clipboard::ClipBoardContentType,
+    commands::Function,
+};
+
+/// How much of a converted snippet is shown as its result's name
+const MAX_PREVIEW_LENGTH: usize = 100;
+
+/// The formats that snippets can be converted between
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl Format {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "json" => Some(Format::Json),
+            "yaml" | "yml" => Some(Format::Yaml),
+            "toml" => Some(Format::Toml),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Format::Json => "JSON",
+            Format::Yaml => "YAML",
+            Format::Toml => "TOML",
+        }
+    }
+
+    fn parse(self, text: &str) -> Result<Value, String> {
+        match self {
+            Format::Json => serde_json::from_str(text).map_err(|e| e.to_string()),
+            Format::Yaml => serde_yaml_ng::from_str(text).map_err(|e| e.to_string()),
+            Format::Toml => toml::from_str::<toml::Value>(text)
+                .map(toml_to_json)
+                .map_err(|e| e.message().to_string()),
+        }
+    }
+
+    fn write(self, value: &Value) -> Result<String, String> {
+        match self {
+            Format::Json => serde_json::to_string_pretty(value).map_err(|e| e.to_string()),
+            Format::Yaml => serde_yaml_ng::to_string(value).map_err(|e| e.to_string()),
+            Format::Toml => toml::to_string_pretty(value).map_err(|e| match value {
+                Value::Object(_) => format!("Can't be written as TOML: {e}"),
+                _ => "TOML can only hold a table at the top level".to_string(),
+            }),
+        }
+    }
+}
+
+/// TOML's values as JSON ones, with dates and times written as strings
+fn toml_to_json(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(x) => Value::String(x),
+        toml::Value::Integer(x) => Value::from(x),
+        toml::Value::Float(x) => Value::from(x),
+        toml::Value::Boolean(x) => Value::Bool(x),
+        toml::Value::Datetime(x) => Value::String(x.to_string()),
+        toml::Value::Array(x) => Value::Array(x.into_iter().map(toml_to_json).collect()),
+        toml::Value::Table(x) => Value::Object(
+            x.into_iter()
+                .map(|(key, value)| (key, toml_to_json(value)))
+                .collect(),
+        ),
+    }
+}
+
+/// The result for a `conv` search: the converted (or checked) snippet, or what is wrong with it
+pub fn conv_apps(query: &str) -> Vec<App> {
+    let query = query.trim_start();
+    let Some(rest) = query
+        .get(..5)
+        .filter(|x| x.eq_ignore_ascii_case("conv "))
+        .map(|_| &query[5..])
+    else {
+        return vec![];
+    };
+
+    let (from, rest) = rest.split_once(' ').unwrap_or((rest, ""));
+    let Some(from) = Format::from_name(from) else {
+        return vec![];
+    };
+    let (to, text) = match rest.split_once(' ').unwrap_or((rest, "")) {
+        (to, text) if Format::from_name(to).is_some() => (Format::from_name(to), text),
+        _ => (None, rest),
+    };
+
+    let text = if text.trim().is_empty() {
+        match Clipboard::new().and_then(|mut x| x.get_text()) {
+            Ok(text) => text,
+            Err(_) => return vec![],
+        }
+    } else {
+        text.to_string()
+    };
+    if text.trim().is_empty() {
+        return vec![];
+    }
+
+    let to_format = to.unwrap_or(from);
+    let converted = from.parse(&text).and_then(|value| to_format.write(&value));
+    let desc = match to {
+        Some(to) => format!("{} → {}", from.name(), to.name()),
+        None => format!("Valid {}", from.name()),
+    };
+
+    vec![match converted {
+        Ok(converted) => snippet_app(converted, desc),
+        Err(error) => snippet_app(error, format!("Invalid {}", from.name())),
+    }]
+}
+
+fn snippet_app(text: String, desc: String) -> App {
+    let preview: String = text
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+        .chars()
+        .take(MAX_PREVIEW_LENGTH)
+        .collect();
+
+    App {
+        ranking: 20,
+        open_command: AppCommand::Function(Function::CopyToClipboard(ClipBoardContentType::Text(
+            text,
+        ))),
+        desc,
+        icons: None,
+        display_name: preview,
+        search_name: String::new(),
+        version: None,
+        last_used: None,
+        children: None,
+    }
+}
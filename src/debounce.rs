@@ -5,24 +5,24 @@ use crate::config::Config;
 use std::time::{Duration, Instant};
 
 /// Fields needed to facilitate debounced queries
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct Debouncer {
     triggered: Option<Instant>,
     delay: Duration,
 }
 
-// TODO: handle variable debounce based on Page enum
 impl Debouncer {
-    pub fn new(delay_ms: u64) -> Self {
-        Self {
-            triggered: None,
-            delay: Duration::from_millis(delay_ms),
-        }
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    /// Reset debounce timer
-    pub fn reset(&mut self) {
+    /// Reset debounce timer, for a query that should wait `delay` before it is run
+    ///
+    /// This cancels any query that is still waiting, since it won't be ready until `delay` after
+    /// this
+    pub fn reset(&mut self, delay: Duration) {
         self.triggered = Some(Instant::now()); // Clear debounce timer
+        self.delay = delay;
     }
 
     pub fn is_ready(&mut self) -> bool {
@@ -0,0 +1,47 @@
+//! Dragging results out of the window and dropping them onto other apps
+//!
+//! Pressing a result and moving the pointer out of it while the button is held drags what the
+//! result is for: the file or app for paths, the link for websites, and the text or image for
+//! clipboard items. Images are saved to a temporary file first, since that is what most apps
+//! (like chat apps) take when something is dropped on them
+
+use std::path::PathBuf;
+
+use crate::{
+    app::apps::{App, AppCommand},
+    clipboard::{self, ClipBoardContentType},
+    commands::Function,
+};
+
+/// What a result is dragged as
+#[derive(Debug, Clone, PartialEq)]
+pub enum Dragged {
+    File(PathBuf),
+    Url(String),
+    Text(String),
+}
+
+/// What dragging the result drags, or `None` if it can't be dragged
+pub fn dragged(app: &App) -> Result<Option<Dragged>, String> {
+    let AppCommand::Function(function) = &app.open_command else {
+        return Ok(None);
+    };
+
+    Ok(match function {
+        Function::OpenApp(path) | Function::OpenAtLine(path, _) => {
+            Some(Dragged::File(PathBuf::from(path)))
+        }
+        Function::OpenWebsite(url) => Some(Dragged::Url(url.clone())),
+        Function::CopyToClipboard(ClipBoardContentType::Text(text)) => {
+            Some(Dragged::Text(text.clone()))
+        }
+        Function::CopyToClipboard(ClipBoardContentType::Image(image)) => {
+            let dir = std::env::temp_dir().join("rustcast-drag");
+            std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+            let path = dir.join("Image.png");
+            clipboard::save_image(image, &path)?;
+            Some(Dragged::File(path))
+        }
+        _ => None,
+    })
+}
@@ -0,0 +1,160 @@
+//! Extensions, which are executables that give rustcast results for a query, in the same JSON
+//! format as Alfred's script filters
+//!
+//! Searching for an extension's keyword (followed by some text) runs the extension with that text
+//! as its last argument. It prints `{"items": [{"title", "subtitle", "arg", "icon"}]}` to stdout,
+//! and each item is shown as a result that opens its `arg`, which can be a url or a file. Other
+//! args are copied to the clipboard, and items without one can't be opened. Items can also have
+//! `children`, which are items of their own that the result can be browsed into
+
+use std::{path::Path, process::Stdio, time::Duration};
+
+use iced::widget::image::Handle;
+use serde::Deserialize;
+
+use crate::{
+    app::apps::{App, AppCommand, Children},
+    clipboard::ClipBoardContentType,
+    commands::Function,
+    config::Extension,
+    utils::handle_from_icns,
+};
+
+/// How long an extension can take to print its results before it is stopped
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// What an extension prints
+#[derive(Debug, Deserialize)]
+struct Output {
+    #[serde(default)]
+    items: Vec<Item>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Item {
+    title: String,
+    #[serde(default)]
+    subtitle: String,
+    arg: Option<String>,
+    icon: Option<Icon>,
+    /// Whether the item can be opened, which Alfred uses for items that are only information
+    #[serde(default = "valid_by_default")]
+    valid: bool,
+    /// The items that browsing into this one shows
+    #[serde(default)]
+    children: Vec<Item>,
+}
+
+fn valid_by_default() -> bool {
+    true
+}
+
+/// The icon of an item, which is either a path or (like in Alfred) an object with the path
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Icon {
+    Path(String),
+    Object { path: String },
+}
+
+impl Icon {
+    fn handle(&self) -> Option<Handle> {
+        let (Icon::Path(path) | Icon::Object { path }) = self;
+        let path = expand_home(path);
+        if path.ends_with(".icns") {
+            handle_from_icns(Path::new(&path))
+        } else {
+            Path::new(&path)
+                .exists()
+                .then(|| Handle::from_path(Path::new(&path)))
+        }
+    }
+}
+
+impl Item {
+    fn into_app(self) -> App {
+        let open_command = match self.arg.filter(|_| self.valid) {
+            Some(arg) if arg.starts_with("http://") || arg.starts_with("https://") => {
+                AppCommand::Function(Function::OpenWebsite(arg))
+            }
+            Some(arg) if Path::new(&expand_home(&arg)).exists() => {
+                AppCommand::Function(Function::OpenApp(expand_home(&arg)))
+            }
+            Some(arg) => {
+                AppCommand::Function(Function::CopyToClipboard(ClipBoardContentType::Text(arg)))
+            }
+            None => AppCommand::Display,
+        };
+
+        let children = (!self.children.is_empty())
+            .then(|| Children::Apps(self.children.into_iter().map(Item::into_app).collect()));
+
+        App {
+            ranking: 0,
+            open_command,
+            desc: self.subtitle,
+            icons: self.icon.and_then(|x| x.handle()),
+            display_name: self.title,
+            search_name: String::new(),
+            version: None,
+            last_used: None,
+            children,
+        }
+    }
+}
+
+fn expand_home(path: &str) -> String {
+    let home = std::env::var("HOME").unwrap_or("/".to_string());
+    path.replacen("~", &home, 1)
+}
+
+/// The extension whose keyword a query starts with, and the text after the keyword
+pub fn matching<'a>(
+    extensions: &'a [Extension],
+    query: &'a str,
+) -> Option<(&'a Extension, &'a str)> {
+    extensions.iter().find_map(|extension| {
+        let keyword = extension.keyword.trim();
+        let rest = query
+            .get(..keyword.len())
+            .filter(|x| !keyword.is_empty() && x.eq_ignore_ascii_case(keyword))
+            .map(|_| &query[keyword.len()..])?;
+
+        if rest.is_empty() {
+            Some((extension, rest))
+        } else {
+            Some((extension, rest.strip_prefix(' ')?.trim()))
+        }
+    })
+}
+
+/// Run an extension with a query, returning its results or why it failed
+pub async fn run(extension: Extension, query: String) -> Result<Vec<App>, String> {
+    let mut command = tokio::process::Command::new(extension.path());
+    command
+        .args(&extension.args)
+        .arg(&query)
+        .envs(&extension.env)
+        .stdin(Stdio::null())
+        .kill_on_drop(true);
+    if let Some(cwd) = &extension.cwd {
+        command.current_dir(expand_home(cwd));
+    }
+
+    let output = tokio::time::timeout(TIMEOUT, command.output())
+        .await
+        .map_err(|_| format!("`{}` took too long to respond", extension.keyword))?
+        .map_err(|e| format!("Could not run {}: {e}", extension.path().display()))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "`{}` failed: {}",
+            extension.keyword,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let output: Output = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("`{}` gave invalid results: {e}", extension.keyword))?;
+    Ok(output.items.into_iter().map(Item::into_app).collect())
+}
@@ -0,0 +1,202 @@
+//! GitHub quick actions, for searches like `gh`, `gh issues rustcast` or `gh pr`
+//!
+//! `gh` (or `gh repos`) lists your repositories, `gh issues` the open issues assigned to you, and
+//! `gh pr` the open pull requests you're involved in, and opening one opens it in the browser. The
+//! rest of the search filters them. Each list is fetched with the configured token (or
+//! `$GITHUB_TOKEN`) once the search stops changing, and the tile keeps it for [`CACHE_SECONDS`],
+//! so typing the filter only filters the list it already has
+
+use serde::Deserialize;
+
+use crate::{
+    app::apps::{App, AppCommand},
+    commands::Function,
+    config::GitHub,
+};
+
+/// How long a list is kept before it is fetched again
+pub const CACHE_SECONDS: u64 = 5 * 60;
+
+/// The lists that can be shown
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum List {
+    Repos,
+    Issues,
+    PullRequests,
+}
+
+impl List {
+    /// The key the list is kept under in the tile's lookup cache
+    pub fn cache_key(self) -> &'static str {
+        match self {
+            List::Repos => "github:repos",
+            List::Issues => "github:issues",
+            List::PullRequests => "github:pulls",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Repo {
+    full_name: String,
+    html_url: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    private: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct Issue {
+    title: String,
+    number: u64,
+    html_url: String,
+    #[serde(default)]
+    repository: Option<IssueRepo>,
+    #[serde(default)]
+    repository_url: Option<String>,
+    #[serde(default)]
+    pull_request: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IssueRepo {
+    full_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResults {
+    items: Vec<Issue>,
+}
+
+/// The token to use, from the config or `$GITHUB_TOKEN`
+pub fn token(settings: &GitHub) -> Option<String> {
+    Some(settings.token.trim().to_string())
+        .filter(|x| !x.is_empty())
+        .or_else(|| std::env::var("GITHUB_TOKEN").ok())
+        .filter(|x| !x.is_empty())
+}
+
+/// The list and the filter of a `gh` search, or `None` if the search isn't one
+pub fn matching(query: &str) -> Option<(List, String)> {
+    let mut words = query.split_whitespace();
+    if !words.next()?.eq_ignore_ascii_case("gh") {
+        return None;
+    }
+
+    let mut words = words.peekable();
+    let list = match words.peek().map(|x| x.to_lowercase()).as_deref() {
+        Some("repos" | "repo") => List::Repos,
+        Some("issues" | "issue") => List::Issues,
+        Some("pr" | "prs" | "pulls") => List::PullRequests,
+        _ => return Some((List::Repos, words.collect::<Vec<&str>>().join(" "))),
+    };
+    words.next();
+    Some((list, words.collect::<Vec<&str>>().join(" ")))
+}
+
+/// Fetch a list
+pub async fn fetch(settings: GitHub, token: String, list: List) -> Result<Vec<App>, String> {
+    tokio::task::spawn_blocking(move || {
+        let api = settings.api_url.trim_end_matches('/');
+        match list {
+            List::Repos => {
+                let repos: Vec<Repo> = get_json(
+                    &format!("{api}/user/repos?per_page=100&sort=pushed"),
+                    &token,
+                )?;
+                Ok(repos.into_iter().map(repo_app).collect())
+            }
+            List::Issues => {
+                let issues: Vec<Issue> = get_json(
+                    &format!("{api}/issues?filter=assigned&state=open&per_page=100"),
+                    &token,
+                )?;
+                // Pull requests are issues too, but they have their own list
+                Ok(issues
+                    .into_iter()
+                    .filter(|x| x.pull_request.is_none())
+                    .map(|x| issue_app(x, "Issue"))
+                    .collect())
+            }
+            List::PullRequests => {
+                let results: SearchResults = get_json(
+                    &format!("{api}/search/issues?q=is:pr+is:open+involves:@me&per_page=100"),
+                    &token,
+                )?;
+                Ok(results
+                    .items
+                    .into_iter()
+                    .map(|x| issue_app(x, "Pull request"))
+                    .collect())
+            }
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+fn get_json<T: serde::de::DeserializeOwned>(url: &str, token: &str) -> Result<T, String> {
+    let response = minreq::get(url)
+        .with_header("User-Agent", "rustcast")
+        .with_header("Accept", "application/vnd.github+json")
+        .with_header("X-GitHub-Api-Version", "2022-11-28")
+        .with_header("Authorization", format!("Bearer {token}"))
+        .with_timeout(10)
+        .send()
+        .map_err(|e| e.to_string())?;
+    match response.status_code {
+        200 => serde_json::from_slice(response.as_bytes()).map_err(|e| e.to_string()),
+        401 => Err("GitHub didn't accept the token".to_string()),
+        code => Err(format!(
+            "GitHub responded with {code} {}",
+            response.reason_phrase
+        )),
+    }
+}
+
+fn repo_app(repo: Repo) -> App {
+    let mut desc = repo.description.unwrap_or_default();
+    if repo.private {
+        desc = format!("Private · {desc}");
+    }
+    app(
+        repo.full_name,
+        desc.trim_end_matches(" · ").to_string(),
+        repo.html_url,
+    )
+}
+
+fn issue_app(issue: Issue, kind: &str) -> App {
+    // Search results only have the repository's API url, which ends with its full name
+    let repo = issue.repository.map(|x| x.full_name).or_else(|| {
+        let url = issue.repository_url?;
+        let mut parts = url.rsplit('/');
+        let name = parts.next()?;
+        let owner = parts.next()?;
+        Some(format!("{owner}/{name}"))
+    });
+
+    app(
+        format!("{} #{}", issue.title, issue.number),
+        match repo {
+            Some(repo) => format!("{kind} · {repo}"),
+            None => kind.to_string(),
+        },
+        issue.html_url,
+    )
+}
+
+fn app(display_name: String, desc: String, url: String) -> App {
+    App {
+        ranking: 20,
+        open_command: AppCommand::Function(Function::OpenWebsite(url)),
+        desc,
+        icons: None,
+        display_name,
+        search_name: String::new(),
+        version: None,
+        last_used: None,
+        children: None,
+    }
+}
@@ -0,0 +1,131 @@
+//! Translations of rustcast's built in text, which are in the `locales` directory
+//!
+//! The files are Fluent (`.ftl`) files, which are read with `fluent-bundle`, so variables,
+//! plurals and other selectors, terms and attributes work the way translators expect. The
+//! language is `language` from the config, or the system's language if that isn't set, and
+//! messages a language doesn't have are shown in English
+
+use std::{
+    collections::HashMap,
+    sync::{OnceLock, RwLock},
+};
+
+use fluent_bundle::{FluentArgs, FluentResource, concurrent::FluentBundle};
+use log::error;
+use unic_langid::LanguageIdentifier;
+
+/// The languages rustcast has been translated to, with their Fluent files
+const LOCALES: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en.ftl")),
+    ("de", include_str!("../locales/de.ftl")),
+];
+
+/// The language that English is used in place of
+const FALLBACK: &str = "en";
+
+/// The language that text is shown in
+static LANGUAGE: RwLock<&str> = RwLock::new(FALLBACK);
+
+/// Translate a message, like `tr!("results-found", count = 3)`
+///
+/// Numbers are passed to Fluent as numbers, so that selectors like `{ $count -> [one] ... }` pick
+/// the right plural form
+macro_rules! tr {
+    ($id:literal) => {
+        $crate::i18n::translate($id, None)
+    };
+    ($id:literal, $($name:ident = $value:expr),+ $(,)?) => {{
+        let mut args = fluent_bundle::FluentArgs::new();
+        $(args.set(stringify!($name), $value);)+
+        $crate::i18n::translate($id, Some(&args))
+    }};
+}
+pub(crate) use tr;
+
+/// The Fluent bundle of each language
+fn bundles() -> &'static HashMap<&'static str, FluentBundle<FluentResource>> {
+    static BUNDLES: OnceLock<HashMap<&'static str, FluentBundle<FluentResource>>> = OnceLock::new();
+    BUNDLES.get_or_init(|| {
+        LOCALES
+            .iter()
+            .map(|(language, file)| (*language, bundle(language, file)))
+            .collect()
+    })
+}
+
+/// Read a language's file into a bundle, leaving out (and logging) the messages that can't be read
+fn bundle(language: &str, file: &str) -> FluentBundle<FluentResource> {
+    let id: LanguageIdentifier = language.parse().unwrap_or_default();
+    let mut bundle = FluentBundle::new_concurrent(vec![id]);
+    // The isolation marks around variables only matter for mixing text directions, and show up
+    // as boxes in some fonts
+    bundle.set_use_isolating(false);
+
+    let resource =
+        FluentResource::try_new(file.to_string()).unwrap_or_else(|(resource, errors)| {
+            error!("Could not read every message in {language}.ftl: {errors:?}");
+            resource
+        });
+    if let Err(errors) = bundle.add_resource(resource) {
+        error!("{language}.ftl has messages more than once: {errors:?}");
+    }
+    bundle
+}
+
+/// The languages that there are translations for
+pub fn languages() -> Vec<&'static str> {
+    LOCALES.iter().map(|(language, _)| *language).collect()
+}
+
+/// Show text in a language, which is a code like `de` or `de-AT`, or the system's language if
+/// there isn't one
+///
+/// Languages without translations are shown in English
+pub fn set_language(language: Option<&str>) {
+    let requested = language
+        .map(str::to_string)
+        .or_else(sys_locale::get_locale)
+        .unwrap_or_default();
+    let language = supported(&requested).unwrap_or(FALLBACK);
+
+    if let Ok(mut current) = LANGUAGE.write() {
+        *current = language;
+    }
+}
+
+/// The translated language for a language code, ignoring the region (so `de-AT` is `de`)
+pub fn supported(language: &str) -> Option<&'static str> {
+    let code = language
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    LOCALES
+        .iter()
+        .map(|(language, _)| *language)
+        .find(|language| *language == code)
+}
+
+/// The message with the given id in the current language, with its variables filled in, or the id
+/// if no language has it
+///
+/// Use the [`tr!`] macro rather than calling this directly
+pub fn translate(id: &str, args: Option<&FluentArgs>) -> String {
+    let language = LANGUAGE.read().map(|x| *x).unwrap_or(FALLBACK);
+    for language in [language, FALLBACK] {
+        let Some(bundle) = bundles().get(language) else {
+            continue;
+        };
+        let Some(pattern) = bundle.get_message(id).and_then(|x| x.value()) else {
+            continue;
+        };
+
+        let mut errors = vec![];
+        let text = bundle.format_pattern(pattern, args, &mut errors);
+        if !errors.is_empty() {
+            error!("Could not fill in {id} in {language}.ftl: {errors:?}");
+        }
+        return text.to_string();
+    }
+    id.to_string()
+}
@@ -0,0 +1,245 @@
+//! Jira and Linear issues: searching for an issue key like `PROJ-123` gives a result that opens
+//! it, and `issues` lists the open issues assigned to you, which the rest of the search filters
+//!
+//! The assigned issues are fetched with the configured token once the search stops changing, and
+//! the tile keeps them for [`CACHE_SECONDS`], like the lists of [`crate::github`]
+
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::{
+    app::apps::{App, AppCommand},
+    commands::Function,
+    config::{IssueTracker, IssueTrackerKind},
+    utils::{base64_encode, percent_encode},
+};
+
+/// How long the assigned issues are kept before they are fetched again
+pub const CACHE_SECONDS: u64 = 5 * 60;
+
+/// The key the assigned issues are kept under in the tile's lookup cache
+pub const CACHE_KEY: &str = "issue_tracker:assigned";
+
+/// The most assigned issues that are fetched
+const MAX_ISSUES: usize = 50;
+
+/// Linear's GraphQL API
+const LINEAR_API_URL: &str = "https://api.linear.app/graphql";
+
+#[derive(Debug, Deserialize)]
+struct JiraResponse {
+    issues: Vec<JiraIssue>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JiraIssue {
+    key: String,
+    fields: JiraFields,
+}
+
+#[derive(Debug, Deserialize)]
+struct JiraFields {
+    summary: String,
+    #[serde(default)]
+    status: Option<Named>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Named {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct LinearResponse {
+    data: LinearData,
+}
+
+#[derive(Debug, Deserialize)]
+struct LinearData {
+    viewer: LinearViewer,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LinearViewer {
+    assigned_issues: LinearIssues,
+}
+
+#[derive(Debug, Deserialize)]
+struct LinearIssues {
+    nodes: Vec<LinearIssue>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LinearIssue {
+    identifier: String,
+    title: String,
+    url: String,
+    #[serde(default)]
+    state: Option<Named>,
+}
+
+/// Whether an issue tracker is set up in the config
+pub fn is_configured(settings: &IssueTracker) -> bool {
+    !settings.base_url.trim().is_empty()
+}
+
+/// A result that opens the issue, if the search is an issue key (of one of the configured
+/// projects, if there are any)
+pub fn key_app(settings: &IssueTracker, query: &str) -> Option<App> {
+    let key = query.trim().to_uppercase();
+    let (project, number) = key.split_once('-')?;
+    let is_key = project.starts_with(|c: char| c.is_ascii_alphabetic())
+        && project
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !number.is_empty()
+        && number.chars().all(|c| c.is_ascii_digit());
+    let is_project = settings.projects.is_empty()
+        || settings
+            .projects
+            .iter()
+            .any(|x| x.eq_ignore_ascii_case(project));
+    if !is_key || !is_project {
+        return None;
+    }
+
+    let base_url = settings.base_url.trim_end_matches('/');
+    let url = match settings.kind {
+        IssueTrackerKind::Jira => format!("{base_url}/browse/{key}"),
+        IssueTrackerKind::Linear => format!("{base_url}/issue/{key}"),
+    };
+    Some(app(
+        format!("Open {key}"),
+        settings.kind.name().to_string(),
+        url,
+    ))
+}
+
+/// The filter of an `issues` search, or `None` if the search isn't one
+pub fn matching(query: &str) -> Option<String> {
+    let mut words = query.split_whitespace();
+    if !words.next()?.eq_ignore_ascii_case("issues") {
+        return None;
+    }
+    Some(words.collect::<Vec<&str>>().join(" "))
+}
+
+/// Fetch the open issues that are assigned to you
+pub async fn fetch_assigned(settings: IssueTracker) -> Result<Vec<App>, String> {
+    tokio::task::spawn_blocking(move || match settings.kind {
+        IssueTrackerKind::Jira => fetch_jira(&settings),
+        IssueTrackerKind::Linear => fetch_linear(&settings),
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+fn fetch_jira(settings: &IssueTracker) -> Result<Vec<App>, String> {
+    let base_url = settings.base_url.trim_end_matches('/');
+    let jql = percent_encode(
+        "assignee = currentUser() AND resolution = Unresolved ORDER BY updated DESC",
+    );
+
+    // Jira Cloud signs in with an email and an API token, and Jira Data Center with a personal
+    // access token, and each has its own search endpoint
+    let (url, authorization) = match &settings.email {
+        Some(email) => (
+            format!("{base_url}/rest/api/3/search/jql"),
+            format!(
+                "Basic {}",
+                base64_encode(format!("{email}:{}", settings.token).as_bytes())
+            ),
+        ),
+        None => (
+            format!("{base_url}/rest/api/2/search"),
+            format!("Bearer {}", settings.token),
+        ),
+    };
+    let url = format!("{url}?jql={jql}&fields=summary,status&maxResults={MAX_ISSUES}");
+
+    let response = minreq::get(url)
+        .with_header("Authorization", authorization)
+        .with_header("Accept", "application/json")
+        .with_timeout(10)
+        .send()
+        .map_err(|e| e.to_string())?;
+    let response: JiraResponse = parse(&response, "Jira")?;
+
+    Ok(response
+        .issues
+        .into_iter()
+        .map(|issue| {
+            let desc = match issue.fields.status {
+                Some(status) => format!("Jira · {}", status.name),
+                None => "Jira".to_string(),
+            };
+            let url = format!("{base_url}/browse/{}", issue.key);
+            app(format!("{} {}", issue.key, issue.fields.summary), desc, url)
+        })
+        .collect())
+}
+
+fn fetch_linear(settings: &IssueTracker) -> Result<Vec<App>, String> {
+    let query = format!(
+        "{{ viewer {{ assignedIssues(first: {MAX_ISSUES}, filter: {{ state: {{ type: {{ nin: \
+         [\"completed\", \"canceled\"] }} }} }}) {{ nodes {{ identifier title url state {{ name }} \
+         }} }} }} }}"
+    );
+
+    let response = minreq::post(LINEAR_API_URL)
+        .with_header("Authorization", settings.token.as_str())
+        .with_header("Content-Type", "application/json")
+        .with_body(json!({ "query": query }).to_string())
+        .with_timeout(10)
+        .send()
+        .map_err(|e| e.to_string())?;
+    let response: LinearResponse = parse(&response, "Linear")?;
+
+    Ok(response
+        .data
+        .viewer
+        .assigned_issues
+        .nodes
+        .into_iter()
+        .map(|issue| {
+            let desc = match issue.state {
+                Some(state) => format!("Linear · {}", state.name),
+                None => "Linear".to_string(),
+            };
+            app(
+                format!("{} {}", issue.identifier, issue.title),
+                desc,
+                issue.url,
+            )
+        })
+        .collect())
+}
+
+fn parse<T: serde::de::DeserializeOwned>(
+    response: &minreq::Response,
+    tracker: &str,
+) -> Result<T, String> {
+    match response.status_code {
+        200 => serde_json::from_slice(response.as_bytes()).map_err(|e| e.to_string()),
+        401 | 403 => Err(format!("{tracker} didn't accept the token")),
+        code => Err(format!(
+            "{tracker} responded with {code} {}",
+            response.reason_phrase
+        )),
+    }
+}
+
+fn app(display_name: String, desc: String, url: String) -> App {
+    App {
+        ranking: 20,
+        open_command: AppCommand::Function(Function::OpenWebsite(url)),
+        desc,
+        icons: None,
+        display_name,
+        search_name: String::new(),
+        version: None,
+        last_used: None,
+        children: None,
+    }
+}
@@ -0,0 +1,49 @@
+//! RustCast, a launcher for searching apps, files and the web, and running commands
+//!
+//! The app itself is in `main.rs`. Everything else is in this library, so that the benchmarks in
+//! `benches/` can use the search (and anything else) without the window
+#![deny(clippy::dbg_macro)]
+
+pub mod app;
+pub mod calculator;
+pub mod calendar;
+pub mod clipboard;
+pub mod commands;
+pub mod config;
+pub mod contacts;
+pub mod containers;
+pub mod content_search;
+pub mod convert;
+pub mod debounce;
+pub mod drag;
+pub mod extensions;
+pub mod github;
+pub mod i18n;
+pub mod issue_tracker;
+pub mod links;
+pub mod logging;
+pub mod markets;
+pub mod media;
+pub mod network;
+pub mod notes;
+pub mod notifications;
+pub mod packages;
+pub mod passwords;
+pub mod path_completion;
+pub mod platform;
+pub mod ports;
+pub mod projects;
+pub mod providers;
+pub mod random;
+pub mod regex_tester;
+pub mod search;
+pub mod single_instance;
+pub mod styles;
+pub mod symbols;
+pub mod timers;
+pub mod transforms;
+pub mod unit_conversion;
+pub mod updater;
+pub mod utils;
+pub mod weather;
+pub mod world_clock;
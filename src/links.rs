@@ -0,0 +1,149 @@
+//! Working with links, for searches that start with `url`
+//!
+//! `url encode` and `url decode` followed by some text percent-encode or decode it, and opening
+//! the result copies it. `url` on its own gives a result for each link in the clipboard's text,
+//! which opens it. `url expand` followed by a shortened link follows its redirects with a `HEAD`
+//! request, but only when `expand_short_urls` is turned on, since that sends the link to its
+//! server
+
+use std::collections::HashSet;
+
+use arboard::Clipboard;
+
+use crate::{
+    app::apps::{App, AppCommand},
+    clipboard::ClipBoardContentType,
+    commands::Function,
+    utils::{percent_decode, percent_encode},
+};
+
+/// The characters that can end a link in text, but are more likely to be punctuation around it
+const TRAILING_PUNCTUATION: &[char] =
+    &['.', ',', ';', ':', '!', '?', ')', ']', '}', '>', '\'', '"'];
+
+/// The text after `url`, if the search starts with it
+fn rest(query: &str) -> Option<&str> {
+    let query = query.trim_start();
+    let rest = query
+        .get(..3)
+        .filter(|x| x.eq_ignore_ascii_case("url"))
+        .map(|_| &query[3..])?;
+
+    if rest.is_empty() {
+        Some(rest)
+    } else {
+        rest.strip_prefix(' ')
+    }
+}
+
+/// The text after a subcommand of `url`, like `encode`
+fn argument<'a>(rest: &'a str, subcommand: &str) -> Option<&'a str> {
+    let argument = rest
+        .get(..subcommand.len())
+        .filter(|x| x.eq_ignore_ascii_case(subcommand))
+        .map(|_| &rest[subcommand.len()..])?
+        .strip_prefix(' ')?;
+    (!argument.trim().is_empty()).then_some(argument)
+}
+
+/// The results for a `url` search, except for `url expand`, which is looked up separately
+pub fn url_apps(query: &str) -> Vec<App> {
+    let Some(rest) = rest(query) else {
+        return vec![];
+    };
+
+    if rest.is_empty() {
+        return match Clipboard::new().and_then(|mut x| x.get_text()) {
+            Ok(text) => extract_urls(&text).into_iter().map(open_app).collect(),
+            Err(_) => vec![],
+        };
+    }
+
+    if let Some(text) = argument(rest, "encode") {
+        return vec![copy_app(percent_encode(text), "URL-encoded")];
+    }
+    if let Some(text) = argument(rest, "decode") {
+        return match percent_decode(text) {
+            Some(decoded) => vec![copy_app(decoded, "URL-decoded")],
+            None => vec![],
+        };
+    }
+    vec![]
+}
+
+/// The link from a `url expand` search
+pub fn expand_target(query: &str) -> Option<String> {
+    argument(rest(query)?, "expand").map(|x| x.trim().to_string())
+}
+
+/// Follow a link's redirects, and return where they lead to
+pub async fn expand(link: String) -> Result<String, String> {
+    let link = if link.contains("://") {
+        link
+    } else {
+        format!("https://{link}")
+    };
+
+    tokio::task::spawn_blocking(move || {
+        let response = minreq::head(&link)
+            .with_header("User-Agent", "rustcast")
+            .with_timeout(5)
+            .send()
+            .map_err(|e| e.to_string())?;
+        if response.status_code >= 400 {
+            return Err(format!(
+                "{link} responded with {} {}",
+                response.status_code, response.reason_phrase
+            ));
+        }
+        Ok(response.url)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// The `http` and `https` links in some text, in order and without duplicates
+fn extract_urls(text: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    text.split(|c: char| c.is_whitespace() || "<>\"'`()[]".contains(c))
+        .filter_map(|word| {
+            let start = word.find("https://").or_else(|| word.find("http://"))?;
+            let url = word[start..].trim_end_matches(TRAILING_PUNCTUATION);
+            url.split_once("://")
+                .is_some_and(|(_, host)| !host.is_empty())
+                .then(|| url.to_string())
+        })
+        .filter(|x| seen.insert(x.clone()))
+        .collect()
+}
+
+/// A result that opens a link
+pub fn open_app(url: String) -> App {
+    App {
+        ranking: 20,
+        open_command: AppCommand::Function(Function::OpenWebsite(url.clone())),
+        desc: "Open Link".to_string(),
+        icons: None,
+        display_name: url,
+        search_name: String::new(),
+        version: None,
+        last_used: None,
+        children: None,
+    }
+}
+
+fn copy_app(text: String, desc: &str) -> App {
+    App {
+        ranking: 20,
+        open_command: AppCommand::Function(Function::CopyToClipboard(ClipBoardContentType::Text(
+            text.clone(),
+        ))),
+        desc: desc.to_string(),
+        icons: None,
+        display_name: text,
+        search_name: String::new(),
+        version: None,
+        last_used: None,
+        children: None,
+    }
+}
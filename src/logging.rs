@@ -0,0 +1,86 @@
+//! Logging to a file in the log directory, with a new file for every day
+//!
+//! The level comes from `$RUSTCAST_LOG` if it is set (which takes filters like `rustcast=debug`),
+//! and otherwise from the `[logging]` table in the config. Every line is written to the file as
+//! soon as it is logged, and panics are logged too, so the log has everything up to a crash
+//!
+//! The files are rolled over (and the oldest ones removed) by `tracing-appender`, which dates them
+//! in UTC, so a new file starts at midnight UTC rather than local midnight
+
+use std::{backtrace::Backtrace, path::PathBuf};
+
+use log::error;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::{EnvFilter, fmt, layer::SubscriberExt, util::SubscriberInitExt};
+
+use crate::config::Logging;
+
+/// The environment variable that overrides the configured log level
+const LOG_ENV: &str = "RUSTCAST_LOG";
+
+/// The directory the log files are kept in
+pub fn log_dir(logging: &Logging) -> PathBuf {
+    if let Some(dir) = &logging.dir {
+        let home = dirs::home_dir().unwrap_or(PathBuf::from("/"));
+        return PathBuf::from(dir.replacen("~", &home.to_string_lossy(), 1));
+    }
+
+    if cfg!(target_os = "macos") {
+        // Logs here show up in Console.app
+        dirs::home_dir()
+            .unwrap_or(PathBuf::from("/"))
+            .join("Library/Logs/RustCast")
+    } else {
+        dirs::state_dir()
+            .or_else(dirs::data_local_dir)
+            .unwrap_or(std::env::temp_dir())
+            .join("rustcast")
+            .join("logs")
+    }
+}
+
+/// Today's log file
+pub fn log_file(logging: &Logging) -> PathBuf {
+    log_dir(logging).join(file_name(&today()))
+}
+
+fn file_name(date: &str) -> String {
+    format!("rustcast.{date}.log")
+}
+
+/// Today's date in UTC, which is the date the current log file is named after
+fn today() -> String {
+    jiff::Timestamp::now().strftime("%Y-%m-%d").to_string()
+}
+
+/// Start logging, to the log file (and to stdout in debug builds)
+pub fn init(logging: &Logging) {
+    let filter = std::env::var(LOG_ENV)
+        .ok()
+        .filter(|x| !x.is_empty())
+        .and_then(|x| EnvFilter::try_new(x).ok())
+        .unwrap_or_else(|| EnvFilter::new(format!("rustcast={}", logging.level.as_str())));
+
+    // Only `keep_days` files are kept, removing the oldest ones each time a new one is started.
+    // The appender isn't the non-blocking one, so that nothing is lost if the app crashes
+    let file = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix("rustcast")
+        .filename_suffix("log")
+        .max_log_files(logging.keep_days.max(1))
+        .build(log_dir(logging))
+        .inspect_err(|e| eprintln!("Couldn't log to {}: {e}", log_dir(logging).display()))
+        .ok();
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(file.map(|x| fmt::layer().with_ansi(false).with_writer(x)))
+        .with(cfg!(debug_assertions).then(fmt::layer))
+        .init();
+
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        error!("{info}\n{}", Backtrace::force_capture());
+        default_hook(info);
+    }));
+}
@@ -1,38 +1,44 @@
 #![deny(clippy::dbg_macro)]
 
-mod app;
-mod calculator;
-mod clipboard;
-mod commands;
-mod config;
-mod debounce;
-mod platform;
-mod quit;
-mod styles;
-mod unit_conversion;
-mod utils;
-
-use std::{collections::HashMap, fs::OpenOptions, path::Path};
-
-use crate::{
+use log::info;
+use rustcast::{
     app::tile::{self, Hotkeys, Tile},
-    config::Config,
-    platform::macos::{get_autostart_status, launching::Shortcut},
+    config::{self, Config},
+    i18n, logging, notifications, platform,
+    platform::set_activation_policy_accessory,
+    single_instance,
+    utils::{config_dir, config_path},
 };
 
-use log::info;
-use tracing_subscriber::{EnvFilter, Layer, util::SubscriberInitExt};
+fn main() -> iced::Result {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args == ["config", "doctor"] {
+        print!("{}", config::doctor::doctor(&config_dir()));
+        return Ok(());
+    }
+    if let [command, title, body @ ..] = args.as_slice()
+        && command == "notify"
+    {
+        // For scripts and shell commands to tell the user when they are done
+        if let Err(error) = notifications::notify(title, &body.join(" "), true) {
+            eprintln!("{error}");
+        }
+        return Ok(());
+    }
 
-use self::platform::set_activation_policy_accessory;
+    if !single_instance::acquire_lock() {
+        match single_instance::show_running_instance() {
+            Ok(()) => println!("RustCast is already running"),
+            Err(error) => eprintln!("{error}"),
+        }
+        return Ok(());
+    }
 
-fn main() -> iced::Result {
     set_activation_policy_accessory();
 
-    let home = std::env::var("HOME").unwrap();
-
-    let file_path = home.clone() + "/.config/rustcast/config.toml";
-    if !Path::new(&file_path).exists() {
-        std::fs::create_dir_all(home.clone() + "/.config/rustcast").unwrap();
+    let file_path = config_path("config.toml");
+    if !file_path.exists() {
+        std::fs::create_dir_all(config_dir()).unwrap();
         std::fs::write(
             &file_path,
             toml::to_string(&Config::default()).unwrap_or_else(|x| x.to_string()),
@@ -40,56 +46,25 @@ fn main() -> iced::Result {
         .unwrap();
     }
 
-    let mut config: Config = match std::fs::read_to_string(&file_path) {
-        Ok(a) => toml::from_str(&a).unwrap_or(Config::default()),
-        Err(_) => Config::default(),
+    let (mut config, config_diagnostics) = match Config::load(&config_dir()) {
+        Ok(a) => a,
+        Err(diagnostic) => (Config::default(), vec![diagnostic]),
     };
 
-    config.start_at_login = get_autostart_status();
-
-    if cfg!(debug_assertions) {
-        let sub = tracing_subscriber::fmt().finish();
-        EnvFilter::new("rustcast=info").with_subscriber(sub).init();
-    } else {
-        let file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(config.log_path.replace("~", &home))
-            .unwrap();
+    config.start_at_login = platform::autostart_enabled();
+    i18n::set_language(config.language.as_deref());
 
-        let sub = tracing_subscriber::fmt().with_writer(file).finish();
-        EnvFilter::new("rustcast=info").with_subscriber(sub).init();
-    };
+    logging::init(&config.logging);
 
     info!("Config loaded");
 
-    let show_hide =
-        Shortcut::parse(&config.toggle_hotkey).unwrap_or(Shortcut::parse("option+space").unwrap());
-
-    let cbhist = Shortcut::parse(&config.clipboard_hotkey.to_lowercase())
-        .unwrap_or_else(|_| Shortcut::parse("cmd+shift+c").unwrap());
-
-    let mut shell_map = HashMap::new();
-
-    for shell in &config.shells {
-        if let Some(hk_str) = &shell.hotkey
-            && let Ok(hk) = Shortcut::parse(hk_str)
-        {
-            shell_map.insert(hk, shell.clone());
-        }
-    }
-
-    let hotkeys = Hotkeys {
-        toggle: show_hide,
-        clipboard_hotkey: cbhist,
-        shells: shell_map,
-    };
+    let hotkeys = Hotkeys::from_config(&config);
 
     info!("Hotkeys loaded");
     info!("Starting rustcast");
 
     iced::daemon(
-        move || tile::elm::new(hotkeys.clone(), &config),
+        move || tile::elm::new(hotkeys.clone(), &config, config_diagnostics.clone()),
         tile::update::handle_update,
         tile::elm::view,
     )
@@ -0,0 +1,212 @@
+//! Stock and crypto prices, for searches like `stock AAPL` or `crypto btc`
+//!
+//! The prices come from a chart API that works like Yahoo Finance's (which is the default), with
+//! crypto looked up as a pair with the configured currency, like `BTC-USD`. The result shows the
+//! price, how much it changed since the previous close, and a sparkline of today's prices, and
+//! opening it copies the price. The tile keeps the result for [`CACHE_SECONDS`], and shows the
+//! last one it has (saying how old it is) when the price can't be looked up, like when offline
+
+use serde::Deserialize;
+
+use crate::{
+    app::apps::{App, AppCommand},
+    clipboard::ClipBoardContentType,
+    commands::Function,
+    config::Markets,
+    utils::percent_encode,
+};
+
+/// How long a price is kept before it is looked up again
+pub const CACHE_SECONDS: u64 = 60;
+
+/// How many bars the sparkline has
+const SPARKLINE_LENGTH: usize = 24;
+
+const SPARKLINE_BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+#[derive(Debug, Deserialize)]
+struct Response {
+    chart: Chart,
+}
+
+#[derive(Debug, Deserialize)]
+struct Chart {
+    result: Option<Vec<ChartResult>>,
+    error: Option<ChartError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChartError {
+    description: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChartResult {
+    meta: Meta,
+    indicators: Indicators,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Meta {
+    symbol: String,
+    #[serde(default)]
+    currency: Option<String>,
+    regular_market_price: f64,
+    #[serde(default)]
+    chart_previous_close: Option<f64>,
+    #[serde(default)]
+    long_name: Option<String>,
+    #[serde(default)]
+    short_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Indicators {
+    quote: Vec<Quote>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Quote {
+    #[serde(default)]
+    close: Vec<Option<f64>>,
+}
+
+/// The symbol to look up for a `stock` or `crypto` search, or `None` if the search isn't one
+pub fn matching(settings: &Markets, query: &str) -> Option<String> {
+    let (keyword, symbol) = query.trim().split_once(' ')?;
+    let symbol = symbol.trim().to_uppercase();
+    if symbol.is_empty() || symbol.contains(' ') {
+        return None;
+    }
+
+    match keyword.to_lowercase().as_str() {
+        "stock" => Some(symbol),
+        "crypto" if symbol.contains('-') => Some(symbol),
+        "crypto" => Some(format!(
+            "{symbol}-{}",
+            settings.crypto_currency.trim().to_uppercase()
+        )),
+        _ => None,
+    }
+}
+
+/// Look up the price of a symbol
+pub async fn lookup(settings: Markets, symbol: String) -> Result<Vec<App>, String> {
+    tokio::task::spawn_blocking(move || {
+        let url = format!(
+            "{}/{}?range=1d&interval=15m",
+            settings.chart_url.trim_end_matches('/'),
+            percent_encode(&symbol)
+        );
+        let response = minreq::get(url)
+            .with_header("User-Agent", "Mozilla/5.0 (rustcast)")
+            .with_timeout(10)
+            .send()
+            .map_err(|e| e.to_string())?;
+        let body: Response = serde_json::from_slice(response.as_bytes()).map_err(|_| {
+            format!(
+                "The price service responded with {} {}",
+                response.status_code, response.reason_phrase
+            )
+        })?;
+
+        if let Some(error) = body.chart.error {
+            return Err(format!("{symbol}: {}", error.description));
+        }
+        let result = body
+            .chart
+            .result
+            .and_then(|x| x.into_iter().next())
+            .ok_or_else(|| format!("There is no price for {symbol}"))?;
+        Ok(vec![quote_app(&result)])
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// The price, with the change since the previous close and a sparkline of the prices
+fn quote_app(result: &ChartResult) -> App {
+    let meta = &result.meta;
+    let price = meta.regular_market_price;
+    let currency = meta.currency.as_deref().unwrap_or_default();
+    let name = meta
+        .long_name
+        .as_deref()
+        .or(meta.short_name.as_deref())
+        .unwrap_or(&meta.symbol);
+
+    let mut desc = vec![name.to_string()];
+    if let Some(previous) = meta.chart_previous_close.filter(|x| *x != 0.0) {
+        let change = price - previous;
+        desc.push(format!(
+            "{change:+.2} ({:+.2}%) today",
+            change / previous * 100.0
+        ));
+    }
+    let closes: Vec<f64> = result
+        .indicators
+        .quote
+        .iter()
+        .flat_map(|x| x.close.iter().flatten().copied())
+        .collect();
+    if let Some(sparkline) = sparkline(&closes) {
+        desc.push(sparkline);
+    }
+
+    App {
+        ranking: 20,
+        open_command: AppCommand::Function(Function::CopyToClipboard(ClipBoardContentType::Text(
+            format_price(price),
+        ))),
+        desc: desc.join(" · "),
+        icons: None,
+        display_name: format!("{}: {} {currency}", meta.symbol, format_price(price))
+            .trim_end()
+            .to_string(),
+        search_name: String::new(),
+        version: None,
+        last_used: None,
+        children: None,
+    }
+}
+
+/// A price with 2 decimal places, or more for prices under 1 (which a lot of crypto has)
+fn format_price(price: f64) -> String {
+    if price.abs() < 1.0 {
+        format!("{price:.6}")
+    } else {
+        format!("{price:.2}")
+    }
+}
+
+/// The prices as bars that are as high as the price is between the lowest and highest one
+fn sparkline(prices: &[f64]) -> Option<String> {
+    if prices.len() < 2 {
+        return None;
+    }
+
+    // Each bar is the last price of its part of the day
+    let step = prices.len().div_ceil(SPARKLINE_LENGTH);
+    let prices: Vec<f64> = prices
+        .chunks(step)
+        .filter_map(|x| x.last().copied())
+        .collect();
+    let min = prices.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = prices.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let range = max - min;
+
+    Some(
+        prices
+            .iter()
+            .map(|x| {
+                let level = if range > 0.0 {
+                    ((x - min) / range * (SPARKLINE_BARS.len() - 1) as f64).round() as usize
+                } else {
+                    SPARKLINE_BARS.len() / 2
+                };
+                SPARKLINE_BARS[level.min(SPARKLINE_BARS.len() - 1)]
+            })
+            .collect(),
+    )
+}
@@ -0,0 +1,99 @@
+//! What's playing, for searches for `media`, `np` or `now playing`
+//!
+//! The track is asked from the platform's media player: Spotify or Music through AppleScript on
+//! macOS, MPRIS (through `playerctl`) on Linux, and the system media transport controls on
+//! Windows. The results are the track, and actions that play or pause it and skip to the next or
+//! previous track
+
+use crate::{
+    app::apps::{App, AppCommand},
+    commands::Function,
+    platform,
+};
+
+/// The track that is playing (or paused)
+#[derive(Debug, Clone, PartialEq)]
+pub struct NowPlaying {
+    pub title: String,
+    pub artist: String,
+    /// The app that's playing it
+    pub player: String,
+    pub playing: bool,
+}
+
+/// What the media player can be asked to do
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MediaAction {
+    PlayPause,
+    Next,
+    Previous,
+}
+
+/// Whether the search is for what's playing
+pub fn matching(query_lc: &str) -> bool {
+    matches!(query_lc.trim(), "media" | "np" | "now playing")
+}
+
+/// Ask the media player what's playing
+pub async fn now_playing() -> Option<NowPlaying> {
+    tokio::task::spawn_blocking(platform::now_playing)
+        .await
+        .ok()
+        .flatten()
+}
+
+/// The track, and the actions for it, or a result saying nothing is playing
+pub fn media_apps(now_playing: Option<NowPlaying>) -> Vec<App> {
+    let Some(track) = now_playing else {
+        return vec![app(
+            "Nothing is playing".to_string(),
+            "Media".to_string(),
+            AppCommand::Display,
+        )];
+    };
+
+    let state = if track.playing { "Playing" } else { "Paused" };
+    let title = if track.artist.is_empty() {
+        track.title.clone()
+    } else {
+        format!("{} — {}", track.title, track.artist)
+    };
+    let control = |action: MediaAction| AppCommand::Function(Function::MediaControl(action));
+
+    vec![
+        app(
+            title,
+            format!("{state} in {}", track.player),
+            control(MediaAction::PlayPause),
+        ),
+        app(
+            if track.playing { "Pause" } else { "Play" }.to_string(),
+            track.player.clone(),
+            control(MediaAction::PlayPause),
+        ),
+        app(
+            "Next Track".to_string(),
+            track.player.clone(),
+            control(MediaAction::Next),
+        ),
+        app(
+            "Previous Track".to_string(),
+            track.player,
+            control(MediaAction::Previous),
+        ),
+    ]
+}
+
+fn app(display_name: String, desc: String, open_command: AppCommand) -> App {
+    App {
+        ranking: 20,
+        open_command,
+        desc,
+        icons: None,
+        display_name,
+        search_name: String::new(),
+        version: None,
+        last_used: None,
+        children: None,
+    }
+}
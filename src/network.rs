@@ -0,0 +1,70 @@
+//! The IP addresses shown when searching for `ip`, which are copied when they are opened
+//!
+//! The local addresses are the ones that traffic to the internet would be sent from, and the
+//! public address is only looked up (from [`PUBLIC_IP_URL`]) when `lookup_public_ip` is turned on
+
+use std::net::{IpAddr, UdpSocket};
+
+use crate::{
+    app::apps::{App, AppCommand},
+    clipboard::ClipBoardContentType,
+    commands::Function,
+};
+
+/// Where the public IP address is looked up, which responds with just the address
+const PUBLIC_IP_URL: &str = "https://api.ipify.org";
+
+/// A result for the local IPv4 and IPv6 addresses, for the ones that this computer has
+pub fn local_ip_apps() -> Vec<App> {
+    [
+        ("0.0.0.0:0", "8.8.8.8:80"),
+        ("[::]:0", "[2001:4860:4860::8888]:80"),
+    ]
+    .into_iter()
+    .filter_map(|(bind, remote)| local_ip(bind, remote))
+    .map(|ip| ip_app(ip.to_string(), "Local IP Address"))
+    .collect()
+}
+
+/// The address that traffic to a remote address would be sent from
+///
+/// Connecting a UDP socket doesn't send anything, it only picks the route
+fn local_ip(bind: &str, remote: &str) -> Option<IpAddr> {
+    let socket = UdpSocket::bind(bind).ok()?;
+    socket.connect(remote).ok()?;
+    let ip = socket.local_addr().ok()?.ip();
+    (!ip.is_unspecified()).then_some(ip)
+}
+
+/// Look up the public IP address
+pub async fn public_ip() -> Result<String, String> {
+    tokio::task::spawn_blocking(|| {
+        let response = minreq::get(PUBLIC_IP_URL)
+            .with_header("User-Agent", "rustcast")
+            .with_timeout(5)
+            .send()
+            .map_err(|e| e.to_string())?;
+        response
+            .as_str()
+            .map(|x| x.trim().to_string())
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+pub fn ip_app(ip: String, desc: &str) -> App {
+    App {
+        ranking: 20,
+        open_command: AppCommand::Function(Function::CopyToClipboard(ClipBoardContentType::Text(
+            ip.clone(),
+        ))),
+        desc: desc.to_string(),
+        icons: None,
+        display_name: ip,
+        search_name: String::new(),
+        version: None,
+        last_used: None,
+        children: None,
+    }
+}
@@ -0,0 +1,120 @@
+//! Quick notes, captured by searching for `note <text>` and browsed by searching for `notes`
+//!
+//! Notes are added as timestamped list items to a markdown file, or to the Reminders app
+
+use std::{fs, io::Write, path::PathBuf};
+
+use crate::{
+    app::apps::{App, AppCommand},
+    clipboard::ClipBoardContentType,
+    commands::Function,
+    config::{NoteDestination, Notes},
+    platform,
+    utils::config_path,
+};
+
+/// How many of the most recent notes are shown when searching for `notes`
+const RECENT_NOTES: usize = 20;
+
+/// The markdown file that notes are added to
+fn notes_file(notes: &Notes) -> PathBuf {
+    match &notes.file {
+        Some(file) => {
+            let home = dirs::home_dir().unwrap_or(PathBuf::from("/"));
+            PathBuf::from(file.replacen("~", &home.to_string_lossy(), 1))
+        }
+        None => config_path("notes.md"),
+    }
+}
+
+/// Capture a note, adding it to the notes file or the Reminders app
+pub fn capture(notes: &Notes, text: &str) -> Result<(), String> {
+    if notes.destination == NoteDestination::Reminders {
+        return platform::add_reminder(text, notes.reminders_list.as_deref());
+    }
+
+    let file = notes_file(notes);
+    if let Some(parent) = file.parent() {
+        fs::create_dir_all(parent).ok();
+    }
+    let timestamp = jiff::Zoned::now().strftime("%Y-%m-%d %H:%M");
+    fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&file)
+        .and_then(|mut x| writeln!(x, "- {timestamp}: {text}"))
+        .map_err(|e| format!("Could not add the note to {}: {e}", file.display()))
+}
+
+/// The results for a `note <text>` search, which captures the text, or a `notes` search, which
+/// lists the most recent notes
+pub fn note_apps(notes: &Notes, query: &str) -> Vec<App> {
+    let query = query.trim();
+    let destination = match notes.destination {
+        NoteDestination::File => "Notes",
+        NoteDestination::Reminders => "Reminders",
+    };
+
+    if query.eq_ignore_ascii_case("notes") {
+        return match notes.destination {
+            NoteDestination::File => recent_notes(notes),
+            NoteDestination::Reminders => vec![App {
+                ranking: 20,
+                open_command: AppCommand::Function(Function::OpenApp(
+                    "/System/Applications/Reminders.app".to_string(),
+                )),
+                desc: "Notes are added to Reminders".to_string(),
+                icons: None,
+                display_name: "Open Reminders".to_string(),
+                search_name: String::new(),
+                version: None,
+                last_used: None,
+                children: None,
+            }],
+        };
+    }
+
+    let Some(text) = query
+        .split_once(' ')
+        .filter(|(keyword, text)| keyword.eq_ignore_ascii_case("note") && !text.trim().is_empty())
+        .map(|(_, text)| text.trim())
+    else {
+        return vec![];
+    };
+
+    vec![App {
+        ranking: 20,
+        open_command: AppCommand::Function(Function::AddNote(text.to_string())),
+        desc: destination.to_string(),
+        icons: None,
+        display_name: format!("Add note: {text}"),
+        search_name: String::new(),
+        version: None,
+        last_used: None,
+        children: None,
+    }]
+}
+
+/// The most recent notes in the notes file, newest first, which copy the note when opened
+fn recent_notes(notes: &Notes) -> Vec<App> {
+    let contents = fs::read_to_string(notes_file(notes)).unwrap_or_default();
+    contents
+        .lines()
+        .rev()
+        .filter_map(|line| line.strip_prefix("- ")?.split_once(": "))
+        .take(RECENT_NOTES)
+        .map(|(timestamp, text)| App {
+            ranking: 20,
+            open_command: AppCommand::Function(Function::CopyToClipboard(
+                ClipBoardContentType::Text(text.to_string()),
+            )),
+            desc: timestamp.to_string(),
+            icons: None,
+            display_name: text.to_string(),
+            search_name: String::new(),
+            version: None,
+            last_used: None,
+            children: None,
+        })
+        .collect()
+}
@@ -0,0 +1,104 @@
+//! System notifications, for timers, shell commands and anything else that finishes in the
+//! background and needs to tell the user
+//!
+//! This uses the notification centre on macOS, toasts on Windows and the desktop's notification
+//! daemon (through D-Bus) on Linux
+
+/// Show a system notification, playing the default notification sound with it if `sound`
+pub fn notify(title: &str, body: &str, sound: bool) -> Result<(), String> {
+    notify_native(title, body, sound).map_err(|e| format!("Could not show a notification: {e}"))
+}
+
+#[cfg(target_os = "macos")]
+fn notify_native(title: &str, body: &str, sound: bool) -> Result<(), String> {
+    use block2::RcBlock;
+    use objc2::runtime::Bool;
+    use objc2_foundation::{NSBundle, NSError, NSString};
+    use objc2_user_notifications::{
+        UNAuthorizationOptions, UNMutableNotificationContent, UNNotificationRequest,
+        UNNotificationSound, UNUserNotificationCenter,
+    };
+
+    // The notification centre only works for apps in a bundle, so running the binary on its own
+    // (like with `cargo run`) falls back to AppleScript
+    if NSBundle::mainBundle().bundleIdentifier().is_none() {
+        return notify_with_applescript(title, body, sound);
+    }
+
+    let center = UNUserNotificationCenter::currentNotificationCenter();
+    // This only asks the user the first time, and does nothing afterwards
+    center.requestAuthorizationWithOptions_completionHandler(
+        UNAuthorizationOptions::Alert | UNAuthorizationOptions::Sound,
+        &RcBlock::new(|_: Bool, _: *mut NSError| {}),
+    );
+
+    let content = UNMutableNotificationContent::new();
+    content.setTitle(&NSString::from_str(title));
+    content.setBody(&NSString::from_str(body));
+    if sound {
+        content.setSound(Some(&UNNotificationSound::defaultSound()));
+    }
+
+    let identifier = format!(
+        "rustcast-{}",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos()
+    );
+    let request = UNNotificationRequest::requestWithIdentifier_content_trigger(
+        &NSString::from_str(&identifier),
+        &content,
+        None,
+    );
+    center.addNotificationRequest_withCompletionHandler(&request, None);
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn notify_with_applescript(title: &str, body: &str, sound: bool) -> Result<(), String> {
+    use crate::platform::macos::applescript_string;
+
+    let mut script = format!(
+        "display notification {} with title {}",
+        applescript_string(body),
+        applescript_string(title)
+    );
+    if sound {
+        script += " sound name \"Glass\"";
+    }
+
+    std::process::Command::new("osascript")
+        .arg("-e")
+        .arg(script)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(target_os = "windows")]
+fn notify_native(title: &str, body: &str, sound: bool) -> Result<(), String> {
+    use tauri_winrt_notification::{Sound, Toast};
+
+    Toast::new(Toast::POWERSHELL_APP_ID)
+        .title(title)
+        .text1(body)
+        .sound(sound.then_some(Sound::Default))
+        .show()
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn notify_native(title: &str, body: &str, sound: bool) -> Result<(), String> {
+    let mut notification = notify_rust::Notification::new();
+    notification.appname("RustCast").summary(title).body(body);
+    if sound {
+        notification.sound_name("message-new-instant");
+    }
+    notification.show().map(|_| ()).map_err(|e| e.to_string())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn notify_native(_: &str, _: &str, _: bool) -> Result<(), String> {
+    Err("notifications aren't supported on this platform".to_string())
+}
@@ -0,0 +1,289 @@
+//! Searching package registries, for searches like `crate serde`, `npm react` or `brew ripgrep`
+//!
+//! crates.io and npm are searched, and Homebrew is asked for the formula (or cask) with the exact
+//! name, since its API has no search. Each package has results that open its docs (or homepage),
+//! copy the command that installs it, and copy the line that adds it as a dependency (or to a
+//! `Brewfile`). The registries are only asked once the search stops changing (see
+//! [`crate::providers::Lookup::debounce`])
+
+use serde::Deserialize;
+
+use crate::{
+    app::apps::{App, AppCommand},
+    clipboard::ClipBoardContentType,
+    commands::Function,
+    utils::percent_encode,
+};
+
+/// How many packages are shown from the registries that are searched
+const MAX_PACKAGES: usize = 4;
+
+/// The registries that can be searched
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Registry {
+    Crates,
+    Npm,
+    Brew,
+}
+
+/// A package from any of the registries
+struct Package {
+    name: String,
+    version: String,
+    description: String,
+    docs: String,
+    install: String,
+    dependency: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CratesResponse {
+    crates: Vec<Crate>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Crate {
+    name: String,
+    #[serde(default)]
+    max_stable_version: Option<String>,
+    max_version: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    documentation: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NpmResponse {
+    objects: Vec<NpmObject>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NpmObject {
+    package: NpmPackage,
+}
+
+#[derive(Debug, Deserialize)]
+struct NpmPackage {
+    name: String,
+    version: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    links: NpmLinks,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct NpmLinks {
+    #[serde(default)]
+    npm: Option<String>,
+    #[serde(default)]
+    homepage: Option<String>,
+}
+
+/// A Homebrew formula or cask, whose names and versions are in different fields (a cask's name is
+/// its token, and its `name` is a list of the app's names)
+#[derive(Debug, Deserialize)]
+struct BrewPackage {
+    #[serde(default)]
+    name: serde_json::Value,
+    #[serde(default)]
+    token: Option<String>,
+    #[serde(default)]
+    versions: Option<BrewVersions>,
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(default)]
+    desc: Option<String>,
+    #[serde(default)]
+    homepage: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BrewVersions {
+    #[serde(default)]
+    stable: Option<String>,
+}
+
+/// The registry and the package name of a package search, or `None` if the search isn't one
+pub fn matching(query: &str) -> Option<(Registry, String)> {
+    let (keyword, name) = query.trim().split_once(' ')?;
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+
+    let registry = match keyword.to_lowercase().as_str() {
+        "crate" => Registry::Crates,
+        "npm" => Registry::Npm,
+        "brew" => Registry::Brew,
+        _ => return None,
+    };
+    Some((registry, name.to_string()))
+}
+
+/// The actions for the packages that match the name
+pub async fn search(registry: Registry, name: String) -> Result<Vec<App>, String> {
+    tokio::task::spawn_blocking(move || {
+        let packages = match registry {
+            Registry::Crates => search_crates(&name)?,
+            Registry::Npm => search_npm(&name)?,
+            Registry::Brew => search_brew(&name)?,
+        };
+        Ok(packages.iter().flat_map(package_apps).collect())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+fn get(url: &str) -> Result<minreq::Response, String> {
+    minreq::get(url)
+        .with_header(
+            "User-Agent",
+            "rustcast (https://github.com/RustCastLabs/rustcast)",
+        )
+        .with_timeout(10)
+        .send()
+        .map_err(|e| e.to_string())
+}
+
+fn get_json<T: serde::de::DeserializeOwned>(url: &str) -> Result<T, String> {
+    let response = get(url)?;
+    if response.status_code != 200 {
+        return Err(format!(
+            "The registry responded with {} {}",
+            response.status_code, response.reason_phrase
+        ));
+    }
+    serde_json::from_slice(response.as_bytes()).map_err(|e| e.to_string())
+}
+
+fn search_crates(name: &str) -> Result<Vec<Package>, String> {
+    let response: CratesResponse = get_json(&format!(
+        "https://crates.io/api/v1/crates?q={}&per_page={MAX_PACKAGES}",
+        percent_encode(name)
+    ))?;
+
+    Ok(response
+        .crates
+        .into_iter()
+        .map(|x| {
+            let version = x.max_stable_version.unwrap_or(x.max_version);
+            Package {
+                docs: x
+                    .documentation
+                    .unwrap_or_else(|| format!("https://docs.rs/{}", x.name)),
+                install: format!("cargo add {}", x.name),
+                dependency: format!("{} = \"{version}\"", x.name),
+                description: x.description.unwrap_or_default(),
+                name: x.name,
+                version,
+            }
+        })
+        .collect())
+}
+
+fn search_npm(name: &str) -> Result<Vec<Package>, String> {
+    let response: NpmResponse = get_json(&format!(
+        "https://registry.npmjs.org/-/v1/search?text={}&size={MAX_PACKAGES}",
+        percent_encode(name)
+    ))?;
+
+    Ok(response
+        .objects
+        .into_iter()
+        .map(|x| {
+            let package = x.package;
+            Package {
+                docs: package
+                    .links
+                    .homepage
+                    .or(package.links.npm)
+                    .unwrap_or_else(|| format!("https://www.npmjs.com/package/{}", package.name)),
+                install: format!("npm install {}", package.name),
+                dependency: format!("\"{}\": \"^{}\"", package.name, package.version),
+                description: package.description.unwrap_or_default(),
+                name: package.name,
+                version: package.version,
+            }
+        })
+        .collect())
+}
+
+/// The formula with the name, or the cask if there isn't one
+fn search_brew(name: &str) -> Result<Vec<Package>, String> {
+    let name = name.to_lowercase();
+    for (kind, install, brewfile) in [
+        ("formula", "brew install", "brew"),
+        ("cask", "brew install --cask", "cask"),
+    ] {
+        let response = get(&format!(
+            "https://formulae.brew.sh/api/{kind}/{}.json",
+            percent_encode(&name)
+        ))?;
+        if response.status_code == 404 {
+            continue;
+        }
+        let package: BrewPackage = serde_json::from_slice(response.as_bytes())
+            .map_err(|_| format!("Homebrew responded with {}", response.status_code))?;
+
+        let name = package
+            .token
+            .or_else(|| package.name.as_str().map(str::to_string))
+            .unwrap_or(name);
+        return Ok(vec![Package {
+            docs: package
+                .homepage
+                .unwrap_or_else(|| format!("https://formulae.brew.sh/{kind}/{name}")),
+            install: format!("{install} {name}"),
+            dependency: format!("{brewfile} \"{name}\""),
+            version: package
+                .versions
+                .and_then(|x| x.stable)
+                .or(package.version)
+                .unwrap_or_default(),
+            description: package.desc.unwrap_or_default(),
+            name,
+        }]);
+    }
+    Err(format!("Homebrew has no formula or cask called {name}"))
+}
+
+/// The results that open a package's docs and copy its install command and dependency line
+fn package_apps(package: &Package) -> Vec<App> {
+    let desc = if package.description.is_empty() {
+        format!("{} {}", package.name, package.version)
+    } else {
+        format!(
+            "{} {} · {}",
+            package.name, package.version, package.description
+        )
+    };
+    let app = |display_name: String, function: Function| App {
+        ranking: 20,
+        open_command: AppCommand::Function(function),
+        desc: desc.clone(),
+        icons: None,
+        display_name,
+        search_name: String::new(),
+        version: None,
+        last_used: None,
+        children: None,
+    };
+    let copy = |text: &str| Function::CopyToClipboard(ClipBoardContentType::Text(text.to_string()));
+
+    vec![
+        app(
+            format!("Open the docs for {}", package.name),
+            Function::OpenWebsite(package.docs.clone()),
+        ),
+        app(
+            format!("Copy `{}`", package.install),
+            copy(&package.install),
+        ),
+        app(
+            format!("Copy `{}`", package.dependency),
+            copy(&package.dependency),
+        ),
+    ]
+}
@@ -0,0 +1,256 @@
+//! Copying logins from 1Password or Bitwarden, for searches that start with the passwords keyword
+//!
+//! The vault's items are listed with the password manager's CLI (`op` or `bw`), and the tile keeps
+//! their names (but nothing secret) for [`CACHE_SECONDS`], so typing part of a name only filters
+//! them. `bw` can't leave the secrets out of its list, so they are skipped while it is read and
+//! the list is zeroed straight after. The username, password or one-time password is only asked
+//! from the CLI when its result is opened, and is then copied, left out of the clipboard history,
+//! and cleared from the clipboard after `clear_after` seconds (see
+//! [`crate::app::Message::SecretRevealed`])
+
+use std::{process::Stdio, time::Duration};
+
+use serde::{Deserialize, de::IgnoredAny};
+
+use crate::{
+    app::{
+        Message,
+        apps::{App, AppCommand},
+    },
+    config::{PasswordManager, Passwords},
+    i18n::tr,
+};
+
+/// How long the items are kept before they are listed again
+pub const CACHE_SECONDS: u64 = 5 * 60;
+
+/// The key the items are kept under in the tile's lookup cache
+pub const CACHE_KEY: &str = "passwords:items";
+
+/// How long the CLI can take, which includes unlocking it (like with Touch ID for `op`)
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The parts of a login that can be copied
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SecretField {
+    Username,
+    Password,
+    OneTimePassword,
+}
+
+impl SecretField {
+    fn name(self) -> &'static str {
+        match self {
+            SecretField::Username => "username",
+            SecretField::Password => "password",
+            SecretField::OneTimePassword => "one-time password",
+        }
+    }
+
+    /// The name of the result that copies the field from an item
+    fn copy_label(self, title: &str) -> String {
+        let title = title.to_string();
+        match self {
+            SecretField::Username => tr!("copy-username", title = title),
+            SecretField::Password => tr!("copy-password", title = title),
+            SecretField::OneTimePassword => tr!("copy-one-time-password", title = title),
+        }
+    }
+}
+
+/// An item from `op item list --format json`
+#[derive(Debug, Deserialize)]
+struct OpItem {
+    id: String,
+    title: String,
+    #[serde(default)]
+    vault: Option<OpVault>,
+    /// The username, for logins
+    #[serde(default)]
+    additional_information: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpVault {
+    name: String,
+}
+
+/// An item from `bw list items`, leaving out everything secret, which serde skips over without
+/// copying it
+#[derive(Debug, Deserialize)]
+struct BwItem {
+    id: String,
+    name: String,
+    #[serde(default)]
+    login: Option<BwLogin>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BwLogin {
+    #[serde(default)]
+    username: Option<String>,
+    /// Whether there is a one-time password, without its secret
+    #[serde(default)]
+    totp: Option<IgnoredAny>,
+}
+
+/// The text after the keyword, if the search starts with it
+pub fn matching<'a>(settings: &Passwords, query: &'a str) -> Option<&'a str> {
+    let keyword = settings.keyword.trim();
+    let rest = query
+        .get(..keyword.len())
+        .filter(|x| !keyword.is_empty() && x.eq_ignore_ascii_case(keyword))
+        .map(|_| &query[keyword.len()..])?;
+
+    if rest.is_empty() {
+        Some(rest)
+    } else {
+        Some(rest.strip_prefix(' ')?.trim())
+    }
+}
+
+/// The actions for every item in the vault
+pub async fn list(settings: Passwords) -> Result<Vec<App>, String> {
+    let manager = settings.manager;
+    match manager {
+        PasswordManager::OnePassword => {
+            let output = run(manager, &["item", "list", "--format", "json"]).await?;
+            let items: Vec<OpItem> = serde_json::from_str(&output).map_err(|e| e.to_string())?;
+            Ok(items
+                .into_iter()
+                .flat_map(|item| {
+                    let mut desc = vec![manager.name().to_string()];
+                    desc.extend(item.vault.map(|x| x.name));
+                    desc.extend(item.additional_information.filter(|x| !x.is_empty()));
+                    item_apps(manager, &item.id, &item.title, &desc.join(" · "), true)
+                })
+                .collect())
+        }
+        PasswordManager::Bitwarden => {
+            let mut output = output(manager, &["list", "items"]).await?;
+            let items = serde_json::from_slice::<Vec<BwItem>>(&output);
+            // Without reading it afterwards, the compiler could leave out zeroing it before it
+            // is freed
+            output.fill(0);
+            std::hint::black_box(&output);
+            Ok(items
+                .map_err(|e| e.to_string())?
+                .into_iter()
+                .filter_map(|item| {
+                    let login = item.login?;
+                    let mut desc = vec![manager.name().to_string()];
+                    desc.extend(login.username.filter(|x| !x.is_empty()));
+                    Some(item_apps(
+                        manager,
+                        &item.id,
+                        &item.name,
+                        &desc.join(" · "),
+                        login.totp.is_some(),
+                    ))
+                })
+                .flatten()
+                .collect())
+        }
+    }
+}
+
+/// Ask the CLI for a part of an item
+pub async fn reveal(
+    manager: PasswordManager,
+    id: String,
+    field: SecretField,
+) -> Result<String, String> {
+    let args: Vec<&str> = match (manager, field) {
+        (PasswordManager::OnePassword, SecretField::Username) => {
+            vec!["item", "get", &id, "--fields", "label=username"]
+        }
+        (PasswordManager::OnePassword, SecretField::Password) => {
+            vec!["item", "get", &id, "--fields", "label=password", "--reveal"]
+        }
+        (PasswordManager::OnePassword, SecretField::OneTimePassword) => {
+            vec!["item", "get", &id, "--otp"]
+        }
+        (PasswordManager::Bitwarden, SecretField::Username) => vec!["get", "username", &id],
+        (PasswordManager::Bitwarden, SecretField::Password) => vec!["get", "password", &id],
+        (PasswordManager::Bitwarden, SecretField::OneTimePassword) => vec!["get", "totp", &id],
+    };
+    let secret = run(manager, &args).await?;
+    let secret = secret.trim_end_matches(['\r', '\n']);
+    if secret.is_empty() {
+        return Err(format!("The item has no {}", field.name()));
+    }
+    Ok(secret.to_string())
+}
+
+/// Clear the clipboard, if it still has the secret on it
+pub fn clear_clipboard(secret: &str) -> Result<(), String> {
+    let mut clipboard = arboard::Clipboard::new().map_err(|e| e.to_string())?;
+    if clipboard.get_text().is_ok_and(|x| x == secret) {
+        clipboard.clear().map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Run the password manager's CLI, giving what it printed
+async fn run(manager: PasswordManager, args: &[&str]) -> Result<String, String> {
+    output(manager, args)
+        .await
+        .map(|x| String::from_utf8_lossy(&x).to_string())
+}
+
+/// Run the password manager's CLI, giving the bytes it printed
+async fn output(manager: PasswordManager, args: &[&str]) -> Result<Vec<u8>, String> {
+    let command = manager.command();
+    let output = tokio::time::timeout(
+        TIMEOUT,
+        tokio::process::Command::new(command)
+            .args(args)
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .map_err(|_| format!("`{command}` took too long to respond"))?
+    .map_err(|e| format!("Could not run {command}: {e}"))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "`{command}` failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(output.stdout)
+}
+
+/// The results that copy an item's username, password and (if it can have one) one-time password
+fn item_apps(
+    manager: PasswordManager,
+    id: &str,
+    title: &str,
+    desc: &str,
+    has_totp: bool,
+) -> Vec<App> {
+    let mut fields = vec![SecretField::Username, SecretField::Password];
+    if has_totp {
+        fields.push(SecretField::OneTimePassword);
+    }
+
+    fields
+        .into_iter()
+        .map(|field| App {
+            ranking: 0,
+            open_command: AppCommand::Message(Message::RevealSecret(
+                manager,
+                id.to_string(),
+                field,
+            )),
+            desc: desc.to_string(),
+            icons: None,
+            display_name: field.copy_label(title),
+            search_name: String::new(),
+            version: None,
+            last_used: None,
+            children: None,
+        })
+        .collect()
+}
@@ -0,0 +1,84 @@
+//! Browsing the file system from the search bar, for searches that start with `/` or `~`
+//!
+//! The search is read as a path, and the entries of its folder that start with its last part are
+//! the results, folders first. Tab completes the search to the focused entry (going into it, for
+//! folders), → browses into a folder's entries, and opening an entry opens it with its default app
+
+use std::path::Path;
+
+use crate::{
+    app::apps::{App, AppCommand, Children},
+    commands::Function,
+};
+
+/// How many entries of a folder are shown
+const MAX_ENTRIES: usize = 100;
+
+/// Whether a search is a path, which is when it starts with `/` or `~`
+pub fn is_path(query: &str) -> bool {
+    query.starts_with('/') || query.starts_with('~')
+}
+
+/// The entries of the search's folder that start with the rest of the search
+///
+/// Hidden entries are only shown when the rest of the search starts with a `.`
+pub fn completions(query: &str) -> Vec<App> {
+    let home_dir = std::env::var("HOME").unwrap_or("/".to_string());
+    let (dir, prefix) = match query.rfind('/') {
+        Some(i) => (&query[..=i], &query[i + 1..]),
+        // Just `~`, which is the home folder's entries
+        None => ("~/", &query[1..]),
+    };
+    let expanded_dir = match dir.strip_prefix('~') {
+        Some(rest) => format!("{home_dir}{rest}"),
+        None => dir.to_string(),
+    };
+    let prefix_lc = prefix.to_lowercase();
+
+    let Ok(entries) = std::fs::read_dir(&expanded_dir) else {
+        return vec![];
+    };
+    let mut entries: Vec<(bool, String)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let shown = name.to_lowercase().starts_with(&prefix_lc)
+                && (!name.starts_with('.') || prefix.starts_with('.'));
+            // Symlinks to folders are gone into like folders
+            shown.then(|| (entry.path().is_dir(), name))
+        })
+        .collect();
+    entries.sort_by(|(left_dir, left), (right_dir, right)| {
+        right_dir
+            .cmp(left_dir)
+            .then_with(|| left.to_lowercase().cmp(&right.to_lowercase()))
+    });
+
+    entries
+        .into_iter()
+        .take(MAX_ENTRIES)
+        .map(|(is_dir, name)| {
+            let path = Path::new(&expanded_dir)
+                .join(&name)
+                .to_string_lossy()
+                .to_string();
+            let completion = if is_dir {
+                format!("{dir}{name}/")
+            } else {
+                format!("{dir}{name}")
+            };
+            App {
+                ranking: 0,
+                open_command: AppCommand::Function(Function::OpenApp(path.clone())),
+                desc: if is_dir { "Folder" } else { "File" }.to_string(),
+                icons: None,
+                display_name: if is_dir { format!("{name}/") } else { name },
+                // What Tab completes the search to
+                search_name: completion,
+                version: None,
+                last_used: None,
+                children: is_dir.then_some(Children::Folder(path)),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,116 @@
+//! Noticing when something is copied, on Linux, so that the clipboard is only read after it changed
+//!
+//! Under Wayland, `wl-paste --watch` (from wl-clipboard) runs a command each time the clipboard
+//! changes, and under X11 (or XWayland) the XFixes extension sends an event each time the
+//! clipboard gets a new owner. Either one counts the changes, on a thread of its own. When neither
+//! works (like under GNOME's Wayland, which `wl-paste --watch` doesn't support, without XWayland),
+//! there is no count, and the clipboard is read each time instead
+
+use std::{
+    io::{BufRead, BufReader},
+    process::{Command, Stdio},
+    sync::{
+        Once,
+        atomic::{AtomicBool, AtomicIsize, Ordering},
+    },
+};
+
+use log::warn;
+use x11rb::{
+    connection::Connection,
+    protocol::{
+        xfixes::{ConnectionExt as _, SelectionEventMask},
+        xproto::ConnectionExt as _,
+    },
+};
+
+/// How many times the clipboard changed
+static CHANGES: AtomicIsize = AtomicIsize::new(0);
+
+/// Whether the changes are being counted, which stops once neither way of watching works
+static WATCHING: AtomicBool = AtomicBool::new(true);
+
+/// A number that changes whenever something is copied, or `None` if the clipboard can't be watched
+pub fn change_count() -> Option<isize> {
+    static START: Once = Once::new();
+    START.call_once(|| {
+        std::thread::spawn(watch);
+    });
+
+    WATCHING
+        .load(Ordering::Relaxed)
+        .then(|| CHANGES.load(Ordering::Relaxed))
+}
+
+/// Count the changes with `wl-paste`, or XFixes once that stops working, until neither works
+fn watch() {
+    if std::env::var_os("WAYLAND_DISPLAY").is_some()
+        && let Err(e) = watch_wayland()
+    {
+        warn!("Couldn't watch the clipboard with wl-paste: {e}");
+    }
+    if std::env::var_os("DISPLAY").is_some()
+        && let Err(e) = watch_x11()
+    {
+        warn!("Couldn't watch the clipboard with XFixes: {e}");
+    }
+
+    warn!("The clipboard can't be watched, so it is read each time instead");
+    WATCHING.store(false, Ordering::Relaxed);
+}
+
+/// Count a change for each line that `wl-paste --watch echo` prints, which is once for each time
+/// something is copied
+fn watch_wayland() -> Result<(), String> {
+    let mut child = Command::new("wl-paste")
+        .args(["--watch", "echo"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Could not run wl-paste: {e}"))?;
+
+    let stdout = child.stdout.take().ok_or("wl-paste has no output")?;
+    for line in BufReader::new(stdout).lines() {
+        line.map_err(|e| e.to_string())?;
+        CHANGES.fetch_add(1, Ordering::Relaxed);
+    }
+
+    let status = child.wait().map_err(|e| e.to_string())?;
+    Err(format!("`wl-paste --watch` stopped ({status})"))
+}
+
+/// Count a change for each time the clipboard gets a new owner, or its owner goes away
+fn watch_x11() -> Result<(), String> {
+    let (connection, screen) = x11rb::connect(None).map_err(|e| e.to_string())?;
+    let root = connection.setup().roots[screen].root;
+    // XFixes has to be told which version is used before any of its other requests
+    connection
+        .xfixes_query_version(5, 0)
+        .map_err(|e| e.to_string())?
+        .reply()
+        .map_err(|e| e.to_string())?;
+    let clipboard = connection
+        .intern_atom(false, b"CLIPBOARD")
+        .map_err(|e| e.to_string())?
+        .reply()
+        .map_err(|e| e.to_string())?
+        .atom;
+    connection
+        .xfixes_select_selection_input(
+            root,
+            clipboard,
+            SelectionEventMask::SET_SELECTION_OWNER
+                | SelectionEventMask::SELECTION_WINDOW_DESTROY
+                | SelectionEventMask::SELECTION_CLIENT_CLOSE,
+        )
+        .map_err(|e| e.to_string())?;
+    connection.flush().map_err(|e| e.to_string())?;
+
+    // What was copied before the watching started is read again
+    CHANGES.fetch_add(1, Ordering::Relaxed);
+    loop {
+        connection.wait_for_event().map_err(|e| e.to_string())?;
+        CHANGES.fetch_add(1, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,192 @@
+//! Talking to Hyprland and Sway through their IPC (with `hyprctl` and `swaymsg`), on Linux
+//!
+//! Wayland doesn't let windows place or focus themselves, so when rustcast runs under one of these
+//! compositors (and `compositor_ipc` is on), it asks the compositor which output is focused,
+//! adds rules that make its window float in the middle of it, and has the compositor focus the
+//! window that had the focus before rustcast's window opened once it hides
+
+use std::process::Command;
+
+use iced::Rectangle;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// The app id (or class) of rustcast's window, which the window rules match
+pub const APP_ID: &str = "rustcast";
+
+/// A compositor that rustcast can talk to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Compositor {
+    Hyprland,
+    Sway,
+}
+
+/// An output from `hyprctl -j monitors`
+#[derive(Debug, Deserialize)]
+struct HyprlandMonitor {
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    scale: f32,
+    #[serde(default)]
+    transform: u8,
+    focused: bool,
+}
+
+/// An output from `swaymsg -t get_outputs`
+#[derive(Debug, Deserialize)]
+struct SwayOutput {
+    #[serde(default)]
+    focused: bool,
+    rect: SwayRect,
+}
+
+#[derive(Debug, Deserialize)]
+struct SwayRect {
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+}
+
+impl Compositor {
+    /// The compositor that rustcast runs under, from the variables it sets for the apps it starts
+    pub fn detect() -> Option<Compositor> {
+        if std::env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_some() {
+            Some(Compositor::Hyprland)
+        } else if std::env::var_os("SWAYSOCK").is_some() {
+            Some(Compositor::Sway)
+        } else {
+            None
+        }
+    }
+
+    /// The frame of the focused output, in logical coordinates
+    pub fn focused_output(self) -> Option<Rectangle> {
+        match self {
+            Compositor::Hyprland => {
+                let monitors: Vec<HyprlandMonitor> =
+                    serde_json::from_str(&run("hyprctl", &["-j", "monitors"]).ok()?).ok()?;
+                let monitor = monitors.into_iter().find(|x| x.focused)?;
+                // Odd transforms turn the monitor on its side
+                let (width, height) = if monitor.transform % 2 == 1 {
+                    (monitor.height, monitor.width)
+                } else {
+                    (monitor.width, monitor.height)
+                };
+                let scale = if monitor.scale > 0. {
+                    monitor.scale
+                } else {
+                    1.
+                };
+                Some(Rectangle {
+                    x: monitor.x,
+                    y: monitor.y,
+                    width: width / scale,
+                    height: height / scale,
+                })
+            }
+            Compositor::Sway => {
+                let outputs: Vec<SwayOutput> =
+                    serde_json::from_str(&run("swaymsg", &["-r", "-t", "get_outputs"]).ok()?)
+                        .ok()?;
+                let rect = outputs.into_iter().find(|x| x.focused)?.rect;
+                Some(Rectangle {
+                    x: rect.x,
+                    y: rect.y,
+                    width: rect.width,
+                    height: rect.height,
+                })
+            }
+        }
+    }
+
+    /// Add the rules that make rustcast's window float in the middle of the focused output,
+    /// instead of being tiled
+    pub fn add_window_rules(self) -> Result<(), String> {
+        match self {
+            Compositor::Hyprland => {
+                for rule in ["float", "center", "pin"] {
+                    let rule = format!("{rule},class:^({APP_ID})$");
+                    run("hyprctl", &["keyword", "windowrulev2", &rule])?;
+                }
+            }
+            Compositor::Sway => {
+                let rule = format!(
+                    "for_window [app_id=\"^{APP_ID}$\"] floating enable, sticky enable, \
+                     move position center"
+                );
+                run("swaymsg", &[&rule])?;
+            }
+        }
+        Ok(())
+    }
+
+    /// An id for the focused window (unless it is rustcast's), which
+    /// [`Compositor::focus_window`] takes
+    pub fn focused_window(self) -> Option<String> {
+        match self {
+            Compositor::Hyprland => {
+                let window: Value =
+                    serde_json::from_str(&run("hyprctl", &["-j", "activewindow"]).ok()?).ok()?;
+                if window["class"].as_str() == Some(APP_ID) {
+                    return None;
+                }
+                window["address"].as_str().map(str::to_string)
+            }
+            Compositor::Sway => {
+                let tree: Value =
+                    serde_json::from_str(&run("swaymsg", &["-r", "-t", "get_tree"]).ok()?).ok()?;
+                let node = focused_node(&tree)?;
+                if node["app_id"].as_str() == Some(APP_ID) {
+                    return None;
+                }
+                node["id"].as_i64().map(|x| x.to_string())
+            }
+        }
+    }
+
+    /// Focus a window, by the id from [`Compositor::focused_window`]
+    pub fn focus_window(self, id: &str) -> Result<(), String> {
+        match self {
+            Compositor::Hyprland => {
+                run(
+                    "hyprctl",
+                    &["dispatch", "focuswindow", &format!("address:{id}")],
+                )?;
+            }
+            Compositor::Sway => {
+                run("swaymsg", &[&format!("[con_id={id}] focus")])?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The focused node in Sway's tree, which is the focused window (or an empty workspace)
+fn focused_node(node: &Value) -> Option<&Value> {
+    if node["focused"].as_bool() == Some(true) {
+        return Some(node);
+    }
+    ["nodes", "floating_nodes"]
+        .into_iter()
+        .filter_map(|key| node[key].as_array())
+        .flatten()
+        .find_map(focused_node)
+}
+
+/// Run `hyprctl` or `swaymsg`, giving what it printed
+fn run(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Could not run {program}: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "`{program}` failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
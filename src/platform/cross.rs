@@ -1,7 +1,6 @@
 use std::{
     fs,
     path::{Path, PathBuf},
-    process::exit,
 };
 
 use log::{error, info};
@@ -38,50 +37,54 @@ pub(crate) fn get_installed_apps(store_icons: bool) -> Vec<App> {
 /// Is a fallback from the method in [`crate::platform::macos::discovery::get_installed_apps`]
 ///
 /// the directories are defined in [`crate::app::tile::elm::new`]
+///
+/// Directories and entries that can't be read are logged and skipped
 fn discover_apps(
     dir: impl AsRef<Path>,
     store_icons: bool,
 ) -> impl IntoParallelIterator<Item = App> {
     info!("Indexing apps started");
-    let entries: Vec<_> = fs::read_dir(dir.as_ref())
-        .unwrap_or_else(|x| {
+    let entries: Vec<_> = match fs::read_dir(dir.as_ref()) {
+        Ok(entries) => entries.filter_map(|x| x.ok()).collect(),
+        Err(x) => {
             error!(
                 "Could not read directry: {} because of:\n{}",
                 dir.as_ref().to_string_lossy(),
                 x
             );
-            exit(1)
-        })
-        .filter_map(|x| x.ok())
-        .collect();
+            vec![]
+        }
+    };
 
     entries.into_par_iter().filter_map(move |x| {
-        let file_type = x.file_type().unwrap_or_else(|e| {
-            error!("Unable to map entries: {}", &e.to_string());
-            exit(1)
-        });
+        let file_type = x
+            .file_type()
+            .inspect_err(|e| error!("Unable to map entries: {}", &e.to_string()))
+            .ok()?;
         if !file_type.is_dir() {
             return None;
         }
 
         let file_name_os = x.file_name();
-        let file_name = file_name_os.into_string().unwrap_or_else(|e| {
-            error!(
-                "Unable to get file name due to: {}",
-                e.to_str().unwrap_or("")
-            );
-            exit(1)
-        });
+        let file_name = file_name_os
+            .into_string()
+            .inspect_err(|e| {
+                error!(
+                    "Unable to get file name due to: {}",
+                    e.to_str().unwrap_or("")
+                )
+            })
+            .ok()?;
 
         if !file_name.ends_with(".app") {
             return None;
         }
 
         let path = x.path();
-        let path_str = path.to_str().map(|x| x.to_string()).unwrap_or_else(|| {
+        let Some(path_str) = path.to_str().map(|x| x.to_string()) else {
             error!("Unable to get file_name");
-            exit(1)
-        });
+            return None;
+        };
 
         let icons = if store_icons {
             match fs::read_to_string(format!("{}/Contents/Info.plist", path_str)).map(|content| {
@@ -152,6 +155,10 @@ fn discover_apps(
             None
         };
 
+        let version = fs::read_to_string(format!("{}/Contents/Info.plist", path_str))
+            .ok()
+            .and_then(|content| plist_string(&content, "CFBundleShortVersionString"));
+
         let name = file_name.strip_suffix(".app").unwrap().to_string();
         Some(App {
             ranking: 0,
@@ -160,6 +167,1871 @@ fn discover_apps(
             icons,
             search_name: name.to_lowercase(),
             display_name: name,
+            version,
+            last_used: None,
+            children: None,
         })
     })
 }
+
+/// The string value of a key in an `Info.plist`, which is on the line after the key
+fn plist_string(plist: &str, key: &str) -> Option<String> {
+    let key = format!("<key>{key}</key>");
+    let mut lines = plist.lines().map(str::trim);
+    lines.find(|x| *x == key)?;
+    let value = lines
+        .next()?
+        .strip_prefix("<string>")?
+        .strip_suffix("</string>")?;
+    Some(value.to_string())
+}
+
+/// Watches directories with change notifications on Windows, and inotify on Linux
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn watch_dirs(dirs: &[String], mut on_change: impl FnMut()) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::ffi::OsStrExt;
+
+        #[link(name = "kernel32")]
+        unsafe extern "system" {
+            fn FindFirstChangeNotificationW(path: *const u16, subtree: i32, filter: u32) -> isize;
+            fn FindNextChangeNotification(handle: isize) -> i32;
+            fn FindCloseChangeNotification(handle: isize) -> i32;
+            fn WaitForMultipleObjects(
+                count: u32,
+                handles: *const isize,
+                wait_all: i32,
+                milliseconds: u32,
+            ) -> u32;
+        }
+        const FILE_NOTIFY_CHANGE_DIR_NAME: u32 = 0x2;
+        const INFINITE: u32 = 0xFFFFFFFF;
+        const INVALID_HANDLE_VALUE: isize = -1;
+
+        let handles: Vec<isize> = dirs
+            .iter()
+            .map(|dir| {
+                let path: Vec<u16> = std::ffi::OsStr::new(dir)
+                    .encode_wide()
+                    .chain(Some(0))
+                    .collect();
+                unsafe {
+                    FindFirstChangeNotificationW(path.as_ptr(), 0, FILE_NOTIFY_CHANGE_DIR_NAME)
+                }
+            })
+            .filter(|handle| *handle != INVALID_HANDLE_VALUE)
+            .collect();
+        if handles.is_empty() {
+            return Err("none of the directories could be watched".to_string());
+        }
+
+        let result = loop {
+            let signalled = unsafe {
+                WaitForMultipleObjects(handles.len() as u32, handles.as_ptr(), 0, INFINITE)
+            };
+            // This is the index of the handle that was signalled, or an error past the handles
+            let Some(handle) = handles.get(signalled as usize) else {
+                break Err(std::io::Error::last_os_error().to_string());
+            };
+            on_change();
+            if unsafe { FindNextChangeNotification(*handle) } == 0 {
+                break Err(std::io::Error::last_os_error().to_string());
+            }
+        };
+
+        for handle in handles {
+            unsafe { FindCloseChangeNotification(handle) };
+        }
+        result
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        use std::ffi::CString;
+
+        let fd = unsafe { libc::inotify_init1(libc::IN_CLOEXEC) };
+        if fd < 0 {
+            return Err(std::io::Error::last_os_error().to_string());
+        }
+
+        let watched = dirs
+            .iter()
+            .filter_map(|dir| CString::new(dir.as_str()).ok())
+            .filter(|dir| {
+                let mask =
+                    libc::IN_CREATE | libc::IN_DELETE | libc::IN_MOVED_FROM | libc::IN_MOVED_TO;
+                unsafe { libc::inotify_add_watch(fd, dir.as_ptr(), mask) >= 0 }
+            })
+            .count();
+        if watched == 0 {
+            unsafe { libc::close(fd) };
+            return Err("none of the directories could be watched".to_string());
+        }
+
+        let mut buffer = [0u8; 4096];
+        let result = loop {
+            let read = unsafe { libc::read(fd, buffer.as_mut_ptr().cast(), buffer.len()) };
+            if read > 0 {
+                on_change();
+            } else {
+                let error = std::io::Error::last_os_error();
+                if error.kind() != std::io::ErrorKind::Interrupted {
+                    break Err(error.to_string());
+                }
+            }
+        };
+
+        unsafe { libc::close(fd) };
+        result
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    {
+        let _ = (dirs, &mut on_change);
+        Err("watching directories isn't supported on this platform".to_string())
+    }
+}
+
+/// Keep the window out of screen captures with `SetWindowDisplayAffinity` on Windows, which other
+/// platforms don't have a way to do
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn set_hidden_from_capture(handle: &iced::wgpu::rwh::WindowHandle, hidden: bool) {
+    #[cfg(target_os = "windows")]
+    {
+        use iced::wgpu::rwh::RawWindowHandle;
+
+        #[link(name = "user32")]
+        unsafe extern "system" {
+            fn SetWindowDisplayAffinity(hwnd: isize, affinity: u32) -> i32;
+        }
+        const WDA_NONE: u32 = 0x00;
+        const WDA_EXCLUDEFROMCAPTURE: u32 = 0x11;
+
+        if let RawWindowHandle::Win32(handle) = handle.as_raw() {
+            let affinity = if hidden {
+                WDA_EXCLUDEFROMCAPTURE
+            } else {
+                WDA_NONE
+            };
+            if unsafe { SetWindowDisplayAffinity(handle.hwnd.get(), affinity) } == 0 {
+                log::warn!("Could not change whether the window is hidden from screen capture");
+            }
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    let _ = (handle, hidden);
+}
+
+/// A number that changes whenever something is copied, from `GetClipboardSequenceNumber`
+#[cfg(target_os = "windows")]
+pub(crate) fn clipboard_change_count() -> isize {
+    #[link(name = "user32")]
+    unsafe extern "system" {
+        fn GetClipboardSequenceNumber() -> u32;
+    }
+
+    unsafe { GetClipboardSequenceNumber() as isize }
+}
+
+/// Put the system backdrop behind the window on Windows 11, which shows through the background
+/// where it isn't opaque
+///
+/// Linux has no common way to blur behind a window, so it only has the background opacity
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn set_blur(
+    handle: &iced::wgpu::rwh::WindowHandle,
+    blur: bool,
+    backdrop: crate::config::WindowsBackdrop,
+) {
+    #[cfg(target_os = "windows")]
+    {
+        use crate::config::WindowsBackdrop;
+        use iced::wgpu::rwh::RawWindowHandle;
+
+        #[repr(C)]
+        struct Margins {
+            left: i32,
+            right: i32,
+            top: i32,
+            bottom: i32,
+        }
+
+        #[link(name = "dwmapi")]
+        unsafe extern "system" {
+            fn DwmSetWindowAttribute(
+                hwnd: isize,
+                attribute: u32,
+                value: *const core::ffi::c_void,
+                size: u32,
+            ) -> i32;
+            fn DwmExtendFrameIntoClientArea(hwnd: isize, margins: *const Margins) -> i32;
+        }
+        const DWMWA_SYSTEMBACKDROP_TYPE: u32 = 38;
+        const DWMSBT_NONE: u32 = 1;
+        const DWMSBT_MAINWINDOW: u32 = 2;
+        const DWMSBT_TRANSIENTWINDOW: u32 = 3;
+
+        if let RawWindowHandle::Win32(handle) = handle.as_raw() {
+            let hwnd = handle.hwnd.get();
+            let backdrop_type = match (blur, backdrop) {
+                (false, _) => DWMSBT_NONE,
+                (true, WindowsBackdrop::Mica) => DWMSBT_MAINWINDOW,
+                (true, WindowsBackdrop::Acrylic) => DWMSBT_TRANSIENTWINDOW,
+            };
+            // The backdrop is drawn in the frame, so the frame has to cover the whole window
+            let margins = Margins {
+                left: -1,
+                right: -1,
+                top: -1,
+                bottom: -1,
+            };
+            let result = unsafe {
+                DwmExtendFrameIntoClientArea(hwnd, &margins);
+                DwmSetWindowAttribute(
+                    hwnd,
+                    DWMWA_SYSTEMBACKDROP_TYPE,
+                    (&backdrop_type as *const u32).cast(),
+                    size_of::<u32>() as u32,
+                )
+            };
+            if result != 0 {
+                log::warn!("Could not set the window backdrop, which needs Windows 11");
+            }
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    let _ = (handle, blur, backdrop);
+}
+
+/// Have Orca read out some text on Linux, through speech-dispatcher (which Orca speaks with),
+/// when it is running
+///
+/// Windows' screen readers only read out UI Automation notifications, which rustcast's window
+/// doesn't have a provider for, so nothing is read out there
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn announce(text: &str) {
+    #[cfg(target_os = "linux")]
+    {
+        let text = text.to_string();
+        // The thread waits for spd-say, so that it doesn't linger once it is done
+        std::thread::spawn(move || {
+            let orca_running = fs::read_dir("/proc")
+                .into_iter()
+                .flatten()
+                .flatten()
+                .any(|x| {
+                    fs::read_to_string(x.path().join("comm")).is_ok_and(|x| x.trim() == "orca")
+                });
+            if !orca_running {
+                return;
+            }
+            // Cancelling the last announcement means moving quickly through the results only reads
+            // out the one that ends up focused
+            if let Err(e) = std::process::Command::new("spd-say")
+                .args(["--cancel", "--", &text])
+                .status()
+            {
+                error!("Failed to read out the text with spd-say: {e}");
+            }
+        });
+    }
+    #[cfg(not(target_os = "linux"))]
+    let _ = text;
+}
+
+/// Press Ctrl+V, with `SendInput` on Windows, and with wtype under Wayland or xdotool under X11 on
+/// Linux
+///
+/// wtype needs a compositor with the virtual keyboard protocol (like Hyprland or Sway), so pasting
+/// fails under GNOME's and KDE's Wayland
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn paste() -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        #[repr(C)]
+        #[derive(Clone, Copy)]
+        struct KeyboardInput {
+            key: u16,
+            scan: u16,
+            flags: u32,
+            time: u32,
+            extra_info: usize,
+        }
+        // The largest of the kinds of input, which sets the size of `INPUT`
+        #[repr(C)]
+        #[derive(Clone, Copy)]
+        struct MouseInput {
+            dx: i32,
+            dy: i32,
+            data: u32,
+            flags: u32,
+            time: u32,
+            extra_info: usize,
+        }
+        #[repr(C)]
+        union InputData {
+            keyboard: KeyboardInput,
+            mouse: MouseInput,
+        }
+        #[repr(C)]
+        struct Input {
+            kind: u32,
+            data: InputData,
+        }
+
+        #[link(name = "user32")]
+        unsafe extern "system" {
+            fn SendInput(count: u32, inputs: *const Input, size: i32) -> u32;
+        }
+        const INPUT_KEYBOARD: u32 = 1;
+        const KEYEVENTF_KEYUP: u32 = 0x2;
+        const VK_CONTROL: u16 = 0x11;
+        const VK_V: u16 = 0x56;
+
+        let key = |key, up: bool| Input {
+            kind: INPUT_KEYBOARD,
+            data: InputData {
+                keyboard: KeyboardInput {
+                    key,
+                    scan: 0,
+                    flags: if up { KEYEVENTF_KEYUP } else { 0 },
+                    time: 0,
+                    extra_info: 0,
+                },
+            },
+        };
+        let inputs = [
+            key(VK_CONTROL, false),
+            key(VK_V, false),
+            key(VK_V, true),
+            key(VK_CONTROL, true),
+        ];
+        let sent = unsafe {
+            SendInput(
+                inputs.len() as u32,
+                inputs.as_ptr(),
+                std::mem::size_of::<Input>() as i32,
+            )
+        };
+        // Windows blocks input to apps that run as administrator, unless rustcast does too
+        if sent != inputs.len() as u32 {
+            return Err(format!(
+                "Windows blocked the paste keystroke ({})",
+                std::io::Error::last_os_error()
+            ));
+        }
+        Ok(())
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let (program, args): (&str, &[&str]) = if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+            ("wtype", &["-M", "ctrl", "v", "-m", "ctrl"])
+        } else {
+            ("xdotool", &["key", "--clearmodifiers", "ctrl+v"])
+        };
+        let status = std::process::Command::new(program)
+            .args(args)
+            .status()
+            .map_err(|e| format!("Could not run {program}: {e}"))?;
+        if !status.success() {
+            return Err(format!("`{program}` could not paste ({status})"));
+        }
+        Ok(())
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    Err("Pasting into other apps isn't supported on this platform".to_string())
+}
+
+/// Find the monitor to open on using the compositor's focused output (with Hyprland or Sway, when
+/// `compositor_ipc` is on), or xrandr, and xdotool for the cursor and active window
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn monitor_frame(config: &crate::config::WindowConfig) -> Option<iced::Rectangle> {
+    use crate::config::OpenOn;
+    use std::process::Command;
+
+    let open_on = config.open_on;
+    #[cfg(target_os = "linux")]
+    if config.compositor_ipc
+        && open_on != OpenOn::Primary
+        && let Some(frame) =
+            super::compositor::Compositor::detect().and_then(|x| x.focused_output())
+    {
+        return Some(frame);
+    }
+
+    let run = |program: &str, args: &[&str]| {
+        Command::new(program)
+            .args(args)
+            .output()
+            .ok()
+            .map(|x| String::from_utf8_lossy(&x.stdout).to_string())
+    };
+
+    // Lines look like ` 0: +*eDP-1 1920/344x1080/193+0+0  eDP-1`, where `*` marks the primary
+    let monitors: Vec<(bool, iced::Rectangle)> = run("xrandr", &["--listactivemonitors"])?
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace().skip(1);
+            let primary = fields.next()?.contains('*');
+            let (size, offset) = fields.next()?.split_once('+')?;
+            let (width, height) = size.split_once('x')?;
+            let (x, y) = offset.split_once('+')?;
+            let parse = |x: &str| x.split('/').next()?.parse::<f32>().ok();
+            Some((
+                primary,
+                iced::Rectangle {
+                    x: parse(x)?,
+                    y: parse(y)?,
+                    width: parse(width)?,
+                    height: parse(height)?,
+                },
+            ))
+        })
+        .collect();
+
+    let point = match open_on {
+        OpenOn::Primary => {
+            return monitors
+                .iter()
+                .find(|(primary, _)| *primary)
+                .or(monitors.first())
+                .map(|(_, frame)| *frame);
+        }
+        OpenOn::CursorMonitor => run("xdotool", &["getmouselocation", "--shell"])?,
+        OpenOn::ActiveWindowMonitor => run(
+            "xdotool",
+            &["getactivewindow", "getwindowgeometry", "--shell"],
+        )?,
+    };
+
+    let value = |key: &str| {
+        point
+            .lines()
+            .find_map(|line| line.strip_prefix(key)?.strip_prefix('='))?
+            .parse::<f32>()
+            .ok()
+    };
+    let point = iced::Point::new(value("X")?, value("Y")?);
+
+    monitors
+        .into_iter()
+        .map(|(_, frame)| frame)
+        .find(|frame| frame.contains(point))
+}
+
+/// The window that had the focus before rustcast's window opened
+#[cfg(not(target_os = "macos"))]
+#[derive(Debug, Clone, PartialEq)]
+pub enum PreviousFocus {
+    /// A window of Hyprland or Sway, by the id the compositor gave it
+    #[cfg(target_os = "linux")]
+    Compositor(super::compositor::Compositor, String),
+    /// An X11 window, by its id
+    #[cfg(target_os = "linux")]
+    X11(u32),
+}
+
+/// Add the rules that make the compositor float rustcast's window
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn add_window_rules(config: &crate::config::WindowConfig) {
+    #[cfg(target_os = "linux")]
+    if config.compositor_ipc
+        && let Some(compositor) = super::compositor::Compositor::detect()
+        && let Err(e) = compositor.add_window_rules()
+    {
+        error!("Failed to add the window rules for {compositor:?}: {e}");
+    }
+    let _ = config;
+}
+
+/// Ask the compositor which window is focused, or the X server (with `_NET_ACTIVE_WINDOW`) when
+/// running on X11
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn previous_focus(config: &crate::config::WindowConfig) -> Option<PreviousFocus> {
+    #[cfg(target_os = "linux")]
+    {
+        if config.compositor_ipc
+            && let Some(compositor) = super::compositor::Compositor::detect()
+        {
+            return compositor
+                .focused_window()
+                .map(|id| PreviousFocus::Compositor(compositor, id));
+        }
+        // Other Wayland compositors only tell XWayland about the X11 windows, so the active
+        // window there may be any window but the focused one
+        if std::env::var_os("WAYLAND_DISPLAY").is_none() {
+            return x11_active_window()
+                .inspect_err(|e| error!("Failed to find the active X11 window: {e}"))
+                .ok()
+                .flatten()
+                .map(PreviousFocus::X11);
+        }
+    }
+    let _ = config;
+    None
+}
+
+/// The X11 window in the root window's `_NET_ACTIVE_WINDOW` (from EWMH), unless there is none or
+/// it is rustcast's
+#[cfg(target_os = "linux")]
+fn x11_active_window() -> Result<Option<u32>, String> {
+    use x11rb::{
+        connection::Connection,
+        protocol::xproto::{AtomEnum, ConnectionExt as _},
+    };
+
+    let (connection, screen) = x11rb::connect(None).map_err(|e| e.to_string())?;
+    let root = connection.setup().roots[screen].root;
+    let active_window = connection
+        .intern_atom(false, b"_NET_ACTIVE_WINDOW")
+        .map_err(|e| e.to_string())?
+        .reply()
+        .map_err(|e| e.to_string())?
+        .atom;
+
+    let Some(id) = connection
+        .get_property(false, root, active_window, AtomEnum::WINDOW, 0, 1)
+        .map_err(|e| e.to_string())?
+        .reply()
+        .map_err(|e| e.to_string())?
+        .value32()
+        .and_then(|mut x| x.next())
+        .filter(|x| *x != 0)
+    else {
+        return Ok(None);
+    };
+
+    // The class is the instance name and the class name, each ending with a nul
+    let class = connection
+        .get_property(false, id, AtomEnum::WM_CLASS, AtomEnum::STRING, 0, 256)
+        .map_err(|e| e.to_string())?
+        .reply()
+        .map_err(|e| e.to_string())?
+        .value;
+    if class
+        .split(|x| *x == 0)
+        .any(|x| x == super::compositor::APP_ID.as_bytes())
+    {
+        return Ok(None);
+    }
+    Ok(Some(id))
+}
+
+/// Ask the window manager to activate an X11 window, with the `_NET_ACTIVE_WINDOW` message from
+/// EWMH (which is what `xdotool windowactivate` sends)
+#[cfg(target_os = "linux")]
+fn x11_activate(id: u32) -> Result<(), String> {
+    use x11rb::{
+        connection::Connection,
+        protocol::xproto::{ClientMessageEvent, ConnectionExt as _, EventMask},
+    };
+
+    let (connection, screen) = x11rb::connect(None).map_err(|e| e.to_string())?;
+    let root = connection.setup().roots[screen].root;
+    let active_window = connection
+        .intern_atom(false, b"_NET_ACTIVE_WINDOW")
+        .map_err(|e| e.to_string())?
+        .reply()
+        .map_err(|e| e.to_string())?
+        .atom;
+
+    // The source is 2 (a pager), since window managers ignore applications that ask for the
+    // focus without a recent user action, and 0 is the current time
+    let message = ClientMessageEvent::new(32, id, active_window, [2, 0, 0, 0, 0]);
+    connection
+        .send_event(
+            false,
+            root,
+            EventMask::SUBSTRUCTURE_REDIRECT | EventMask::SUBSTRUCTURE_NOTIFY,
+            message,
+        )
+        .map_err(|e| e.to_string())?
+        .check()
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Have the compositor (or the X server's window manager) focus the window again
+///
+/// This happens on a thread of its own, since `hyprctl` and `swaymsg` can take a while to answer
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn restore_focus(focus: PreviousFocus) {
+    std::thread::spawn(move || match focus {
+        #[cfg(target_os = "linux")]
+        PreviousFocus::Compositor(compositor, id) => {
+            if let Err(e) = compositor.focus_window(&id) {
+                error!("Failed to focus the previous window: {e}");
+            }
+        }
+        #[cfg(target_os = "linux")]
+        PreviousFocus::X11(id) => {
+            if let Err(e) = x11_activate(id) {
+                error!("Failed to focus the previous X11 window: {e}");
+            }
+        }
+    });
+}
+
+/// Run a command in Windows Terminal (falling back to a plain console window) on Windows, and in
+/// `x-terminal-emulator` elsewhere
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn run_in_terminal(command: &crate::commands::ShellCommand) -> Result<(), String> {
+    use std::process::Command;
+
+    #[cfg(target_os = "windows")]
+    let result = {
+        let (program, flag) = match command.shell {
+            Some(shell) => (shell.program(), shell.command_flag()),
+            None => ("cmd", "/k"),
+        };
+        let cwd = command.cwd().unwrap_or(".".to_string());
+        Command::new("wt")
+            .args(["-d", &cwd, program, flag, &command.command])
+            .envs(&command.env)
+            .spawn()
+            .or_else(|_| {
+                Command::new("cmd")
+                    .args(["/c", "start", "/d", &cwd, program, flag, &command.command])
+                    .envs(&command.env)
+                    .spawn()
+            })
+    };
+
+    #[cfg(not(target_os = "windows"))]
+    let result = Command::new("x-terminal-emulator")
+        .arg("-e")
+        .arg("sh")
+        .arg("-c")
+        .arg(format!("{}; exec \"${{SHELL:-sh}}\"", command.script()))
+        .spawn();
+
+    result
+        .map(|_| ())
+        .map_err(|e| format!("Could not open a terminal: {e}"))
+}
+
+/// The registry key that Windows starts programs from at login
+#[cfg(target_os = "windows")]
+const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+
+/// The XDG autostart entry that starts rustcast at login
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn autostart_entry() -> std::path::PathBuf {
+    dirs::config_dir()
+        .unwrap_or(std::path::PathBuf::from("/"))
+        .join("autostart/rustcast.desktop")
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) fn autostart_enabled() -> bool {
+    std::process::Command::new("reg")
+        .args(["query", RUN_KEY, "/v", "RustCast"])
+        .output()
+        .is_ok_and(|x| x.status.success())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub(crate) fn autostart_enabled() -> bool {
+    autostart_entry().exists()
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) fn set_autostart(enabled: bool) -> Result<(), String> {
+    let output = if enabled {
+        let exe = std::env::current_exe()
+            .map_err(|e| format!("Could not find the rustcast executable: {e}"))?;
+        let exe = format!("\"{}\"", exe.display());
+        std::process::Command::new("reg")
+            .args([
+                "add", RUN_KEY, "/v", "RustCast", "/t", "REG_SZ", "/d", &exe, "/f",
+            ])
+            .output()
+    } else {
+        std::process::Command::new("reg")
+            .args(["delete", RUN_KEY, "/v", "RustCast", "/f"])
+            .output()
+    };
+
+    match output {
+        Ok(output) if output.status.success() || !enabled => Ok(()),
+        Ok(output) => Err(format!(
+            "Could not change the login items: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )),
+        Err(e) => Err(format!("Could not change the login items: {e}")),
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub(crate) fn set_autostart(enabled: bool) -> Result<(), String> {
+    let path = autostart_entry();
+    if !enabled {
+        return match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(format!("Could not remove {}: {e}", path.display()))
+            }
+            _ => Ok(()),
+        };
+    }
+
+    let exe = std::env::current_exe()
+        .map_err(|e| format!("Could not find the rustcast executable: {e}"))?;
+    let entry = format!(
+        "[Desktop Entry]\nType=Application\nName=RustCast\nExec=\"{}\"\nX-GNOME-Autostart-enabled=true\n",
+        exe.display()
+    );
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).ok();
+    }
+    std::fs::write(&path, entry).map_err(|e| format!("Could not write {}: {e}", path.display()))
+}
+
+/// Open a url or path with its default app, using `ShellExecuteW` on Windows and `xdg-open`
+/// elsewhere
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn open(target: &str) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    let result = shell_execute(target, None);
+
+    #[cfg(not(target_os = "windows"))]
+    let result = std::process::Command::new("xdg-open")
+        .arg(target)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| e.to_string());
+
+    result.map_err(|e| format!("Could not open {target}: {e}"))
+}
+
+/// Show a path selected in the file manager, with `explorer /select` on Windows, and the
+/// FileManager1 D-Bus interface (which most file managers have) elsewhere
+///
+/// Without a file manager that has the D-Bus interface, the folder the path is in is opened
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn reveal(path: &str) -> Result<(), String> {
+    use std::process::Command;
+
+    if !std::path::Path::new(path).exists() {
+        return Err(format!("{path} does not exist"));
+    }
+
+    #[cfg(target_os = "windows")]
+    let result = Command::new("explorer")
+        .arg(format!("/select,{path}"))
+        .spawn()
+        .map(|_| ());
+
+    #[cfg(not(target_os = "windows"))]
+    let result = {
+        let uri = format!("file://{path}");
+        let shown = Command::new("dbus-send")
+            .args([
+                "--session",
+                "--dest=org.freedesktop.FileManager1",
+                "--type=method_call",
+                "/org/freedesktop/FileManager1",
+                "org.freedesktop.FileManager1.ShowItems",
+                &format!("array:string:{uri}"),
+                "string:",
+            ])
+            .status()
+            .is_ok_and(|x| x.success());
+
+        if shown {
+            Ok(())
+        } else {
+            let parent = std::path::Path::new(path)
+                .parent()
+                .map(|x| x.to_string_lossy().to_string())
+                .unwrap_or(path.to_string());
+            Command::new("xdg-open").arg(parent).spawn().map(|_| ())
+        }
+    };
+
+    result.map_err(|e| format!("Could not show {path}: {e}"))
+}
+
+/// The apps that can open a file or url
+///
+/// On Windows these are the programs in the extension's "Open with" list (or the browsers, for
+/// urls), and elsewhere the desktop entries that list the file's mime type (or the url's scheme)
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn apps_for(target: &str) -> Vec<(String, String)> {
+    #[cfg(target_os = "windows")]
+    {
+        let is_url = target.contains("://");
+        if is_url {
+            let key = r"HKLM\SOFTWARE\Clients\StartMenuInternet";
+            return reg_query(key)
+                .into_iter()
+                .filter_map(|line| {
+                    let name = line.strip_prefix(&format!("{key}\\"))?.to_string();
+                    let command = reg_query(&format!(r"{key}\{name}\shell\open\command"))
+                        .into_iter()
+                        .find_map(|x| reg_value(&x, "(Default)"))?;
+                    Some((name, split_command_line(&command).0.to_string()))
+                })
+                .collect();
+        }
+
+        let Some(extension) = Path::new(target).extension() else {
+            return vec![];
+        };
+        let key = format!(
+            r"HKCU\Software\Microsoft\Windows\CurrentVersion\Explorer\FileExts\.{}\OpenWithList",
+            extension.to_string_lossy()
+        );
+        reg_query(&key)
+            .into_iter()
+            .filter(|line| !line.trim_start().starts_with("MRUList"))
+            .filter_map(|line| line.split("REG_SZ").nth(1).map(|x| x.trim().to_string()))
+            .filter(|program| !program.is_empty())
+            .map(|program| {
+                let name = program.trim_end_matches(".exe").to_string();
+                (name, program)
+            })
+            .collect()
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let mime_type = match target.split_once("://") {
+            Some((scheme, _)) => format!("x-scheme-handler/{scheme}"),
+            None => std::process::Command::new("xdg-mime")
+                .args(["query", "filetype", target])
+                .output()
+                .map(|x| String::from_utf8_lossy(&x.stdout).trim().to_string())
+                .unwrap_or_default(),
+        };
+        if mime_type.is_empty() {
+            return vec![];
+        }
+
+        let mut apps: Vec<(String, String)> = vec![];
+        for dir in application_dirs() {
+            for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+                let path = entry.path();
+                let Some((name, _)) =
+                    desktop_entry(&path).filter(|(_, entry)| entry.mime_types.contains(&mime_type))
+                else {
+                    continue;
+                };
+                if !apps.iter().any(|(x, _)| *x == name) {
+                    apps.push((name, path.to_string_lossy().to_string()));
+                }
+            }
+        }
+        apps
+    }
+}
+
+/// Open a file or url with one of the apps from [`apps_for`]
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn open_with(target: &str, app: &str) -> Result<(), String> {
+    // The target is quoted, which is all it needs since paths can't have quotes in them
+    #[cfg(target_os = "windows")]
+    let result = shell_execute(app, Some(&format!("\"{target}\"")));
+
+    #[cfg(not(target_os = "windows"))]
+    let result = {
+        let Some((_, entry)) = desktop_entry(Path::new(app)) else {
+            return Err(format!("{app} is not a desktop entry"));
+        };
+        let Some(args) = exec_args(&entry.exec, target) else {
+            return Err(format!("{app} has nothing to run"));
+        };
+        match args.split_first() {
+            Some((program, args)) => std::process::Command::new(program)
+                .args(args)
+                .spawn()
+                .map(|_| ())
+                .map_err(|e| e.to_string()),
+            None => return Err(format!("{app} has nothing to run")),
+        }
+    };
+
+    result.map_err(|e| format!("Could not open {target} with {app}: {e}"))
+}
+
+/// Open a file (or run a program, or open a url) the way Explorer does, with `parameters` for a
+/// program
+///
+/// Unlike `cmd /c start`, nothing in the file or parameters is taken as a shell command, and
+/// programs are also found by their registered name (like `winword.exe`)
+#[cfg(target_os = "windows")]
+fn shell_execute(file: &str, parameters: Option<&str>) -> Result<(), String> {
+    use std::os::windows::ffi::OsStrExt;
+
+    #[link(name = "shell32")]
+    unsafe extern "system" {
+        fn ShellExecuteW(
+            window: isize,
+            operation: *const u16,
+            file: *const u16,
+            parameters: *const u16,
+            directory: *const u16,
+            show: i32,
+        ) -> isize;
+    }
+    const SW_SHOWNORMAL: i32 = 1;
+
+    let wide = |text: &str| -> Vec<u16> {
+        std::ffi::OsStr::new(text)
+            .encode_wide()
+            .chain(Some(0))
+            .collect()
+    };
+    let operation = wide("open");
+    let file = wide(file);
+    let parameters = parameters.map(wide);
+    let result = unsafe {
+        ShellExecuteW(
+            0,
+            operation.as_ptr(),
+            file.as_ptr(),
+            parameters.as_ref().map_or(std::ptr::null(), |x| x.as_ptr()),
+            std::ptr::null(),
+            SW_SHOWNORMAL,
+        )
+    };
+    // Results up to 32 are errors, most of which are the system's error codes
+    if result <= 32 {
+        return Err(std::io::Error::from_raw_os_error(result as i32).to_string());
+    }
+    Ok(())
+}
+
+/// The program and arguments that a desktop entry's `Exec` runs to open the target
+///
+/// The arguments are split and unquoted by the Desktop Entry rules, which are a subset of the
+/// shell's, and the field codes for files and urls (`%f`, `%u` and their lists) are replaced by the
+/// target, which is added at the end if there are none. The other field codes are removed
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn exec_args(exec: &str, target: &str) -> Option<Vec<String>> {
+    // The value is unescaped as a string first, so that `\\\\` in the file is a `\\` when
+    // unquoting. Other backslashes are kept for the quoting, since files often have them
+    let mut unescaped = String::with_capacity(exec.len());
+    let mut chars = exec.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('s') => unescaped.push(' '),
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some('r') => unescaped.push('\r'),
+            Some('\\') | None => unescaped.push('\\'),
+            Some(c) => {
+                unescaped.push('\\');
+                unescaped.push(c);
+            }
+        }
+    }
+
+    let mut has_target = false;
+    let mut args = vec![];
+    for arg in shlex::split(&unescaped)? {
+        match arg.as_str() {
+            "%f" | "%F" | "%u" | "%U" => {
+                has_target = true;
+                args.push(target.to_string());
+            }
+            "%i" | "%c" | "%k" | "%d" | "%D" | "%n" | "%N" | "%v" | "%m" => {}
+            _ => args.push(arg.replace("%%", "%")),
+        }
+    }
+    if !has_target {
+        args.push(target.to_string());
+    }
+    Some(args)
+}
+
+/// Whether an app has a name to find its uninstaller or package by
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn can_uninstall(path: &str) -> bool {
+    Path::new(path).file_stem().is_some()
+}
+
+/// Uninstall an app, by running the uninstaller from its registry entry on Windows, and elsewhere
+/// by opening its entry in the package manager (through an `appstream://` url, which GNOME
+/// Software and Discover handle)
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn uninstall_app(path: &str) -> Result<(), String> {
+    use std::process::Command;
+
+    let name = Path::new(path)
+        .file_stem()
+        .map(|x| x.to_string_lossy().to_string())
+        .unwrap_or(path.to_string());
+
+    #[cfg(target_os = "windows")]
+    let result = {
+        use std::os::windows::process::CommandExt;
+
+        let uninstaller = windows_uninstaller(path, &name)?;
+        let (program, args) = split_command_line(&uninstaller);
+        let mut command = Command::new(program);
+        if !args.is_empty() {
+            // Uninstallers parse their own arguments, like MsiExec's `/X{...}`
+            command.raw_arg(args);
+        }
+        command.spawn()
+    };
+
+    #[cfg(not(target_os = "windows"))]
+    let result = Command::new("xdg-open")
+        .arg(format!("appstream://{name}"))
+        .spawn();
+
+    result
+        .map(|_| ())
+        .map_err(|e| format!("Could not uninstall {name}: {e}"))
+}
+
+/// The `UninstallString` of the app's registry entry, found by its name, or else by the folder it
+/// was installed to
+///
+/// Several entries with different uninstallers are an error, since running the wrong one would
+/// uninstall another app
+#[cfg(target_os = "windows")]
+fn windows_uninstaller(path: &str, name: &str) -> Result<String, String> {
+    let path = Path::new(path);
+    let mut by_name: Vec<(String, String)> = vec![];
+    let mut by_location: Vec<(String, String)> = vec![];
+    for key in [
+        r"HKLM\SOFTWARE\Microsoft\Windows\CurrentVersion\Uninstall",
+        r"HKLM\SOFTWARE\WOW6432Node\Microsoft\Windows\CurrentVersion\Uninstall",
+        r"HKCU\Software\Microsoft\Windows\CurrentVersion\Uninstall",
+    ]
+    .into_iter()
+    .flat_map(reg_query)
+    .filter(|line| line.starts_with("HKEY_"))
+    {
+        let values = reg_query(&key);
+        let value = |value_name| values.iter().find_map(|line| reg_value(line, value_name));
+        let Some(uninstaller) = value("UninstallString") else {
+            continue;
+        };
+        let display_name = value("DisplayName").unwrap_or_default();
+        if display_name.eq_ignore_ascii_case(name) {
+            by_name.push((display_name, uninstaller));
+        } else if let Some(location) = value("InstallLocation") {
+            // Paths are compared by their components, ignoring case like Windows does, so that
+            // `C:\Apps\Foo` isn't taken as the folder of `C:\Apps\Foobar\foobar.exe`
+            let location = location.trim().trim_matches('"').to_lowercase();
+            if !location.is_empty()
+                && Path::new(&path.to_string_lossy().to_lowercase()).starts_with(&location)
+            {
+                by_location.push((display_name, uninstaller));
+            }
+        }
+    }
+
+    let mut matches = if by_name.is_empty() {
+        by_location
+    } else {
+        by_name
+    };
+    matches.sort_by(|a, b| a.1.cmp(&b.1));
+    matches.dedup_by(|a, b| a.1 == b.1);
+    match matches.as_slice() {
+        [] => Err(format!("{name} has no uninstaller")),
+        [(_, uninstaller)] => Ok(uninstaller.clone()),
+        several => Err(format!(
+            "{name} matches several installed apps ({}), so none of them is uninstalled",
+            several
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+/// The program of a Windows command line, and the arguments after it
+///
+/// The program is either quoted, or runs up to its `.exe` (so that unquoted paths with spaces,
+/// which the registry is full of, still work) or else the first space
+#[cfg(target_os = "windows")]
+fn split_command_line(line: &str) -> (&str, &str) {
+    let line = line.trim();
+    if let Some(rest) = line.strip_prefix('"') {
+        return match rest.split_once('"') {
+            Some((program, args)) => (program, args.trim()),
+            None => (rest, ""),
+        };
+    }
+
+    let end = line
+        .to_ascii_lowercase()
+        .match_indices(".exe")
+        .map(|(i, _)| i + 4)
+        .find(|&i| line[i..].is_empty() || line[i..].starts_with(' '))
+        .or_else(|| line.find(' '))
+        .unwrap_or(line.len());
+    (&line[..end], line[end..].trim())
+}
+
+/// The lines that `reg query` prints for a registry key
+#[cfg(target_os = "windows")]
+fn reg_query(key: &str) -> Vec<String> {
+    std::process::Command::new("reg")
+        .args(["query", key])
+        .output()
+        .map(|x| {
+            String::from_utf8_lossy(&x.stdout)
+                .lines()
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The data of a value in a line that `reg query` printed, if the line is for that value
+#[cfg(target_os = "windows")]
+fn reg_value(line: &str, name: &str) -> Option<String> {
+    let rest = line.trim_start().strip_prefix(name)?;
+    Some(rest.split_once("REG_SZ")?.1.trim().to_string())
+}
+
+/// The directories that desktop entries are in
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn application_dirs() -> Vec<PathBuf> {
+    let data_home = dirs::data_dir().unwrap_or(PathBuf::from("/usr/local/share"));
+    let data_dirs = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|x| !x.is_empty())
+        .unwrap_or("/usr/local/share:/usr/share".to_string());
+
+    std::iter::once(data_home)
+        .chain(data_dirs.split(':').map(PathBuf::from))
+        .map(|x| x.join("applications"))
+        .collect()
+}
+
+/// The parts of a desktop entry that are used to open files with it
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+#[derive(Default)]
+struct DesktopEntry {
+    exec: String,
+    mime_types: Vec<String>,
+}
+
+/// The name and the rest of a desktop entry file, if it is one that can be run
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn desktop_entry(path: &Path) -> Option<(String, DesktopEntry)> {
+    if path.extension()? != "desktop" {
+        return None;
+    }
+
+    let contents = fs::read_to_string(path).ok()?;
+    let mut name = None;
+    let mut entry = DesktopEntry::default();
+    let mut in_main_group = false;
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_main_group = line == "[Desktop Entry]";
+            continue;
+        }
+        if !in_main_group {
+            continue;
+        }
+        match line.split_once('=') {
+            Some(("Name", value)) => name = Some(value.to_string()),
+            Some(("Exec", value)) => entry.exec = value.to_string(),
+            Some(("MimeType", value)) => {
+                entry.mime_types = value
+                    .split(';')
+                    .filter(|x| !x.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            }
+            Some(("NoDisplay", "true")) | Some(("Hidden", "true")) => return None,
+            _ => {}
+        }
+    }
+
+    if entry.exec.is_empty() {
+        return None;
+    }
+    Some((name?, entry))
+}
+
+/// Quit an app by its process name, with `taskkill` on Windows and `pkill` elsewhere
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn terminate_app(name: &str) -> Result<(), String> {
+    use std::process::Command;
+
+    #[cfg(target_os = "windows")]
+    let result = Command::new("taskkill")
+        .args(["/IM", &format!("{name}.exe")])
+        .spawn();
+
+    #[cfg(not(target_os = "windows"))]
+    let result = Command::new("pkill").args(["-x", name]).spawn();
+
+    result
+        .map(|_| ())
+        .map_err(|e| format!("Could not quit {name}: {e}"))
+}
+
+/// The paths of the running executables, from `/proc` on Linux, and by asking Windows for the
+/// image of each process there
+///
+/// Processes that Windows doesn't give the image of (like the system's, and ones that run as
+/// administrator) are left out
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn running_app_paths() -> Vec<String> {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::ffi::OsStringExt;
+
+        #[link(name = "kernel32")]
+        unsafe extern "system" {
+            fn K32EnumProcesses(ids: *mut u32, size: u32, returned: *mut u32) -> i32;
+            fn OpenProcess(access: u32, inherit: i32, id: u32) -> isize;
+            fn QueryFullProcessImageNameW(
+                process: isize,
+                flags: u32,
+                name: *mut u16,
+                size: *mut u32,
+            ) -> i32;
+            fn CloseHandle(handle: isize) -> i32;
+        }
+        const PROCESS_QUERY_LIMITED_INFORMATION: u32 = 0x1000;
+
+        // The list is asked for again with more room until it fits
+        let mut ids = vec![0u32; 1024];
+        let count = loop {
+            let size = (ids.len() * std::mem::size_of::<u32>()) as u32;
+            let mut returned = 0;
+            if unsafe { K32EnumProcesses(ids.as_mut_ptr(), size, &mut returned) } == 0 {
+                return vec![];
+            }
+            if returned < size {
+                break returned as usize / std::mem::size_of::<u32>();
+            }
+            ids.resize(ids.len() * 2, 0);
+        };
+
+        ids[..count]
+            .iter()
+            .filter_map(|&id| {
+                let process = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, id) };
+                if process == 0 {
+                    return None;
+                }
+                let mut name = [0u16; 1024];
+                let mut size = name.len() as u32;
+                let found =
+                    unsafe { QueryFullProcessImageNameW(process, 0, name.as_mut_ptr(), &mut size) };
+                unsafe { CloseHandle(process) };
+                (found != 0).then(|| {
+                    std::ffi::OsString::from_wide(&name[..size as usize])
+                        .to_string_lossy()
+                        .to_string()
+                })
+            })
+            .collect()
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        running_processes().map(|(_, exe)| exe).collect()
+    }
+}
+
+/// The ids of the running processes with the paths of their executables, from `/proc`
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn running_processes() -> impl Iterator<Item = (i32, String)> {
+    fs::read_dir("/proc")
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|x| {
+            let pid = x.file_name().to_str()?.parse().ok()?;
+            let exe = fs::read_link(x.path().join("exe")).ok()?;
+            Some((pid, exe.to_string_lossy().to_string()))
+        })
+}
+
+/// Quit (or kill) the processes started from a path, with `taskkill` on Windows, and by sending
+/// the processes whose executables are in it `SIGTERM` (or `SIGKILL`) elsewhere
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn quit_app_at(path: &str, force: bool) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        let name = Path::new(path)
+            .file_name()
+            .map(|x| x.to_string_lossy().to_string())
+            .unwrap_or(path.to_string());
+        let mut command = std::process::Command::new("taskkill");
+        if force {
+            command.arg("/F");
+        }
+        command
+            .args(["/IM", &name])
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| format!("Could not quit {path}: {e}"))
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let signal = if force { libc::SIGKILL } else { libc::SIGTERM };
+        let pids: Vec<i32> = running_processes()
+            .filter(|(_, exe)| super::runs_from(exe, path))
+            .map(|(pid, _)| pid)
+            .collect();
+        if pids.is_empty() {
+            return Err(format!("{path} is not running"));
+        }
+
+        for pid in pids {
+            if unsafe { libc::kill(pid, signal) } != 0 {
+                return Err(format!(
+                    "Could not quit {path}: {}",
+                    std::io::Error::last_os_error()
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The text in an image file, from the `tesseract` CLI (which has to be installed), or an error
+/// saying that rustcast needs to be built with the `tesseract` feature for it
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn recognize_text(path: &Path) -> Result<String, String> {
+    #[cfg(feature = "tesseract")]
+    let result = {
+        let output = std::process::Command::new("tesseract")
+            .arg(path)
+            .arg("stdout")
+            .output()
+            .map_err(|e| format!("Could not run tesseract: {e}"))?;
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        } else {
+            Err(format!(
+                "tesseract failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))
+        }
+    };
+
+    #[cfg(not(feature = "tesseract"))]
+    let result = {
+        let _ = path;
+        Err("Extracting text needs rustcast to be built with the `tesseract` feature".to_string())
+    };
+
+    result
+}
+
+/// Switch between the light and dark appearance, through the `Personalize` registry values on
+/// Windows and GNOME's `color-scheme` setting elsewhere
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn toggle_dark_mode() -> Result<(), String> {
+    use std::process::Command;
+
+    #[cfg(target_os = "windows")]
+    let result = {
+        let key = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Themes\Personalize";
+        let is_light = Command::new("reg")
+            .args(["query", key, "/v", "AppsUseLightTheme"])
+            .output()
+            .map(|x| String::from_utf8_lossy(&x.stdout).contains("0x1"))
+            .unwrap_or(true);
+        let value = if is_light { "0" } else { "1" };
+        ["AppsUseLightTheme", "SystemUsesLightTheme"]
+            .iter()
+            .try_for_each(|name| {
+                Command::new("reg")
+                    .args(["add", key, "/v", name, "/t", "REG_DWORD", "/d", value, "/f"])
+                    .output()
+                    .map(|_| ())
+            })
+    };
+
+    #[cfg(not(target_os = "windows"))]
+    let result = {
+        let is_dark = Command::new("gsettings")
+            .args(["get", "org.gnome.desktop.interface", "color-scheme"])
+            .output()
+            .map(|x| String::from_utf8_lossy(&x.stdout).contains("prefer-dark"))
+            .unwrap_or(false);
+        let scheme = if is_dark { "default" } else { "prefer-dark" };
+        Command::new("gsettings")
+            .args(["set", "org.gnome.desktop.interface", "color-scheme", scheme])
+            .spawn()
+            .map(|_| ())
+    };
+
+    result.map_err(|e| format!("Could not switch dark mode: {e}"))
+}
+
+/// Turn Wi-Fi off if it's on, and on if it's off, with `netsh` on Windows (which needs rustcast to
+/// be run as an administrator) and NetworkManager's `nmcli` elsewhere
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn toggle_wifi() -> Result<(), String> {
+    use std::process::Command;
+
+    let run = |program: &str, args: &[&str]| {
+        Command::new(program)
+            .args(args)
+            .output()
+            .map_err(|e| format!("Could not run {program}: {e}"))
+            .and_then(|x| {
+                if x.status.success() {
+                    Ok(String::from_utf8_lossy(&x.stdout).to_string())
+                } else {
+                    Err(format!(
+                        "{program} failed: {}",
+                        String::from_utf8_lossy(&x.stderr).trim()
+                    ))
+                }
+            })
+    };
+
+    #[cfg(target_os = "windows")]
+    {
+        let is_on = run("netsh", &["interface", "show", "interface", "name=Wi-Fi"])?
+            .lines()
+            .any(|x| x.contains("Administrative state") && x.contains("Enabled"));
+        let admin = if is_on {
+            "admin=disabled"
+        } else {
+            "admin=enabled"
+        };
+        run(
+            "netsh",
+            &["interface", "set", "interface", "name=Wi-Fi", admin],
+        )?;
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let is_on = run("nmcli", &["radio", "wifi"])?.trim() == "enabled";
+        run(
+            "nmcli",
+            &["radio", "wifi", if is_on { "off" } else { "on" }],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Turn Bluetooth off if it's on, and on if it's off, with `bluetoothctl` (which Windows doesn't
+/// have a command like)
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn toggle_bluetooth() -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    let result = Err("Turning Bluetooth on and off isn't supported on Windows".to_string());
+
+    #[cfg(not(target_os = "windows"))]
+    let result = {
+        use std::process::Command;
+
+        let is_on = Command::new("bluetoothctl")
+            .arg("show")
+            .output()
+            .map_err(|e| format!("Could not run bluetoothctl: {e}"))?
+            .stdout;
+        let is_on = String::from_utf8_lossy(&is_on).contains("Powered: yes");
+        Command::new("bluetoothctl")
+            .args(["power", if is_on { "off" } else { "on" }])
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| format!("Could not run bluetoothctl: {e}"))
+    };
+
+    result
+}
+
+/// Keeps the computer and its display awake until it is dropped, with `SetThreadExecutionState`
+/// on Windows (which lasts while the thread that called it does, which is the main thread) and a
+/// `systemd-inhibit` lock elsewhere, which is held while its `sleep` runs
+#[cfg(not(target_os = "macos"))]
+pub struct SleepAssertion {
+    #[cfg(not(target_os = "windows"))]
+    inhibitor: std::process::Child,
+}
+
+#[cfg(target_os = "windows")]
+mod execution_state {
+    pub const ES_CONTINUOUS: u32 = 0x80000000;
+    pub const ES_SYSTEM_REQUIRED: u32 = 0x00000001;
+    pub const ES_DISPLAY_REQUIRED: u32 = 0x00000002;
+
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        pub fn SetThreadExecutionState(flags: u32) -> u32;
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+impl SleepAssertion {
+    pub(crate) fn new() -> Result<Self, String> {
+        #[cfg(target_os = "windows")]
+        {
+            use execution_state::*;
+
+            let previous = unsafe {
+                SetThreadExecutionState(ES_CONTINUOUS | ES_SYSTEM_REQUIRED | ES_DISPLAY_REQUIRED)
+            };
+            if previous == 0 {
+                return Err("Could not prevent sleep".to_string());
+            }
+            Ok(SleepAssertion {})
+        }
+
+        #[cfg(not(target_os = "windows"))]
+        {
+            std::process::Command::new("systemd-inhibit")
+                .args([
+                    "--what=idle:sleep",
+                    "--who=RustCast",
+                    "--why=Preventing sleep",
+                    "sleep",
+                    "infinity",
+                ])
+                .spawn()
+                .map(|inhibitor| SleepAssertion { inhibitor })
+                .map_err(|e| format!("Could not run systemd-inhibit: {e}"))
+        }
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+impl Drop for SleepAssertion {
+    fn drop(&mut self) {
+        #[cfg(target_os = "windows")]
+        unsafe {
+            execution_state::SetThreadExecutionState(execution_state::ES_CONTINUOUS)
+        };
+
+        #[cfg(not(target_os = "windows"))]
+        {
+            self.inhibitor.kill().ok();
+            self.inhibitor.wait().ok();
+        }
+    }
+}
+
+/// The processes listening on a TCP port, by their pid and name, from `netstat` and `tasklist` on
+/// Windows and `ss` elsewhere
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn listening_processes(port: u16) -> Vec<(u32, String)> {
+    use std::process::Command;
+
+    #[cfg(target_os = "windows")]
+    let processes = {
+        let Ok(output) = Command::new("netstat").args(["-ano", "-p", "TCP"]).output() else {
+            return vec![];
+        };
+        let mut pids: Vec<u32> = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                let columns: Vec<&str> = line.split_whitespace().collect();
+                let [_, local, _, state, pid] = columns.as_slice() else {
+                    return None;
+                };
+                (*state == "LISTENING" && local.ends_with(&format!(":{port}")))
+                    .then(|| pid.parse().ok())
+                    .flatten()
+            })
+            .collect();
+        pids.dedup();
+
+        pids.into_iter()
+            .map(|pid| {
+                // `tasklist` prints `"name.exe","pid",...`
+                let name = Command::new("tasklist")
+                    .args(["/FI", &format!("PID eq {pid}"), "/FO", "CSV", "/NH"])
+                    .output()
+                    .ok()
+                    .and_then(|x| {
+                        String::from_utf8_lossy(&x.stdout)
+                            .split('"')
+                            .nth(1)
+                            .map(str::to_string)
+                    })
+                    .unwrap_or_default();
+                (pid, name)
+            })
+            .collect()
+    };
+
+    #[cfg(not(target_os = "windows"))]
+    let processes = {
+        let Ok(output) = Command::new("ss")
+            .args(["-Hltnp", "sport", "=", &format!(":{port}")])
+            .output()
+        else {
+            return vec![];
+        };
+        // The processes are listed like `users:(("node",pid=1234,fd=20))`
+        let mut processes: Vec<(u32, String)> = vec![];
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            for process in line.split("((").skip(1).flat_map(|x| x.split("),(")) {
+                let mut fields = process.split(',');
+                let name = fields.next().unwrap_or_default().trim_matches('"');
+                let pid = fields
+                    .find_map(|x| x.strip_prefix("pid="))
+                    .and_then(|x| x.parse().ok());
+                if let Some(pid) = pid
+                    && !processes.iter().any(|(x, _)| *x == pid)
+                {
+                    processes.push((pid, name.to_string()));
+                }
+            }
+        }
+        processes
+    };
+
+    processes
+}
+
+/// Ask every app with a window to close, through PowerShell on Windows and `wmctrl` elsewhere
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn terminate_all_apps() -> Result<(), String> {
+    use std::process::Command;
+
+    #[cfg(target_os = "windows")]
+    {
+        let script = format!(
+            "Get-Process | Where-Object {{ $_.MainWindowHandle -ne 0 -and $_.Id -ne {} }} \
+            | ForEach-Object {{ $_.CloseMainWindow() }}",
+            std::process::id()
+        );
+        Command::new("powershell")
+            .args(["-NoProfile", "-Command", &script])
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| format!("Could not quit apps: {e}"))
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        // Lines look like `0x03a00003  0 1234   hostname Window title`, with the pid third
+        let windows = Command::new("wmctrl")
+            .args(["-l", "-p"])
+            .output()
+            .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+            .map_err(|e| format!("Could not list windows with wmctrl: {e}"))?;
+
+        let own_pid = std::process::id().to_string();
+        for window in windows.lines() {
+            let mut fields = window.split_whitespace();
+            let (Some(id), Some(pid)) = (fields.next(), fields.nth(1)) else {
+                continue;
+            };
+            if pid != own_pid {
+                Command::new("wmctrl").args(["-i", "-c", id]).spawn().ok();
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The track that the media player is playing (or has paused), from the system media transport
+/// controls on Windows and MPRIS (through `playerctl`) elsewhere
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn now_playing() -> Option<crate::media::NowPlaying> {
+    use std::process::Command;
+
+    #[cfg(target_os = "windows")]
+    let output = {
+        // WinRT's async operations have to be turned into tasks to be waited on from PowerShell
+        let script = "\
+            Add-Type -AssemblyName System.Runtime.WindowsRuntime
+            $asTask = [System.WindowsRuntimeSystemExtensions].GetMethods() | Where-Object { \
+                $_.Name -eq 'AsTask' -and $_.GetParameters().Count -eq 1 -and \
+                $_.GetParameters()[0].ParameterType.Name -eq 'IAsyncOperation`1' } | \
+                Select-Object -First 1
+            function Await($operation, $type) {
+                $task = $asTask.MakeGenericMethod($type).Invoke($null, @($operation))
+                $task.Wait(-1) | Out-Null
+                $task.Result
+            }
+            $managerType = [Windows.Media.Control.GlobalSystemMediaTransportControlsSessionManager, \
+                Windows.Media.Control, ContentType = WindowsRuntime]
+            $propertiesType = [Windows.Media.Control.GlobalSystemMediaTransportControlsSessionMediaProperties, \
+                Windows.Media.Control, ContentType = WindowsRuntime]
+            $session = (Await ($managerType::RequestAsync()) $managerType).GetCurrentSession()
+            if ($session) {
+                $properties = Await ($session.TryGetMediaPropertiesAsync()) $propertiesType
+                $properties.Title
+                $properties.Artist
+                $session.GetPlaybackInfo().PlaybackStatus
+                $session.SourceAppUserModelId
+            }";
+        Command::new("powershell")
+            .args(["-NoProfile", "-Command", script])
+            .output()
+            .ok()?
+    };
+
+    #[cfg(not(target_os = "windows"))]
+    let output = Command::new("playerctl")
+        .args([
+            "metadata",
+            "--format",
+            "{{title}}\n{{artist}}\n{{status}}\n{{playerName}}",
+        ])
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+    let output = String::from_utf8_lossy(&output.stdout).to_string();
+    let mut lines = output.lines().map(str::trim);
+    let title = lines.next().filter(|x| !x.is_empty())?.to_string();
+    let artist = lines.next().unwrap_or_default().to_string();
+    let playing = lines.next() == Some("Playing");
+    // Windows gives the app's id, like `Spotify.exe` or `Microsoft.ZuneMusic_8wekyb3d8bbwe!Microsoft.ZuneMusic`
+    let player = lines
+        .next()
+        .map(|x| {
+            x.split('!')
+                .next_back()
+                .unwrap_or(x)
+                .trim_end_matches(".exe")
+        })
+        .filter(|x| !x.is_empty())
+        .unwrap_or("Media")
+        .to_string();
+
+    Some(crate::media::NowPlaying {
+        title,
+        artist,
+        player,
+        playing,
+    })
+}
+
+/// Play or pause the media player, or skip to its next or previous track, with the media keys on
+/// Windows and MPRIS (through `playerctl`) elsewhere
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn media_control(action: crate::media::MediaAction) -> Result<(), String> {
+    use std::process::Command;
+
+    use crate::media::MediaAction;
+
+    #[cfg(target_os = "windows")]
+    let result = {
+        let key = match action {
+            MediaAction::PlayPause => 179,
+            MediaAction::Next => 176,
+            MediaAction::Previous => 177,
+        };
+        Command::new("powershell")
+            .args([
+                "-NoProfile",
+                "-Command",
+                &format!("(New-Object -ComObject WScript.Shell).SendKeys([char]{key})"),
+            ])
+            .status()
+    };
+
+    #[cfg(not(target_os = "windows"))]
+    let result = {
+        let command = match action {
+            MediaAction::PlayPause => "play-pause",
+            MediaAction::Next => "next",
+            MediaAction::Previous => "previous",
+        };
+        Command::new("playerctl").arg(command).status()
+    };
+
+    match result {
+        Ok(status) if status.success() => Ok(()),
+        Ok(_) => Err("No media player is running".to_string()),
+        Err(e) => Err(format!("Could not control the media player: {e}")),
+    }
+}
+
+/// The events in Outlook's calendar that happen (at least partly) between two times on Windows,
+/// which is the only other platform with a system calendar that can be read
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn calendar_events(
+    from: jiff::Timestamp,
+    to: jiff::Timestamp,
+) -> Result<Vec<crate::calendar::Event>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        use std::process::Command;
+
+        // Each event is a line of its fields separated by tabs, with the whitespace in each field
+        // (like the lines of the body) turned into spaces
+        let script = format!(
+            "[Console]::OutputEncoding = [Text.Encoding]::UTF8
+            $items = (New-Object -ComObject Outlook.Application).Session.GetDefaultFolder(9).Items
+            $items.IncludeRecurrences = $true
+            $items.Sort('[Start]')
+            $from = [DateTimeOffset]::FromUnixTimeSeconds({}).LocalDateTime.ToString('g')
+            $to = [DateTimeOffset]::FromUnixTimeSeconds({}).LocalDateTime.ToString('g')
+            foreach ($item in $items.Restrict(\"[Start] < '$to' AND [End] > '$from'\")) {{
+                $fields = @(
+                    ([DateTimeOffset]$item.Start).ToUnixTimeSeconds(),
+                    ([DateTimeOffset]$item.End).ToUnixTimeSeconds(),
+                    $item.AllDayEvent, $item.Subject, $item.Location, $item.Body
+                )
+                ($fields | ForEach-Object {{ \"$_\" -replace '\\s+', ' ' }}) -join \"`t\"
+            }}",
+            from.as_second(),
+            to.as_second()
+        );
+        let output = Command::new("powershell")
+            .args(["-NoProfile", "-Command", &script])
+            .output()
+            .map_err(|e| format!("Could not run PowerShell: {e}"))?;
+        if !output.status.success() {
+            return Err(format!(
+                "Could not read Outlook's calendar: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split('\t').collect();
+                let [start, end, all_day, title, location, notes] = fields.as_slice() else {
+                    return None;
+                };
+                Some(crate::calendar::Event {
+                    title: title.to_string(),
+                    start: jiff::Timestamp::from_second(start.parse().ok()?).ok()?,
+                    end: jiff::Timestamp::from_second(end.parse().ok()?).ok()?,
+                    all_day: *all_day == "True",
+                    location: location.to_string(),
+                    notes: notes.to_string(),
+                    calendar: "Outlook".to_string(),
+                })
+            })
+            .collect())
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (from, to);
+        Err(
+            "The system calendar can only be read on macOS and Windows, but `ics_urls` work \
+             everywhere"
+                .to_string(),
+        )
+    }
+}
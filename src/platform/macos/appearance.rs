@@ -0,0 +1,133 @@
+//! Toggling how the screen looks: dark mode through System Events, and Night Shift and True Tone
+//! through the private CoreBrightness framework (the same way the `nightlight` CLI does)
+//!
+//! CoreBrightness isn't in Apple's `.tbd` stub files, so it is loaded with `dlopen` the first time
+//! it is needed, and its classes are then found by name. Its methods are called through
+//! `objc_msgSend` directly, as their argument types aren't ones that `msg_send!` can check
+
+use std::{ffi::CStr, mem, sync::LazyLock};
+
+use objc2::{
+    ffi::objc_msgSend,
+    msg_send,
+    rc::Retained,
+    runtime::{AnyClass, AnyObject, Bool, Sel},
+    sel,
+};
+
+/// Path to the CoreBrightness framework binary
+const CORE_BRIGHTNESS_PATH: &CStr =
+    c"/System/Library/PrivateFrameworks/CoreBrightness.framework/CoreBrightness";
+
+/// `CBBlueLightClient`'s status, which `getBlueLightStatus:` fills in
+///
+/// Only some of the fields are read, but all of them are needed for the layout
+#[repr(C)]
+#[derive(Default)]
+#[allow(dead_code)]
+struct BlueLightStatus {
+    active: u8,
+    enabled: u8,
+    sun_schedule_permitted: u8,
+    mode: i32,
+    /// The hours and minutes that the schedule starts and ends at
+    schedule: [i32; 4],
+    disable_flags: u64,
+    available: u8,
+}
+
+type MsgSendFn = unsafe extern "C-unwind" fn();
+type GetStatusFn = unsafe extern "C-unwind" fn(*const AnyObject, Sel, *mut BlueLightStatus) -> Bool;
+type GetBoolFn = unsafe extern "C-unwind" fn(*const AnyObject, Sel) -> Bool;
+type SetBoolFn = unsafe extern "C-unwind" fn(*const AnyObject, Sel, Bool) -> Bool;
+
+/// Switch between the light and dark appearance
+pub fn toggle_dark_mode() -> Result<(), String> {
+    std::process::Command::new("osascript")
+        .arg("-e")
+        .arg(
+            "tell application \"System Events\" to tell appearance preferences to set dark mode \
+             to not dark mode",
+        )
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Could not switch dark mode: {e}"))
+}
+
+/// Turn Night Shift off if it's on, and on if it's off
+pub fn toggle_night_shift() -> Result<(), String> {
+    let client = client(c"CBBlueLightClient")?;
+    let mut status = BlueLightStatus::default();
+
+    // SAFETY: The method takes a pointer to the status struct, which has the same layout, and
+    // objc_msgSend is called with the method's signature
+    let got_status = unsafe {
+        let get_status = mem::transmute::<MsgSendFn, GetStatusFn>(objc_msgSend);
+        get_status(
+            Retained::as_ptr(&client),
+            sel!(getBlueLightStatus:),
+            &mut status,
+        )
+    };
+    if !got_status.as_bool() || status.available == 0 {
+        return Err("Night Shift isn't available on this Mac".to_string());
+    }
+
+    // SAFETY: The method takes and returns a BOOL
+    unsafe {
+        let set_enabled = mem::transmute::<MsgSendFn, SetBoolFn>(objc_msgSend);
+        set_enabled(
+            Retained::as_ptr(&client),
+            sel!(setEnabled:),
+            Bool::new(status.enabled == 0),
+        );
+    }
+    Ok(())
+}
+
+/// Turn True Tone off if it's on, and on if it's off
+pub fn toggle_true_tone() -> Result<(), String> {
+    let client = client(c"CBTrueToneClient")?;
+
+    // SAFETY: The methods take no arguments (or a BOOL) and return a BOOL
+    unsafe {
+        let get = mem::transmute::<MsgSendFn, GetBoolFn>(objc_msgSend);
+        if !get(Retained::as_ptr(&client), sel!(supported)).as_bool() {
+            return Err("True Tone isn't supported by this Mac's display".to_string());
+        }
+        let enabled = get(Retained::as_ptr(&client), sel!(enabled)).as_bool();
+
+        let set_enabled = mem::transmute::<MsgSendFn, SetBoolFn>(objc_msgSend);
+        set_enabled(
+            Retained::as_ptr(&client),
+            sel!(setEnabled:),
+            Bool::new(!enabled),
+        );
+    }
+    Ok(())
+}
+
+/// A new instance of one of CoreBrightness's client classes
+fn client(class_name: &CStr) -> Result<Retained<AnyObject>, String> {
+    // The library handle is kept open for the process lifetime, since its classes stay registered
+    static LOADED: LazyLock<bool> = LazyLock::new(|| {
+        // SAFETY: We pass a valid null-terminated path string to dlopen
+        let lib = unsafe {
+            libc::dlopen(
+                CORE_BRIGHTNESS_PATH.as_ptr(),
+                libc::RTLD_NOW | libc::RTLD_LOCAL,
+            )
+        };
+        !lib.is_null()
+    });
+
+    if !*LOADED {
+        return Err("Could not load the CoreBrightness framework".to_string());
+    }
+    let class = AnyClass::get(class_name)
+        .ok_or_else(|| format!("CoreBrightness has no {}", class_name.to_string_lossy()))?;
+
+    // SAFETY: `new` takes no arguments and returns a retained instance
+    let client: Option<Retained<AnyObject>> = unsafe { msg_send![class, new] };
+    client.ok_or_else(|| format!("Could not create a {}", class_name.to_string_lossy()))
+}
@@ -0,0 +1,150 @@
+//! Reading events from Calendar through EventKit, which macOS asks permission for the first time
+//!
+//! EventKit is loaded with `dlopen` the first time it is needed (like CoreBrightness in
+//! [`super::appearance`]), and its classes are then found by name. Unlike asking Calendar through
+//! AppleScript, this gives each occurrence of recurring events
+
+use std::{
+    ffi::CStr,
+    sync::{LazyLock, mpsc},
+    time::Duration,
+};
+
+use block2::RcBlock;
+use jiff::Timestamp;
+use objc2::{
+    msg_send,
+    rc::Retained,
+    runtime::{AnyClass, AnyObject, Bool},
+    sel,
+};
+use objc2_foundation::{NSArray, NSDate, NSError, NSString, NSURL};
+
+use crate::calendar::Event;
+
+/// Path to the EventKit framework binary
+const EVENT_KIT_PATH: &CStr = c"/System/Library/Frameworks/EventKit.framework/EventKit";
+
+/// `EKEntityTypeEvent`
+const ENTITY_TYPE_EVENT: isize = 0;
+
+/// `EKAuthorizationStatusNotDetermined`, before the user has been asked
+const NOT_DETERMINED: isize = 0;
+
+/// `EKAuthorizationStatusFullAccess` (which was `EKAuthorizationStatusAuthorized` before macOS 14)
+const FULL_ACCESS: isize = 3;
+
+/// How long to wait for the user to answer the permission prompt
+const PERMISSION_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The events in every calendar that happen (at least partly) between two times
+pub fn events(from: Timestamp, to: Timestamp) -> Result<Vec<Event>, String> {
+    // The library handle is kept open for the process lifetime, since its classes stay registered
+    static LOADED: LazyLock<bool> = LazyLock::new(|| {
+        // SAFETY: We pass a valid null-terminated path string to dlopen
+        let lib =
+            unsafe { libc::dlopen(EVENT_KIT_PATH.as_ptr(), libc::RTLD_NOW | libc::RTLD_LOCAL) };
+        !lib.is_null()
+    });
+    if !*LOADED {
+        return Err("Could not load the EventKit framework".to_string());
+    }
+    let class = AnyClass::get(c"EKEventStore").ok_or("EventKit has no EKEventStore")?;
+
+    // SAFETY: `new` takes no arguments and returns a retained instance, and
+    // `authorizationStatusForEntityType:` takes and returns an NSInteger
+    let (store, status) = unsafe {
+        let store: Option<Retained<AnyObject>> = msg_send![class, new];
+        let status: isize = msg_send![class, authorizationStatusForEntityType: ENTITY_TYPE_EVENT];
+        (store.ok_or("Could not create an event store")?, status)
+    };
+    let allowed = match status {
+        NOT_DETERMINED => request_access(&store),
+        status => status == FULL_ACCESS,
+    };
+    if !allowed {
+        return Err(
+            "rustcast isn't allowed to read your calendars, which can be changed in Privacy & \
+             Security in System Settings"
+                .to_string(),
+        );
+    }
+
+    let start = NSDate::dateWithTimeIntervalSince1970(from.as_second() as f64);
+    let end = NSDate::dateWithTimeIntervalSince1970(to.as_second() as f64);
+    // SAFETY: The predicate is made from two dates and no calendars (which means all of them), and
+    // the events matching it are an array of EKEvents
+    let found: Retained<NSArray<AnyObject>> = unsafe {
+        let predicate: Retained<AnyObject> = msg_send![
+            &store,
+            predicateForEventsWithStartDate: &*start,
+            endDate: &*end,
+            calendars: None::<&AnyObject>
+        ];
+        msg_send![&store, eventsMatchingPredicate: &*predicate]
+    };
+
+    Ok(found.iter().filter_map(|x| event(&x)).collect())
+}
+
+/// Ask the user to let rustcast read their calendars, and wait for them to answer
+fn request_access(store: &AnyObject) -> bool {
+    let (sender, receiver) = mpsc::channel();
+    let completion = RcBlock::new(move |granted: Bool, _: *mut NSError| {
+        sender.send(granted.as_bool()).ok();
+    });
+
+    // SAFETY: Both methods take a completion block with a BOOL and an NSError, and macOS 14
+    // replaced the older one with the one that asks for full access
+    unsafe {
+        let has_full_access: bool = msg_send![
+            store,
+            respondsToSelector: sel!(requestFullAccessToEventsWithCompletion:)
+        ];
+        if has_full_access {
+            let _: () = msg_send![store, requestFullAccessToEventsWithCompletion: &*completion];
+        } else {
+            let _: () = msg_send![
+                store,
+                requestAccessToEntityType: ENTITY_TYPE_EVENT,
+                completion: &*completion
+            ];
+        }
+    }
+    receiver.recv_timeout(PERMISSION_TIMEOUT).unwrap_or(false)
+}
+
+/// An EKEvent as an [`Event`]
+fn event(event: &AnyObject) -> Option<Event> {
+    let text = |x: Option<Retained<NSString>>| x.map(|x| x.to_string()).unwrap_or_default();
+
+    // SAFETY: These are EKEvent's (and EKCalendar's) properties, with their types
+    unsafe {
+        let start: Option<Retained<NSDate>> = msg_send![event, startDate];
+        let end: Option<Retained<NSDate>> = msg_send![event, endDate];
+        let all_day: bool = msg_send![event, isAllDay];
+        let title: Option<Retained<NSString>> = msg_send![event, title];
+        let location: Option<Retained<NSString>> = msg_send![event, location];
+        let notes: Option<Retained<NSString>> = msg_send![event, notes];
+        let url: Option<Retained<NSURL>> = msg_send![event, URL];
+        let calendar: Option<Retained<AnyObject>> = msg_send![event, calendar];
+        let calendar_title: Option<Retained<NSString>> = match &calendar {
+            Some(calendar) => msg_send![calendar, title],
+            None => None,
+        };
+
+        let mut notes = text(notes);
+        if let Some(url) = url.and_then(|x| x.absoluteString()) {
+            notes = format!("{url}\n{notes}");
+        }
+        Some(Event {
+            title: text(title),
+            start: Timestamp::from_second(start?.timeIntervalSince1970() as i64).ok()?,
+            end: Timestamp::from_second(end?.timeIntervalSince1970() as i64).ok()?,
+            all_day,
+            location: text(location),
+            notes,
+            calendar: text(calendar_title),
+        })
+    }
+}
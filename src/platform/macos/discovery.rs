@@ -184,7 +184,7 @@ fn is_in_user_app_directory(path: &Path) -> bool {
 
 /// Extracts application metadata from a bundle URL.
 ///
-/// Queries the bundle's `Info.plist` for display name and icon, with the
+/// Queries the bundle's `Info.plist` for display name, version and icon, with the
 /// following fallback chain for the app name:
 /// 1. `CFBundleDisplayName` - localized display name
 /// 2. `CFBundleName` - short bundle name
@@ -270,6 +270,9 @@ fn query_app(url: impl AsRef<NSURL>, store_icons: bool) -> Option<App> {
         desc: "Application".to_string(),
         icons,
         open_command: AppCommand::Function(Function::OpenApp(path.to_string_lossy().into_owned())),
+        version: get_string(ns_string!("CFBundleShortVersionString")),
+        last_used: None,
+        children: None,
     })
 }
 
@@ -0,0 +1,136 @@
+//! Dragging results out of the window with an AppKit dragging session
+//!
+//! A dragging session needs a source that tells AppKit what the drop can do with what is dragged,
+//! which is a small class that copies (so that dropping a file moves or deletes nothing). Since
+//! iced handles the mouse itself, the session is started from the mouse event AppKit is handling,
+//! or one made at the pointer if it has none
+
+use std::cell::OnceCell;
+
+use iced::wgpu::rwh::WindowHandle;
+use objc2::{
+    AllocAnyThread, MainThreadMarker, MainThreadOnly, define_class, msg_send,
+    rc::Retained,
+    runtime::{AnyObject, NSObject, ProtocolObject},
+};
+use objc2_app_kit::{
+    NSApp, NSDragOperation, NSDraggingContext, NSDraggingItem, NSDraggingSession, NSDraggingSource,
+    NSEvent, NSEventModifierFlags, NSEventType, NSImage, NSPasteboardWriting, NSWorkspace,
+};
+use objc2_foundation::{NSArray, NSObjectProtocol, NSPoint, NSRect, NSSize, NSString, NSURL};
+
+use crate::drag::Dragged;
+
+/// How big the image that follows the pointer is
+const DRAG_IMAGE_SIZE: f64 = 32.0;
+
+define_class!(
+    // SAFETY: NSObject has no subclassing requirements, and the source doesn't implement Drop
+    #[unsafe(super(NSObject))]
+    #[thread_kind = MainThreadOnly]
+    #[name = "RustcastDraggingSource"]
+    struct DraggingSource;
+
+    unsafe impl NSObjectProtocol for DraggingSource {}
+
+    unsafe impl NSDraggingSource for DraggingSource {
+        #[unsafe(method(draggingSession:sourceOperationMaskForDraggingContext:))]
+        fn operation_mask(
+            &self,
+            _session: &NSDraggingSession,
+            _context: NSDraggingContext,
+        ) -> NSDragOperation {
+            NSDragOperation::Copy
+        }
+    }
+);
+
+impl DraggingSource {
+    fn new(mtm: MainThreadMarker) -> Retained<Self> {
+        let this = Self::alloc(mtm).set_ivars(());
+        // SAFETY: NSObject's init takes nothing and gives the initialised object
+        unsafe { msg_send![super(this), init] }
+    }
+}
+
+thread_local! {
+    /// The source of every drag, which is kept since AppKit doesn't keep it while dragging
+    static SOURCE: OnceCell<Retained<DraggingSource>> = const { OnceCell::new() };
+}
+
+/// Start dragging something from the pointer's position in the window
+pub fn start_drag(handle: &WindowHandle, dragged: &Dragged) -> Result<(), String> {
+    let mtm = MainThreadMarker::new().ok_or("Dragging can only start on the main thread")?;
+    let view = super::ns_view(handle);
+    let window = view.window().ok_or("The view isn't in a window")?;
+
+    let (writer, image): (Retained<ProtocolObject<dyn NSPasteboardWriting>>, _) = match dragged {
+        Dragged::File(path) => {
+            let path = NSString::from_str(&path.to_string_lossy());
+            let image = NSWorkspace::sharedWorkspace().iconForFile(&path);
+            let url = NSURL::fileURLWithPath(&path);
+            (ProtocolObject::from_retained(url), Some(image))
+        }
+        Dragged::Url(url) => {
+            let url = NSURL::URLWithString(&NSString::from_str(url))
+                .ok_or_else(|| format!("{url} isn't a valid link"))?;
+            (ProtocolObject::from_retained(url), symbol("link"))
+        }
+        Dragged::Text(text) => (
+            ProtocolObject::from_retained(NSString::from_str(text)),
+            symbol("doc.plaintext"),
+        ),
+    };
+
+    let location = window.mouseLocationOutsideOfEventStream();
+    let point = view.convertPoint_fromView(location, None);
+    let frame = NSRect::new(
+        NSPoint::new(
+            point.x - DRAG_IMAGE_SIZE / 2.0,
+            point.y - DRAG_IMAGE_SIZE / 2.0,
+        ),
+        NSSize::new(DRAG_IMAGE_SIZE, DRAG_IMAGE_SIZE),
+    );
+    let item = NSDraggingItem::initWithPasteboardWriter(NSDraggingItem::alloc(), &writer);
+    let contents: Option<&AnyObject> = image.as_deref().map(|x| x.as_ref());
+    // SAFETY: The contents of a dragging frame are an NSImage (or nothing)
+    unsafe { item.setDraggingFrame_contents(frame, contents) };
+
+    let event = NSApp(mtm)
+        .currentEvent()
+        .filter(|x| {
+            matches!(
+                x.r#type(),
+                NSEventType::LeftMouseDown | NSEventType::LeftMouseDragged
+            )
+        })
+        .or_else(|| {
+            NSEvent::mouseEventWithType_location_modifierFlags_timestamp_windowNumber_context_eventNumber_clickCount_pressure(
+                NSEventType::LeftMouseDragged,
+                location,
+                NSEventModifierFlags::empty(),
+                0.0,
+                window.windowNumber(),
+                None,
+                0,
+                1,
+                1.0,
+            )
+        })
+        .ok_or("Could not find the mouse event to drag from")?;
+
+    SOURCE.with(|source| {
+        let source = source.get_or_init(|| DraggingSource::new(mtm));
+        view.beginDraggingSessionWithItems_event_source(
+            &NSArray::from_retained_slice(&[item]),
+            &event,
+            ProtocolObject::from_ref(&**source),
+        );
+    });
+    Ok(())
+}
+
+/// An SF Symbol, for what is dragged when it isn't a file
+fn symbol(name: &str) -> Option<Retained<NSImage>> {
+    NSImage::imageWithSystemSymbolName_accessibilityDescription(&NSString::from_str(name), None)
+}
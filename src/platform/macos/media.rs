@@ -0,0 +1,67 @@
+//! Asking Spotify or Music what's playing, and controlling them, through AppleScript
+//!
+//! Spotify is asked first, as it is the one that is usually playing if both are open. An app that
+//! isn't running isn't asked, so that asking doesn't open it
+
+use std::process::Command;
+
+use crate::media::{MediaAction, NowPlaying};
+
+/// The players that are asked, in order
+const PLAYERS: [&str; 2] = ["Spotify", "Music"];
+
+/// The track that Spotify or Music is playing (or has paused)
+pub fn now_playing() -> Option<NowPlaying> {
+    PLAYERS.into_iter().find_map(|player| {
+        // The fields are on their own lines, as track names can have anything else in them
+        let script = format!(
+            "if application \"{player}\" is running then
+                tell application \"{player}\"
+                    if player state is stopped then return \"\"
+                    return (name of current track) & linefeed & (artist of current track) \
+                    & linefeed & (player state as string)
+                end tell
+            end if
+            return \"\""
+        );
+        let output = run(&script).ok()?;
+        let mut lines = output.lines();
+        let title = lines.next().filter(|x| !x.is_empty())?.to_string();
+        let artist = lines.next().unwrap_or_default().to_string();
+        let playing = lines.next() == Some("playing");
+        Some(NowPlaying {
+            title,
+            artist,
+            player: player.to_string(),
+            playing,
+        })
+    })
+}
+
+/// Play or pause the player that has a track, or skip to its next or previous one
+pub fn media_control(action: MediaAction) -> Result<(), String> {
+    let player = now_playing()
+        .map(|x| x.player)
+        .ok_or("Neither Spotify nor Music has a track to play")?;
+    let command = match action {
+        MediaAction::PlayPause => "playpause",
+        MediaAction::Next => "next track",
+        MediaAction::Previous => "previous track",
+    };
+    run(&format!("tell application \"{player}\" to {command}")).map(|_| ())
+}
+
+fn run(script: &str) -> Result<String, String> {
+    let output = Command::new("osascript")
+        .arg("-e")
+        .arg(script)
+        .output()
+        .map_err(|e| format!("Could not run osascript: {e}"))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .trim_end()
+            .to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
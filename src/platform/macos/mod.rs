@@ -1,36 +1,85 @@
 //! Macos specific logic, such as window settings, etc.
+pub mod appearance;
+pub mod calendar;
+pub mod contacts;
 pub mod discovery;
+pub mod drag;
 pub mod haptics;
 pub mod launching;
+pub mod media;
+pub mod ocr;
+pub mod paste;
+pub mod quit;
 
 use iced::wgpu::rwh::WindowHandle;
 
 pub(super) use self::discovery::get_installed_apps;
 pub(super) use self::haptics::perform_haptic;
+pub(super) use self::paste::paste;
 
 use objc2_service_management::SMAppService;
 
-pub fn start_at_login() {
-    unsafe {
-        SMAppService::mainAppService().registerAndReturnError().ok();
-    }
+use std::{fs, path::PathBuf};
+
+use crate::{clipboard::ClipboardSource, commands::ShellCommand, config::OpenOn};
+
+/// The LaunchAgent that starts rustcast at login
+fn launch_agent_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or(PathBuf::from("/"))
+        .join("Library/LaunchAgents/com.umangsurana.rustcast.plist")
 }
 
-pub fn stop_at_login() {
-    unsafe {
-        SMAppService::mainAppService()
-            .unregisterAndReturnError()
-            .ok();
-    }
+pub(super) fn autostart_enabled() -> bool {
+    launch_agent_path().exists()
 }
 
-pub fn get_autostart_status() -> bool {
-    unsafe {
-        SMAppService::mainAppService()
-            .registerAndReturnError()
-            .ok()
-            .is_some()
+pub(super) fn set_autostart(enabled: bool) -> Result<(), String> {
+    let path = launch_agent_path();
+    if !enabled {
+        // Older versions registered rustcast as a login item instead, which is removed as well
+        unsafe {
+            SMAppService::mainAppService()
+                .unregisterAndReturnError()
+                .ok();
+        }
+        return match fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(format!("Could not remove {}: {e}", path.display()))
+            }
+            _ => Ok(()),
+        };
+    }
+
+    let exe = std::env::current_exe()
+        .map_err(|e| format!("Could not find the rustcast executable: {e}"))?;
+    let exe = exe
+        .to_string_lossy()
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+    let plist = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>com.umangsurana.rustcast</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{exe}</string>
+    </array>
+    <key>RunAtLoad</key>
+    <true/>
+</dict>
+</plist>
+"#
+    );
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).ok();
     }
+    fs::write(&path, plist).map_err(|e| format!("Could not write {}: {e}", path.display()))
 }
 
 /// This sets the activation policy of the app to Accessory, allowing rustcast to be visible ontop
@@ -46,22 +95,130 @@ pub(super) fn set_activation_policy_accessory() {
 
 /// This carries out the window configuration for the macos window (only things that are macos specific)
 pub(super) fn macos_window_config(handle: &WindowHandle) {
+    use objc2_app_kit::{NSFloatingWindowLevel, NSWindowCollectionBehavior};
+
+    let ns_window = ns_window(handle);
+    ns_window.setLevel(NSFloatingWindowLevel);
+
+    ns_window.setCollectionBehavior(NSWindowCollectionBehavior::CanJoinAllSpaces);
+}
+
+pub(super) fn set_opacity(handle: &WindowHandle, opacity: f32) {
+    ns_window(handle).setAlphaValue(opacity as f64);
+}
+
+/// Whether Reduce motion is turned on in the accessibility settings
+pub(super) fn prefers_reduced_motion() -> bool {
+    objc2_app_kit::NSWorkspace::sharedWorkspace().accessibilityDisplayShouldReduceMotion()
+}
+
+pub(super) fn set_hidden_from_capture(handle: &WindowHandle, hidden: bool) {
+    use objc2_app_kit::NSWindowSharingType;
+
+    ns_window(handle).setSharingType(if hidden {
+        NSWindowSharingType::None
+    } else {
+        NSWindowSharingType::ReadOnly
+    });
+}
+
+/// Have VoiceOver read out some text, if it is running
+pub(super) fn announce(text: &str) {
+    use objc2::{MainThreadMarker, runtime::AnyObject};
+    use objc2_app_kit::{
+        NSAccessibilityAnnouncementKey, NSAccessibilityAnnouncementRequestedNotification,
+        NSAccessibilityPostNotificationWithUserInfo, NSAccessibilityPriorityKey,
+        NSAccessibilityPriorityLevel, NSApp, NSWorkspace,
+    };
+    use objc2_foundation::{NSDictionary, NSNumber, NSString};
+
+    if !NSWorkspace::sharedWorkspace().isVoiceOverEnabled() {
+        return;
+    }
+    let Some(mtm) = MainThreadMarker::new() else {
+        return;
+    };
+
+    let text = NSString::from_str(text);
+    // High priority interrupts the last announcement, so moving quickly through the results only
+    // reads out the one that ends up focused
+    let priority = NSNumber::new_isize(NSAccessibilityPriorityLevel::High.0);
+    let objects: [&AnyObject; 2] = [&text, &priority];
+    let user_info = unsafe {
+        NSDictionary::from_slices(
+            &[NSAccessibilityAnnouncementKey, NSAccessibilityPriorityKey],
+            &objects,
+        )
+    };
+
+    unsafe {
+        NSAccessibilityPostNotificationWithUserInfo(
+            &NSApp(mtm),
+            NSAccessibilityAnnouncementRequestedNotification,
+            Some(&user_info),
+        );
+    }
+}
+
+/// Put a vibrancy view behind the window's contents, or take it away
+pub(super) fn set_blur(handle: &WindowHandle, blur: bool, radius: f32) {
+    use objc2::{ClassType, MainThreadMarker, MainThreadOnly};
+    use objc2_app_kit::{
+        NSAutoresizingMaskOptions, NSVisualEffectBlendingMode, NSVisualEffectMaterial,
+        NSVisualEffectState, NSVisualEffectView, NSWindowOrderingMode,
+    };
+    use objc2_foundation::NSObjectProtocol;
+
+    let Some(content_view) = ns_window(handle).contentView() else {
+        return;
+    };
+
+    // Take away the view from the last time the blur was set
+    for subview in content_view.subviews().iter() {
+        if subview.isKindOfClass(NSVisualEffectView::class()) {
+            subview.removeFromSuperview();
+        }
+    }
+    if !blur {
+        return;
+    }
+
+    let mtm = MainThreadMarker::new().expect("must be on main thread");
+    let effect_view =
+        NSVisualEffectView::initWithFrame(NSVisualEffectView::alloc(mtm), content_view.bounds());
+    effect_view.setMaterial(NSVisualEffectMaterial::HUDWindow);
+    effect_view.setBlendingMode(NSVisualEffectBlendingMode::BehindWindow);
+    effect_view.setState(NSVisualEffectState::Active);
+    effect_view.setAutoresizingMask(
+        NSAutoresizingMaskOptions::ViewWidthSizable | NSAutoresizingMaskOptions::ViewHeightSizable,
+    );
+
+    // Round the corners to match the border around the contents
+    effect_view.setWantsLayer(true);
+    if let Some(layer) = effect_view.layer() {
+        layer.setCornerRadius(radius.into());
+        layer.setMasksToBounds(true);
+    }
+
+    content_view.addSubview_positioned_relativeTo(&effect_view, NSWindowOrderingMode::Below, None);
+}
+
+/// The AppKit window behind a window handle
+fn ns_window(handle: &WindowHandle) -> objc2::rc::Retained<objc2_app_kit::NSWindow> {
+    ns_view(handle)
+        .window()
+        .expect("view was not installed in a window")
+}
+
+/// The AppKit view behind a window handle, which has the window's contents
+fn ns_view(handle: &WindowHandle) -> objc2::rc::Retained<objc2_app_kit::NSView> {
     use iced::wgpu::rwh::RawWindowHandle;
     use objc2::rc::Retained;
-    use objc2_app_kit::NSView;
 
     match handle.as_raw() {
         RawWindowHandle::AppKit(handle) => {
             let ns_view = handle.ns_view.as_ptr();
-            let ns_view: Retained<NSView> = unsafe { Retained::retain(ns_view.cast()) }.unwrap();
-            let ns_window = ns_view
-                .window()
-                .expect("view was not installed in a window");
-
-            use objc2_app_kit::{NSFloatingWindowLevel, NSWindowCollectionBehavior};
-            ns_window.setLevel(NSFloatingWindowLevel);
-
-            ns_window.setCollectionBehavior(NSWindowCollectionBehavior::CanJoinAllSpaces);
+            unsafe { Retained::retain(ns_view.cast()) }.unwrap()
         }
         _ => {
             panic!(
@@ -71,6 +228,433 @@ pub(super) fn macos_window_config(handle: &WindowHandle) {
     }
 }
 
+/// The general pasteboard's change count, which goes up every time something is copied
+pub(super) fn clipboard_change_count() -> isize {
+    objc2_app_kit::NSPasteboard::generalPasteboard().changeCount()
+}
+
+/// Gets the name and bundle identifier of the frontmost app
+pub(super) fn frontmost_app() -> Option<ClipboardSource> {
+    use objc2_app_kit::NSWorkspace;
+
+    let app = NSWorkspace::sharedWorkspace().frontmostApplication()?;
+    Some(ClipboardSource {
+        name: app.localizedName()?.to_string(),
+        bundle_id: app.bundleIdentifier().map(|x| x.to_string()),
+    })
+}
+
+/// The app that had the focus before rustcast's window opened
+pub type PreviousFocus = objc2::rc::Retained<objc2_app_kit::NSRunningApplication>;
+
+/// Gets the frontmost application to focus later
+pub(super) fn previous_focus() -> Option<PreviousFocus> {
+    objc2_app_kit::NSWorkspace::sharedWorkspace().frontmostApplication()
+}
+
+/// Activates the app that was frontmost before the window opened
+#[allow(deprecated)]
+pub(super) fn restore_focus(app: PreviousFocus) {
+    use objc2_app_kit::NSApplicationActivationOptions;
+
+    app.activateWithOptions(NSApplicationActivationOptions::ActivateIgnoringOtherApps);
+}
+
+/// Gets the frame of the monitor to open on, converted from AppKit's coordinates (which start at
+/// the bottom left of the primary screen) to ones that start at the top left
+///
+/// The active window's monitor is the screen with the active menu bar, which follows the focused
+/// window when displays have separate spaces
+pub(super) fn monitor_frame(open_on: OpenOn) -> Option<iced::Rectangle> {
+    use objc2::MainThreadMarker;
+    use objc2_app_kit::{NSEvent, NSScreen};
+
+    let mtm = MainThreadMarker::new()?;
+    let screens = NSScreen::screens(mtm);
+    let primary = screens.firstObject()?;
+
+    let screen = match open_on {
+        OpenOn::Primary => primary.clone(),
+        OpenOn::ActiveWindowMonitor => NSScreen::mainScreen(mtm)?,
+        OpenOn::CursorMonitor => {
+            let mouse = NSEvent::mouseLocation();
+            screens.iter().find(|screen| {
+                let frame = screen.frame();
+                mouse.x >= frame.origin.x
+                    && mouse.x <= frame.origin.x + frame.size.width
+                    && mouse.y >= frame.origin.y
+                    && mouse.y <= frame.origin.y + frame.size.height
+            })?
+        }
+    };
+
+    let frame = screen.frame();
+    let primary_height = primary.frame().size.height;
+    Some(iced::Rectangle {
+        x: frame.origin.x as f32,
+        y: (primary_height - frame.origin.y - frame.size.height) as f32,
+        width: frame.size.width as f32,
+        height: frame.size.height as f32,
+    })
+}
+
+/// Watches directories with kqueue, which tells us when an entry in one of them is added, removed
+/// or renamed
+pub(super) fn watch_dirs(dirs: &[String], mut on_change: impl FnMut()) -> Result<(), String> {
+    use std::ffi::CString;
+
+    let kq = unsafe { libc::kqueue() };
+    if kq < 0 {
+        return Err(std::io::Error::last_os_error().to_string());
+    }
+
+    let changes: Vec<libc::kevent> = dirs
+        .iter()
+        .filter_map(|dir| CString::new(dir.as_str()).ok())
+        .map(|dir| unsafe { libc::open(dir.as_ptr(), libc::O_EVTONLY) })
+        .filter(|fd| *fd >= 0)
+        .map(|fd| libc::kevent {
+            ident: fd as usize,
+            filter: libc::EVFILT_VNODE,
+            flags: libc::EV_ADD | libc::EV_CLEAR,
+            fflags: libc::NOTE_WRITE,
+            data: 0,
+            udata: std::ptr::null_mut(),
+        })
+        .collect();
+    if changes.is_empty() {
+        unsafe { libc::close(kq) };
+        return Err("none of the directories could be opened".to_string());
+    }
+
+    let registered = unsafe {
+        libc::kevent(
+            kq,
+            changes.as_ptr(),
+            changes.len() as i32,
+            std::ptr::null_mut(),
+            0,
+            std::ptr::null(),
+        )
+    };
+    let result = if registered < 0 {
+        Err(std::io::Error::last_os_error().to_string())
+    } else {
+        let mut events: [libc::kevent; 16] = unsafe { std::mem::zeroed() };
+        loop {
+            let count = unsafe {
+                libc::kevent(
+                    kq,
+                    std::ptr::null(),
+                    0,
+                    events.as_mut_ptr(),
+                    events.len() as i32,
+                    std::ptr::null(),
+                )
+            };
+            if count > 0 {
+                on_change();
+            } else if count < 0 {
+                let error = std::io::Error::last_os_error();
+                if error.kind() != std::io::ErrorKind::Interrupted {
+                    break Err(error.to_string());
+                }
+            }
+        }
+    };
+
+    for change in changes {
+        unsafe { libc::close(change.ident as i32) };
+    }
+    unsafe { libc::close(kq) };
+    result
+}
+
+/// Opens a url with NSWorkspace, off the main thread
+pub(super) fn open_url(url: &str) -> Result<(), String> {
+    use objc2_app_kit::NSWorkspace;
+    use objc2_foundation::{NSString, NSURL};
+
+    NSURL::URLWithString_relativeToURL(&NSString::from_str(url), None)
+        .ok_or(format!("{url} is not a valid url"))?;
+
+    let url = url.to_owned();
+    std::thread::spawn(move || {
+        if let Some(url) = NSURL::URLWithString_relativeToURL(&NSString::from_str(&url), None) {
+            NSWorkspace::new().openURL(&url);
+        }
+    });
+    Ok(())
+}
+
+/// Opens a file path with NSWorkspace, off the main thread
+pub(super) fn open_path(path: &str) -> Result<(), String> {
+    use objc2_app_kit::NSWorkspace;
+    use objc2_foundation::{NSString, NSURL};
+
+    if !std::path::Path::new(path).exists() {
+        return Err(format!("{path} does not exist"));
+    }
+
+    let path = path.to_owned();
+    std::thread::spawn(move || {
+        NSWorkspace::new().openURL(&NSURL::fileURLWithPath(&NSString::from_str(&path)));
+    });
+    Ok(())
+}
+
+/// Selects a file path in Finder with NSWorkspace, off the main thread
+pub(super) fn reveal_path(path: &str) -> Result<(), String> {
+    use objc2_app_kit::NSWorkspace;
+    use objc2_foundation::{NSArray, NSString, NSURL};
+
+    if !std::path::Path::new(path).exists() {
+        return Err(format!("{path} does not exist"));
+    }
+
+    let path = path.to_owned();
+    std::thread::spawn(move || {
+        let url = NSURL::fileURLWithPath(&NSString::from_str(&path));
+        NSWorkspace::new().activateFileViewerSelectingURLs(&NSArray::from_retained_slice(&[url]));
+    });
+    Ok(())
+}
+
+/// The apps that Launch Services says can open a file path or url
+pub(super) fn apps_for(target: &str) -> Vec<(String, String)> {
+    use objc2_app_kit::NSWorkspace;
+    use objc2_foundation::{NSString, NSURL};
+
+    let url = if std::path::Path::new(target).exists() {
+        NSURL::fileURLWithPath(&NSString::from_str(target))
+    } else {
+        match NSURL::URLWithString_relativeToURL(&NSString::from_str(target), None) {
+            Some(url) => url,
+            None => return vec![],
+        }
+    };
+
+    let mut apps: Vec<(String, String)> = vec![];
+    for app in NSWorkspace::new().URLsForApplicationsToOpenURL(&url) {
+        let Some(path) = app.path().map(|x| x.to_string()) else {
+            continue;
+        };
+        if apps.iter().any(|(_, x)| *x == path) {
+            continue;
+        }
+        let name = std::path::Path::new(&path)
+            .file_stem()
+            .map(|x| x.to_string_lossy().to_string())
+            .unwrap_or(path.clone());
+        apps.push((name, path));
+    }
+    apps
+}
+
+/// Opens a file path or url with an app, through `open -a`
+pub(super) fn open_with(target: &str, app: &str) -> Result<(), String> {
+    std::process::Command::new("open")
+        .args(["-a", app, target])
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Could not open {target} with {app}: {e}"))
+}
+
+/// Whether the path is an app bundle, which is all [`uninstall_app`] moves to the Trash
+pub(super) fn can_uninstall(path: &str) -> bool {
+    path.ends_with(".app")
+}
+
+/// Moves an app to the Trash with NSFileManager, like dragging it there in Finder
+pub(super) fn uninstall_app(path: &str) -> Result<(), String> {
+    use objc2_foundation::{NSFileManager, NSString, NSURL};
+
+    if !can_uninstall(path) {
+        return Err(format!("{path} is not an app bundle"));
+    }
+
+    let url = NSURL::fileURLWithPath(&NSString::from_str(path));
+    NSFileManager::defaultManager()
+        .trashItemAtURL_resultingItemURL_error(&url, None)
+        .map_err(|e| {
+            format!(
+                "Could not move {path} to the Trash: {}",
+                e.localizedDescription()
+            )
+        })
+}
+
+/// Turn Wi-Fi off if it's on, and on if it's off, with `networksetup`
+pub(super) fn toggle_wifi() -> Result<(), String> {
+    use std::process::Command;
+
+    let networksetup = |args: &[&str]| {
+        Command::new("networksetup")
+            .args(args)
+            .output()
+            .map(|x| String::from_utf8_lossy(&x.stdout).to_string())
+            .map_err(|e| format!("Could not run networksetup: {e}"))
+    };
+
+    // The ports are listed like `Hardware Port: Wi-Fi` followed by `Device: en0`
+    let ports = networksetup(&["-listallhardwareports"])?;
+    let device = ports
+        .split("Hardware Port: ")
+        .find(|x| x.starts_with("Wi-Fi") || x.starts_with("AirPort"))
+        .and_then(|x| x.lines().find_map(|x| x.strip_prefix("Device: ")))
+        .ok_or("This Mac has no Wi-Fi")?
+        .trim()
+        .to_string();
+
+    let is_on = networksetup(&["-getairportpower", &device])?
+        .trim()
+        .ends_with("On");
+    networksetup(&[
+        "-setairportpower",
+        &device,
+        if is_on { "off" } else { "on" },
+    ])?;
+    Ok(())
+}
+
+#[link(name = "IOBluetooth", kind = "framework")]
+unsafe extern "C" {
+    fn IOBluetoothPreferenceGetControllerPowerState() -> i32;
+    fn IOBluetoothPreferenceSetControllerPowerState(state: i32);
+}
+
+/// Turn Bluetooth off if it's on, and on if it's off, with the same IOBluetooth functions that
+/// `blueutil` uses, as macOS has no command for it
+pub(super) fn toggle_bluetooth() -> Result<(), String> {
+    unsafe {
+        let is_on = IOBluetoothPreferenceGetControllerPowerState() != 0;
+        IOBluetoothPreferenceSetControllerPowerState(if is_on { 0 } else { 1 });
+    }
+    Ok(())
+}
+
+#[link(name = "IOKit", kind = "framework")]
+unsafe extern "C" {
+    fn IOPMAssertionCreateWithName(
+        assertion_type: *const std::ffi::c_void,
+        level: u32,
+        name: *const std::ffi::c_void,
+        id: *mut u32,
+    ) -> i32;
+    fn IOPMAssertionRelease(id: u32) -> i32;
+}
+
+/// An IOPMAssertion that keeps the display (and so the Mac) awake, which is released when it is
+/// dropped
+pub struct SleepAssertion(u32);
+
+impl SleepAssertion {
+    pub(super) fn new() -> Result<Self, String> {
+        use objc2_foundation::NSString;
+
+        /// `kIOPMAssertionLevelOn`
+        const ASSERTION_LEVEL_ON: u32 = 255;
+
+        // NSStrings are toll-free bridged to the CFStrings that IOKit takes
+        let assertion_type = NSString::from_str("PreventUserIdleDisplaySleep");
+        let name = NSString::from_str("RustCast is preventing sleep");
+        let mut id = 0;
+        let result = unsafe {
+            IOPMAssertionCreateWithName(
+                objc2::rc::Retained::as_ptr(&assertion_type).cast(),
+                ASSERTION_LEVEL_ON,
+                objc2::rc::Retained::as_ptr(&name).cast(),
+                &mut id,
+            )
+        };
+
+        if result == 0 {
+            Ok(SleepAssertion(id))
+        } else {
+            Err(format!("Could not prevent sleep (IOKit error {result})"))
+        }
+    }
+}
+
+impl Drop for SleepAssertion {
+    fn drop(&mut self) {
+        unsafe { IOPMAssertionRelease(self.0) };
+    }
+}
+
+/// The processes listening on a TCP port, by their pid and name, from `lsof`
+pub(super) fn listening_processes(port: u16) -> Vec<(u32, String)> {
+    let Ok(output) = std::process::Command::new("lsof")
+        .args(["-nP", &format!("-iTCP:{port}"), "-sTCP:LISTEN", "-Fpc"])
+        .output()
+    else {
+        return vec![];
+    };
+
+    // Each process is a `p<pid>` line followed by a `c<name>` line
+    let mut processes: Vec<(u32, String)> = vec![];
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if let Some(pid) = line.strip_prefix('p').and_then(|x| x.parse().ok()) {
+            processes.push((pid, String::new()));
+        } else if let Some(name) = line.strip_prefix('c')
+            && let Some((_, process_name)) = processes.last_mut()
+        {
+            *process_name = name.to_string();
+        }
+    }
+    processes
+}
+
+/// Runs a command in iTerm if it is installed, and Terminal otherwise, through AppleScript
+pub(super) fn run_in_terminal(command: &ShellCommand) -> Result<(), String> {
+    let command = command.script().replace('\\', "\\\\").replace('"', "\\\"");
+    let script = if std::path::Path::new("/Applications/iTerm.app").exists() {
+        format!(
+            "tell application \"iTerm\"
+                activate
+                set newWindow to (create window with default profile)
+                tell current session of newWindow to write text \"{command}\"
+            end tell"
+        )
+    } else {
+        format!(
+            "tell application \"Terminal\"
+                activate
+                do script \"{command}\"
+            end tell"
+        )
+    };
+
+    std::process::Command::new("osascript")
+        .arg("-e")
+        .arg(script)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Could not open a terminal: {e}"))
+}
+
+/// Quote text as an AppleScript string
+pub(crate) fn applescript_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+pub(super) fn add_reminder(text: &str, list: Option<&str>) -> Result<(), String> {
+    let list = list
+        .map(|x| format!(" at end of list {}", applescript_string(x)))
+        .unwrap_or_default();
+    let script = format!(
+        "tell application \"Reminders\" to make new reminder{list} with properties {{name:{}}}",
+        applescript_string(text)
+    );
+
+    std::process::Command::new("osascript")
+        .arg("-e")
+        .arg(script)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Could not add a reminder: {e}"))
+}
+
 /// This is the function that forces focus onto rustcast
 #[allow(deprecated)]
 pub(super) fn focus_this_app() {
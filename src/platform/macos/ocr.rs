@@ -0,0 +1,64 @@
+//! Reading the text in images with the Vision framework
+//!
+//! There are no bindings for Vision in rustcast's dependencies, so its classes are found by name
+//! and messaged with `msg_send!`, which checks the messages against the classes' methods in debug
+//! builds
+
+use std::path::Path;
+
+use objc2::{
+    msg_send,
+    rc::{Allocated, Retained},
+    runtime::{AnyClass, AnyObject},
+};
+use objc2_foundation::{NSArray, NSDictionary, NSError, NSString, NSURL};
+
+#[link(name = "Vision", kind = "framework")]
+unsafe extern "C" {}
+
+/// `VNRequestTextRecognitionLevelAccurate`
+const RECOGNITION_LEVEL_ACCURATE: isize = 0;
+
+/// The text in an image file, with a line for each line of text that was found
+pub fn recognize_text(path: &Path) -> Result<String, String> {
+    let handler_class =
+        AnyClass::get(c"VNImageRequestHandler").ok_or("The Vision framework couldn't be loaded")?;
+    let request_class = AnyClass::get(c"VNRecognizeTextRequest")
+        .ok_or("Text recognition needs macOS 10.15 or later")?;
+
+    let url = NSURL::fileURLWithPath(&NSString::from_str(&path.to_string_lossy()));
+    let options = NSDictionary::<AnyObject, AnyObject>::new();
+
+    // SAFETY: The messages match the methods of `VNImageRequestHandler`, `VNRecognizeTextRequest`
+    // and `VNRecognizedTextObservation`, and the objects are only used on this thread
+    unsafe {
+        let handler: Allocated<AnyObject> = msg_send![handler_class, alloc];
+        let handler: Retained<AnyObject> =
+            msg_send![handler, initWithURL: &*url, options: &*options];
+
+        let request: Retained<AnyObject> = msg_send![request_class, new];
+        let _: () = msg_send![&*request, setRecognitionLevel: RECOGNITION_LEVEL_ACCURATE];
+        let _: () = msg_send![&*request, setUsesLanguageCorrection: true];
+
+        let requests = NSArray::from_retained_slice(std::slice::from_ref(&request));
+        let performed: Result<(), Retained<NSError>> =
+            msg_send![&*handler, performRequests: &*requests, error: _];
+        performed
+            .map_err(|e| format!("Could not recognize the text: {}", e.localizedDescription()))?;
+
+        let observations: Option<Retained<NSArray<AnyObject>>> = msg_send![&*request, results];
+        let lines: Vec<String> = observations
+            .iter()
+            .flat_map(|x| x.iter())
+            .filter_map(|observation| {
+                let candidates: Retained<NSArray<AnyObject>> =
+                    msg_send![&*observation, topCandidates: 1usize];
+                let candidate = candidates.firstObject()?;
+                let text: Retained<NSString> = msg_send![&*candidate, string];
+                Some(text.to_string())
+            })
+            .collect();
+
+        Ok(lines.join("\n"))
+    }
+}
@@ -0,0 +1,37 @@
+//! Synthesizes the paste keystroke so that rustcast can paste into the frontmost app
+//! This needs rustcast to be granted accessibility permissions, otherwise the events are dropped
+use std::ffi::c_void;
+
+use objc2_core_foundation::CFType;
+
+/// The virtual key code for the V key on an ANSI keyboard
+const KEY_CODE_V: u16 = 9;
+
+const EVENT_FLAG_COMMAND: u64 = 1 << 20;
+
+/// `kCGHIDEventTap`, which posts the event as if it came from the keyboard
+const HID_EVENT_TAP: u32 = 0;
+
+#[link(name = "CoreGraphics", kind = "framework")]
+unsafe extern "C" {
+    fn CGEventCreateKeyboardEvent(source: *const c_void, key: u16, key_down: bool) -> *mut CFType;
+    fn CGEventSetFlags(event: *mut CFType, flags: u64);
+    fn CGEventPost(tap: u32, event: *mut CFType);
+    fn CFRelease(cf: *mut CFType);
+}
+
+/// Press and release Cmd+V
+pub fn paste() -> Result<(), String> {
+    for key_down in [true, false] {
+        unsafe {
+            let event = CGEventCreateKeyboardEvent(std::ptr::null(), KEY_CODE_V, key_down);
+            if event.is_null() {
+                return Err("Could not create the paste keystroke".to_string());
+            }
+            CGEventSetFlags(event, EVENT_FLAG_COMMAND);
+            CGEventPost(HID_EVENT_TAP, event);
+            CFRelease(event);
+        }
+    }
+    Ok(())
+}
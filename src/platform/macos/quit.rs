@@ -7,9 +7,11 @@ use objc2_foundation::NSString;
 use crate::{
     app::apps::{App, AppCommand},
     commands::Function,
-    platform::macos::discovery::icon_of_path_ns,
+    platform,
 };
 
+use super::discovery::icon_of_path_ns;
+
 pub fn get_open_apps(store_icons: bool) -> Vec<App> {
     let open_apps = NSWorkspace::sharedWorkspace().runningApplications();
 
@@ -47,6 +49,9 @@ pub fn get_open_apps(store_icons: bool) -> Vec<App> {
                 icons,
                 search_name: format!("quit {}", name.to_lowercase()),
                 desc: name.to_string(),
+                version: None,
+                last_used: None,
+                children: None,
             })
         })
         .collect()
@@ -66,6 +71,42 @@ pub fn terminate_app(name: String) {
     }
 }
 
+/// The bundle paths of the running apps
+pub fn running_app_paths() -> Vec<String> {
+    NSWorkspace::sharedWorkspace()
+        .runningApplications()
+        .iter()
+        .filter(|app| app.activationPolicy() == NSApplicationActivationPolicy::Regular)
+        .filter_map(|app| Some(app.bundleURL()?.path()?.to_string()))
+        .collect()
+}
+
+/// Ask the running apps with the given bundle path (or bundles inside it) to quit, or force them
+/// to
+pub fn quit_app_at(path: &str, force: bool) -> Result<(), String> {
+    let apps: Vec<_> = NSWorkspace::sharedWorkspace()
+        .runningApplications()
+        .into_iter()
+        .filter(|app| {
+            app.bundleURL()
+                .and_then(|x| x.path())
+                .is_some_and(|x| platform::runs_from(&x.to_string(), path))
+        })
+        .collect();
+    if apps.is_empty() {
+        return Err(format!("{path} is not running"));
+    }
+
+    for app in apps {
+        if force {
+            app.forceTerminate();
+        } else {
+            app.terminate();
+        }
+    }
+    Ok(())
+}
+
 pub fn terminate_all_apps() {
     let open_apps = NSWorkspace::sharedWorkspace().runningApplications();
     for app in open_apps {
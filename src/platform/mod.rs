@@ -1,23 +1,91 @@
 //! This handles all of the platform specific stuff.
+use std::collections::HashMap;
+
 use iced::wgpu::rwh::WindowHandle;
+use jiff::Timestamp;
 
 pub use self::cross::default_app_paths;
-use crate::app::apps::App;
+#[cfg(not(target_os = "macos"))]
+pub use self::cross::{PreviousFocus, SleepAssertion};
+#[cfg(target_os = "macos")]
+pub use self::macos::{PreviousFocus, SleepAssertion};
+use crate::{
+    app::{
+        apps::{App, AppCommand},
+        tile::ExtSender,
+    },
+    calendar::Event,
+    clipboard::ClipboardSource,
+    commands::{Function, ShellCommand},
+    config::{Theme, WindowConfig},
+    contacts::Contact,
+    drag::Dragged,
+    media::{MediaAction, NowPlaying},
+};
 
+#[cfg(target_os = "linux")]
+mod clipboard_watch;
+#[cfg(target_os = "linux")]
+pub mod compositor;
 pub mod cross;
 #[cfg(target_os = "macos")]
 pub mod macos;
+pub mod shortcut;
+
+pub use self::shortcut::Shortcut;
 
 pub fn set_activation_policy_accessory() {
     #[cfg(target_os = "macos")]
     self::macos::set_activation_policy_accessory();
 }
 
+#[cfg(target_os = "macos")]
 pub fn window_config(handle: &WindowHandle) {
-    #[cfg(target_os = "macos")]
     self::macos::macos_window_config(handle);
 }
 
+#[cfg(not(target_os = "macos"))]
+pub fn window_config(handle: &WindowHandle) {
+    let _ = handle;
+}
+
+/// Keep the window out of (or let it back into) screenshots, screen recordings and screen sharing
+#[cfg(target_os = "macos")]
+pub fn set_hidden_from_capture(handle: &WindowHandle, hidden: bool) {
+    self::macos::set_hidden_from_capture(handle, hidden);
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn set_hidden_from_capture(handle: &WindowHandle, hidden: bool) {
+    self::cross::set_hidden_from_capture(handle, hidden);
+}
+
+/// Blur what is behind the window, with vibrancy on macOS and the acrylic or mica backdrop on
+/// Windows
+#[cfg(target_os = "macos")]
+pub fn set_blur(handle: &WindowHandle, theme: &Theme) {
+    self::macos::set_blur(handle, theme.blur, theme.advanced.window_radius);
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn set_blur(handle: &WindowHandle, theme: &Theme) {
+    self::cross::set_blur(handle, theme.blur, theme.windows_backdrop);
+}
+
+/// Send the shortcuts that are pressed (in any app) to the tile as [`Message::KeyPressed`]
+///
+/// [`Message::KeyPressed`]: crate::app::Message::KeyPressed
+#[cfg(target_os = "macos")]
+pub fn listen_for_shortcuts(sender: ExtSender) {
+    self::macos::launching::global_handler(sender);
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn listen_for_shortcuts(sender: ExtSender) {
+    let _ = sender;
+    log::warn!("Global shortcuts are only supported on macOS");
+}
+
 pub fn focus_this_app() {
     #[cfg(target_os = "macos")]
     self::macos::focus_this_app();
@@ -47,12 +115,549 @@ pub fn perform_haptic(_: HapticPattern) -> bool {
     false
 }
 
+/// Set how opaque the window is, for fading it in and out
+///
+/// This only does anything on macOS for now
+#[cfg(target_os = "macos")]
+pub fn set_opacity(handle: &WindowHandle, opacity: f32) {
+    self::macos::set_opacity(handle, opacity);
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn set_opacity(_: &WindowHandle, _: f32) {}
+
+/// Whether the system asks apps to keep motion to a minimum, like Reduce motion on macOS
+#[cfg(target_os = "macos")]
+pub fn prefers_reduced_motion() -> bool {
+    self::macos::prefers_reduced_motion()
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn prefers_reduced_motion() -> bool {
+    false
+}
+
+/// Have the screen reader read out some text, like the result that was just focused
+///
+/// This works with VoiceOver on macOS and Orca on Linux, but not on Windows yet
+#[cfg(target_os = "macos")]
+pub fn announce(text: &str) {
+    self::macos::announce(text);
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn announce(text: &str) {
+    self::cross::announce(text);
+}
+
+/// Start dragging something out of the window, while the mouse button is held
+#[cfg(target_os = "macos")]
+pub fn start_drag(handle: &WindowHandle, dragged: &Dragged) -> Result<(), String> {
+    self::macos::drag::start_drag(handle, dragged)
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn start_drag(_: &WindowHandle, _: &Dragged) -> Result<(), String> {
+    Err("Dragging results out of rustcast is only supported on macOS for now".to_string())
+}
+
+/// Paste the clipboard into the frontmost app by sending it the paste keystroke, or why it can't
+#[cfg(target_os = "macos")]
+pub fn paste() -> Result<(), String> {
+    self::macos::paste()
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn paste() -> Result<(), String> {
+    self::cross::paste()
+}
+
+/// A number that changes whenever something is copied, so that the clipboard is only read after
+/// it changed, or `None` if the platform doesn't have one
+#[cfg(target_os = "macos")]
+pub fn clipboard_change_count() -> Option<isize> {
+    Some(self::macos::clipboard_change_count())
+}
+
+#[cfg(target_os = "linux")]
+pub fn clipboard_change_count() -> Option<isize> {
+    self::clipboard_watch::change_count()
+}
+
+#[cfg(target_os = "windows")]
+pub fn clipboard_change_count() -> Option<isize> {
+    Some(self::cross::clipboard_change_count())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+pub fn clipboard_change_count() -> Option<isize> {
+    None
+}
+
+/// The app that is currently frontmost, if it can be found
+#[cfg(target_os = "macos")]
+pub fn frontmost_app() -> Option<ClipboardSource> {
+    self::macos::frontmost_app()
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn frontmost_app() -> Option<ClipboardSource> {
+    None
+}
+
+/// The frame of the monitor to open the window on, in logical coordinates with the origin at the
+/// top left of the primary monitor
+#[cfg(target_os = "macos")]
+pub fn monitor_frame(config: &WindowConfig) -> Option<iced::Rectangle> {
+    self::macos::monitor_frame(config.open_on)
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn monitor_frame(config: &WindowConfig) -> Option<iced::Rectangle> {
+    self::cross::monitor_frame(config)
+}
+
+/// Tell the compositor to float the window (on Linux with Hyprland or Sway, when `compositor_ipc`
+/// is on), since tiling compositors would otherwise tile it like any other window
+#[cfg(target_os = "macos")]
+pub fn add_window_rules(config: &WindowConfig) {
+    let _ = config;
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn add_window_rules(config: &WindowConfig) {
+    self::cross::add_window_rules(config);
+}
+
+/// The app (or window) that has the focus, to give it back once the window hides
+#[cfg(target_os = "macos")]
+pub fn previous_focus(config: &WindowConfig) -> Option<PreviousFocus> {
+    let _ = config;
+    self::macos::previous_focus()
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn previous_focus(config: &WindowConfig) -> Option<PreviousFocus> {
+    self::cross::previous_focus(config)
+}
+
+/// Give the focus back to what had it before the window opened
+#[cfg(target_os = "macos")]
+pub fn restore_focus(focus: PreviousFocus) {
+    self::macos::restore_focus(focus);
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn restore_focus(focus: PreviousFocus) {
+    self::cross::restore_focus(focus);
+}
+
+/// Run a shell command in a new window of the user's terminal, which stays open afterwards so
+/// that the output can be read
+#[cfg(target_os = "macos")]
+pub fn run_in_terminal(command: &ShellCommand) -> Result<(), String> {
+    self::macos::run_in_terminal(command)
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn run_in_terminal(command: &ShellCommand) -> Result<(), String> {
+    self::cross::run_in_terminal(command)
+}
+
+/// Watch directories for entries being added, removed or renamed, calling `on_change` each time
+/// that happens
+///
+/// This blocks for as long as the directories are watched, and returns an error if they can't be
+#[cfg(target_os = "macos")]
+pub fn watch_dirs(dirs: &[String], on_change: impl FnMut()) -> Result<(), String> {
+    self::macos::watch_dirs(dirs, on_change)
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn watch_dirs(dirs: &[String], on_change: impl FnMut()) -> Result<(), String> {
+    self::cross::watch_dirs(dirs, on_change)
+}
+
+/// Open a url with the default app for it, e.g. a website in the default browser
+#[cfg(target_os = "macos")]
+pub fn open_url(url: &str) -> Result<(), String> {
+    self::macos::open_url(url)
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn open_url(url: &str) -> Result<(), String> {
+    self::cross::open(url)
+}
+
+/// Open a file, folder or app
+#[cfg(target_os = "macos")]
+pub fn open_path(path: &str) -> Result<(), String> {
+    self::macos::open_path(path)
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn open_path(path: &str) -> Result<(), String> {
+    self::cross::open(path)
+}
+
+/// Show a file, folder or app in the file manager, selected
+#[cfg(target_os = "macos")]
+pub fn reveal_path(path: &str) -> Result<(), String> {
+    self::macos::reveal_path(path)
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn reveal_path(path: &str) -> Result<(), String> {
+    self::cross::reveal(path)
+}
+
+/// The apps that can open a file or url, as their names and paths, with the default one first
+#[cfg(target_os = "macos")]
+pub fn apps_for(target: &str) -> Vec<(String, String)> {
+    self::macos::apps_for(target)
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn apps_for(target: &str) -> Vec<(String, String)> {
+    self::cross::apps_for(target)
+}
+
+/// Open a file or url with one of the apps from [`apps_for`]
+#[cfg(target_os = "macos")]
+pub fn open_with(target: &str, app: &str) -> Result<(), String> {
+    self::macos::open_with(target, app)
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn open_with(target: &str, app: &str) -> Result<(), String> {
+    self::cross::open_with(target, app)
+}
+
+/// The paths of the running apps, which are the bundle paths on macOS and the executables'
+/// paths elsewhere
+#[cfg(target_os = "macos")]
+pub fn running_app_paths() -> Vec<String> {
+    self::macos::quit::running_app_paths()
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn running_app_paths() -> Vec<String> {
+    self::cross::running_app_paths()
+}
+
+/// Whether a path from [`running_app_paths`] is the app at `path`, or a program inside it
+///
+/// Paths are compared by their components, so `/Applications/Foo.app` doesn't match
+/// `/Applications/Foo.app Beta.app`, and without their case on Windows, like its file system
+pub fn runs_from(running: &str, path: &str) -> bool {
+    if cfg!(target_os = "windows") {
+        return std::path::Path::new(&running.to_lowercase()).starts_with(path.to_lowercase());
+    }
+    std::path::Path::new(running).starts_with(path)
+}
+
+/// Quit the running app at the given path (and the programs inside it), or force them to quit
+#[cfg(target_os = "macos")]
+pub fn quit_app_at(path: &str, force: bool) -> Result<(), String> {
+    self::macos::quit::quit_app_at(path, force)
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn quit_app_at(path: &str, force: bool) -> Result<(), String> {
+    self::cross::quit_app_at(path, force)
+}
+
+/// Keep the computer from going to sleep, until the assertion is dropped
+pub fn prevent_sleep() -> Result<SleepAssertion, String> {
+    SleepAssertion::new()
+}
+
+/// The text in an image file, read with Vision on macOS
+#[cfg(target_os = "macos")]
+pub fn recognize_text(path: &std::path::Path) -> Result<String, String> {
+    self::macos::ocr::recognize_text(path)
+}
+
+/// The text in an image file, read with the `tesseract` CLI when rustcast is built with the
+/// `tesseract` feature
+#[cfg(not(target_os = "macos"))]
+pub fn recognize_text(path: &std::path::Path) -> Result<String, String> {
+    self::cross::recognize_text(path)
+}
+
+/// Switch between the light and dark appearance
+#[cfg(target_os = "macos")]
+pub fn toggle_dark_mode() -> Result<(), String> {
+    self::macos::appearance::toggle_dark_mode()
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn toggle_dark_mode() -> Result<(), String> {
+    self::cross::toggle_dark_mode()
+}
+
+/// Turn Night Shift off if it's on, and on if it's off, which only macOS has
+#[cfg(target_os = "macos")]
+pub fn toggle_night_shift() -> Result<(), String> {
+    self::macos::appearance::toggle_night_shift()
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn toggle_night_shift() -> Result<(), String> {
+    Err("Night Shift is only on macOS".to_string())
+}
+
+/// Turn True Tone off if it's on, and on if it's off, which only macOS has
+#[cfg(target_os = "macos")]
+pub fn toggle_true_tone() -> Result<(), String> {
+    self::macos::appearance::toggle_true_tone()
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn toggle_true_tone() -> Result<(), String> {
+    Err("True Tone is only on macOS".to_string())
+}
+
+/// Turn Wi-Fi off if it's on, and on if it's off
+#[cfg(target_os = "macos")]
+pub fn toggle_wifi() -> Result<(), String> {
+    self::macos::toggle_wifi()
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn toggle_wifi() -> Result<(), String> {
+    self::cross::toggle_wifi()
+}
+
+/// Turn Bluetooth off if it's on, and on if it's off
+#[cfg(target_os = "macos")]
+pub fn toggle_bluetooth() -> Result<(), String> {
+    self::macos::toggle_bluetooth()
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn toggle_bluetooth() -> Result<(), String> {
+    self::cross::toggle_bluetooth()
+}
+
+/// The events in the system's calendars that happen (at least partly) between two times, from
+/// Calendar on macOS and Outlook on Windows
+#[cfg(target_os = "macos")]
+pub fn calendar_events(from: Timestamp, to: Timestamp) -> Result<Vec<Event>, String> {
+    self::macos::calendar::events(from, to)
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn calendar_events(from: Timestamp, to: Timestamp) -> Result<Vec<Event>, String> {
+    self::cross::calendar_events(from, to)
+}
+
+/// Every person in Contacts, which only macOS has
+#[cfg(target_os = "macos")]
+pub fn contacts() -> Result<Vec<Contact>, String> {
+    self::macos::contacts::contacts()
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn contacts() -> Result<Vec<Contact>, String> {
+    Err("Contacts can only be searched on macOS".to_string())
+}
+
+/// The track that the media player is playing (or has paused), if there is one
+#[cfg(target_os = "macos")]
+pub fn now_playing() -> Option<NowPlaying> {
+    self::macos::media::now_playing()
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn now_playing() -> Option<NowPlaying> {
+    self::cross::now_playing()
+}
+
+/// Play or pause the media player, or skip to its next or previous track
+#[cfg(target_os = "macos")]
+pub fn media_control(action: MediaAction) -> Result<(), String> {
+    self::macos::media::media_control(action)
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn media_control(action: MediaAction) -> Result<(), String> {
+    self::cross::media_control(action)
+}
+
+/// The processes listening on a TCP port, by their pid and name
+#[cfg(target_os = "macos")]
+pub fn listening_processes(port: u16) -> Vec<(u32, String)> {
+    self::macos::listening_processes(port)
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn listening_processes(port: u16) -> Vec<(u32, String)> {
+    self::cross::listening_processes(port)
+}
+
+/// Kill a process, by asking it to stop (with `SIGTERM`) where that's possible
+#[cfg(not(target_os = "windows"))]
+pub fn kill_process(pid: u32) -> Result<(), String> {
+    if unsafe { libc::kill(pid as libc::pid_t, libc::SIGTERM) } == 0 {
+        Ok(())
+    } else {
+        Err(format!(
+            "Could not kill process {pid}: {}",
+            std::io::Error::last_os_error()
+        ))
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn kill_process(pid: u32) -> Result<(), String> {
+    std::process::Command::new("taskkill")
+        .args(["/F", "/PID", &pid.to_string()])
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Could not kill process {pid}: {e}"))
+}
+
+/// Whether [`uninstall_app`] can uninstall the app at the given path
+#[cfg(target_os = "macos")]
+pub fn can_uninstall(path: &str) -> bool {
+    self::macos::can_uninstall(path)
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn can_uninstall(path: &str) -> bool {
+    self::cross::can_uninstall(path)
+}
+
+/// Uninstall the app at the given path
+#[cfg(target_os = "macos")]
+pub fn uninstall_app(path: &str) -> Result<(), String> {
+    self::macos::uninstall_app(path)
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn uninstall_app(path: &str) -> Result<(), String> {
+    self::cross::uninstall_app(path)
+}
+
+/// "Quit" results for the running apps that have windows
+#[cfg(target_os = "macos")]
+pub fn open_apps(store_icons: bool) -> Vec<App> {
+    self::macos::quit::get_open_apps(store_icons)
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn open_apps(store_icons: bool) -> Vec<App> {
+    let _ = store_icons;
+    vec![]
+}
+
+/// Quit the app with the given name
+#[cfg(target_os = "macos")]
+pub fn terminate_app(name: &str) -> Result<(), String> {
+    self::macos::quit::terminate_app(name.to_string());
+    Ok(())
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn terminate_app(name: &str) -> Result<(), String> {
+    self::cross::terminate_app(name)
+}
+
+/// Quit every app that has a window, other than rustcast
+#[cfg(target_os = "macos")]
+pub fn terminate_all_apps() -> Result<(), String> {
+    self::macos::quit::terminate_all_apps();
+    Ok(())
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn terminate_all_apps() -> Result<(), String> {
+    self::cross::terminate_all_apps()
+}
+
+/// Add a reminder to the Reminders app, in the given list or the default one
+#[cfg(target_os = "macos")]
+pub fn add_reminder(text: &str, list: Option<&str>) -> Result<(), String> {
+    self::macos::add_reminder(text, list)
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn add_reminder(_: &str, _: Option<&str>) -> Result<(), String> {
+    Err("Reminders are only available on macOS".to_string())
+}
+
+/// Whether rustcast starts when the user logs in
+#[cfg(target_os = "macos")]
+pub fn autostart_enabled() -> bool {
+    self::macos::autostart_enabled()
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn autostart_enabled() -> bool {
+    self::cross::autostart_enabled()
+}
+
+/// Make rustcast start (or stop starting) when the user logs in, with a LaunchAgent on macOS, a
+/// `Run` registry key on Windows and an XDG autostart entry elsewhere
+#[cfg(target_os = "macos")]
+pub fn set_autostart(enabled: bool) -> Result<(), String> {
+    self::macos::set_autostart(enabled)
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn set_autostart(enabled: bool) -> Result<(), String> {
+    self::cross::set_autostart(enabled)
+}
+
+/// The installed apps, with each app only once even if it was found in more than one place
 #[cfg(target_os = "macos")]
 pub fn get_installed_apps(store_icons: bool) -> Vec<App> {
-    self::macos::get_installed_apps(store_icons)
+    dedup_apps(self::macos::get_installed_apps(store_icons))
 }
 
 #[cfg(not(target_os = "macos"))]
 pub fn get_installed_apps(store_icons: bool) -> Vec<App> {
-    self::cross::get_installed_apps(store_icons)
+    dedup_apps(self::cross::get_installed_apps(store_icons))
+}
+
+/// Keep one of the apps that open the same path, which can be found more than once through
+/// symlinks or folders that overlap, sometimes under slightly different names
+///
+/// The paths are compared after resolving symlinks (and ignoring case on Windows), and the app with
+/// an icon and a display name is kept over the others
+fn dedup_apps(apps: Vec<App>) -> Vec<App> {
+    let target = |app: &App| match &app.open_command {
+        AppCommand::Function(Function::OpenApp(path)) => {
+            let path = std::fs::canonicalize(path)
+                .map(|x| x.to_string_lossy().to_string())
+                .unwrap_or(path.to_owned());
+            let path = path.trim_end_matches(['/', '\\']);
+            Some(if cfg!(target_os = "windows") {
+                path.to_lowercase()
+            } else {
+                path.to_string()
+            })
+        }
+        _ => None,
+    };
+    let quality = |app: &App| (app.icons.is_some(), !app.display_name.is_empty());
+
+    let mut deduped: Vec<App> = Vec::with_capacity(apps.len());
+    let mut by_target: HashMap<String, usize> = HashMap::new();
+    for app in apps {
+        let Some(target) = target(&app) else {
+            deduped.push(app);
+            continue;
+        };
+        match by_target.get(&target) {
+            Some(&i) if quality(&app) > quality(&deduped[i]) => deduped[i] = app,
+            Some(_) => {}
+            None => {
+                by_target.insert(target, deduped.len());
+                deduped.push(app);
+            }
+        }
+    }
+    deduped
 }
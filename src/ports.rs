@@ -0,0 +1,63 @@
+//! Finding what is listening on a port, for searches like `port 3000`, so it can be killed
+//!
+//! Listing the processes can take a while (it runs `lsof`, `ss` or `netstat`), so they are looked
+//! up off the UI thread once the search stops changing, and kept for a few seconds
+
+use crate::{
+    app::apps::{App, AppCommand},
+    commands::Function,
+    i18n::tr,
+    platform,
+};
+
+/// How long the processes listening on a port are kept before they are looked up again, which is
+/// short since they change as things are started and killed
+pub const CACHE_SECONDS: u64 = 5;
+
+/// The port of a `port` search
+pub fn matching(query_lc: &str) -> Option<u16> {
+    query_lc
+        .trim()
+        .strip_prefix("port ")
+        .and_then(|x| x.trim().parse::<u16>().ok())
+}
+
+/// A result that kills each process listening on the port, or one saying that nothing is
+pub async fn lookup(port: u16) -> Result<Vec<App>, String> {
+    let processes = tokio::task::spawn_blocking(move || platform::listening_processes(port))
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(port_apps(port, processes))
+}
+
+fn port_apps(port: u16, processes: Vec<(u32, String)>) -> Vec<App> {
+    let port = port.to_string();
+    if processes.is_empty() {
+        return vec![App {
+            ranking: 20,
+            open_command: AppCommand::Display,
+            desc: tr!("port-desc"),
+            icons: None,
+            display_name: tr!("port-nothing-listening", port = port),
+            search_name: String::new(),
+            version: None,
+            last_used: None,
+            children: None,
+        }];
+    }
+
+    processes
+        .into_iter()
+        .map(|(pid, name)| App {
+            ranking: 20,
+            open_command: AppCommand::Function(Function::KillProcess(pid)),
+            desc: tr!("port-listening", port = port.clone()),
+            icons: None,
+            display_name: tr!("port-kill", name = name, pid = pid.to_string()),
+            search_name: String::new(),
+            version: None,
+            last_used: None,
+            children: None,
+        })
+        .collect()
+}
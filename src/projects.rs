@@ -0,0 +1,344 @@
+//! Recent projects from code editors, so they can be opened straight from the search bar
+//!
+//! VS Code (and its variants) keep the folders and workspaces they opened in their `storage.json`,
+//! and the JetBrains IDEs keep theirs in `recentProjects.xml` (under a folder for each version of
+//! each IDE, of which the newest one is read). Each project that still exists is a result that
+//! opens it in the editor it was last opened in
+
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+#[cfg(not(target_os = "macos"))]
+use crate::commands::ShellCommand;
+use crate::{
+    app::apps::{App, AppCommand},
+    commands::Function,
+    utils::percent_decode,
+};
+
+/// An editor, by the name of its folder in the config folder, the name of its app on macOS, its
+/// launcher elsewhere, and the name it's shown with
+struct Editor {
+    folder: &'static str,
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    app_name: &'static str,
+    #[cfg_attr(target_os = "macos", allow(dead_code))]
+    launcher: &'static str,
+    name: &'static str,
+}
+
+const VS_CODE_EDITORS: &[Editor] = &[
+    Editor {
+        folder: "Code",
+        app_name: "Visual Studio Code",
+        launcher: "code",
+        name: "VS Code",
+    },
+    Editor {
+        folder: "Code - Insiders",
+        app_name: "Visual Studio Code - Insiders",
+        launcher: "code-insiders",
+        name: "VS Code Insiders",
+    },
+    Editor {
+        folder: "VSCodium",
+        app_name: "VSCodium",
+        launcher: "codium",
+        name: "VSCodium",
+    },
+];
+
+/// The JetBrains IDEs, whose folders are followed by their version (like `IntelliJIdea2024.3`)
+///
+/// Longer folder names come before the ones they start with, so `PyCharmCE` isn't read as `PyCharm`
+const JETBRAINS_EDITORS: &[Editor] = &[
+    Editor {
+        folder: "IntelliJIdea",
+        app_name: "IntelliJ IDEA",
+        launcher: "idea",
+        name: "IntelliJ IDEA",
+    },
+    Editor {
+        folder: "IdeaIC",
+        app_name: "IntelliJ IDEA CE",
+        launcher: "idea",
+        name: "IntelliJ IDEA CE",
+    },
+    Editor {
+        folder: "PyCharmCE",
+        app_name: "PyCharm CE",
+        launcher: "pycharm",
+        name: "PyCharm CE",
+    },
+    Editor {
+        folder: "PyCharm",
+        app_name: "PyCharm",
+        launcher: "pycharm",
+        name: "PyCharm",
+    },
+    Editor {
+        folder: "WebStorm",
+        app_name: "WebStorm",
+        launcher: "webstorm",
+        name: "WebStorm",
+    },
+    Editor {
+        folder: "GoLand",
+        app_name: "GoLand",
+        launcher: "goland",
+        name: "GoLand",
+    },
+    Editor {
+        folder: "CLion",
+        app_name: "CLion",
+        launcher: "clion",
+        name: "CLion",
+    },
+    Editor {
+        folder: "RustRover",
+        app_name: "RustRover",
+        launcher: "rustrover",
+        name: "RustRover",
+    },
+    Editor {
+        folder: "PhpStorm",
+        app_name: "PhpStorm",
+        launcher: "phpstorm",
+        name: "PhpStorm",
+    },
+    Editor {
+        folder: "RubyMine",
+        app_name: "RubyMine",
+        launcher: "rubymine",
+        name: "RubyMine",
+    },
+    Editor {
+        folder: "Rider",
+        app_name: "Rider",
+        launcher: "rider",
+        name: "Rider",
+    },
+    Editor {
+        folder: "DataGrip",
+        app_name: "DataGrip",
+        launcher: "datagrip",
+        name: "DataGrip",
+    },
+];
+
+/// The recent projects of every editor that has any
+pub fn project_apps() -> Vec<App> {
+    let Some(config_dir) = config_dir() else {
+        return vec![];
+    };
+    let home_dir = std::env::var("HOME").unwrap_or("/".to_string());
+    let mut taken_names = HashSet::new();
+    let mut apps = vec![];
+
+    for editor in VS_CODE_EDITORS {
+        let storage = config_dir
+            .join(editor.folder)
+            .join("User/globalStorage/storage.json");
+        for path in vs_code_projects(&storage) {
+            apps.push(project_app(editor, &path, &home_dir, &mut taken_names));
+        }
+    }
+
+    let jetbrains_dir = config_dir.join("JetBrains");
+    for editor in JETBRAINS_EDITORS {
+        let Some(dir) = newest_version_dir(&jetbrains_dir, editor.folder) else {
+            continue;
+        };
+        for path in jetbrains_projects(&dir.join("options/recentProjects.xml"), &home_dir) {
+            apps.push(project_app(editor, &path, &home_dir, &mut taken_names));
+        }
+    }
+
+    apps
+}
+
+/// Where the editors keep their settings
+fn config_dir() -> Option<PathBuf> {
+    #[cfg(target_os = "macos")]
+    let dir = std::env::var("HOME")
+        .ok()
+        .map(|x| Path::new(&x).join("Library/Application Support"));
+
+    #[cfg(target_os = "windows")]
+    let dir = std::env::var("APPDATA").ok().map(PathBuf::from);
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let dir = std::env::var("XDG_CONFIG_HOME")
+        .ok()
+        .filter(|x| !x.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var("HOME")
+                .ok()
+                .map(|x| Path::new(&x).join(".config"))
+        });
+
+    dir
+}
+
+/// The folders and workspaces in a VS Code `storage.json`, which are every `file://` uri in it
+/// (as values or as keys) that is a folder or a `.code-workspace` file
+fn vs_code_projects(storage: &Path) -> Vec<PathBuf> {
+    let Some(storage) = std::fs::read_to_string(storage)
+        .ok()
+        .and_then(|x| serde_json::from_str::<serde_json::Value>(&x).ok())
+    else {
+        return vec![];
+    };
+
+    let mut uris = vec![];
+    collect_file_uris(&storage, &mut uris);
+
+    let mut seen = HashSet::new();
+    uris.iter()
+        .filter_map(|x| file_uri_path(x))
+        .filter(|x| x.is_dir() || x.extension().is_some_and(|x| x == "code-workspace"))
+        .filter(|x| seen.insert(x.clone()))
+        .collect()
+}
+
+fn collect_file_uris(value: &serde_json::Value, uris: &mut Vec<String>) {
+    match value {
+        serde_json::Value::String(x) if x.starts_with("file://") => uris.push(x.clone()),
+        serde_json::Value::Array(values) => {
+            for value in values {
+                collect_file_uris(value, uris);
+            }
+        }
+        serde_json::Value::Object(map) => {
+            for (key, value) in map {
+                if key.starts_with("file://") {
+                    uris.push(key.clone());
+                }
+                collect_file_uris(value, uris);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The path of a local `file://` uri, with its percent escapes decoded
+fn file_uri_path(uri: &str) -> Option<PathBuf> {
+    let decoded = percent_decode(uri.strip_prefix("file://")?)?;
+
+    // Windows paths look like `/c:/Users/...`
+    #[cfg(target_os = "windows")]
+    let decoded = decoded.strip_prefix('/').unwrap_or(&decoded).to_string();
+
+    Some(PathBuf::from(decoded))
+}
+
+/// The newest version's folder of a JetBrains IDE, like `IntelliJIdea2024.3`
+fn newest_version_dir(jetbrains_dir: &Path, folder: &str) -> Option<PathBuf> {
+    std::fs::read_dir(jetbrains_dir)
+        .ok()?
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let version = name.strip_prefix(folder)?;
+            // So `PyCharm` doesn't also take `PyCharmCE`'s folders
+            version
+                .starts_with(|x: char| x.is_ascii_digit())
+                .then(|| (version_key(version), entry.path()))
+        })
+        .max_by(|(left, _), (right, _)| left.cmp(right))
+        .map(|(_, path)| path)
+}
+
+/// A version like `2024.3` as numbers, so `2024.10` is newer than `2024.9`
+fn version_key(version: &str) -> Vec<u32> {
+    version.split('.').map(|x| x.parse().unwrap_or(0)).collect()
+}
+
+/// The projects in a JetBrains `recentProjects.xml`, which are the keys of its entries
+fn jetbrains_projects(recent_projects: &Path, home_dir: &str) -> Vec<PathBuf> {
+    let Ok(xml) = std::fs::read_to_string(recent_projects) else {
+        return vec![];
+    };
+
+    let mut seen = HashSet::new();
+    xml.split("<entry key=\"")
+        .skip(1)
+        .filter_map(|x| x.split('"').next())
+        .map(|x| PathBuf::from(x.replace("$USER_HOME$", home_dir)))
+        .filter(|x| x.is_dir())
+        .filter(|x| seen.insert(x.clone()))
+        .collect()
+}
+
+/// The result that opens a project in an editor
+///
+/// Projects are named after their folder, along with the folder above it when another project
+/// already has that name
+fn project_app(
+    editor: &Editor,
+    path: &Path,
+    home_dir: &str,
+    taken_names: &mut HashSet<String>,
+) -> App {
+    let path_str = path.to_string_lossy().to_string();
+    let display_path = match path_str.strip_prefix(home_dir) {
+        Some(suffix) => format!("~{suffix}"),
+        None => path_str.clone(),
+    };
+
+    let file_name = |x: &Path| {
+        x.file_stem()
+            .map(|x| x.to_string_lossy().to_string())
+            .unwrap_or_default()
+    };
+    let mut name = file_name(path);
+    let mut search_name = format!("{name} {}", editor.name).to_lowercase();
+    if !taken_names.insert(search_name.clone())
+        && let Some(parent) = path.parent()
+    {
+        name = format!("{}/{name}", file_name(parent));
+        search_name = format!("{name} {}", editor.name).to_lowercase();
+        taken_names.insert(search_name.clone());
+    }
+
+    #[cfg(target_os = "macos")]
+    let function = Function::OpenWith(path_str, editor.app_name.to_string());
+
+    // The launcher is run in the project's folder, so its path doesn't need quoting
+    #[cfg(not(target_os = "macos"))]
+    let function = {
+        let (cwd, target) = match path.parent().filter(|_| path.is_file()) {
+            Some(parent) => (
+                parent.to_string_lossy().to_string(),
+                file_name_with_ext(path),
+            ),
+            None => (path_str, ".".to_string()),
+        };
+        Function::RunShellCommand(ShellCommand {
+            cwd: Some(cwd),
+            ..ShellCommand::new(format!("{} \"{target}\"", editor.launcher))
+        })
+    };
+
+    App {
+        ranking: 0,
+        open_command: AppCommand::Function(function),
+        desc: display_path,
+        icons: None,
+        display_name: format!("Open {name} in {}", editor.name),
+        search_name,
+        version: None,
+        last_used: None,
+        children: None,
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn file_name_with_ext(path: &Path) -> String {
+    path.file_name()
+        .map(|x| x.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
@@ -0,0 +1,461 @@
+//! The providers of the results for a search, besides the apps
+//!
+//! Most providers give results for searches that start with their keyword (like `weather` or
+//! `gh`), and the results of all of them are shown together. Some look their results up (like
+//! online), which are added to the others once they arrive, and kept in the tile's lookup cache
+//! for a while. The fallback providers (like the calculator and web search) are only asked when
+//! nothing else has results, in order of their priority, and the results of the first one that has
+//! any are shown. Other providers (like ones from plugins) can be added to the tile's providers to
+//! join the same pipeline
+
+mod keywords;
+
+use std::{io::Cursor, sync::Arc};
+
+use iced::futures::future::BoxFuture;
+use iced::widget::image::Handle;
+use log::debug;
+
+use crate::{
+    app::{
+        ErrorKind, RUSTCAST_DESC_NAME, ToApp,
+        apps::{App, AppCommand},
+    },
+    calculator::Expr,
+    calendar::Event,
+    clipboard::ClipBoardContentType,
+    commands::Function,
+    config::Config,
+    contacts::Contact,
+    i18n::tr,
+    timers::Timers,
+    unit_conversion,
+    updater::Release,
+    utils::is_valid_url,
+};
+
+/// What providers are given to find results for a search
+pub struct Search<'a> {
+    /// The search as it was typed
+    pub query: &'a str,
+    pub query_lc: &'a str,
+    pub config: &'a Config,
+    pub timers: &'a Timers,
+    pub contacts: &'a [Contact],
+    /// The upcoming events, and when they were read
+    pub events: &'a [Event],
+    pub events_updated: u64,
+    pub available_update: Option<&'a Release>,
+}
+
+/// How the results that a provider looks up are kept and shown
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lookup {
+    /// The key the results are kept under in the tile's lookup cache
+    pub key: String,
+    /// How long the results are kept, where 0 doesn't keep them
+    pub cache_seconds: u64,
+    /// Whether they are only looked up once the search hasn't changed for the debounce delay
+    pub debounce: bool,
+    /// The words the results have to match, for lists that are looked up whole and filtered by
+    /// the rest of the search
+    pub filter: Option<String>,
+    /// Whether the last results (and how old they are) are shown when they can't be looked up
+    pub keep_stale: bool,
+    /// The kind of the error shown when they can't be looked up
+    pub error: ErrorKind,
+}
+
+impl Lookup {
+    /// A lookup that isn't cached, debounced or filtered
+    pub fn new(key: impl Into<String>, error: ErrorKind) -> Self {
+        Lookup {
+            key: key.into(),
+            cache_seconds: 0,
+            debounce: false,
+            filter: None,
+            keep_stale: false,
+            error,
+        }
+    }
+
+    /// Whether a result that was looked up matches the filter
+    pub fn matches(&self, app: &App) -> bool {
+        self.filter
+            .as_ref()
+            .is_none_or(|filter| app.matches_words(filter))
+    }
+}
+
+/// Looking up the results of a [`Lookup`], or why they couldn't be
+pub type Fetch = BoxFuture<'static, Result<Vec<App>, String>>;
+
+/// Something that gives results for a search
+pub trait ResultProvider: Send + Sync {
+    /// The name of the provider, which its settings in the config are under
+    fn name(&self) -> &'static str;
+
+    /// Providers with a higher priority are asked for results first
+    fn priority(&self) -> i32;
+
+    /// Whether the provider is only asked when nothing else has results for the search
+    fn fallback(&self) -> bool {
+        false
+    }
+
+    /// The results for a search, which can be empty
+    fn provide(&self, _search: &Search) -> Vec<App> {
+        vec![]
+    }
+
+    /// Looking up more results for a search, unless there are none to look up
+    ///
+    /// Fallback providers aren't asked for lookups
+    fn lookup(&self, _search: &Search) -> Option<(Lookup, Fetch)> {
+        None
+    }
+}
+
+/// The providers that are built into rustcast
+fn builtin() -> Vec<Arc<dyn ResultProvider>> {
+    keywords::providers()
+        .into_iter()
+        .chain([
+            Arc::new(EasterEggs) as Arc<dyn ResultProvider>,
+            Arc::new(UrlOpener),
+            Arc::new(UnitConverter),
+            Arc::new(Calculator),
+            Arc::new(EmojiShortcodes),
+            Arc::new(WebSearch),
+        ])
+        .collect()
+}
+
+/// The names of the built in providers, which their settings in the config are under
+pub fn names() -> Vec<&'static str> {
+    builtin().iter().map(|x| x.name()).collect()
+}
+
+/// The built in providers that are enabled in the config, from the highest priority to the lowest
+///
+/// Priorities set in the config replace the providers' own ones
+pub fn configured(config: &Config) -> Vec<Arc<dyn ResultProvider>> {
+    let mut providers: Vec<Arc<dyn ResultProvider>> = builtin()
+        .into_iter()
+        .filter(|x| config.providers.get(x.name()).is_none_or(|x| x.enabled))
+        .collect();
+    providers.sort_by_key(|x| {
+        let priority = config.providers.get(x.name()).and_then(|x| x.priority);
+        -priority.unwrap_or(x.priority())
+    });
+    providers
+}
+
+/// The results of every provider that isn't a fallback, in order of their priority
+///
+/// The providers have to already be sorted by their priority
+pub fn provide(providers: &[Arc<dyn ResultProvider>], search: &Search) -> Vec<App> {
+    providers
+        .iter()
+        .filter(|x| !x.fallback())
+        .flat_map(|x| x.provide(search))
+        .collect()
+}
+
+/// The lookups of every provider that isn't a fallback, with the provider's name
+pub fn lookups(
+    providers: &[Arc<dyn ResultProvider>],
+    search: &Search,
+) -> Vec<(&'static str, Lookup, Fetch)> {
+    providers
+        .iter()
+        .filter(|x| !x.fallback())
+        .filter_map(|x| {
+            let (lookup, fetch) = x.lookup(search)?;
+            Some((x.name(), lookup, fetch))
+        })
+        .collect()
+}
+
+/// The results of the first fallback provider that has any for the search
+///
+/// The providers have to already be sorted by their priority
+pub fn fallback(providers: &[Arc<dyn ResultProvider>], search: &Search) -> Vec<App> {
+    providers
+        .iter()
+        .filter(|x| x.fallback())
+        .find_map(|provider| {
+            let results = provider.provide(search);
+            if results.is_empty() {
+                return None;
+            }
+            debug!(
+                "{} results from the {} provider",
+                results.len(),
+                provider.name()
+            );
+            Some(results)
+        })
+        .unwrap_or_default()
+}
+
+/// `lemon` and `67`
+struct EasterEggs;
+
+impl ResultProvider for EasterEggs {
+    fn name(&self) -> &'static str {
+        "easter_eggs"
+    }
+
+    fn priority(&self) -> i32 {
+        50
+    }
+
+    fn fallback(&self) -> bool {
+        true
+    }
+
+    fn provide(&self, search: &Search) -> Vec<App> {
+        let app = match search.query.trim().to_lowercase().as_str() {
+            "lemon" => App {
+                ranking: 0,
+                open_command: AppCommand::Display,
+                desc: "Easter Egg".to_string(),
+                icons: lemon_icon_handle(),
+                display_name: "Lemon".to_string(),
+                search_name: "".to_string(),
+                version: None,
+                last_used: None,
+                children: None,
+            },
+            "67" => App {
+                ranking: 0,
+                open_command: AppCommand::Function(Function::CopyToClipboard(
+                    ClipBoardContentType::Text(67.to_string()),
+                )),
+                desc: "Easter egg".to_string(),
+                icons: None,
+                display_name: 67.to_string(),
+                search_name: String::new(),
+                version: None,
+                last_used: None,
+                children: None,
+            },
+            _ => return vec![],
+        };
+        vec![app]
+    }
+}
+
+fn lemon_icon_handle() -> Option<Handle> {
+    image::ImageReader::new(Cursor::new(include_bytes!("../docs/lemon.png")))
+        .with_guessed_format()
+        .unwrap()
+        .decode()
+        .ok()
+        .map(|img| Handle::from_rgba(img.width(), img.height(), img.into_bytes()))
+}
+
+/// Opens searches that are urls in the browser
+struct UrlOpener;
+
+impl ResultProvider for UrlOpener {
+    fn name(&self) -> &'static str {
+        "url"
+    }
+
+    fn priority(&self) -> i32 {
+        40
+    }
+
+    fn fallback(&self) -> bool {
+        true
+    }
+
+    fn provide(&self, search: &Search) -> Vec<App> {
+        let query = search.query;
+        if !is_valid_url(query) {
+            return vec![];
+        }
+
+        vec![App {
+            ranking: 0,
+            open_command: AppCommand::Function(Function::OpenWebsite(query.to_string())),
+            desc: "Web Browsing".to_string(),
+            icons: None,
+            display_name: "Open Website: ".to_string() + query,
+            search_name: String::new(),
+            version: None,
+            last_used: None,
+            children: None,
+        }]
+    }
+}
+
+/// Unit conversions, like `5 km to mi`
+struct UnitConverter;
+
+impl ResultProvider for UnitConverter {
+    fn name(&self) -> &'static str {
+        "units"
+    }
+
+    fn priority(&self) -> i32 {
+        30
+    }
+
+    fn fallback(&self) -> bool {
+        true
+    }
+
+    fn provide(&self, search: &Search) -> Vec<App> {
+        unit_conversion::convert_query(search.query)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|conversion| conversion.to_app())
+            .collect()
+    }
+}
+
+/// Calculations, which are copied when they are opened
+struct Calculator;
+
+impl ResultProvider for Calculator {
+    fn name(&self) -> &'static str {
+        "calculator"
+    }
+
+    fn priority(&self) -> i32 {
+        20
+    }
+
+    fn fallback(&self) -> bool {
+        true
+    }
+
+    fn provide(&self, search: &Search) -> Vec<App> {
+        let Ok(res) = search.query.parse::<Expr>() else {
+            return vec![];
+        };
+
+        vec![App {
+            ranking: 0,
+            open_command: AppCommand::Function(Function::Calculate(res.clone())),
+            // The brackets show how the expression was read, which catches typos in long ones
+            desc: if res.steps().is_empty() {
+                RUSTCAST_DESC_NAME.to_string()
+            } else {
+                res.explicit()
+            },
+            icons: None,
+            display_name: res.eval().map(|x| x.to_string()).unwrap_or("".to_string()),
+            search_name: "".to_string(),
+            version: None,
+            last_used: None,
+            children: None,
+        }]
+    }
+}
+
+/// Emojis by their shortcodes, for searches like `:thumbsup`
+struct EmojiShortcodes;
+
+impl ResultProvider for EmojiShortcodes {
+    fn name(&self) -> &'static str {
+        "emoji"
+    }
+
+    fn priority(&self) -> i32 {
+        15
+    }
+
+    fn fallback(&self) -> bool {
+        true
+    }
+
+    fn provide(&self, search: &Search) -> Vec<App> {
+        let Some(shortcode) = search
+            .query
+            .trim()
+            .strip_prefix(':')
+            .filter(|x| !x.is_empty())
+        else {
+            return vec![];
+        };
+        let shortcode = shortcode.trim_end_matches(':').to_lowercase();
+
+        emojis::iter()
+            .filter(|x| x.shortcodes().any(|x| x.starts_with(&shortcode)))
+            .take(10)
+            .map(|x| App::from_emoji(x, search.config.emoji_skin_tone))
+            .collect()
+    }
+}
+
+/// Searching the web, for searches that end with `?` or have at least three words
+struct WebSearch;
+
+impl ResultProvider for WebSearch {
+    fn name(&self) -> &'static str {
+        "web_search"
+    }
+
+    fn priority(&self) -> i32 {
+        10
+    }
+
+    fn fallback(&self) -> bool {
+        true
+    }
+
+    fn provide(&self, search: &Search) -> Vec<App> {
+        let query = search.query;
+        if !query.ends_with("?") && query.split_whitespace().nth(2).is_none() {
+            return vec![];
+        }
+
+        web_search_apps(search.config, query)
+    }
+}
+
+/// A result for searching the web with the default search engine, and one for each of the others
+pub fn web_search_apps(config: &Config, query: &str) -> Vec<App> {
+    let default = App {
+        ranking: 0,
+        open_command: AppCommand::Function(Function::GoogleSearch(query.to_string())),
+        icons: None,
+        desc: tr!("web-search-desc"),
+        display_name: tr!("web-search", query = query),
+        search_name: String::new(),
+        version: None,
+        last_used: None,
+        children: None,
+    };
+
+    std::iter::once(default)
+        .chain(
+            config
+                .search_engines
+                .iter()
+                .filter(|engine| engine.url != config.search_url)
+                .map(|engine| App {
+                    ranking: 0,
+                    open_command: AppCommand::Function(Function::SearchWith(
+                        engine.clone(),
+                        query.to_string(),
+                    )),
+                    icons: None,
+                    desc: tr!("web-search-desc"),
+                    display_name: tr!(
+                        "web-search-with",
+                        engine = engine.name.clone(),
+                        query = query
+                    ),
+                    search_name: String::new(),
+                    version: None,
+                    last_used: None,
+                    children: None,
+                }),
+        )
+        .collect()
+}
@@ -0,0 +1,643 @@
+//! The built in providers for searches that start with a keyword, like `timer`, `weather` or `gh`
+
+use std::sync::Arc;
+
+use iced::futures::FutureExt;
+
+use super::{Fetch, Lookup, ResultProvider, Search};
+use crate::{
+    app::{ErrorKind, ToApp, apps::App},
+    calendar,
+    contacts::contact_apps,
+    containers,
+    convert::conv_apps,
+    extensions, github, issue_tracker, links, markets, media, network,
+    notes::note_apps,
+    packages, passwords, platform, ports,
+    random::random_apps,
+    regex_tester::regex_apps,
+    transforms::case_apps,
+    utils::unix_now,
+    weather,
+    world_clock::world_clock_apps,
+};
+
+/// The keyword providers, in the order their results are shown in when they rank the same
+pub(super) fn providers() -> Vec<Arc<dyn ResultProvider>> {
+    vec![
+        Arc::new(Extensions),
+        Arc::new(Containers),
+        Arc::new(Quit),
+        Arc::new(Update),
+        Arc::new(Timers),
+        Arc::new(Notes),
+        Arc::new(Ports),
+        Arc::new(Case),
+        Arc::new(Links),
+        Arc::new(Conv),
+        Arc::new(Regex),
+        Arc::new(Random),
+        Arc::new(Contacts),
+        Arc::new(Ip),
+        Arc::new(Weather),
+        Arc::new(Markets),
+        Arc::new(Packages),
+        Arc::new(GitHub),
+        Arc::new(IssueTracker),
+        Arc::new(Calendar),
+        Arc::new(Media),
+        Arc::new(Passwords),
+        Arc::new(WorldClock),
+        Arc::new(Shells),
+    ]
+}
+
+/// The extensions from the config, by their keywords
+struct Extensions;
+
+impl ResultProvider for Extensions {
+    fn name(&self) -> &'static str {
+        "extensions"
+    }
+
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    fn lookup(&self, search: &Search) -> Option<(Lookup, Fetch)> {
+        let (extension, argument) = extensions::matching(&search.config.extensions, search.query)?;
+        Some((
+            Lookup::new(
+                format!("extension:{}", extension.keyword),
+                ErrorKind::Extension,
+            ),
+            extensions::run(extension.clone(), argument.to_string()).boxed(),
+        ))
+    }
+}
+
+/// The containers, with actions to start, stop and open a shell in them
+struct Containers;
+
+impl ResultProvider for Containers {
+    fn name(&self) -> &'static str {
+        "containers"
+    }
+
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    fn lookup(&self, search: &Search) -> Option<(Lookup, Fetch)> {
+        let settings = &search.config.containers;
+        let filter = containers::matching(settings, search.query).filter(|_| settings.enabled)?;
+        Some((
+            Lookup::new("containers", ErrorKind::Containers),
+            containers::list(settings.clone(), filter.to_string()).boxed(),
+        ))
+    }
+}
+
+/// Quitting the apps that are open, for `quit` searches
+struct Quit;
+
+impl ResultProvider for Quit {
+    fn name(&self) -> &'static str {
+        "quit"
+    }
+
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    fn provide(&self, search: &Search) -> Vec<App> {
+        if !search.query_lc.starts_with("quit") {
+            return vec![];
+        }
+
+        platform::open_apps(search.config.theme.show_icons)
+            .into_iter()
+            .filter(|x| x.search_name.starts_with(search.query_lc))
+            .collect()
+    }
+}
+
+/// The newer release of rustcast, for `update` searches
+struct Update;
+
+impl ResultProvider for Update {
+    fn name(&self) -> &'static str {
+        "update"
+    }
+
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    fn provide(&self, search: &Search) -> Vec<App> {
+        if !search.query_lc.starts_with("update") {
+            return vec![];
+        }
+
+        search.available_update.iter().map(|x| x.to_app()).collect()
+    }
+}
+
+/// Starting timers, and the running ones or the stopwatch
+struct Timers;
+
+impl ResultProvider for Timers {
+    fn name(&self) -> &'static str {
+        "timers"
+    }
+
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    fn provide(&self, search: &Search) -> Vec<App> {
+        search.timers.apps(search.query)
+    }
+}
+
+/// Writing and searching notes
+struct Notes;
+
+impl ResultProvider for Notes {
+    fn name(&self) -> &'static str {
+        "notes"
+    }
+
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    fn provide(&self, search: &Search) -> Vec<App> {
+        note_apps(&search.config.notes, search.query)
+    }
+}
+
+/// The processes listening on ports
+struct Ports;
+
+impl ResultProvider for Ports {
+    fn name(&self) -> &'static str {
+        "ports"
+    }
+
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    fn lookup(&self, search: &Search) -> Option<(Lookup, Fetch)> {
+        let port = ports::matching(search.query_lc)?;
+        Some((
+            Lookup {
+                cache_seconds: ports::CACHE_SECONDS,
+                debounce: true,
+                ..Lookup::new(format!("port:{port}"), ErrorKind::Ports)
+            },
+            ports::lookup(port).boxed(),
+        ))
+    }
+}
+
+/// Changing the case of text
+struct Case;
+
+impl ResultProvider for Case {
+    fn name(&self) -> &'static str {
+        "case"
+    }
+
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    fn provide(&self, search: &Search) -> Vec<App> {
+        case_apps(search.query)
+    }
+}
+
+/// Cleaning links up, and expanding short ones
+struct Links;
+
+impl ResultProvider for Links {
+    fn name(&self) -> &'static str {
+        "links"
+    }
+
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    fn provide(&self, search: &Search) -> Vec<App> {
+        links::url_apps(search.query)
+    }
+
+    fn lookup(&self, search: &Search) -> Option<(Lookup, Fetch)> {
+        let link =
+            links::expand_target(search.query).filter(|_| search.config.expand_short_urls)?;
+        Some((
+            Lookup::new(format!("expand:{link}"), ErrorKind::UrlExpansion),
+            links::expand(link)
+                .map(|result| result.map(|url| vec![links::open_app(url)]))
+                .boxed(),
+        ))
+    }
+}
+
+/// Converting between JSON, YAML and TOML
+struct Conv;
+
+impl ResultProvider for Conv {
+    fn name(&self) -> &'static str {
+        "conv"
+    }
+
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    fn provide(&self, search: &Search) -> Vec<App> {
+        conv_apps(search.query)
+    }
+}
+
+/// Testing regular expressions
+struct Regex;
+
+impl ResultProvider for Regex {
+    fn name(&self) -> &'static str {
+        "regex"
+    }
+
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    fn provide(&self, search: &Search) -> Vec<App> {
+        regex_apps(search.query)
+    }
+}
+
+/// Random numbers, passwords and UUIDs
+struct Random;
+
+impl ResultProvider for Random {
+    fn name(&self) -> &'static str {
+        "random"
+    }
+
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    fn provide(&self, search: &Search) -> Vec<App> {
+        random_apps(search.query, &search.config.random)
+    }
+}
+
+/// The people in Contacts
+struct Contacts;
+
+impl ResultProvider for Contacts {
+    fn name(&self) -> &'static str {
+        "contacts"
+    }
+
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    fn provide(&self, search: &Search) -> Vec<App> {
+        contact_apps(search.contacts, search.query)
+    }
+}
+
+/// The local IP addresses, and the public one, for `ip` searches
+struct Ip;
+
+impl ResultProvider for Ip {
+    fn name(&self) -> &'static str {
+        "ip"
+    }
+
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    fn provide(&self, search: &Search) -> Vec<App> {
+        if search.query_lc != "ip" {
+            return vec![];
+        }
+
+        network::local_ip_apps()
+    }
+
+    fn lookup(&self, search: &Search) -> Option<(Lookup, Fetch)> {
+        if search.query_lc != "ip" || !search.config.lookup_public_ip {
+            return None;
+        }
+
+        Some((
+            Lookup::new("ip:public", ErrorKind::Network),
+            network::public_ip()
+                .map(|result| result.map(|ip| vec![network::ip_app(ip, "Public IP Address")]))
+                .boxed(),
+        ))
+    }
+}
+
+/// The weather for a location
+struct Weather;
+
+impl ResultProvider for Weather {
+    fn name(&self) -> &'static str {
+        "weather"
+    }
+
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    fn lookup(&self, search: &Search) -> Option<(Lookup, Fetch)> {
+        let location = weather::matching(&search.config.weather, search.query)?;
+        Some((
+            Lookup {
+                cache_seconds: weather::CACHE_SECONDS,
+                ..Lookup::new(
+                    format!("weather:{}", location.to_lowercase()),
+                    ErrorKind::Weather,
+                )
+            },
+            weather::lookup(search.config.weather.clone(), location).boxed(),
+        ))
+    }
+}
+
+/// The prices of stocks and currencies
+struct Markets;
+
+impl ResultProvider for Markets {
+    fn name(&self) -> &'static str {
+        "markets"
+    }
+
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    fn lookup(&self, search: &Search) -> Option<(Lookup, Fetch)> {
+        let symbol = markets::matching(&search.config.markets, search.query)?;
+        Some((
+            Lookup {
+                cache_seconds: markets::CACHE_SECONDS,
+                // The last price is better than none when it can't be looked up, like when
+                // offline
+                keep_stale: true,
+                ..Lookup::new(
+                    format!("quote:{}", symbol.to_lowercase()),
+                    ErrorKind::Markets,
+                )
+            },
+            markets::lookup(search.config.markets.clone(), symbol).boxed(),
+        ))
+    }
+}
+
+/// Searching the package registries
+struct Packages;
+
+impl ResultProvider for Packages {
+    fn name(&self) -> &'static str {
+        "packages"
+    }
+
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    fn lookup(&self, search: &Search) -> Option<(Lookup, Fetch)> {
+        let (registry, name) = packages::matching(search.query)?;
+        Some((
+            Lookup {
+                debounce: true,
+                ..Lookup::new("packages", ErrorKind::Packages)
+            },
+            packages::search(registry, name).boxed(),
+        ))
+    }
+}
+
+/// Your repositories, issues and pull requests on GitHub
+struct GitHub;
+
+impl ResultProvider for GitHub {
+    fn name(&self) -> &'static str {
+        "github"
+    }
+
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    fn lookup(&self, search: &Search) -> Option<(Lookup, Fetch)> {
+        let (list, filter) = github::matching(search.query)?;
+        let token = github::token(&search.config.github)?;
+        Some((
+            Lookup {
+                cache_seconds: github::CACHE_SECONDS,
+                debounce: true,
+                filter: Some(filter),
+                ..Lookup::new(list.cache_key(), ErrorKind::GitHub)
+            },
+            github::fetch(search.config.github.clone(), token, list).boxed(),
+        ))
+    }
+}
+
+/// Opening issues by their keys, and the issues assigned to you
+struct IssueTracker;
+
+impl ResultProvider for IssueTracker {
+    fn name(&self) -> &'static str {
+        "issue_tracker"
+    }
+
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    fn provide(&self, search: &Search) -> Vec<App> {
+        let settings = &search.config.issue_tracker;
+        if !issue_tracker::is_configured(settings) {
+            return vec![];
+        }
+
+        issue_tracker::key_app(settings, search.query)
+            .into_iter()
+            .collect()
+    }
+
+    fn lookup(&self, search: &Search) -> Option<(Lookup, Fetch)> {
+        let settings = &search.config.issue_tracker;
+        if !issue_tracker::is_configured(settings) || settings.token.is_empty() {
+            return None;
+        }
+
+        let filter = issue_tracker::matching(search.query)?;
+        Some((
+            Lookup {
+                cache_seconds: issue_tracker::CACHE_SECONDS,
+                debounce: true,
+                filter: Some(filter),
+                ..Lookup::new(issue_tracker::CACHE_KEY, ErrorKind::IssueTracker)
+            },
+            issue_tracker::fetch_assigned(settings.clone()).boxed(),
+        ))
+    }
+}
+
+/// The upcoming events, from the events read when the window opened while they are new enough
+struct Calendar;
+
+impl ResultProvider for Calendar {
+    fn name(&self) -> &'static str {
+        "calendar"
+    }
+
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    fn provide(&self, search: &Search) -> Vec<App> {
+        let Some(filter) = calendar_filter(search) else {
+            return vec![];
+        };
+        if unix_now().saturating_sub(search.events_updated) >= calendar::CACHE_SECONDS {
+            return vec![];
+        }
+
+        calendar::calendar_apps(search.events, &filter)
+    }
+
+    fn lookup(&self, search: &Search) -> Option<(Lookup, Fetch)> {
+        let filter = calendar_filter(search)?;
+        if unix_now().saturating_sub(search.events_updated) < calendar::CACHE_SECONDS {
+            return None;
+        }
+
+        Some((
+            Lookup {
+                cache_seconds: calendar::CACHE_SECONDS,
+                filter: Some(filter),
+                ..Lookup::new("calendar", ErrorKind::Calendar)
+            },
+            calendar::upcoming(search.config.calendar.clone())
+                .map(|result| result.map(|events| calendar::calendar_apps(&events, "")))
+                .boxed(),
+        ))
+    }
+}
+
+/// The filter of a `cal` search, if there are calendars to read
+fn calendar_filter(search: &Search) -> Option<String> {
+    calendar::matching(search.query).filter(|_| calendar::is_configured(&search.config.calendar))
+}
+
+/// What is playing, with actions to control it
+struct Media;
+
+impl ResultProvider for Media {
+    fn name(&self) -> &'static str {
+        "media"
+    }
+
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    fn lookup(&self, search: &Search) -> Option<(Lookup, Fetch)> {
+        if !media::matching(search.query_lc) {
+            return None;
+        }
+
+        Some((
+            Lookup::new("media", ErrorKind::Media),
+            media::now_playing()
+                .map(|now_playing| Ok(media::media_apps(now_playing)))
+                .boxed(),
+        ))
+    }
+}
+
+/// The items in the password manager, which copy their secrets
+struct Passwords;
+
+impl ResultProvider for Passwords {
+    fn name(&self) -> &'static str {
+        "passwords"
+    }
+
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    fn lookup(&self, search: &Search) -> Option<(Lookup, Fetch)> {
+        let settings = &search.config.passwords;
+        let filter = passwords::matching(settings, search.query).filter(|_| settings.enabled)?;
+        Some((
+            Lookup {
+                cache_seconds: passwords::CACHE_SECONDS,
+                debounce: true,
+                filter: Some(filter.to_string()),
+                ..Lookup::new(passwords::CACHE_KEY, ErrorKind::Passwords)
+            },
+            passwords::list(settings.clone()).boxed(),
+        ))
+    }
+}
+
+/// The time in the cities from the config, for `time` searches
+struct WorldClock;
+
+impl ResultProvider for WorldClock {
+    fn name(&self) -> &'static str {
+        "world_clock"
+    }
+
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    fn provide(&self, search: &Search) -> Vec<App> {
+        if search.query_lc != "time" {
+            return vec![];
+        }
+
+        world_clock_apps(&search.config.world_clock)
+    }
+}
+
+/// Running the search as an argument to the shell commands from the config
+struct Shells;
+
+impl ResultProvider for Shells {
+    fn name(&self) -> &'static str {
+        "shells"
+    }
+
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    fn provide(&self, search: &Search) -> Vec<App> {
+        search
+            .config
+            .shells
+            .iter()
+            .filter_map(|shell| shell.with_argument(search.query))
+            .collect()
+    }
+}
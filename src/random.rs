@@ -0,0 +1,185 @@
+//! Random data, for searches that start with `rand`, `roll` or `flip`
+//!
+//! - `rand` picks a number from 1 to the configured maximum, and `rand 1-100` (or `rand 6`, from
+//!   1) picks one from a range
+//! - `rand lorem` gives the configured number of paragraphs of lorem ipsum, and `rand lorem 5`
+//!   gives 5
+//! - `roll 2d6` rolls dice (`roll` on its own rolls one six-sided die)
+//! - `flip` (or `rand coin`) flips a coin
+//!
+//! Opening a result copies it
+
+use rand::seq::IndexedRandom;
+
+use crate::{
+    app::apps::{App, AppCommand},
+    clipboard::ClipBoardContentType,
+    commands::Function,
+    config::Random,
+};
+
+/// The most dice (and sides) that can be rolled at once
+const MAX_DICE: u32 = 100;
+
+/// The most paragraphs of lorem ipsum that can be made at once
+const MAX_PARAGRAPHS: usize = 50;
+
+const LOREM_START: &str = "Lorem ipsum dolor sit amet, consectetur adipiscing elit";
+
+/// The words that the rest of the lorem ipsum is made of
+const LOREM_WORDS: &str = "\
+    lorem ipsum dolor sit amet consectetur adipiscing elit sed do eiusmod tempor incididunt ut \
+    labore et dolore magna aliqua enim ad minim veniam quis nostrud exercitation ullamco \
+    laboris nisi aliquip ex ea commodo consequat duis aute irure in reprehenderit voluptate \
+    velit esse cillum fugiat nulla pariatur excepteur sint occaecat cupidatat non proident \
+    sunt culpa qui officia deserunt mollit anim id est laborum";
+
+/// The results for a `rand`, `roll` or `flip` search
+pub fn random_apps(query: &str, settings: &Random) -> Vec<App> {
+    let query = query.trim().to_lowercase();
+    let (keyword, rest) = query.split_once(' ').unwrap_or((&query, ""));
+    let rest = rest.trim();
+
+    match keyword {
+        "rand" => match rest.split_once(' ').unwrap_or((rest, "")) {
+            ("", _) => vec![
+                number_app(1, settings.max.max(1)),
+                lorem_app(settings.lorem_paragraphs),
+                coin_app(),
+            ],
+            ("lorem", count) => match count.trim() {
+                "" => vec![lorem_app(settings.lorem_paragraphs)],
+                count => count.parse().map(lorem_app).into_iter().collect(),
+            },
+            ("coin", "") => vec![coin_app()],
+            (range, "") => parse_range(range)
+                .map(|(min, max)| number_app(min, max))
+                .into_iter()
+                .collect(),
+            _ => vec![],
+        },
+        "roll" => {
+            let dice = if rest.is_empty() { "1d6" } else { rest };
+            roll_app(dice).into_iter().collect()
+        }
+        "flip" if rest.is_empty() => vec![coin_app()],
+        _ => vec![],
+    }
+}
+
+/// A range like `1-100` or `-5-5`, or `6` for 1 to 6
+fn parse_range(range: &str) -> Option<(i64, i64)> {
+    if let Ok(max) = range.parse::<i64>() {
+        return (max >= 1).then_some((1, max));
+    }
+
+    // The first character can be the minimum's minus sign
+    let split = range.get(1..)?.find('-')? + 1;
+    let min = range[..split].parse().ok()?;
+    let max = range[split + 1..].parse().ok()?;
+    (min <= max).then_some((min, max))
+}
+
+fn number_app(min: i64, max: i64) -> App {
+    let number = rand::random_range(min..=max);
+    app(
+        number.to_string(),
+        format!("Random number from {min} to {max}"),
+    )
+}
+
+fn coin_app() -> App {
+    let side = if rand::random() { "Heads" } else { "Tails" };
+    app(side.to_string(), "Coin flip".to_string())
+}
+
+/// Rolls dice like `2d6`, showing the total and each die
+fn roll_app(dice: &str) -> Option<App> {
+    let (count, sides) = dice.split_once('d')?;
+    let count: u32 = if count.is_empty() {
+        1
+    } else {
+        count.parse().ok()?
+    };
+    let sides: u32 = sides.parse().ok()?;
+    if !(1..=MAX_DICE).contains(&count) || !(2..=MAX_DICE).contains(&sides) {
+        return None;
+    }
+
+    let rolls: Vec<u32> = (0..count).map(|_| rand::random_range(1..=sides)).collect();
+    let total: u32 = rolls.iter().sum();
+    let desc = if count == 1 {
+        format!("Rolled {count}d{sides}")
+    } else {
+        let rolls: Vec<String> = rolls.iter().map(|x| x.to_string()).collect();
+        format!("Rolled {count}d{sides}: {}", rolls.join(" + "))
+    };
+    Some(app(total.to_string(), desc))
+}
+
+/// Paragraphs of lorem ipsum, the first of which starts with "Lorem ipsum dolor sit amet"
+fn lorem_app(paragraphs: usize) -> App {
+    let paragraphs = paragraphs.clamp(1, MAX_PARAGRAPHS);
+    let mut rng = rand::rng();
+    let words: Vec<&str> = LOREM_WORDS.split_whitespace().collect();
+
+    let text = (0..paragraphs)
+        .map(|paragraph| {
+            let sentences = rand::random_range(4..=7);
+            (0..sentences)
+                .map(|sentence| {
+                    if paragraph == 0 && sentence == 0 {
+                        return format!("{LOREM_START}.");
+                    }
+                    let length = rand::random_range(6..=14);
+                    let words: Vec<&str> = (0..length)
+                        .filter_map(|_| words.choose(&mut rng).copied())
+                        .collect();
+                    let sentence = words.join(" ");
+                    let mut chars = sentence.chars();
+                    match chars.next() {
+                        Some(first) => format!("{}{}.", first.to_uppercase(), chars.as_str()),
+                        None => String::new(),
+                    }
+                })
+                .collect::<Vec<String>>()
+                .join(" ")
+        })
+        .collect::<Vec<String>>()
+        .join("\n\n");
+
+    let desc = if paragraphs == 1 {
+        "Lorem ipsum (1 paragraph)".to_string()
+    } else {
+        format!("Lorem ipsum ({paragraphs} paragraphs)")
+    };
+    App {
+        ranking: 20,
+        open_command: AppCommand::Function(Function::CopyToClipboard(ClipBoardContentType::Text(
+            text,
+        ))),
+        desc,
+        icons: None,
+        display_name: format!("{LOREM_START}…"),
+        search_name: String::new(),
+        version: None,
+        last_used: None,
+        children: None,
+    }
+}
+
+fn app(display_name: String, desc: String) -> App {
+    App {
+        ranking: 20,
+        open_command: AppCommand::Function(Function::CopyToClipboard(ClipBoardContentType::Text(
+            display_name.clone(),
+        ))),
+        desc,
+        icons: None,
+        display_name,
+        search_name: String::new(),
+        version: None,
+        last_used: None,
+        children: None,
+    }
+}
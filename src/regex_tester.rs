@@ -0,0 +1,145 @@
+//! Testing regular expressions, for searches like `re <pattern> :: <sample>`
+//!
+//! The first result says whether (and how many times) the pattern matches the sample, and there
+//! is a result for each match after it, which shows the match in «» with the text around it, and
+//! its capture groups. Without a sample, the text in the clipboard is matched. Opening a match's
+//! result copies the match
+//!
+//! The patterns use the syntax of the `regex` crate, whose engine (`regex-automata`) is already
+//! one of rustcast's dependencies
+
+use arboard::Clipboard;
+use regex_automata::{meta::Regex, util::captures::Captures};
+
+use crate::{
+    app::apps::{App, AppCommand},
+    clipboard::ClipBoardContentType,
+    commands::Function,
+};
+
+/// How many matches get a result of their own
+const MAX_MATCHES: usize = 20;
+
+/// How many characters of the sample are shown on each side of a match
+const CONTEXT_LENGTH: usize = 30;
+
+/// The results for a `re` search: whether the pattern matches, and each match
+pub fn regex_apps(query: &str) -> Vec<App> {
+    let query = query.trim_start();
+    let Some(rest) = query
+        .get(..3)
+        .filter(|x| x.eq_ignore_ascii_case("re "))
+        .map(|_| &query[3..])
+    else {
+        return vec![];
+    };
+
+    let (pattern, sample) = match rest.split_once(" :: ") {
+        Some((pattern, sample)) => (pattern, sample.to_string()),
+        None => match Clipboard::new().and_then(|mut x| x.get_text()) {
+            Ok(text) => (rest.trim_end_matches(" ::"), text),
+            Err(_) => return vec![],
+        },
+    };
+    if pattern.is_empty() {
+        return vec![];
+    }
+
+    let regex = match Regex::new(pattern) {
+        Ok(regex) => regex,
+        Err(error) => {
+            // Syntax errors point at the problem over several lines, of which the last says what
+            // it is
+            let message = match error.syntax_error() {
+                Some(error) => error
+                    .to_string()
+                    .lines()
+                    .last()
+                    .unwrap_or_default()
+                    .to_string(),
+                None => error.to_string(),
+            };
+            return vec![app(
+                message,
+                "Invalid regular expression".to_string(),
+                pattern.to_string(),
+            )];
+        }
+    };
+
+    let matches: Vec<Captures> = regex.captures_iter(&sample).collect();
+    let summary = match matches.len() {
+        0 => "No match".to_string(),
+        1 => "1 match".to_string(),
+        count => format!("{count} matches"),
+    };
+    let all_matches = matches
+        .iter()
+        .filter_map(|x| x.get_match())
+        .map(|x| &sample[x.range()])
+        .collect::<Vec<&str>>()
+        .join("\n");
+
+    let mut apps = vec![app(summary, format!("/{pattern}/"), all_matches)];
+    apps.extend(
+        matches
+            .iter()
+            .take(MAX_MATCHES)
+            .filter_map(|caps| match_app(caps, &sample)),
+    );
+    apps
+}
+
+/// A result for a match, showing where it is in the sample and what its groups captured
+fn match_app(caps: &Captures, sample: &str) -> Option<App> {
+    let range = caps.get_match()?.range();
+
+    let before: String = sample[..range.start]
+        .chars()
+        .rev()
+        .take(CONTEXT_LENGTH)
+        .collect::<Vec<char>>()
+        .into_iter()
+        .rev()
+        .collect();
+    let after: String = sample[range.end..].chars().take(CONTEXT_LENGTH).collect();
+    let highlighted = format!("{before}«{}»{after}", &sample[range.clone()]).replace('\n', "⏎");
+
+    let names = caps.group_info().pattern_names(caps.pattern()?);
+    let groups: Vec<String> = caps
+        .iter()
+        .zip(names)
+        .enumerate()
+        .skip(1)
+        .map(|(i, (span, name))| {
+            let label = name.map_or(format!("${i}"), |x| x.to_string());
+            match span {
+                Some(span) => format!("{label} = {}", &sample[span.range()]),
+                None => format!("{label} unmatched"),
+            }
+        })
+        .collect();
+    let desc = if groups.is_empty() {
+        format!("Match at {}..{}", range.start, range.end)
+    } else {
+        groups.join(" · ").replace('\n', "⏎")
+    };
+
+    Some(app(highlighted, desc, sample[range].to_string()))
+}
+
+fn app(display_name: String, desc: String, copied: String) -> App {
+    App {
+        ranking: 20,
+        open_command: AppCommand::Function(Function::CopyToClipboard(ClipBoardContentType::Text(
+            copied,
+        ))),
+        desc,
+        icons: None,
+        display_name,
+        search_name: String::new(),
+        version: None,
+        last_used: None,
+        children: None,
+    }
+}
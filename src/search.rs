@@ -0,0 +1,365 @@
+//! Searching the apps that rustcast knows about
+//!
+//! This doesn't depend on the window, so searches (and the order of their results) can be tried
+//! out by building a [`Searcher`] from some apps and querying it
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use rayon::{
+    iter::{IntoParallelRefIterator, ParallelIterator},
+    slice::ParallelSliceMut,
+};
+
+use crate::{
+    app::apps::{App, AppCommand},
+    commands::Function,
+    config::Scoring,
+    utils::normalize_search,
+};
+
+/// How a result's name matched the query, from the best kind of match to the worst
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MatchKind {
+    /// The name starts with the query
+    Prefix,
+    /// A word in the name starts with the query
+    WordPrefix,
+    /// The first letters of the name's words start with the query, e.g. "vsc" for "Visual Studio
+    /// Code"
+    Initials,
+    /// The name contains the query somewhere else
+    Substring,
+    /// Each word of the query is in the name, in any order, e.g. "code visual" for "Visual
+    /// Studio Code"
+    Words,
+}
+
+/// A search result, with what it is ordered by
+#[derive(Debug, Clone)]
+pub struct ScoredResult {
+    pub app: Arc<App>,
+    pub kind: MatchKind,
+}
+
+impl ScoredResult {
+    /// How the result ranks against the others, where higher is better
+    ///
+    /// This is the app's ranking (how often it was opened, or -1 for favourites) with the points
+    /// from the [`Scoring`] in the config, and results with the same score are ordered by their
+    /// [`MatchKind`]
+    pub fn score(&self, scoring: &Scoring) -> f32 {
+        let kind = match self.kind {
+            MatchKind::Prefix => scoring.prefix,
+            MatchKind::WordPrefix => scoring.word_prefix,
+            MatchKind::Initials => scoring.initials,
+            MatchKind::Substring => scoring.substring,
+            MatchKind::Words => scoring.words,
+        };
+        let source = match &self.app.open_command {
+            AppCommand::Function(Function::OpenApp(path)) if path.ends_with(".app") => scoring.apps,
+            AppCommand::Function(Function::OpenApp(_)) => scoring.files,
+            AppCommand::Function(Function::RunShellCommand(_) | Function::RunInTerminal(_)) => {
+                scoring.shell_commands
+            }
+            _ => 0.,
+        };
+        let length = self.app.display_name.chars().count() as f32;
+
+        self.app.ranking as f32 + kind + source - scoring.long_name_penalty * length
+    }
+}
+
+/// All the indexed apps that rustcast can search for
+///
+/// The apps are kept by their search names after [`normalize_search`], so names are looked up the
+/// same way. They are shared with the results as [`Arc`]s, so searching doesn't copy them
+///
+/// The names are also indexed by their trigrams (every three characters in a row), so that names
+/// containing a query anywhere are found without going through all of them, and by the starts of
+/// their [`initials`]
+#[derive(Clone, Debug, Default)]
+pub struct Searcher {
+    by_name: HashMap<String, Arc<App>>,
+    trigrams: HashMap<[char; 3], HashSet<String>>,
+    initials: HashMap<String, HashSet<String>>,
+}
+
+/// Every three characters in a row in some text
+fn trigrams(text: &str) -> Vec<[char; 3]> {
+    let chars: Vec<char> = text.chars().collect();
+    chars.windows(3).map(|x| [x[0], x[1], x[2]]).collect()
+}
+
+/// The first letter of each word in a name, e.g. "vsc" for "visual studio code"
+fn initials(name: &str) -> String {
+    name.split_whitespace()
+        .filter_map(|x| x.chars().next())
+        .collect()
+}
+
+/// Whether every word of a query starts a word in a name, is in the name's initials (like "ce" for
+/// "community edition"), or (for words of a trigram or longer) is somewhere in it
+fn words_match(name: &str, words: &[&str]) -> bool {
+    let name_initials = initials(name);
+    words.iter().all(|word| {
+        name.split_whitespace().any(|x| x.starts_with(word))
+            || (word.chars().count() >= 2 && name_initials.contains(word))
+            || (word.chars().count() >= 3 && name.contains(word))
+    })
+}
+
+/// How a name matches a query, if it does without being a [`MatchKind::Substring`]
+fn prefix_kind(name: &str, query: &str) -> Option<MatchKind> {
+    if name.starts_with(query) {
+        Some(MatchKind::Prefix)
+    } else if name.contains(&format!(" {query}")) {
+        Some(MatchKind::WordPrefix)
+    } else {
+        None
+    }
+}
+
+impl Searcher {
+    /// Search for the apps whose names start with the query, or have a word that starts with it,
+    /// or (for queries of a trigram or longer) contain it anywhere else, e.g. "code" finds "Xcode"
+    ///
+    /// Queries of one word also find the names whose initials start with it, and queries with more
+    /// than one word find the names that have all of the words, in any order
+    ///
+    /// The results are best first, by [`ScoredResult::score`] and then by [`MatchKind`], and then
+    /// by which was used most recently, so that the one of "Firefox" and "Firefox Developer
+    /// Edition" that is actually used comes first
+    pub fn query(&self, query: &str, scoring: &Scoring) -> Vec<ScoredResult> {
+        let query = normalize_search(query);
+        let query_trigrams = trigrams(&query);
+        let words: Vec<&str> = query.split_whitespace().collect();
+
+        let mut results: Vec<ScoredResult> = if words.len() > 1 {
+            // The words can be anywhere in the names, so the trigrams of the query don't help
+            self.by_name
+                .par_iter()
+                .filter_map(|(name, app)| {
+                    let kind = prefix_kind(name, &query)
+                        .or_else(|| name.contains(&query).then_some(MatchKind::Substring))
+                        .or_else(|| words_match(name, &words).then_some(MatchKind::Words))?;
+                    Some(ScoredResult {
+                        app: app.clone(),
+                        kind,
+                    })
+                })
+                .collect()
+        } else if query_trigrams.is_empty() {
+            self.by_name
+                .par_iter()
+                .filter_map(|(name, app)| {
+                    Some(ScoredResult {
+                        app: app.clone(),
+                        kind: prefix_kind(name, &query)?,
+                    })
+                })
+                .collect()
+        } else {
+            // Only the names that have all of the query's trigrams can contain it
+            match query_trigrams
+                .iter()
+                .map(|x| self.trigrams.get(x))
+                .collect::<Option<Vec<&HashSet<String>>>>()
+            {
+                Some(mut postings) => {
+                    postings.sort_unstable_by_key(|x| x.len());
+                    let (smallest, rest) = postings.split_first().expect("the query has trigrams");
+
+                    smallest
+                        .iter()
+                        .filter(|name| {
+                            rest.iter().all(|x| x.contains(*name)) && name.contains(&query)
+                        })
+                        .filter_map(|name| {
+                            Some(ScoredResult {
+                                app: self.by_name.get(name)?.clone(),
+                                kind: prefix_kind(name, &query).unwrap_or(MatchKind::Substring),
+                            })
+                        })
+                        .collect()
+                }
+                None => vec![],
+            }
+        };
+
+        if words.len() == 1
+            && let Some(names) = self.initials.get(&query)
+        {
+            for app in names.iter().filter_map(|name| self.by_name.get(name)) {
+                if !results.iter().any(|x| Arc::ptr_eq(&x.app, app)) {
+                    results.push(ScoredResult {
+                        app: app.clone(),
+                        kind: MatchKind::Initials,
+                    });
+                }
+            }
+        }
+
+        results.par_sort_by(|left, right| {
+            right
+                .score(scoring)
+                .total_cmp(&left.score(scoring))
+                .then(left.kind.cmp(&right.kind))
+                .then(right.app.last_used.cmp(&left.app.last_used))
+        });
+        results
+    }
+
+    pub fn get(&self, name: &str) -> Option<&App> {
+        self.by_name.get(&normalize_search(name)).map(Arc::as_ref)
+    }
+
+    pub fn update_ranking(&mut self, name: &str) {
+        let app = match self.by_name.get_mut(&normalize_search(name)) {
+            Some(a) => a,
+            None => return,
+        };
+
+        Arc::make_mut(app).ranking += 1;
+    }
+
+    /// Add an app to the index, replacing the one with the same search name but keeping its ranking
+    /// and when it was last used
+    pub fn insert(&mut self, mut app: App) {
+        let key = normalize_search(&app.search_name);
+        if let Some(old) = self.by_name.get(&key) {
+            app.ranking = old.ranking;
+            app.last_used = old.last_used;
+        }
+        self.index_trigrams(&key);
+        self.by_name.insert(key, Arc::new(app));
+    }
+
+    /// Take an app out of the index
+    pub fn remove(&mut self, name: &str) {
+        let key = normalize_search(name);
+        if self.by_name.remove(&key).is_none() {
+            return;
+        }
+        for trigram in trigrams(&key) {
+            if let Some(names) = self.trigrams.get_mut(&trigram) {
+                names.remove(&key);
+            }
+        }
+        for names in self.initials.values_mut() {
+            names.remove(&key);
+        }
+    }
+
+    pub fn set_ranking(&mut self, name: &str, rank: i32) {
+        let app = match self.by_name.get_mut(&normalize_search(name)) {
+            Some(a) => a,
+            None => return,
+        };
+
+        Arc::make_mut(app).ranking = rank;
+    }
+
+    pub fn get_rankings(&self) -> HashMap<String, i32> {
+        HashMap::from_iter(self.by_name.iter().filter_map(|(name, app)| {
+            if app.ranking > 0 {
+                Some((name.to_owned(), app.ranking.to_owned()))
+            } else {
+                None
+            }
+        }))
+    }
+
+    /// Set when an app was last opened, in seconds since the unix epoch
+    pub fn set_last_used(&mut self, name: &str, time: u64) {
+        let app = match self.by_name.get_mut(&normalize_search(name)) {
+            Some(a) => a,
+            None => return,
+        };
+
+        Arc::make_mut(app).last_used = Some(time);
+    }
+
+    pub fn get_last_used(&self) -> HashMap<String, u64> {
+        self.by_name
+            .iter()
+            .filter_map(|(name, app)| Some((name.to_owned(), app.last_used?)))
+            .collect()
+    }
+
+    pub fn top_ranked(&self, limit: usize) -> Vec<Arc<App>> {
+        let mut ranked: Vec<Arc<App>> = self
+            .by_name
+            .values()
+            .filter(|app| app.ranking > 0)
+            .cloned()
+            .collect();
+
+        ranked.par_sort_by(|left, right| {
+            right
+                .ranking
+                .cmp(&left.ranking)
+                .then_with(|| left.display_name.cmp(&right.display_name))
+        });
+        ranked.truncate(limit);
+        ranked
+    }
+
+    pub fn get_favourites(&self) -> Vec<Arc<App>> {
+        let mut favs: Vec<Arc<App>> = self
+            .by_name
+            .values()
+            .filter(|x| x.ranking == -1)
+            .cloned()
+            .collect();
+        favs.sort_by(|a, b| a.display_name.cmp(&b.display_name));
+        favs
+    }
+
+    fn index_trigrams(&mut self, name: &str) {
+        for trigram in trigrams(name) {
+            self.trigrams
+                .entry(trigram)
+                .or_default()
+                .insert(name.to_string());
+        }
+
+        // Single letters already find the names through their words
+        let initials = initials(name);
+        for end in initials
+            .char_indices()
+            .map(|(i, c)| i + c.len_utf8())
+            .skip(1)
+        {
+            self.initials
+                .entry(initials[..end].to_string())
+                .or_default()
+                .insert(name.to_string());
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_name.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_name.is_empty()
+    }
+
+    /// Factory function for creating
+    pub fn from_apps(options: Vec<App>) -> Self {
+        let mut index = Searcher::default();
+        for app in options {
+            let key = normalize_search(&app.search_name);
+            index.index_trigrams(&key);
+            index.by_name.insert(key, Arc::new(app));
+        }
+
+        index
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,160 @@
+//! The order of the results for searches through the apps in `corpus.txt`, so that changes to the
+//! ranking are caught
+
+use super::{MatchKind, ScoredResult, Searcher};
+use crate::{app::apps::App, config::Scoring};
+
+/// The names of the apps in the corpus
+fn corpus() -> Vec<&'static str> {
+    include_str!("corpus.txt")
+        .lines()
+        .filter(|x| !x.is_empty() && !x.starts_with('#'))
+        .collect()
+}
+
+fn searcher() -> Searcher {
+    Searcher::from_apps(corpus().into_iter().map(App::test_app).collect())
+}
+
+fn names(results: &[ScoredResult]) -> Vec<&str> {
+    results
+        .iter()
+        .map(|x| x.app.display_name.as_str())
+        .collect()
+}
+
+fn search(searcher: &Searcher, query: &str) -> Vec<String> {
+    names(&searcher.query(query, &Scoring::default()))
+        .into_iter()
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn every_app_is_found_first_by_its_name() {
+    let searcher = searcher();
+    // Names like "Firefox" also start longer ones, like "Firefox Developer Edition", which only
+    // come after it because their names are longer
+    let scoring = Scoring {
+        long_name_penalty: 0.01,
+        ..Scoring::default()
+    };
+
+    for name in corpus() {
+        let results = searcher.query(name, &scoring);
+        assert_eq!(names(&results).first(), Some(&name), "searching for {name}");
+    }
+}
+
+#[test]
+fn prefix_matches_come_before_substring_matches() {
+    let searcher = searcher();
+    assert_eq!(search(&searcher, "term"), ["Terminal", "iTerm"]);
+    assert_eq!(search(&searcher, "code"), ["Visual Studio Code", "Xcode"]);
+}
+
+#[test]
+fn initials_find_apps() {
+    let searcher = searcher();
+    let results = searcher.query("vsc", &Scoring::default());
+    assert_eq!(names(&results), ["Visual Studio Code"]);
+    assert_eq!(results[0].kind, MatchKind::Initials);
+}
+
+#[test]
+fn words_match_in_any_order() {
+    let searcher = searcher();
+    assert_eq!(search(&searcher, "code visual"), ["Visual Studio Code"]);
+    // "ce" is in the initials of "Community Edition"
+    assert_eq!(
+        search(&searcher, "idea ce"),
+        ["IntelliJ IDEA Community Edition"]
+    );
+}
+
+#[test]
+fn short_queries_only_match_the_starts_of_words() {
+    let searcher = searcher();
+    let results = searcher.query("s", &Scoring::default());
+
+    assert!(!results.is_empty());
+    assert!(
+        results
+            .iter()
+            .all(|x| matches!(x.kind, MatchKind::Prefix | MatchKind::WordPrefix))
+    );
+    assert!(results.is_sorted_by_key(|x| x.kind));
+}
+
+#[test]
+fn accents_and_case_are_ignored() {
+    let searcher = searcher();
+    assert_eq!(search(&searcher, "cafe"), ["Café Racer"]);
+    assert_eq!(search(&searcher, "SAFARI"), ["Safari"]);
+}
+
+#[test]
+fn unknown_queries_find_nothing() {
+    let searcher = searcher();
+    assert!(search(&searcher, "xyzzy").is_empty());
+    assert!(search(&searcher, "qq").is_empty());
+}
+
+#[test]
+fn apps_opened_more_often_come_first() {
+    let mut searcher = searcher();
+    searcher.update_ranking("Microsoft Word");
+    searcher.update_ranking("Microsoft Word");
+    searcher.update_ranking("Microsoft Teams");
+
+    let results = search(&searcher, "micro");
+    assert_eq!(results.len(), 5);
+    assert_eq!(results[..2], ["Microsoft Word", "Microsoft Teams"]);
+}
+
+#[test]
+fn the_app_used_last_comes_first_when_the_scores_are_the_same() {
+    let mut searcher = searcher();
+    searcher.set_last_used("Firefox", 100);
+    searcher.set_last_used("Firefox Developer Edition", 200);
+    assert_eq!(
+        search(&searcher, "fire"),
+        ["Firefox Developer Edition", "Firefox"]
+    );
+}
+
+#[test]
+fn scoring_changes_the_order() {
+    let searcher = searcher();
+    let scoring = Scoring {
+        long_name_penalty: 1.,
+        ..Scoring::default()
+    };
+    assert_eq!(
+        names(&searcher.query("google", &scoring)),
+        ["Google Chrome", "Google Chrome Canary"]
+    );
+
+    // Enough points for substring matches put them above prefix matches
+    let scoring = Scoring {
+        substring: 10.,
+        ..Scoring::default()
+    };
+    assert_eq!(
+        names(&searcher.query("term", &scoring)),
+        ["iTerm", "Terminal"]
+    );
+}
+
+#[test]
+fn inserting_keeps_the_ranking_and_removing_forgets_the_app() {
+    let mut searcher = searcher();
+    searcher.update_ranking("Safari");
+    searcher.insert(App::test_app("Safari"));
+    assert_eq!(searcher.get("Safari").map(|x| x.ranking), Some(1));
+
+    searcher.remove("Safari");
+    assert!(search(&searcher, "saf").is_empty());
+    assert!(search(&searcher, "safari").is_empty());
+    assert_eq!(searcher.len(), corpus().len() - 1);
+}
@@ -0,0 +1,128 @@
+//! Making sure only one rustcast runs at a time
+//!
+//! The running instance holds a lock on `rustcast.lock` in the config directory and listens on a
+//! control socket. Launching rustcast again can't take the lock, so it tells the running instance
+//! to show its window through the socket and exits, instead of registering the hotkeys and tray
+//! icon a second time
+
+use std::{
+    fs::{File, OpenOptions, TryLockError},
+    io::{self, Write},
+    sync::OnceLock,
+};
+
+use iced::futures::{SinkExt, Stream};
+use iced::stream;
+use log::{info, warn};
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+use crate::{
+    app::Message,
+    utils::{config_dir, config_path},
+};
+
+/// The control socket, which is a unix socket on unix
+#[cfg(unix)]
+const SOCKET: &str = "rustcast.sock";
+
+/// The file with the port of the control socket, which is a localhost TCP socket on Windows
+#[cfg(windows)]
+const PORT_FILE: &str = "rustcast.port";
+
+/// The lock file, which is kept open (and so locked) until rustcast quits
+static LOCK: OnceLock<File> = OnceLock::new();
+
+/// Take the lock that makes this the running instance, returning false if another instance
+/// already has it
+///
+/// If the lock file can't be used at all, this lets rustcast run rather than refusing to start
+pub fn acquire_lock() -> bool {
+    std::fs::create_dir_all(config_dir()).ok();
+    let Ok(file) = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(config_path("rustcast.lock"))
+    else {
+        return true;
+    };
+
+    match file.try_lock() {
+        Ok(()) => {
+            LOCK.set(file).ok();
+            true
+        }
+        Err(TryLockError::WouldBlock) => false,
+        Err(TryLockError::Error(_)) => true,
+    }
+}
+
+/// Tell the running instance to show its window
+pub fn show_running_instance() -> Result<(), String> {
+    send_command("show")
+        .map_err(|e| format!("Could not reach the running instance of RustCast: {e}"))
+}
+
+#[cfg(unix)]
+fn send_command(command: &str) -> io::Result<()> {
+    let mut stream = std::os::unix::net::UnixStream::connect(config_path(SOCKET))?;
+    writeln!(stream, "{command}")
+}
+
+#[cfg(windows)]
+fn send_command(command: &str) -> io::Result<()> {
+    let port: u16 = std::fs::read_to_string(config_path(PORT_FILE))?
+        .trim()
+        .parse()
+        .map_err(io::Error::other)?;
+    let mut stream = std::net::TcpStream::connect(("127.0.0.1", port))?;
+    writeln!(stream, "{command}")
+}
+
+/// Listens on the control socket for commands from instances that were launched after this one
+pub fn listen() -> impl Stream<Item = Message> {
+    stream::channel(10, async |mut output| {
+        let listener = match bind().await {
+            Ok(listener) => listener,
+            Err(e) => {
+                warn!("Could not open the control socket: {e}");
+                return;
+            }
+        };
+
+        loop {
+            let Ok((stream, _)) = listener.accept().await else {
+                continue;
+            };
+
+            let mut lines = BufReader::new(stream).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                match line.trim() {
+                    "show" => {
+                        info!("RustCast was launched again, showing the window");
+                        output.send(Message::ShowWindow).await.ok();
+                    }
+                    command => warn!("Unknown control command: {command}"),
+                }
+            }
+        }
+    })
+}
+
+#[cfg(unix)]
+async fn bind() -> io::Result<tokio::net::UnixListener> {
+    let path = config_path(SOCKET);
+    // This instance has the lock, so a socket that is already there was left behind by a crash
+    std::fs::remove_file(&path).ok();
+    tokio::net::UnixListener::bind(path)
+}
+
+#[cfg(windows)]
+async fn bind() -> io::Result<tokio::net::TcpListener> {
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", 0)).await?;
+    std::fs::write(
+        config_path(PORT_FILE),
+        listener.local_addr()?.port().to_string(),
+    )?;
+    Ok(listener)
+}
@@ -21,32 +21,52 @@ pub fn with_alpha(mut c: Color, a: f32) -> Color {
 
 /// Styling for the main text box
 pub fn rustcast_text_input_style(theme: &ConfigTheme) -> text_input::Style {
-    let base = theme.bg_color();
     let focused = false; // if you have state, pass it in and use it
-    let surface = glass_surface(base, focused);
+    let surface = glass_surface(theme, focused);
     text_input::Style {
         background: Background::Color(surface),
         border: Border {
-            color: glass_border(theme.text_color(0.), focused),
+            color: glass_border(theme, focused),
             width: 0.,
             radius: Radius::new(10.).bottom(0.),
         },
         icon: theme.text_color(0.),
         placeholder: theme.text_color(0.2),
         value: theme.text_color(0.9),
-        selection: theme.text_color(0.2),
+        selection: selection_color(theme),
     }
 }
 
 /// Container styling for all the elements in the rustcast window
 pub fn contents_style(theme: &ConfigTheme) -> container::Style {
+    let background = match theme.gradient() {
+        Some(gradient) => Background::Gradient(gradient),
+        None => Background::Color(theme.bg_color()),
+    };
     container::Style {
-        background: None,
+        background: Some(background),
         text_color: None,
         border: iced::Border {
             color: theme.text_color(0.9),
-            width: 0.4,
-            radius: Radius::new(14.0),
+            width: theme.advanced.border_width,
+            radius: Radius::new(theme.advanced.window_radius),
+        },
+        ..Default::default()
+    }
+}
+
+/// The layer over the background image that dims it
+pub fn background_dim_style(theme: &ConfigTheme) -> container::Style {
+    container::Style {
+        background: Some(Background::Color(Color::from_rgba(
+            0.,
+            0.,
+            0.,
+            theme.background_dim.clamp(0., 1.),
+        ))),
+        border: Border {
+            radius: Radius::new(theme.advanced.window_radius),
+            ..Default::default()
         },
         ..Default::default()
     }
@@ -70,7 +90,8 @@ pub fn delete_button_style(theme: &ConfigTheme) -> button::Style {
 pub fn result_button_style(theme: &ConfigTheme) -> button::Style {
     button::Style {
         text_color: theme.text_color(1.),
-        background: Some(Background::Color(theme.bg_color())),
+        // The row and the window already draw the background
+        background: None,
         ..Default::default()
     }
 }
@@ -94,7 +115,7 @@ pub fn favourite_button_style(
 
     button::Style {
         text_color,
-        background: Some(Background::Color(theme.bg_color())),
+        background: None,
         ..Default::default()
     }
 }
@@ -157,11 +178,11 @@ pub fn settings_radio_button_style(theme: &ConfigTheme) -> radio::Style {
 /// Each rustcast results rows style
 pub fn result_row_container_style(tile: &ConfigTheme, focused: bool) -> container::Style {
     container::Style {
-        background: Some(Background::Color(glass_surface(tile.bg_color(), focused))),
+        background: Some(Background::Color(glass_surface(tile, focused))),
         border: Border {
-            color: glass_border(tile.text_color(1.), focused),
+            color: glass_border(tile, focused),
             width: 0.,
-            radius: Radius::new(0.0),
+            radius: Radius::new(tile.advanced.row_radius),
         },
         text_color: Some(tile.text_color(1.0)),
         ..Default::default()
@@ -173,13 +194,10 @@ pub fn result_row_container_style(tile: &ConfigTheme, focused: bool) -> containe
 /// Takes a focused boolean, to know if this specific button is focused or not
 pub fn emoji_button_container_style(tile_theme: &ConfigTheme, focused: bool) -> container::Style {
     container::Style {
-        background: Some(Background::Color(glass_surface(
-            tile_theme.bg_color(),
-            focused,
-        ))),
+        background: Some(Background::Color(glass_surface(tile_theme, focused))),
         text_color: Some(tile_theme.text_color(1.0)),
         border: Border {
-            color: glass_border(tile_theme.text_color(1.0), focused),
+            color: glass_border(tile_theme, focused),
             width: 1.0,
             radius: Radius::new(10.0),
         },
@@ -187,6 +205,20 @@ pub fn emoji_button_container_style(tile_theme: &ConfigTheme, focused: bool) ->
     }
 }
 
+/// The chips before the search bar that show where the search is, like the keyword it is scoped to
+pub fn scope_chip_style(theme: &ConfigTheme) -> container::Style {
+    container::Style {
+        background: Some(Background::Color(glass_surface(theme, true))),
+        text_color: Some(theme.text_color(1.0)),
+        border: Border {
+            color: glass_border(theme, true),
+            width: 1.0,
+            radius: Radius::new(8.0),
+        },
+        ..Default::default()
+    }
+}
+
 /// Emoji buttons styling
 pub fn emoji_button_style(tile_theme: &ConfigTheme) -> button::Style {
     let base = tile_theme.bg_color();
@@ -195,7 +227,7 @@ pub fn emoji_button_style(tile_theme: &ConfigTheme) -> button::Style {
         background: Some(Background::Color(bg)),
         text_color: tile_theme.text_color(1.0),
         border: Border {
-            color: glass_border(tile_theme.text_color(1.0), false),
+            color: glass_border(tile_theme, false),
             width: 1.0,
             radius: Radius::new(10.0),
         },
@@ -204,19 +236,18 @@ pub fn emoji_button_style(tile_theme: &ConfigTheme) -> button::Style {
 }
 
 pub fn settings_text_input_item_style(theme: &ConfigTheme) -> text_input::Style {
-    let base = theme.bg_color();
-    let surface = glass_surface(base, false);
+    let surface = glass_surface(theme, false);
     text_input::Style {
         background: Background::Color(surface),
         border: Border {
-            color: glass_border(theme.text_color(1.0), false),
+            color: glass_border(theme, false),
             width: 0.2,
             radius: Radius::new(10.),
         },
         icon: theme.text_color(0.75),
         placeholder: theme.text_color(0.50),
         value: theme.text_color(1.0),
-        selection: with_alpha(theme.text_color(1.0), 0.20),
+        selection: selection_color(theme),
     }
 }
 
@@ -282,15 +313,35 @@ pub fn settings_slider_style(theme: &ConfigTheme) -> slider::Style {
     }
 }
 
-/// Helper fn for making a color look like its glassy
-pub fn glass_surface(base: Color, focused: bool) -> Color {
-    let t = if focused { 0.2 } else { 0.06 };
-    let a = if focused { 0.9 } else { 0.58 };
-    with_alpha(tint(base, t), a)
+/// Helper fn for making the background color look like its glassy
+pub fn glass_surface(theme: &ConfigTheme, focused: bool) -> Color {
+    let advanced = &theme.advanced;
+    if focused && let Some((r, g, b)) = advanced.focused_row_color {
+        return Color::from_rgba(r, g, b, advanced.focused_surface_opacity);
+    }
+
+    let (t, a) = if focused {
+        (advanced.focused_tint, advanced.focused_surface_opacity)
+    } else {
+        (advanced.tint, advanced.surface_opacity)
+    };
+    with_alpha(tint(theme.bg_color(), t), a)
 }
 
 /// Helper fn for making a borders color look like its glassy
-pub fn glass_border(base_text: Color, focused: bool) -> Color {
-    let a = if focused { 0.35 } else { 0.22 };
-    with_alpha(base_text, a)
+pub fn glass_border(theme: &ConfigTheme, focused: bool) -> Color {
+    let a = if focused {
+        theme.advanced.focused_border_opacity
+    } else {
+        theme.advanced.border_opacity
+    };
+    theme.text_color(a)
+}
+
+/// The color of selected text
+pub fn selection_color(theme: &ConfigTheme) -> Color {
+    match theme.advanced.selection_color {
+        Some((r, g, b)) => Color::from_rgba(r, g, b, 0.2),
+        None => theme.text_color(0.2),
+    }
 }
@@ -0,0 +1,122 @@
+//! Math, arrow and currency symbols that can be searched for on the emoji page
+
+use crate::{
+    app::apps::{App, AppCommand},
+    clipboard::ClipBoardContentType,
+    commands::Function,
+};
+
+/// A symbol, with its name and the kind of symbol it is
+struct SymbolDef {
+    symbol: &'static str,
+    name: &'static str,
+    kind: &'static str,
+}
+
+const fn sym(symbol: &'static str, name: &'static str, kind: &'static str) -> SymbolDef {
+    SymbolDef { symbol, name, kind }
+}
+
+const SYMBOLS: &[SymbolDef] = &[
+    // Arrows
+    sym("←", "leftwards arrow left", "arrow"),
+    sym("→", "rightwards arrow right", "arrow"),
+    sym("↑", "upwards arrow up", "arrow"),
+    sym("↓", "downwards arrow down", "arrow"),
+    sym("↔", "left right arrow", "arrow"),
+    sym("↕", "up down arrow", "arrow"),
+    sym("⇐", "leftwards double arrow implied by", "arrow"),
+    sym("⇒", "rightwards double arrow implies", "arrow"),
+    sym("⇔", "left right double arrow if and only if iff", "arrow"),
+    sym("↩", "leftwards arrow with hook return", "arrow"),
+    sym("↪", "rightwards arrow with hook", "arrow"),
+    sym("⇧", "upwards white arrow shift key", "arrow"),
+    sym("⌘", "place of interest sign command key", "key"),
+    sym("⌥", "option key", "key"),
+    sym("⌃", "up arrowhead control key", "key"),
+    sym("⏎", "return symbol enter key", "key"),
+    sym("⌫", "erase to the left backspace delete key", "key"),
+    sym("⎋", "broken circle with northwest arrow escape key", "key"),
+    // Math
+    sym("±", "plus minus sign", "math"),
+    sym("×", "multiplication sign times", "math"),
+    sym("÷", "division sign divide", "math"),
+    sym("≠", "not equal to", "math"),
+    sym("≈", "almost equal to approximately", "math"),
+    sym("≤", "less than or equal to", "math"),
+    sym("≥", "greater than or equal to", "math"),
+    sym("∞", "infinity", "math"),
+    sym("√", "square root", "math"),
+    sym("∑", "n-ary summation sum sigma", "math"),
+    sym("∏", "n-ary product", "math"),
+    sym("∫", "integral", "math"),
+    sym("∂", "partial differential", "math"),
+    sym("∆", "increment delta", "math"),
+    sym("∇", "nabla del", "math"),
+    sym("π", "greek small letter pi", "math"),
+    sym("°", "degree sign", "math"),
+    sym("‰", "per mille sign", "math"),
+    sym("∈", "element of", "math"),
+    sym("∉", "not an element of", "math"),
+    sym("∩", "intersection", "math"),
+    sym("∪", "union", "math"),
+    sym("⊂", "subset of", "math"),
+    sym("⊃", "superset of", "math"),
+    sym("∀", "for all", "math"),
+    sym("∃", "there exists", "math"),
+    sym("¬", "not sign negation", "math"),
+    sym("∧", "logical and", "math"),
+    sym("∨", "logical or", "math"),
+    sym("µ", "micro sign mu", "math"),
+    sym("²", "superscript two squared", "math"),
+    sym("³", "superscript three cubed", "math"),
+    sym("½", "vulgar fraction one half", "math"),
+    sym("¼", "vulgar fraction one quarter", "math"),
+    sym("¾", "vulgar fraction three quarters", "math"),
+    // Currency
+    sym("$", "dollar sign", "currency"),
+    sym("€", "euro sign", "currency"),
+    sym("£", "pound sign sterling", "currency"),
+    sym("¥", "yen sign yuan", "currency"),
+    sym("₹", "indian rupee sign", "currency"),
+    sym("₩", "won sign", "currency"),
+    sym("₽", "ruble sign", "currency"),
+    sym("₿", "bitcoin sign", "currency"),
+    sym("¢", "cent sign", "currency"),
+    sym("₺", "turkish lira sign", "currency"),
+    sym("₫", "dong sign", "currency"),
+    sym("₱", "peso sign", "currency"),
+    // Typography
+    sym("©", "copyright sign", "typography"),
+    sym("®", "registered sign", "typography"),
+    sym("™", "trade mark sign", "typography"),
+    sym("§", "section sign", "typography"),
+    sym("¶", "pilcrow sign paragraph", "typography"),
+    sym("•", "bullet", "typography"),
+    sym("…", "horizontal ellipsis", "typography"),
+    sym("—", "em dash", "typography"),
+    sym("–", "en dash", "typography"),
+];
+
+/// All the symbols as App structs
+///
+/// The search name is the symbols name followed by its kind (e.g. "math" or "currency") so that
+/// every symbol of a kind can be found by searching for it
+pub fn symbol_apps() -> Vec<App> {
+    SYMBOLS
+        .iter()
+        .map(|def| App {
+            ranking: 0,
+            icons: None,
+            display_name: def.symbol.to_string(),
+            search_name: format!("{} {} symbol", def.name, def.kind),
+            open_command: AppCommand::Function(Function::CopyToClipboard(
+                ClipBoardContentType::Text(def.symbol.to_string()),
+            )),
+            desc: def.name.to_string(),
+            version: None,
+            last_used: None,
+            children: None,
+        })
+        .collect()
+}
@@ -0,0 +1,210 @@
+//! Timers and a stopwatch, started by searching for `timer 10m tea` and `stopwatch`
+//!
+//! Timers run in the background, and show a notification when they are done
+
+use std::time::{Duration, Instant};
+
+use crate::{
+    app::{
+        Message,
+        apps::{App, AppCommand},
+    },
+    notifications,
+};
+
+/// A timer that is counting down
+#[derive(Debug, Clone)]
+pub struct Timer {
+    pub id: u32,
+    pub label: String,
+    pub duration: Duration,
+    started: Instant,
+}
+
+impl Timer {
+    pub fn remaining(&self) -> Duration {
+        self.duration.saturating_sub(self.started.elapsed())
+    }
+
+    /// Show the notification saying that the timer is done
+    pub fn notify(&self, sound: bool) -> Result<(), String> {
+        let title = if self.label.is_empty() {
+            "Timer"
+        } else {
+            &self.label
+        };
+        notifications::notify(
+            title,
+            &format!("Your {} timer is done", describe_duration(self.duration)),
+            sound,
+        )
+    }
+}
+
+/// The running timers, and the stopwatch
+#[derive(Debug, Clone, Default)]
+pub struct Timers {
+    timers: Vec<Timer>,
+    next_id: u32,
+    stopwatch: Option<Instant>,
+}
+
+impl Timers {
+    /// Start a timer, returning its id
+    pub fn start(&mut self, duration: Duration, label: String) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.timers.push(Timer {
+            id,
+            label,
+            duration,
+            started: Instant::now(),
+        });
+        id
+    }
+
+    /// Remove a timer, either because it was cancelled or because it is done
+    pub fn remove(&mut self, id: u32) -> Option<Timer> {
+        let index = self.timers.iter().position(|x| x.id == id)?;
+        Some(self.timers.remove(index))
+    }
+
+    pub fn start_stopwatch(&mut self) {
+        self.stopwatch = Some(Instant::now());
+    }
+
+    /// Stop the stopwatch, returning how long it ran for
+    pub fn stop_stopwatch(&mut self) -> Option<Duration> {
+        self.stopwatch.take().map(|x| x.elapsed())
+    }
+
+    /// The results for a `timer` or `stopwatch` search, which are a result to start a timer (when
+    /// the search has a duration) and the running timers, or the stopwatch
+    pub fn apps(&self, query: &str) -> Vec<App> {
+        let query = query.trim();
+        let query_lc = query.to_lowercase();
+
+        if query_lc == "stopwatch" {
+            return vec![match self.stopwatch {
+                Some(started) => timer_app(
+                    format!("Stopwatch: {}", clock(started.elapsed())),
+                    "Stopwatch, press enter to stop it and copy the time",
+                    Message::StopStopwatch,
+                ),
+                None => timer_app(
+                    "Start a stopwatch".to_string(),
+                    "Stopwatch",
+                    Message::StartStopwatch,
+                ),
+            }];
+        }
+
+        if !query_lc.starts_with("timer") {
+            return vec![];
+        }
+
+        let mut apps = vec![];
+        let mut args = query
+            .get("timer".len()..)
+            .unwrap_or("")
+            .trim()
+            .splitn(2, ' ');
+        if let Some(duration) = args.next().and_then(parse_duration) {
+            let label = args.next().unwrap_or("").trim().to_string();
+            let name = if label.is_empty() {
+                String::new()
+            } else {
+                format!(": {label}")
+            };
+            apps.push(timer_app(
+                format!("Start a {} timer{name}", describe_duration(duration)),
+                "Timer",
+                Message::StartTimer(duration, label),
+            ));
+        }
+
+        apps.extend(self.timers.iter().map(|timer| {
+            let label = if timer.label.is_empty() {
+                "Timer"
+            } else {
+                &timer.label
+            };
+            timer_app(
+                format!("{label}: {} left", clock(timer.remaining())),
+                "Running timer, press enter to cancel it",
+                Message::CancelTimer(timer.id),
+            )
+        }));
+        apps
+    }
+}
+
+fn timer_app(display_name: String, desc: &str, message: Message) -> App {
+    App {
+        ranking: 20,
+        open_command: AppCommand::Message(message),
+        desc: desc.to_string(),
+        icons: None,
+        display_name,
+        search_name: String::new(),
+        version: None,
+        last_used: None,
+        children: None,
+    }
+}
+
+/// Parses a duration like `10m`, `1h30m` or `90s`, where a plain number is a number of minutes
+pub fn parse_duration(text: &str) -> Option<Duration> {
+    if let Ok(minutes) = text.parse::<u64>() {
+        return Some(Duration::from_secs(minutes * 60));
+    }
+
+    let mut seconds = 0;
+    let mut number = String::new();
+    for c in text.to_lowercase().chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let unit = match c {
+            'h' => 3600,
+            'm' => 60,
+            's' => 1,
+            _ => return None,
+        };
+        seconds += number.parse::<u64>().ok()? * unit;
+        number.clear();
+    }
+
+    (number.is_empty() && seconds > 0).then(|| Duration::from_secs(seconds))
+}
+
+/// A duration in words, like "1h 30m"
+pub fn describe_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    [
+        (seconds / 3600, "h"),
+        (seconds / 60 % 60, "m"),
+        (seconds % 60, "s"),
+    ]
+    .into_iter()
+    .filter(|(amount, _)| *amount > 0)
+    .map(|(amount, unit)| format!("{amount}{unit}"))
+    .collect::<Vec<String>>()
+    .join(" ")
+}
+
+/// A duration as a clock, like "1:02:03" or "4:05"
+pub fn clock(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    if seconds >= 3600 {
+        format!(
+            "{}:{:02}:{:02}",
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60
+        )
+    } else {
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+}
@@ -0,0 +1,188 @@
+//! Changing the case and formatting of text, for searches that start with `case`
+//!
+//! `case` followed by some text shows that text in each case and format, and `case` on its own
+//! does the same for the text in the clipboard (which the clipboard history's "Transform" button
+//! copies there first). Opening a result copies it
+
+use arboard::Clipboard;
+
+use crate::{
+    app::apps::{App, AppCommand},
+    clipboard::ClipBoardContentType,
+    commands::Function,
+};
+
+/// How much of a transformed text is shown as its result's name
+const MAX_PREVIEW_LENGTH: usize = 100;
+
+/// The ways text can be transformed
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Transform {
+    Upper,
+    Lower,
+    Title,
+    Camel,
+    Snake,
+    Trim,
+    JsonPretty,
+    JsonMinify,
+}
+
+impl Transform {
+    const ALL: [Transform; 8] = [
+        Transform::Upper,
+        Transform::Lower,
+        Transform::Title,
+        Transform::Camel,
+        Transform::Snake,
+        Transform::Trim,
+        Transform::JsonPretty,
+        Transform::JsonMinify,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Transform::Upper => "UPPERCASE",
+            Transform::Lower => "lowercase",
+            Transform::Title => "Title Case",
+            Transform::Camel => "camelCase",
+            Transform::Snake => "snake_case",
+            Transform::Trim => "Trimmed whitespace",
+            Transform::JsonPretty => "Pretty-printed JSON",
+            Transform::JsonMinify => "Minified JSON",
+        }
+    }
+
+    /// The transformed text, if the text can be transformed this way (only JSON can be printed as
+    /// JSON)
+    fn apply(self, text: &str) -> Option<String> {
+        let transformed = match self {
+            Transform::Upper => text.to_uppercase(),
+            Transform::Lower => text.to_lowercase(),
+            Transform::Title => text
+                .split(' ')
+                .map(|word| {
+                    let mut chars = word.chars();
+                    match chars.next() {
+                        Some(first) => first
+                            .to_uppercase()
+                            .chain(chars.flat_map(char::to_lowercase))
+                            .collect(),
+                        None => String::new(),
+                    }
+                })
+                .collect::<Vec<String>>()
+                .join(" "),
+            Transform::Camel => words(text)
+                .iter()
+                .enumerate()
+                .map(|(i, word)| {
+                    let word = word.to_lowercase();
+                    let mut chars = word.chars();
+                    match chars.next() {
+                        Some(first) if i > 0 => first.to_uppercase().chain(chars).collect(),
+                        _ => word,
+                    }
+                })
+                .collect(),
+            Transform::Snake => words(text)
+                .iter()
+                .map(|x| x.to_lowercase())
+                .collect::<Vec<String>>()
+                .join("_"),
+            Transform::Trim => text
+                .trim()
+                .lines()
+                .map(str::trim_end)
+                .collect::<Vec<&str>>()
+                .join("\n"),
+            Transform::JsonPretty => {
+                let json: serde_json::Value = serde_json::from_str(text).ok()?;
+                serde_json::to_string_pretty(&json).ok()?
+            }
+            Transform::JsonMinify => {
+                let json: serde_json::Value = serde_json::from_str(text).ok()?;
+                serde_json::to_string(&json).ok()?
+            }
+        };
+        Some(transformed)
+    }
+}
+
+/// The words of some text, which are split at anything that isn't a letter or a number, and
+/// where a lowercase letter (or a number) is followed by an uppercase one, like in `camelCase`
+fn words(text: &str) -> Vec<String> {
+    let mut words = vec![];
+    let mut word = String::new();
+    let mut previous: Option<char> = None;
+
+    for c in text.chars() {
+        if !c.is_alphanumeric() {
+            words.push(std::mem::take(&mut word));
+        } else {
+            if c.is_uppercase() && previous.is_some_and(|x| x.is_lowercase() || x.is_numeric()) {
+                words.push(std::mem::take(&mut word));
+            }
+            word.push(c);
+        }
+        previous = Some(c);
+    }
+    words.push(word);
+
+    words.retain(|x| !x.is_empty());
+    words
+}
+
+/// A result for each way the text from a `case` search can be transformed
+pub fn case_apps(query: &str) -> Vec<App> {
+    let query = query.trim_start();
+    let Some(rest) = query
+        .get(..4)
+        .filter(|x| x.eq_ignore_ascii_case("case"))
+        .map(|_| &query[4..])
+    else {
+        return vec![];
+    };
+
+    let text = if rest.is_empty() {
+        match Clipboard::new().and_then(|mut x| x.get_text()) {
+            Ok(text) => text,
+            Err(_) => return vec![],
+        }
+    } else if let Some(text) = rest.strip_prefix(' ') {
+        text.to_string()
+    } else {
+        return vec![];
+    };
+    if text.trim().is_empty() {
+        return vec![];
+    }
+
+    Transform::ALL
+        .iter()
+        .filter_map(|transform| {
+            let transformed = transform.apply(&text)?;
+            let preview: String = transformed
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .chars()
+                .take(MAX_PREVIEW_LENGTH)
+                .collect();
+
+            Some(App {
+                ranking: 20,
+                open_command: AppCommand::Function(Function::CopyToClipboard(
+                    ClipBoardContentType::Text(transformed),
+                )),
+                desc: transform.name().to_string(),
+                icons: None,
+                display_name: preview,
+                search_name: String::new(),
+                version: None,
+                last_used: None,
+                children: None,
+            })
+        })
+        .collect()
+}
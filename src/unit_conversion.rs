@@ -262,6 +262,9 @@ impl ToApp for ConversionResult {
             icons: None,
             display_name: target,
             search_name: String::new(),
+            version: None,
+            last_used: None,
+            children: None,
         }
     }
 }
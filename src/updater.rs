@@ -0,0 +1,137 @@
+//! Checking GitHub for new releases of rustcast, which is turned on with `check_for_updates`
+//!
+//! When a newer release is found, it is shown in the tray menu and as a result in the main page,
+//! which opens the download for this platform (or the release page if there isn't one)
+
+use std::time::Duration;
+
+use iced::futures::{SinkExt, Stream};
+use iced::stream;
+use log::{info, warn};
+
+use crate::{
+    app::{
+        Message, RUSTCAST_DESC_NAME, ToApp,
+        apps::{App, AppCommand},
+    },
+    commands::Function,
+    i18n::tr,
+};
+
+/// How often to check for a new release
+const CHECK_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// The GitHub API endpoint for the latest release
+const LATEST_RELEASE_URL: &str =
+    "https://api.github.com/repos/RustCastLabs/rustcast/releases/latest";
+
+/// A release of rustcast on GitHub
+#[derive(Debug, Clone, PartialEq)]
+pub struct Release {
+    pub version: String,
+    /// The download for this platform, or the release page if there isn't one
+    pub url: String,
+}
+
+impl ToApp for Release {
+    /// The result that opens the download for the release
+    fn to_app(&self) -> App {
+        App {
+            ranking: 0,
+            open_command: AppCommand::Function(Function::OpenWebsite(self.url.clone())),
+            desc: RUSTCAST_DESC_NAME.to_string(),
+            icons: None,
+            display_name: tr!("update-available", version = self.version.clone()),
+            search_name: String::new(),
+            version: None,
+            last_used: None,
+            children: None,
+        }
+    }
+}
+
+/// Checks for a new release when it starts, and then every [`CHECK_INTERVAL`]
+pub fn check_for_updates() -> impl Stream<Item = Message> {
+    stream::channel(100, async |mut output| {
+        let Some(current_version) = option_env!("APP_VERSION").filter(|x| !x.is_empty()) else {
+            info!("Not checking for updates, as this build has no version");
+            return;
+        };
+
+        loop {
+            match tokio::task::spawn_blocking(latest_release).await {
+                Ok(Ok(release)) if is_newer(&release.version, current_version) => {
+                    info!("New version available: {}", release.version);
+                    output.send(Message::UpdateAvailable(release)).await.ok();
+                }
+                Ok(Ok(_)) => info!("RustCast is up to date"),
+                Ok(Err(e)) => warn!("Could not check for updates: {e}"),
+                Err(e) => warn!("Could not check for updates: {e}"),
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    })
+}
+
+/// Gets the latest release from GitHub
+fn latest_release() -> Result<Release, String> {
+    let response = minreq::get(LATEST_RELEASE_URL)
+        .with_header("User-Agent", "rustcast-update-checker")
+        .with_header("Accept", "application/vnd.github+json")
+        .with_header("X-GitHub-Api-Version", "2022-11-28")
+        .send()
+        .map_err(|e| e.to_string())?;
+    let release: serde_json::Value = response
+        .as_str()
+        .map_err(|e| e.to_string())
+        .and_then(|x| serde_json::from_str(x).map_err(|e| e.to_string()))?;
+
+    let version = release
+        .get("tag_name")
+        .and_then(|x| x.as_str())
+        .ok_or("the release has no tag")?
+        .to_string();
+    let page = release
+        .get("html_url")
+        .and_then(|x| x.as_str())
+        .unwrap_or("https://github.com/RustCastLabs/rustcast/releases/latest");
+
+    let download = release
+        .get("assets")
+        .and_then(|x| x.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|asset| {
+            let name = asset.get("name")?.as_str()?.to_lowercase();
+            let url = asset.get("browser_download_url")?.as_str()?;
+            is_for_this_platform(&name).then(|| url.to_string())
+        })
+        .next();
+
+    Ok(Release {
+        version,
+        url: download.unwrap_or(page.to_string()),
+    })
+}
+
+/// Whether a release asset is the download for this platform, going by its file name
+fn is_for_this_platform(name: &str) -> bool {
+    match std::env::consts::OS {
+        "macos" => name.ends_with(".dmg") || name.contains("macos") || name.contains("darwin"),
+        "windows" => name.ends_with(".msi") || name.ends_with(".exe") || name.contains("windows"),
+        _ => name.ends_with(".appimage") || name.contains("linux"),
+    }
+}
+
+/// Whether the version `latest` is newer than `current`, where both look like `v1.2.3`
+pub fn is_newer(latest: &str, current: &str) -> bool {
+    let parse = |version: &str| -> Vec<u64> {
+        version
+            .trim()
+            .trim_start_matches('v')
+            .split(['.', '-'])
+            .map_while(|x| x.parse().ok())
+            .collect()
+    };
+    parse(latest) > parse(current)
+}
@@ -1,11 +1,12 @@
 //! This has all the utility functions that rustcast uses
-use std::{path::Path, thread};
+use std::{
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 use iced::widget::image::Handle;
 use icns::IconFamily;
 use image::RgbaImage;
-use objc2_app_kit::NSWorkspace;
-use objc2_foundation::NSURL;
 
 pub fn icns_data_to_handle(data: Vec<u8>) -> Option<Handle> {
     let family = IconFamily::read(std::io::Cursor::new(&data)).ok()?;
@@ -21,21 +22,143 @@ pub fn icns_data_to_handle(data: Vec<u8>) -> Option<Handle> {
     ))
 }
 
+/// The directory that rustcast's config file and the files it saves are kept in
+///
+/// This is `$RUSTCAST_CONFIG` if it is set (which is useful for testing), and otherwise `rustcast`
+/// in `$XDG_CONFIG_HOME`, or in `~/.config` (on every platform, as that's where it has always been)
+pub fn config_dir() -> PathBuf {
+    let from_env = |var: &str| {
+        std::env::var_os(var)
+            .filter(|x| !x.is_empty())
+            .map(PathBuf::from)
+    };
+
+    from_env("RUSTCAST_CONFIG").unwrap_or_else(|| {
+        from_env("XDG_CONFIG_HOME")
+            .or_else(|| dirs::home_dir().map(|x| x.join(".config")))
+            .unwrap_or(PathBuf::from("/"))
+            .join("rustcast")
+    })
+}
+
+/// A file in the config directory
+pub fn config_path(file: &str) -> PathBuf {
+    config_dir().join(file)
+}
+
+/// The hostname of this machine, which Windows has in `COMPUTERNAME`
+#[cfg(target_os = "windows")]
+pub(crate) fn hostname() -> Option<String> {
+    std::env::var("COMPUTERNAME").ok()
+}
+
+/// The hostname of this machine
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn hostname() -> Option<String> {
+    let mut buffer = [0u8; 256];
+    if unsafe { libc::gethostname(buffer.as_mut_ptr().cast(), buffer.len()) } != 0 {
+        return None;
+    }
+    let length = buffer.iter().position(|x| *x == 0)?;
+    String::from_utf8(buffer[..length].to_vec()).ok()
+}
+
 /// This converts an icns file to an iced image handle
 pub(crate) fn handle_from_icns(path: &Path) -> Option<Handle> {
     let data = std::fs::read(path).ok()?;
     icns_data_to_handle(data)
 }
 
+/// The seconds since the unix epoch
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|x| x.as_secs())
+        .unwrap_or(0)
+}
+
+/// How long ago a time in seconds since the unix epoch was, like "5 minutes ago"
+pub fn time_ago(time: u64) -> String {
+    let seconds = unix_now().saturating_sub(time);
+    let (count, unit) = match seconds {
+        0..60 => return "just now".to_string(),
+        60..3600 => (seconds / 60, "minute"),
+        3600..86400 => (seconds / 3600, "hour"),
+        86400..2592000 => (seconds / 86400, "day"),
+        2592000..31536000 => (seconds / 2592000, "month"),
+        _ => (seconds / 31536000, "year"),
+    };
+    if count == 1 {
+        format!("1 {unit} ago")
+    } else {
+        format!("{count} {unit}s ago")
+    }
+}
+
+/// Percent-encode everything in some text except letters, numbers and `-_.~`, so that it can be
+/// put anywhere in a URL
+pub fn percent_encode(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-_.~".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+/// Decode the `%XX` escapes in some text, leaving any `%` that isn't followed by two hex digits as
+/// it is. Returns `None` if the decoded bytes aren't valid UTF-8
+pub fn percent_decode(text: &str) -> Option<String> {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| text.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|x| u8::from_str_radix(x, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+/// Encode bytes as standard base64, with padding
+pub fn base64_encode(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = (chunk[0] as u32) << 16
+            | (*chunk.get(1).unwrap_or(&0) as u32) << 8
+            | *chunk.get(2).unwrap_or(&0) as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
 /// Open a provided URL (Platform specific)
 pub fn open_url(url: &str) {
-    let url = url.to_owned();
-    thread::spawn(move || {
-        NSWorkspace::new().openURL(
-            &NSURL::URLWithString_relativeToURL(&objc2_foundation::NSString::from_str(&url), None)
-                .unwrap(),
-        );
-    });
+    if let Err(e) = crate::platform::open_url(url) {
+        log::error!("Failed to open {url}: {e}");
+    }
 }
 
 /// Check if the provided string is a valid url
@@ -63,3 +186,57 @@ pub fn is_valid_url(s: &str) -> bool {
         _ => false,
     }
 }
+
+/// Normalize text for searching, so that "cafe" matches "Café" and "ＦＵＬＬ" matches "full"
+///
+/// This lowercases the text (which handles every script with cases, like Cyrillic and Greek), then
+/// drops combining accents (which is how macOS stores accents in file names), takes the accents
+/// off precomposed Latin letters and turns full width letters into normal ones
+pub fn normalize_search(text: &str) -> String {
+    let mut normalized = String::with_capacity(text.len());
+    for c in text.to_lowercase().chars() {
+        if ('\u{0300}'..='\u{036f}').contains(&c) {
+            continue;
+        }
+        if ('\u{ff01}'..='\u{ff5e}').contains(&c) {
+            normalized.extend(char::from_u32(c as u32 - 0xfee0));
+            continue;
+        }
+        match strip_accent(c) {
+            Some(base) => normalized.push_str(base),
+            None => normalized.push(c),
+        }
+    }
+    normalized
+}
+
+/// The unaccented letters for a lowercase accented Latin letter
+fn strip_accent(c: char) -> Option<&'static str> {
+    Some(match c {
+        'à'..='å' | 'ā' | 'ă' | 'ą' => "a",
+        'æ' => "ae",
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => "c",
+        'ð' | 'ď' | 'đ' => "d",
+        'è'..='ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => "e",
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => "g",
+        'ĥ' | 'ħ' => "h",
+        'ì'..='ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => "i",
+        'ĳ' => "ij",
+        'ĵ' => "j",
+        'ķ' | 'ĸ' => "k",
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => "l",
+        'ñ' | 'ń' | 'ņ' | 'ň' | 'ŉ' | 'ŋ' => "n",
+        'ò'..='ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => "o",
+        'œ' => "oe",
+        'ŕ' | 'ŗ' | 'ř' => "r",
+        'ß' => "ss",
+        'ś' | 'ŝ' | 'ş' | 'š' | 'ș' | 'ſ' => "s",
+        'ţ' | 'ť' | 'ŧ' | 'ț' => "t",
+        'þ' => "th",
+        'ù'..='ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => "u",
+        'ŵ' => "w",
+        'ý' | 'ÿ' | 'ŷ' => "y",
+        'ź' | 'ż' | 'ž' => "z",
+        _ => return None,
+    })
+}
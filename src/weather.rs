@@ -0,0 +1,237 @@
+//! The weather, for searches for `weather` (at the configured location) or `weather <place>`
+//!
+//! The place is found with Open-Meteo's geocoding API and its forecast with Open-Meteo's forecast
+//! API (or compatible ones set in the config), which don't need an API key. The results are the
+//! current conditions and the next few days, and opening one copies it. The tile keeps the
+//! results for each place for [`CACHE_SECONDS`], so searching again doesn't look them up again
+
+use jiff::civil::Date;
+use serde::Deserialize;
+
+use crate::{
+    app::apps::{App, AppCommand},
+    clipboard::ClipBoardContentType,
+    commands::Function,
+    config::{Weather, WeatherUnits},
+    utils::percent_encode,
+};
+
+/// How long the weather for a place is kept before it is looked up again
+pub const CACHE_SECONDS: u64 = 15 * 60;
+
+/// How many days the forecast covers, including today
+const FORECAST_DAYS: usize = 3;
+
+/// A place from the geocoding API
+#[derive(Debug, Deserialize)]
+struct Place {
+    name: String,
+    latitude: f64,
+    longitude: f64,
+    #[serde(default)]
+    country: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Places {
+    #[serde(default)]
+    results: Vec<Place>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Forecast {
+    current: Current,
+    current_units: CurrentUnits,
+    daily: Daily,
+}
+
+#[derive(Debug, Deserialize)]
+struct Current {
+    temperature_2m: f64,
+    apparent_temperature: f64,
+    relative_humidity_2m: f64,
+    wind_speed_10m: f64,
+    weather_code: u8,
+}
+
+#[derive(Debug, Deserialize)]
+struct CurrentUnits {
+    temperature_2m: String,
+    wind_speed_10m: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Daily {
+    time: Vec<String>,
+    weather_code: Vec<u8>,
+    temperature_2m_max: Vec<f64>,
+    temperature_2m_min: Vec<f64>,
+    #[serde(default)]
+    precipitation_probability_max: Vec<Option<f64>>,
+}
+
+/// The place after `weather`, which is the configured location if there isn't one, or `None` if
+/// the search isn't for the weather
+pub fn matching(settings: &Weather, query: &str) -> Option<String> {
+    let query = query.trim();
+    let rest = query
+        .get(..7)
+        .filter(|x| x.eq_ignore_ascii_case("weather"))
+        .map(|_| &query[7..])?;
+
+    if rest.is_empty() {
+        let location = settings.location.trim();
+        (!location.is_empty()).then(|| location.to_string())
+    } else {
+        Some(rest.strip_prefix(' ')?.trim().to_string())
+    }
+}
+
+/// Look up the weather at a place
+pub async fn lookup(settings: Weather, location: String) -> Result<Vec<App>, String> {
+    tokio::task::spawn_blocking(move || {
+        let places: Places = get_json(&format!(
+            "{}?name={}&count=1",
+            settings.geocoding_url,
+            percent_encode(&location)
+        ))?;
+        let place = places
+            .results
+            .into_iter()
+            .next()
+            .ok_or_else(|| format!("Couldn't find a place called {location}"))?;
+
+        let mut url = format!(
+            "{}?latitude={}&longitude={}&timezone=auto&forecast_days={FORECAST_DAYS}\
+             &current=temperature_2m,apparent_temperature,relative_humidity_2m,wind_speed_10m,\
+             weather_code&daily=weather_code,temperature_2m_max,temperature_2m_min,\
+             precipitation_probability_max",
+            settings.forecast_url, place.latitude, place.longitude
+        );
+        if settings.units == WeatherUnits::Imperial {
+            url.push_str("&temperature_unit=fahrenheit&wind_speed_unit=mph");
+        }
+        let forecast: Forecast = get_json(&url)?;
+
+        Ok(weather_apps(&place, &forecast))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+fn get_json<T: serde::de::DeserializeOwned>(url: &str) -> Result<T, String> {
+    let response = minreq::get(url)
+        .with_header("User-Agent", "rustcast")
+        .with_timeout(10)
+        .send()
+        .map_err(|e| e.to_string())?;
+    if response.status_code != 200 {
+        return Err(format!(
+            "The weather service responded with {} {}",
+            response.status_code, response.reason_phrase
+        ));
+    }
+    serde_json::from_slice(response.as_bytes()).map_err(|e| e.to_string())
+}
+
+/// A result for the current conditions, and one for each day of the forecast
+fn weather_apps(place: &Place, forecast: &Forecast) -> Vec<App> {
+    let current = &forecast.current;
+    let units = &forecast.current_units;
+    let place_name = match &place.country {
+        Some(country) => format!("{}, {country}", place.name),
+        None => place.name.clone(),
+    };
+
+    let mut apps = vec![app(
+        format!(
+            "{}: {}{}, {}",
+            place.name,
+            current.temperature_2m.round(),
+            units.temperature_2m,
+            condition(current.weather_code)
+        ),
+        format!(
+            "{place_name} · Feels like {}{} · Humidity {}% · Wind {} {}",
+            current.apparent_temperature.round(),
+            units.temperature_2m,
+            current.relative_humidity_2m.round(),
+            current.wind_speed_10m.round(),
+            units.wind_speed_10m
+        ),
+    )];
+
+    let daily = &forecast.daily;
+    for (i, day) in daily.time.iter().enumerate().take(FORECAST_DAYS) {
+        let (Some(code), Some(max), Some(min)) = (
+            daily.weather_code.get(i),
+            daily.temperature_2m_max.get(i),
+            daily.temperature_2m_min.get(i),
+        ) else {
+            break;
+        };
+        let date = day.parse::<Date>().ok();
+        let name = match i {
+            0 => "Today".to_string(),
+            1 => "Tomorrow".to_string(),
+            _ => date.map_or(day.clone(), |x| x.strftime("%A").to_string()),
+        };
+
+        let mut desc = date.map_or(day.clone(), |x| x.strftime("%a %-d %b").to_string());
+        if let Some(Some(chance)) = daily.precipitation_probability_max.get(i) {
+            desc.push_str(&format!(" · {}% chance of precipitation", chance.round()));
+        }
+        apps.push(app(
+            format!(
+                "{name}: {}° / {}°, {}",
+                max.round(),
+                min.round(),
+                condition(*code)
+            ),
+            desc,
+        ));
+    }
+    apps
+}
+
+/// What a WMO weather code means
+fn condition(code: u8) -> &'static str {
+    match code {
+        0 => "Clear sky",
+        1 => "Mainly clear",
+        2 => "Partly cloudy",
+        3 => "Overcast",
+        45 | 48 => "Fog",
+        51 | 53 | 55 => "Drizzle",
+        56 | 57 => "Freezing drizzle",
+        61 => "Light rain",
+        63 => "Rain",
+        65 => "Heavy rain",
+        66 | 67 => "Freezing rain",
+        71 => "Light snow",
+        73 => "Snow",
+        75 => "Heavy snow",
+        77 => "Snow grains",
+        80..=82 => "Rain showers",
+        85 | 86 => "Snow showers",
+        95 => "Thunderstorm",
+        96 | 99 => "Thunderstorm with hail",
+        _ => "Unknown conditions",
+    }
+}
+
+fn app(display_name: String, desc: String) -> App {
+    App {
+        ranking: 20,
+        open_command: AppCommand::Function(Function::CopyToClipboard(ClipBoardContentType::Text(
+            format!("{display_name} ({desc})"),
+        ))),
+        desc,
+        icons: None,
+        display_name,
+        search_name: String::new(),
+        version: None,
+        last_used: None,
+        children: None,
+    }
+}
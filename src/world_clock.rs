@@ -0,0 +1,71 @@
+//! The world clock, which shows the current time in the cities from the config when searching
+//! for `time`
+//!
+//! The timezone database is bundled with rustcast, so this works offline and doesn't depend on the
+//! system's copy of it
+
+use jiff::{Timestamp, tz::TimeZone};
+
+use crate::{
+    app::apps::{App, AppCommand},
+    clipboard::ClipBoardContentType,
+    commands::Function,
+    config::{City, WorldClock},
+};
+
+/// A result for each city on the world clock, with its current time
+///
+/// Cities with unknown timezones are left out, as they are reported when the config is loaded
+pub fn world_clock_apps(world_clock: &WorldClock) -> Vec<App> {
+    let now = Timestamp::now();
+    let local_offset = TimeZone::system().to_offset(now).seconds();
+
+    world_clock
+        .cities
+        .iter()
+        .filter_map(|city| city_app(city, now, local_offset))
+        .collect()
+}
+
+fn city_app(city: &City, now: Timestamp, local_offset: i32) -> Option<App> {
+    let time = now.to_zoned(TimeZone::get(&city.timezone).ok()?);
+    let clock = time.strftime("%H:%M").to_string();
+
+    Some(App {
+        ranking: 20,
+        open_command: AppCommand::Function(Function::CopyToClipboard(ClipBoardContentType::Text(
+            clock.clone(),
+        ))),
+        desc: format!(
+            "{}, {}",
+            time.strftime("%A %-d %B"),
+            relative_offset(time.offset().seconds() - local_offset)
+        ),
+        icons: None,
+        display_name: format!("{clock} in {}", city.name),
+        search_name: String::new(),
+        version: None,
+        last_used: None,
+        children: None,
+    })
+}
+
+/// How far ahead or behind of the local time a timezone is, like "5h 30m ahead"
+fn relative_offset(seconds: i32) -> String {
+    if seconds == 0 {
+        return "same time as here".to_string();
+    }
+
+    let minutes = seconds.unsigned_abs() / 60;
+    let difference = match (minutes / 60, minutes % 60) {
+        (hours, 0) => format!("{hours}h"),
+        (0, minutes) => format!("{minutes}m"),
+        (hours, minutes) => format!("{hours}h {minutes}m"),
+    };
+
+    if seconds > 0 {
+        format!("{difference} ahead")
+    } else {
+        format!("{difference} behind")
+    }
+}